@@ -0,0 +1,13 @@
+CREATE TABLE IF NOT EXISTS default.orders
+(
+    `number` UInt32,
+    `group` UInt32,
+    `string` String,
+    `boolean` Bool,
+    `date` Date,
+    `round` Float64
+)
+ENGINE = MergeTree
+ORDER BY number;
+
+CREATE TABLE IF NOT EXISTS default.orders_copy AS default.orders;
@@ -0,0 +1,11 @@
+#!/bin/sh
+# Create the queues and the topic used by the sqs and sns examples.
+awslocal sqs create-queue --queue-name chewdata-orders-dlq
+awslocal sqs create-queue --queue-name chewdata-orders \
+    --attributes '{"RedrivePolicy":"{\"deadLetterTargetArn\":\"arn:aws:sqs:us-east-1:000000000000:chewdata-orders-dlq\",\"maxReceiveCount\":\"3\"}"}'
+awslocal sns create-topic --name chewdata-orders
+awslocal sns subscribe \
+    --topic-arn arn:aws:sns:us-east-1:000000000000:chewdata-orders \
+    --protocol sqs \
+    --notification-endpoint arn:aws:sqs:us-east-1:000000000000:chewdata-orders \
+    --attributes RawMessageDelivery=true
@@ -0,0 +1,155 @@
+#[cfg(not(feature = "azure_blob"))]
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    return Err("the azure_blob feature is required for this example. Please enable it in your Cargo.toml file. cargo example EXAMPLE_NAME --features azure_blob".into());
+}
+
+use env_applier::EnvApply;
+use json_value_merge::Merge;
+use json_value_search::Search;
+use macro_rules_attribute::apply;
+use smol_macros::main;
+use std::io;
+
+#[cfg(feature = "azure_blob")]
+#[apply(main!)]
+async fn main() -> io::Result<()> {
+    use tracing_subscriber::prelude::__tracing_subscriber_SubscriberExt;
+    use tracing_subscriber::util::SubscriberInitExt;
+    use tracing_subscriber::EnvFilter;
+    use tracing_subscriber::{self, Layer};
+
+    let mut layers = Vec::new();
+    let (non_blocking, _guard) = tracing_appender::non_blocking(io::stdout());
+    let layer = tracing_subscriber::fmt::layer()
+        .pretty()
+        .with_line_number(true)
+        .with_writer(non_blocking)
+        .with_filter(EnvFilter::from_default_env())
+        .boxed();
+    layers.push(layer);
+
+    tracing_subscriber::registry().with(layers).init();
+
+    run().await
+}
+
+#[cfg(feature = "azure_blob")]
+async fn insert() -> io::Result<()> {
+    let config = r#"
+    [
+        {
+            "type": "e",
+            "connector":{
+                "type": "azure_blob",
+                "endpoint": "{{ AZURE_BLOB_ENDPOINT }}",
+                "access_key": "{{ AZURE_STORAGE_KEY }}",
+                "container": "my-container",
+                "path": "data/out/read_write_*.jsonl"
+            }
+        },{
+            "type": "r",
+            "connector":{
+                "type": "local",
+                "path": "./data/multi_lines.json"
+            }
+        },{
+            "type": "w",
+            "connector":{
+                "type": "azure_blob",
+                "endpoint": "{{ AZURE_BLOB_ENDPOINT }}",
+                "access_key": "{{ AZURE_STORAGE_KEY }}",
+                "container": "my-container",
+                "path": "data/out/read_write_{{ group }}.jsonl",
+                "tags": {
+                    "service:writer:owner": "my_team_name",
+                    "service:writer:env": "dev",
+                    "service:writer:context": "example"
+                }
+            },
+            "document": {
+                "type": "jsonl"
+            },
+            "concurrency_limit": 1
+        }
+    ]
+    "#;
+
+    chewdata::exec(
+        deser_hjson::from_str(config.apply().as_str())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?,
+        None,
+        None,
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[cfg(feature = "azure_blob")]
+async fn select() -> io::Result<()> {
+    let config = r#"
+    [
+        {
+            "type": "r",
+            "connector":{
+                "type": "azure_blob",
+                "endpoint": "{{ AZURE_BLOB_ENDPOINT }}",
+                "access_key": "{{ AZURE_STORAGE_KEY }}",
+                "container": "my-container",
+                "path": "data/out/read_write_*.jsonl"
+            },
+            "document": {
+                "type": "jsonl"
+            }
+        },{
+            "type": "w"
+        }
+    ]"#;
+
+    // Test example with asserts
+    let (sender_output, receiver_output) = async_channel::unbounded();
+    chewdata::exec(
+        deser_hjson::from_str(config.apply().as_str())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?,
+        None,
+        Some(sender_output),
+    )
+    .await?;
+
+    let mut result = serde_json::json!([]);
+    while let Ok(output) = receiver_output.recv().await {
+        result.merge(&output.input().to_value());
+    }
+
+    let mut numbers: Vec<u64> =
+        serde_json::from_value(result.clone().search("/*/number")?.unwrap_or_default())?;
+    numbers.sort();
+
+    assert_eq!(
+        vec![10, 20, 30],
+        numbers,
+        "The result not match the expected value"
+    );
+
+    Ok(())
+}
+
+#[cfg(feature = "azure_blob")]
+async fn run() -> io::Result<()> {
+    self::insert().await?;
+    self::select().await?;
+
+    Ok(())
+}
+
+#[cfg(feature = "azure_blob")]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use smol_macros::test;
+
+    #[apply(test!)]
+    async fn test_example() {
+        run().await.unwrap();
+    }
+}
@@ -0,0 +1,136 @@
+#[cfg(not(feature = "clickhouse"))]
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    return Err("the clickhouse feature is required for this example. Please enable it in your Cargo.toml file. cargo example EXAMPLE_NAME --features clickhouse".into());
+}
+
+use env_applier::EnvApply;
+use json_value_merge::Merge;
+use json_value_search::Search;
+use macro_rules_attribute::apply;
+use smol_macros::main;
+use std::io;
+
+#[cfg(feature = "clickhouse")]
+#[apply(main!)]
+async fn main() -> io::Result<()> {
+    use tracing_subscriber::prelude::__tracing_subscriber_SubscriberExt;
+    use tracing_subscriber::util::SubscriberInitExt;
+    use tracing_subscriber::EnvFilter;
+    use tracing_subscriber::{self, Layer};
+
+    let mut layers = Vec::new();
+    let (non_blocking, _guard) = tracing_appender::non_blocking(io::stdout());
+    let layer = tracing_subscriber::fmt::layer()
+        .pretty()
+        .with_line_number(true)
+        .with_writer(non_blocking)
+        .with_filter(EnvFilter::from_default_env())
+        .boxed();
+    layers.push(layer);
+
+    tracing_subscriber::registry().with(layers).init();
+
+    run().await
+}
+
+// Replace the rows of the table by the records of a local file, inserted in RowBinary.
+#[cfg(feature = "clickhouse")]
+async fn write() -> io::Result<()> {
+    let config = r#"
+    [
+        {
+            "type": "e",
+            "connector":{
+                "type": "clickhouse",
+                "endpoint": "{{ CLICKHOUSE_ENDPOINT }}",
+                "table": "orders"
+            }
+        },{
+            "type": "r",
+            "connector":{
+                "type": "local",
+                "path": "./data/multi_lines.json"
+            }
+        },{
+            "type": "w",
+            "connector":{
+                "type": "clickhouse",
+                "endpoint": "{{ CLICKHOUSE_ENDPOINT }}",
+                "table": "orders",
+                "format": "row_binary"
+            }
+        }
+    ]
+    "#;
+
+    chewdata::exec(
+        deser_hjson::from_str(config.apply().as_str())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?,
+        None,
+        None,
+    )
+    .await
+}
+
+// Stream the result of a query with a query parameter.
+#[cfg(feature = "clickhouse")]
+async fn read() -> io::Result<()> {
+    let config = r#"
+    [
+        {
+            "type": "r",
+            "connector":{
+                "type": "clickhouse",
+                "endpoint": "{{ CLICKHOUSE_ENDPOINT }}",
+                "query": "SELECT number, group FROM orders WHERE number > {number:UInt32} ORDER BY number",
+                "parameters": {
+                    "number": 10
+                }
+            }
+        }
+    ]
+    "#;
+
+    // Test example with asserts
+    let (sender_output, receiver_output) = async_channel::unbounded();
+    chewdata::exec(
+        deser_hjson::from_str(config.apply().as_str())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?,
+        None,
+        Some(sender_output),
+    )
+    .await?;
+
+    let mut result = serde_json::json!([]);
+    while let Ok(output) = receiver_output.recv().await {
+        result.merge(&output.input().to_value());
+    }
+
+    let numbers: Vec<u64> =
+        serde_json::from_value(result.search("/*/number")?.unwrap_or_default())?;
+    assert_eq!(
+        vec![20, 30],
+        numbers,
+        "The result not match the expected value"
+    );
+
+    Ok(())
+}
+
+#[cfg(feature = "clickhouse")]
+async fn run() -> io::Result<()> {
+    write().await?;
+    read().await
+}
+
+#[cfg(feature = "clickhouse")]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use smol_macros::test;
+
+    #[apply(test!)]
+    async fn test_example() {
+        run().await.unwrap();
+    }
+}
@@ -0,0 +1,157 @@
+#[cfg(not(all(feature = "smtp", feature = "imap")))]
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    return Err("the smtp and imap features are required for this example. Please enable them in your Cargo.toml file. cargo example EXAMPLE_NAME --features smtp,imap".into());
+}
+
+use env_applier::EnvApply;
+use json_value_merge::Merge;
+use json_value_search::Search;
+use macro_rules_attribute::apply;
+use smol_macros::main;
+use std::io;
+
+#[cfg(all(feature = "smtp", feature = "imap"))]
+#[apply(main!)]
+async fn main() -> io::Result<()> {
+    use tracing_subscriber::prelude::__tracing_subscriber_SubscriberExt;
+    use tracing_subscriber::util::SubscriberInitExt;
+    use tracing_subscriber::EnvFilter;
+    use tracing_subscriber::{self, Layer};
+
+    let mut layers = Vec::new();
+    let (non_blocking, _guard) = tracing_appender::non_blocking(io::stdout());
+    let layer = tracing_subscriber::fmt::layer()
+        .pretty()
+        .with_line_number(true)
+        .with_writer(non_blocking)
+        .with_filter(EnvFilter::from_default_env())
+        .boxed();
+    layers.push(layer);
+
+    tracing_subscriber::registry().with(layers).init();
+
+    run().await
+}
+
+// Clean the mailbox and send the records of a local file in attachment of an email.
+#[cfg(all(feature = "smtp", feature = "imap"))]
+async fn send() -> io::Result<()> {
+    let config = r#"
+    [
+        {
+            "type": "e",
+            "connector":{
+                "type": "imap",
+                "endpoint": "{{ IMAP_ENDPOINT }}",
+                "username": "data@example.com",
+                "password": "secret"
+            }
+        },{
+            "type": "r",
+            "connector":{
+                "type": "local",
+                "path": "./data/multi_lines.json"
+            }
+        },{
+            "type": "w",
+            "connector":{
+                "type": "smtp",
+                "endpoint": "{{ SMTP_ENDPOINT }}",
+                "from": "Reports <reports@example.com>",
+                "to": ["data@example.com"],
+                "subject": "Export of {{ dataset | length }} lines",
+                "body": "Find the export in attachment.",
+                "attachment": "export.jsonl"
+            },
+            "document": {
+                "type": "jsonl"
+            }
+        }
+    ]
+    "#;
+
+    chewdata::exec(
+        deser_hjson::from_str(config.apply().as_str())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?,
+        None,
+        None,
+    )
+    .await
+}
+
+// Read the records in attachment of the emails received.
+#[cfg(all(feature = "smtp", feature = "imap"))]
+async fn receive() -> io::Result<()> {
+    let config = r#"
+    [
+        {
+            "type": "r",
+            "connector":{
+                "type": "imap",
+                "endpoint": "{{ IMAP_ENDPOINT }}",
+                "username": "data@example.com",
+                "password": "secret",
+                "search": "FROM \"reports@example.com\"",
+                "attachment": "*.jsonl"
+            },
+            "document": {
+                "type": "jsonl"
+            }
+        }
+    ]
+    "#;
+
+    // Test example with asserts
+    let (sender_output, receiver_output) = async_channel::unbounded();
+    chewdata::exec(
+        deser_hjson::from_str(config.apply().as_str())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?,
+        None,
+        Some(sender_output),
+    )
+    .await?;
+
+    let mut result = serde_json::json!([]);
+    while let Ok(output) = receiver_output.recv().await {
+        result.merge(&output.input().to_value());
+    }
+
+    assert_eq!(
+        serde_json::json!([10, 20, 30]),
+        result.clone().search("/*/number")?.unwrap_or_default(),
+        "The result not match the expected value"
+    );
+    assert_eq!(
+        serde_json::json!([
+            "Export of 3 lines",
+            "Export of 3 lines",
+            "Export of 3 lines"
+        ]),
+        result.search("/*/_email/subject")?.unwrap_or_default(),
+        "The result not match the expected value"
+    );
+
+    Ok(())
+}
+
+#[cfg(all(feature = "smtp", feature = "imap"))]
+async fn run() -> io::Result<()> {
+    send().await?;
+
+    // Wait the delivery of the email.
+    smol::Timer::after(std::time::Duration::from_millis(500)).await;
+
+    receive().await
+}
+
+#[cfg(all(feature = "smtp", feature = "imap"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use smol_macros::test;
+
+    #[apply(test!)]
+    async fn test_example() {
+        run().await.unwrap();
+    }
+}
@@ -0,0 +1,103 @@
+#[cfg(not(feature = "exec"))]
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    return Err("the exec feature is required for this example. Please enable it in your Cargo.toml file. cargo example EXAMPLE_NAME --features exec".into());
+}
+
+use json_value_merge::Merge;
+use json_value_search::Search;
+use std::io;
+
+use macro_rules_attribute::apply;
+use smol_macros::main;
+
+#[cfg(feature = "exec")]
+#[apply(main!)]
+async fn main() -> io::Result<()> {
+    use tracing_subscriber::prelude::__tracing_subscriber_SubscriberExt;
+    use tracing_subscriber::util::SubscriberInitExt;
+    use tracing_subscriber::EnvFilter;
+    use tracing_subscriber::{self, Layer};
+
+    let mut layers = Vec::new();
+    let (non_blocking, _guard) = tracing_appender::non_blocking(io::stdout());
+    let layer = tracing_subscriber::fmt::layer()
+        .pretty()
+        .with_line_number(true)
+        .with_writer(non_blocking)
+        .with_filter(EnvFilter::from_default_env())
+        .boxed();
+    layers.push(layer);
+
+    tracing_subscriber::registry().with(layers).init();
+
+    run().await
+}
+
+// Read the records printed by a command and transform them with another command.
+#[cfg(feature = "exec")]
+async fn run() -> io::Result<()> {
+    let config = r#"
+    [
+        {
+            "type": "r",
+            "connector":{
+                "type": "exec",
+                "command": "cat",
+                "args": ["./data/multi_lines.json"]
+            }
+        },{
+            "type": "w",
+            "connector":{
+                "type": "exec",
+                "command": "sed",
+                "args": ["s/value to/transformed by a command to/"],
+                "timeout": 10
+            },
+            "document": {
+                "type": "jsonl"
+            }
+        }
+    ]
+    "#;
+
+    // Test example with asserts
+    let (sender_output, receiver_output) = async_channel::unbounded();
+    chewdata::exec(
+        deser_hjson::from_str(config)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?,
+        None,
+        Some(sender_output),
+    )
+    .await?;
+
+    let mut result = serde_json::json!([]);
+    while let Ok(output) = receiver_output.recv().await {
+        result.merge(&output.input().to_value());
+    }
+
+    let expected = serde_json::json!([
+        "transformed by a command to test",
+        "transformed by a command to test 2",
+        "transformed by a command to test 3"
+    ]);
+
+    assert_eq!(
+        expected,
+        result.clone().search("/*/string")?.unwrap_or_default(),
+        "The result not match the expected value"
+    );
+
+    Ok(())
+}
+
+#[cfg(feature = "exec")]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use smol_macros::test;
+
+    #[apply(test!)]
+    async fn test_example() {
+        run().await.unwrap();
+    }
+}
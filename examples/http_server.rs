@@ -0,0 +1,158 @@
+#[cfg(not(feature = "http_server"))]
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    return Err("the http_server feature is required for this example. Please enable it in your Cargo.toml file. cargo example EXAMPLE_NAME --features http_server".into());
+}
+
+use json_value_merge::Merge;
+use json_value_search::Search;
+use macro_rules_attribute::apply;
+use smol_macros::main;
+use std::io;
+
+#[cfg(feature = "http_server")]
+#[apply(main!)]
+async fn main() -> io::Result<()> {
+    use tracing_subscriber::prelude::__tracing_subscriber_SubscriberExt;
+    use tracing_subscriber::util::SubscriberInitExt;
+    use tracing_subscriber::EnvFilter;
+    use tracing_subscriber::{self, Layer};
+
+    let mut layers = Vec::new();
+    let (non_blocking, _guard) = tracing_appender::non_blocking(io::stdout());
+    let layer = tracing_subscriber::fmt::layer()
+        .pretty()
+        .with_line_number(true)
+        .with_writer(non_blocking)
+        .with_filter(EnvFilter::from_default_env())
+        .boxed();
+    layers.push(layer);
+
+    tracing_subscriber::registry().with(layers).init();
+
+    run().await
+}
+
+// Listen one webhook and write its records in a local file. Respond to the webhook with the result of the writer.
+#[cfg(feature = "http_server")]
+async fn listen() -> io::Result<()> {
+    let config = r#"
+    [
+        {
+            "type": "e",
+            "connector":{
+                "type": "local",
+                "path": "./data/out/http_server.jsonl"
+            }
+        },{
+            "type": "r",
+            "connector":{
+                "type": "http_server",
+                "endpoint": "127.0.0.1:8686",
+                "path": "/webhook",
+                "mode": "sync",
+                "request_limit": 1
+            },
+            "document": {
+                "type": "json"
+            }
+        },{
+            "type": "w",
+            "connector":{
+                "type": "local",
+                "path": "./data/out/http_server.jsonl"
+            },
+            "document": {
+                "type": "jsonl"
+            },
+            "record_limit": 1
+        }
+    ]
+    "#;
+
+    // Test example with asserts
+    let (sender_output, receiver_output) = async_channel::unbounded();
+    chewdata::exec(
+        deser_hjson::from_str(config)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?,
+        None,
+        Some(sender_output),
+    )
+    .await?;
+
+    let mut result = serde_json::json!([]);
+    while let Ok(output) = receiver_output.recv().await {
+        result.merge(&output.input().to_value());
+    }
+
+    let mut numbers: Vec<u64> =
+        serde_json::from_value(result.clone().search("/*/number")?.unwrap_or_default())?;
+    numbers.sort();
+
+    assert_eq!(
+        vec![10, 20, 30],
+        numbers,
+        "The result not match the expected value"
+    );
+
+    Ok(())
+}
+
+// Push the records of a local file to the webhook.
+#[cfg(feature = "http_server")]
+async fn push() -> io::Result<()> {
+    let config = r#"
+    [
+        {
+            "type": "r",
+            "connector":{
+                "type": "local",
+                "path": "./data/multi_lines.json"
+            }
+        },{
+            "type": "w",
+            "connector":{
+                "type": "curl",
+                "endpoint": "http://127.0.0.1:8686",
+                "path": "/webhook",
+                "method": "post"
+            },
+            "document": {
+                "type": "json"
+            },
+            "record_limit": 3
+        }
+    ]
+    "#;
+
+    // Wait the server to listen.
+    smol::Timer::after(std::time::Duration::from_millis(500)).await;
+
+    chewdata::exec(
+        deser_hjson::from_str(config)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?,
+        None,
+        None,
+    )
+    .await
+}
+
+#[cfg(feature = "http_server")]
+async fn run() -> io::Result<()> {
+    let (listened, pushed) = futures::future::join(self::listen(), self::push()).await;
+    listened?;
+    pushed?;
+
+    Ok(())
+}
+
+#[cfg(feature = "http_server")]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use smol_macros::test;
+
+    #[apply(test!)]
+    async fn test_example() {
+        run().await.unwrap();
+    }
+}
@@ -0,0 +1,132 @@
+#[cfg(not(feature = "bson"))]
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    return Err("the bson feature is required for this example. Please enable it in your Cargo.toml file. cargo example EXAMPLE_NAME --features bson".into());
+}
+
+use json_value_merge::Merge;
+use json_value_search::Search;
+use macro_rules_attribute::apply;
+use smol_macros::main;
+use std::io;
+
+#[cfg(feature = "bson")]
+#[apply(main!)]
+async fn main() -> io::Result<()> {
+    use tracing_subscriber::prelude::__tracing_subscriber_SubscriberExt;
+    use tracing_subscriber::util::SubscriberInitExt;
+    use tracing_subscriber::EnvFilter;
+    use tracing_subscriber::{self, Layer};
+
+    let mut layers = Vec::new();
+    let (non_blocking, _guard) = tracing_appender::non_blocking(io::stdout());
+    let layer = tracing_subscriber::fmt::layer()
+        .pretty()
+        .with_line_number(true)
+        .with_writer(non_blocking)
+        .with_filter(EnvFilter::from_default_env())
+        .boxed();
+    layers.push(layer);
+
+    tracing_subscriber::registry().with(layers).init();
+
+    run().await
+}
+
+// Write the records of a json file as concatenated BSON documents, like a mongodump file.
+#[cfg(feature = "bson")]
+async fn write() -> io::Result<()> {
+    let config = r#"
+    [
+        {
+            "type": "reader",
+            "connector":{
+                "type": "local",
+                "path": "./data/multi_lines.json"
+            }
+        },
+        {
+            "type": "writer",
+            "document": {
+                "type": "bson"
+            },
+            "connector": {
+                "type":"local",
+                "path": "./data/out/bson_test_local.bson"
+            }
+        }
+    ]
+    "#;
+
+    chewdata::exec(
+        deser_hjson::from_str(config)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?,
+        None,
+        None,
+    )
+    .await
+}
+
+// Read the BSON documents of the file.
+#[cfg(feature = "bson")]
+async fn read() -> io::Result<()> {
+    let config = r#"
+    [
+        {
+            "type": "reader",
+            "document": {
+                "type": "bson"
+            },
+            "connector":{
+                "type": "local",
+                "path": "./data/out/bson_test_local.bson"
+            }
+        }
+    ]
+    "#;
+
+    // Test example with asserts
+    let (sender_output, receiver_output) = async_channel::unbounded();
+    chewdata::exec(
+        deser_hjson::from_str(config)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?,
+        None,
+        Some(sender_output),
+    )
+    .await?;
+
+    let mut result = serde_json::json!([]);
+    while let Ok(output) = receiver_output.recv().await {
+        result.merge(&output.input().to_value());
+    }
+
+    assert_eq!(
+        serde_json::json!([10, 20, 30]),
+        result.clone().search("/*/number")?.unwrap_or_default(),
+        "The result not match the expected value"
+    );
+    assert_eq!(
+        serde_json::json!(["é", "à", "€"]),
+        result.search("/*/special_char")?.unwrap_or_default(),
+        "The result not match the expected value"
+    );
+
+    Ok(())
+}
+
+#[cfg(feature = "bson")]
+async fn run() -> io::Result<()> {
+    write().await?;
+    read().await
+}
+
+#[cfg(feature = "bson")]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use smol_macros::test;
+
+    #[apply(test!)]
+    async fn test_example() {
+        run().await.unwrap();
+    }
+}
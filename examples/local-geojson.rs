@@ -0,0 +1,161 @@
+#[cfg(not(feature = "geojson"))]
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    return Err("the geojson feature is required for this example. Please enable it in your Cargo.toml file. cargo example EXAMPLE_NAME --features geojson".into());
+}
+
+use json_value_merge::Merge;
+use json_value_search::Search;
+use macro_rules_attribute::apply;
+use smol_macros::main;
+use std::io;
+
+#[cfg(feature = "geojson")]
+#[apply(main!)]
+async fn main() -> io::Result<()> {
+    use tracing_subscriber::prelude::__tracing_subscriber_SubscriberExt;
+    use tracing_subscriber::util::SubscriberInitExt;
+    use tracing_subscriber::EnvFilter;
+    use tracing_subscriber::{self, Layer};
+
+    let mut layers = Vec::new();
+    let (non_blocking, _guard) = tracing_appender::non_blocking(io::stdout());
+    let layer = tracing_subscriber::fmt::layer()
+        .pretty()
+        .with_line_number(true)
+        .with_writer(non_blocking)
+        .with_filter(EnvFilter::from_default_env())
+        .boxed();
+    layers.push(layer);
+
+    tracing_subscriber::registry().with(layers).init();
+
+    run().await
+}
+
+// Read the cities in GeoJSON, locate them with the geometry filters and write them in GeoJSON.
+#[cfg(feature = "geojson")]
+async fn write() -> io::Result<()> {
+    let config = r#"
+    [
+        {
+            "type": "reader",
+            "document": {
+                "type": "geojson",
+                "geometry_format": "wkt"
+            },
+            "connector":{
+                "type": "local",
+                "path": "./data/multi_lines.geojson"
+            }
+        },
+        {
+            "type": "transformer",
+            "actions": [
+                {
+                    "field": "/",
+                    "pattern": "{{ input | json_encode() }}"
+                },
+                {
+                    "field": "in_france",
+                    "pattern": "{{ input.geometry | within(polygon='POLYGON ((-4.8 42.3, 8.2 42.3, 8.2 51.1, -4.8 51.1, -4.8 42.3))') }}"
+                },
+                {
+                    "field": "distance_to_paris",
+                    "pattern": "{{ input.geometry | distance(to='POINT (2.3522 48.8566)', unit='km') | round }}"
+                }
+            ]
+        },
+        {
+            "type": "writer",
+            "document": {
+                "type": "geojson"
+            },
+            "connector": {
+                "type":"local",
+                "path": "./data/out/geojson_test_local.geojson"
+            }
+        }
+    ]
+    "#;
+
+    chewdata::exec(
+        deser_hjson::from_str(config)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?,
+        None,
+        None,
+    )
+    .await
+}
+
+// Read the features of the file.
+#[cfg(feature = "geojson")]
+async fn read() -> io::Result<()> {
+    let config = r#"
+    [
+        {
+            "type": "reader",
+            "document": {
+                "type": "geojson"
+            },
+            "connector":{
+                "type": "local",
+                "path": "./data/out/geojson_test_local.geojson"
+            }
+        }
+    ]
+    "#;
+
+    // Test example with asserts
+    let (sender_output, receiver_output) = async_channel::unbounded();
+    chewdata::exec(
+        deser_hjson::from_str(config)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?,
+        None,
+        Some(sender_output),
+    )
+    .await?;
+
+    let mut result = serde_json::json!([]);
+    while let Ok(output) = receiver_output.recv().await {
+        result.merge(&output.input().to_value());
+    }
+
+    assert_eq!(
+        serde_json::json!([true, true, false]),
+        result.clone().search("/*/in_france")?.unwrap_or_default(),
+        "The result not match the expected value"
+    );
+    assert_eq!(
+        serde_json::json!([0, 391, 344]),
+        result
+            .clone()
+            .search("/*/distance_to_paris")?
+            .unwrap_or_default(),
+        "The result not match the expected value"
+    );
+    assert_eq!(
+        serde_json::json!({"type": "Point", "coordinates": [4.8357, 45.764]}),
+        result.search("/1/geometry")?.unwrap_or_default(),
+        "The result not match the expected value"
+    );
+
+    Ok(())
+}
+
+#[cfg(feature = "geojson")]
+async fn run() -> io::Result<()> {
+    write().await?;
+    read().await
+}
+
+#[cfg(feature = "geojson")]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use smol_macros::test;
+
+    #[apply(test!)]
+    async fn test_example() {
+        run().await.unwrap();
+    }
+}
@@ -0,0 +1,149 @@
+#[cfg(not(feature = "socket"))]
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    return Err("the socket feature is required for this example. Please enable it in your Cargo.toml file. cargo example EXAMPLE_NAME --features socket".into());
+}
+
+use json_value_merge::Merge;
+use json_value_search::Search;
+use std::io;
+
+use macro_rules_attribute::apply;
+use smol_macros::main;
+
+#[cfg(feature = "socket")]
+#[apply(main!)]
+async fn main() -> io::Result<()> {
+    use tracing_subscriber::prelude::__tracing_subscriber_SubscriberExt;
+    use tracing_subscriber::util::SubscriberInitExt;
+    use tracing_subscriber::EnvFilter;
+    use tracing_subscriber::{self, Layer};
+
+    let mut layers = Vec::new();
+    let (non_blocking, _guard) = tracing_appender::non_blocking(io::stdout());
+    let layer = tracing_subscriber::fmt::layer()
+        .pretty()
+        .with_line_number(true)
+        .with_writer(non_blocking)
+        .with_filter(EnvFilter::from_default_env())
+        .boxed();
+    layers.push(layer);
+
+    tracing_subscriber::registry().with(layers).init();
+
+    run().await
+}
+
+// Listen a TCP socket and write the first three lines received in a local file.
+#[cfg(feature = "socket")]
+async fn listen() -> io::Result<()> {
+    let config = r#"
+    [
+        {
+            "type": "r",
+            "connector":{
+                "type": "socket",
+                "endpoint": "tcp://127.0.0.1:5146",
+                "mode": "listen",
+                "message_limit": 3,
+                "timeout": 10
+            },
+            "document": {
+                "type": "jsonl"
+            }
+        },{
+            "type": "w",
+            "connector":{
+                "type": "local",
+                "path": "./data/out/socket.jsonl"
+            },
+            "document": {
+                "type": "jsonl"
+            }
+        }
+    ]
+    "#;
+
+    // Test example with asserts
+    let (sender_output, receiver_output) = async_channel::unbounded();
+    chewdata::exec(
+        deser_hjson::from_str(config)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?,
+        None,
+        Some(sender_output),
+    )
+    .await?;
+
+    let mut result = serde_json::json!([]);
+    while let Ok(output) = receiver_output.recv().await {
+        result.merge(&output.input().to_value());
+    }
+
+    let mut numbers: Vec<u64> =
+        serde_json::from_value(result.clone().search("/*/number")?.unwrap_or_default())?;
+    numbers.sort();
+
+    assert_eq!(
+        vec![10, 20, 30],
+        numbers,
+        "The result not match the expected value"
+    );
+
+    Ok(())
+}
+
+// Send the records of a local file in the TCP socket, one line per record.
+#[cfg(feature = "socket")]
+async fn push() -> io::Result<()> {
+    let config = r#"
+    [
+        {
+            "type": "r",
+            "connector":{
+                "type": "local",
+                "path": "./data/multi_lines.json"
+            }
+        },{
+            "type": "w",
+            "connector":{
+                "type": "socket",
+                "endpoint": "tcp://127.0.0.1:5146"
+            },
+            "document": {
+                "type": "jsonl"
+            }
+        }
+    ]
+    "#;
+
+    // Wait the server to listen.
+    smol::Timer::after(std::time::Duration::from_millis(500)).await;
+
+    chewdata::exec(
+        deser_hjson::from_str(config)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?,
+        None,
+        None,
+    )
+    .await
+}
+
+#[cfg(feature = "socket")]
+async fn run() -> io::Result<()> {
+    let (listened, pushed) = futures::future::join(self::listen(), self::push()).await;
+    listened?;
+    pushed?;
+
+    Ok(())
+}
+
+#[cfg(feature = "socket")]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use smol_macros::test;
+
+    #[apply(test!)]
+    async fn test_example() {
+        run().await.unwrap();
+    }
+}
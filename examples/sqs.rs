@@ -0,0 +1,152 @@
+#[cfg(not(all(feature = "sqs", feature = "sns")))]
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    return Err("the sqs and sns features are required for this example. Please enable them in your Cargo.toml file. cargo example EXAMPLE_NAME --features sqs,sns".into());
+}
+
+use env_applier::EnvApply;
+use json_value_merge::Merge;
+use json_value_search::Search;
+use macro_rules_attribute::apply;
+use smol_macros::main;
+use std::io;
+
+#[cfg(all(feature = "sqs", feature = "sns"))]
+#[apply(main!)]
+async fn main() -> io::Result<()> {
+    use tracing_subscriber::prelude::__tracing_subscriber_SubscriberExt;
+    use tracing_subscriber::util::SubscriberInitExt;
+    use tracing_subscriber::EnvFilter;
+    use tracing_subscriber::{self, Layer};
+
+    let mut layers = Vec::new();
+    let (non_blocking, _guard) = tracing_appender::non_blocking(io::stdout());
+    let layer = tracing_subscriber::fmt::layer()
+        .pretty()
+        .with_line_number(true)
+        .with_writer(non_blocking)
+        .with_filter(EnvFilter::from_default_env())
+        .boxed();
+    layers.push(layer);
+
+    tracing_subscriber::registry().with(layers).init();
+
+    run().await
+}
+
+// Publish the records of a local file in the topic. The topic forwards the messages to the queue.
+#[cfg(all(feature = "sqs", feature = "sns"))]
+async fn publish() -> io::Result<()> {
+    let config = r#"
+    [
+        {
+            "type": "r",
+            "connector":{
+                "type": "local",
+                "path": "./data/multi_lines.json"
+            }
+        },{
+            "type": "w",
+            "connector":{
+                "type": "sns",
+                "endpoint": "{{ SNS_ENDPOINT }}",
+                "topic": "arn:aws:sns:us-east-1:000000000000:chewdata-orders",
+                "attributes": {
+                    "number": "{{ input.number }}"
+                }
+            }
+        }
+    ]
+    "#;
+
+    chewdata::exec(
+        deser_hjson::from_str(config.apply().as_str())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?,
+        None,
+        None,
+    )
+    .await
+}
+
+// Receive the messages of the queue. Each message is deleted once written.
+#[cfg(all(feature = "sqs", feature = "sns"))]
+async fn receive() -> io::Result<()> {
+    let config = r#"
+    [
+        {
+            "type": "r",
+            "connector":{
+                "type": "sqs",
+                "endpoint": "{{ SQS_ENDPOINT }}",
+                "queue": "chewdata-orders",
+                "wait_time": 1,
+                "message_limit": 3,
+                "timeout": 10
+            }
+        },{
+            "type": "w",
+            "connector":{
+                "type": "local",
+                "path": "./data/out/sqs.jsonl"
+            },
+            "document": {
+                "type": "jsonl"
+            }
+        }
+    ]
+    "#;
+
+    // Test example with asserts
+    let (sender_output, receiver_output) = async_channel::unbounded();
+    chewdata::exec(
+        deser_hjson::from_str(config.apply().as_str())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?,
+        None,
+        Some(sender_output),
+    )
+    .await?;
+
+    let mut result = serde_json::json!([]);
+    while let Ok(output) = receiver_output.recv().await {
+        result.merge(&output.input().to_value());
+    }
+
+    let mut numbers: Vec<u64> =
+        serde_json::from_value(result.clone().search("/*/number")?.unwrap_or_default())?;
+    numbers.sort();
+    assert_eq!(
+        vec![10, 20, 30],
+        numbers,
+        "The result not match the expected value"
+    );
+    let mut attributes: Vec<String> = serde_json::from_value(
+        result
+            .search("/*/_sqs/attributes/number")?
+            .unwrap_or_default(),
+    )?;
+    attributes.sort();
+    assert_eq!(
+        vec!["10", "20", "30"],
+        attributes,
+        "The result not match the expected value"
+    );
+
+    Ok(())
+}
+
+#[cfg(all(feature = "sqs", feature = "sns"))]
+async fn run() -> io::Result<()> {
+    publish().await?;
+    receive().await
+}
+
+#[cfg(all(feature = "sqs", feature = "sns"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use smol_macros::test;
+
+    #[apply(test!)]
+    async fn test_example() {
+        run().await.unwrap();
+    }
+}
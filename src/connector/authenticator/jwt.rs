@@ -58,7 +58,7 @@ use crate::{connector::ConnectorType, document::jsonl::Jsonl};
 use async_lock::Mutex;
 use async_trait::async_trait;
 use http::header;
-use jsonwebtoken::{decode, Algorithm, DecodingKey, Validation};
+use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use smol::stream::StreamExt;
@@ -233,6 +233,35 @@ impl Jwt {
 
         Ok(())
     }
+    /// Sign the claims with the key and return the JWT.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::authenticator::jwt::{Jwt, SigningType};
+    ///
+    /// let mut auth = Jwt::default();
+    /// auth.key = "my_key".to_string();
+    /// auth.signing_type = Some(SigningType::Secret);
+    /// let token = auth.encode(&serde_json::json!({"sub": "chewdata", "exp": 33156416077_u64})).unwrap();
+    /// assert!(auth.decode(&token).is_ok());
+    /// ```
+    pub fn encode(&self, claims: &Value) -> jsonwebtoken::errors::Result<String> {
+        let encoding_key = match &self.signing_type {
+            None | Some(SigningType::Secret) => EncodingKey::from_secret(self.key.as_ref()),
+            Some(SigningType::Base64Secret) => EncodingKey::from_base64_secret(self.key.as_ref())?,
+            Some(SigningType::RsaPem) => EncodingKey::from_rsa_pem(self.key.as_ref())?,
+            Some(SigningType::RsaDer) => EncodingKey::from_rsa_der(self.key.as_ref()),
+            Some(SigningType::EcPem) => EncodingKey::from_ec_pem(self.key.as_ref())?,
+            Some(SigningType::EcDer) => EncodingKey::from_ec_der(self.key.as_ref()),
+            // The public components of a JWK can't sign a token.
+            Some(SigningType::RsaComponents) => {
+                return Err(jsonwebtoken::errors::ErrorKind::InvalidKeyFormat.into())
+            }
+        };
+
+        encode(&Header::new(self.algorithm), claims, &encoding_key)
+    }
     // Used to verify the signature from the JWT.
     pub fn decode(&self, token_value: &str) -> jsonwebtoken::errors::Result<()> {
         if let Some(signing_type) = &self.signing_type {
@@ -448,6 +477,19 @@ mod tests {
             Err(e) => assert!(false, "The token can't be refreshed: '{}'", e),
         };
     }
+    #[test]
+    fn encode() {
+        let mut auth = Jwt::default();
+        auth.key = "my_key".to_string();
+        auth.signing_type = Some(SigningType::Secret);
+        let token = auth
+            .encode(&serde_json::json!({"sub": "chewdata", "exp": 33156416077_u64}))
+            .unwrap();
+        assert!(auth.decode(&token).is_ok());
+
+        auth.signing_type = Some(SigningType::RsaComponents);
+        assert!(auth.encode(&serde_json::json!({})).is_err());
+    }
     #[apply(test!)]
     async fn refresh_with_keycloak() {
         let mut connector = Curl::default();
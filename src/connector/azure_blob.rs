@@ -0,0 +1,1302 @@
+//! Read and write data into Azure Blob Storage containers.
+//!
+//! The connector authenticates with a shared key or with a SAS token. If the document can be appended,
+//! the data are appended at the end of an append blob, otherwise a block blob is written.
+//!
+//! ### Configuration
+//!
+//! | key           | alias        | Description                                                            | Default Value                                  | Possible Values            |
+//! | ------------- | ------------ | ---------------------------------------------------------------------- | ---------------------------------------------- | -------------------------- |
+//! | type          | -            | Required in order to use this connector                                | `azure_blob`                                   | `azure_blob` / `azure`     |
+//! | metadata      | meta         | Override metadata information                                          | `null`                                         | [`crate::Metadata`]        |
+//! | endpoint      | -            | Endpoint of the storage account                                        | `http://127.0.0.1:10000/devstoreaccount1`      | String                     |
+//! | account       | account_name | The storage account name. Deduced from the endpoint if not set         | `null`                                         | String                     |
+//! | access_key    | account_key  | The shared key used for the authentification                           | `null`                                         | String                     |
+//! | sas_token     | sas          | The SAS token used for the authentification instead of the shared key | `null`                                         | String                     |
+//! | container     | -            | The container name                                                     | `null`                                         | String                     |
+//! | path          | blob         | The path of the blob. Can use `*` in order to read multiple blobs      | `null`                                         | String                     |
+//! | parameters    | params       | The parameters used to remplace variables in the path                  | `null`                                         | Object or Array of objects |
+//! | limit         | -            | Limit the number of blobs to read                                      | `null`                                         | Unsigned number            |
+//! | skip          | -            | Skip N blobs before to start to read the next blobs                    | `0`                                            | Unsigned number            |
+//! | version       | version_id   | Read a specific version of a blob                                      | `null`                                         | String                     |
+//! | tags          | -            | List of tags to apply on the blob. Used to give more context to a blob | `(service:writer:name,chewdata)`               | List of Key/Value          |
+//! | cache_control | -            | Override the blob cache control                                        | `null`                                         | String                     |
+//! | timeout       | -            | Time in secound before to abort the call                               | `5`                                            | Unsigned number            |
+//!
+//! If not set, the `endpoint`, `account`, `access_key` and `sas_token` are read from the environment variables
+//! `AZURE_BLOB_ENDPOINT`, `AZURE_STORAGE_ACCOUNT`, `AZURE_STORAGE_KEY` and `AZURE_STORAGE_SAS_TOKEN`.
+//!
+//! ### Examples
+//!
+//! ```json
+//! [
+//!     {
+//!         "type": "r",
+//!         "connector": {
+//!             "type": "azure_blob",
+//!             "endpoint": "{{ AZURE_BLOB_ENDPOINT }}",
+//!             "account": "{{ AZURE_STORAGE_ACCOUNT }}",
+//!             "access_key": "{{ AZURE_STORAGE_KEY }}",
+//!             "container": "my-container",
+//!             "path": "data/*.json*",
+//!             "limit": 10,
+//!             "skip": 0,
+//!             "tags": {
+//!                 "service:writer": "my_service",
+//!                 "service:writer:owner": "my_team_name"
+//!             }
+//!         }
+//!     }
+//! ]
+//! ```
+use super::curl::Curl;
+use super::Connector;
+use crate::document::Document;
+use crate::helper::mustache::Mustache;
+use crate::helper::string::{percent_encode, DisplayOnlyForDebugging};
+use crate::{ConnectorStream, DataSet, DataStream, Metadata};
+use async_stream::stream;
+use async_trait::async_trait;
+use base64::Engine;
+use bytes::Bytes;
+use futures::Stream;
+use hmac::{Hmac, Mac};
+use http::{HeaderMap, HeaderName, HeaderValue, Method};
+use json_value_merge::Merge;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sha2::Sha256;
+use std::collections::{BTreeMap, HashMap};
+use std::env;
+use std::pin::Pin;
+use std::vec::IntoIter;
+use std::{
+    fmt,
+    io::{Cursor, Error, ErrorKind, Result, Seek, SeekFrom, Write},
+};
+
+const DEFAULT_TAG_SERVICE_WRITER_NAME: (&str, &str) = ("service:writer:name", "chewdata");
+const DEFAULT_ENDPOINT: &str = "http://127.0.0.1:10000/devstoreaccount1";
+const API_VERSION: &str = "2021-12-02";
+const APPEND_BLOB: &str = "AppendBlob";
+// Maximum size of a block appended in one call.
+const APPEND_BLOCK_MAX_SIZE: usize = 4 * 1024 * 1024;
+
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct AzureBlob {
+    #[serde(skip)]
+    document: Option<Box<dyn Document>>,
+    #[serde(rename = "metadata")]
+    #[serde(alias = "meta")]
+    pub metadata: Metadata,
+    pub endpoint: Option<String>,
+    #[serde(alias = "account_name")]
+    pub account: Option<String>,
+    #[serde(alias = "account_key")]
+    pub access_key: Option<String>,
+    #[serde(alias = "sas")]
+    pub sas_token: Option<String>,
+    pub container: String,
+    #[serde(alias = "blob")]
+    pub path: String,
+    #[serde(alias = "params")]
+    pub parameters: Box<Value>,
+    pub limit: Option<usize>,
+    pub skip: usize,
+    #[serde(alias = "version_id")]
+    pub version: Option<String>,
+    pub tags: HashMap<String, String>,
+    pub cache_control: Option<String>,
+    pub timeout: Option<u64>,
+}
+
+impl fmt::Debug for AzureBlob {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AzureBlob")
+            .field("document", &self.document.display_only_for_debugging())
+            .field("metadata", &self.metadata.display_only_for_debugging())
+            .field("endpoint", &self.endpoint)
+            .field("account", &self.account)
+            // Can contain sensitive data
+            .field("access_key", &self.access_key.display_only_for_debugging())
+            .field("sas_token", &self.sas_token.display_only_for_debugging())
+            .field("container", &self.container)
+            .field("path", &self.path)
+            .field("parameters", &self.parameters.display_only_for_debugging())
+            .field("limit", &self.limit)
+            .field("skip", &self.skip)
+            .field("version", &self.version)
+            .field("tags", &self.tags)
+            .field("cache_control", &self.cache_control)
+            .field("timeout", &self.timeout)
+            .finish()
+    }
+}
+
+impl Default for AzureBlob {
+    fn default() -> Self {
+        let mut tags = HashMap::default();
+        tags.insert(
+            DEFAULT_TAG_SERVICE_WRITER_NAME.0.to_string(),
+            DEFAULT_TAG_SERVICE_WRITER_NAME.1.to_string(),
+        );
+
+        AzureBlob {
+            document: None,
+            metadata: Metadata::default(),
+            endpoint: None,
+            account: None,
+            access_key: None,
+            sas_token: None,
+            container: String::default(),
+            path: String::default(),
+            parameters: Box::<Value>::default(),
+            limit: None,
+            skip: 0,
+            version: None,
+            tags,
+            cache_control: None,
+            timeout: None,
+        }
+    }
+}
+
+/// Properties of an existing blob.
+#[derive(Debug, Default, Clone)]
+pub struct BlobProperties {
+    pub len: usize,
+    pub blob_type: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct EnumerationResults {
+    #[serde(default)]
+    blobs: Blobs,
+    next_marker: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Blobs {
+    #[serde(rename = "Blob", default)]
+    blobs: Vec<BlobItem>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct BlobItem {
+    name: String,
+}
+
+impl AzureBlob {
+    /// Get the endpoint of the storage account.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::azure_blob::AzureBlob;
+    ///
+    /// let mut connector = AzureBlob::default();
+    /// connector.endpoint = Some("https://myaccount.blob.core.windows.net/".to_string());
+    /// assert_eq!("https://myaccount.blob.core.windows.net", connector.endpoint());
+    /// ```
+    pub fn endpoint(&self) -> String {
+        match (self.endpoint.clone(), env::var("AZURE_BLOB_ENDPOINT")) {
+            (Some(endpoint), _) => endpoint,
+            (None, Ok(endpoint)) => endpoint,
+            (None, Err(_)) => DEFAULT_ENDPOINT.to_string(),
+        }
+        .trim_end_matches('/')
+        .to_string()
+    }
+    /// Get the storage account name. Deduced from the endpoint if not set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::azure_blob::AzureBlob;
+    ///
+    /// let mut connector = AzureBlob::default();
+    /// connector.endpoint = Some("https://myaccount.blob.core.windows.net".to_string());
+    /// assert_eq!("myaccount", connector.account());
+    /// connector.endpoint = Some("http://127.0.0.1:10000/devstoreaccount1".to_string());
+    /// assert_eq!("devstoreaccount1", connector.account());
+    /// ```
+    pub fn account(&self) -> String {
+        if let Some(account) = &self.account {
+            return account.clone();
+        }
+        if let Ok(account) = env::var("AZURE_STORAGE_ACCOUNT") {
+            return account;
+        }
+
+        let endpoint = self.endpoint();
+        let host_and_path = endpoint
+            .split_once("://")
+            .map(|(_, rest)| rest)
+            .unwrap_or(endpoint.as_str());
+
+        match host_and_path.split_once('/') {
+            Some((_, path)) if !path.is_empty() => path.split('/').next().unwrap_or_default(),
+            _ => host_and_path.split('.').next().unwrap_or_default(),
+        }
+        .to_string()
+    }
+    fn access_key(&self) -> Option<String> {
+        self.access_key
+            .clone()
+            .or_else(|| env::var("AZURE_STORAGE_KEY").ok())
+    }
+    fn sas_token(&self) -> Option<String> {
+        self.sas_token
+            .clone()
+            .or_else(|| env::var("AZURE_STORAGE_SAS_TOKEN").ok())
+            .map(|sas_token| sas_token.trim_start_matches('?').to_string())
+    }
+    fn tagging(&self) -> String {
+        let mut tags = AzureBlob::default().tags;
+        tags.extend(self.tags.clone());
+
+        let mut tags: Vec<(String, String)> = tags.into_iter().collect();
+        tags.sort();
+
+        tags.iter()
+            .map(|(k, v)| format!("{}={}", percent_encode(k, false), percent_encode(v, false)))
+            .collect::<Vec<String>>()
+            .join("&")
+    }
+    /// Path of the uri targeting the container or a blob in the container.
+    fn resource_path(&self, blob: Option<&str>) -> String {
+        let endpoint = self.endpoint();
+        let endpoint_path = endpoint
+            .split_once("://")
+            .map(|(_, rest)| rest)
+            .unwrap_or(endpoint.as_str())
+            .split_once('/')
+            .map(|(_, path)| format!("/{}", path))
+            .unwrap_or_default();
+
+        match blob {
+            Some(blob) => format!(
+                "{}/{}/{}",
+                endpoint_path,
+                self.container,
+                percent_encode(blob.trim_start_matches('/'), true)
+            ),
+            None => format!("{}/{}", endpoint_path, self.container),
+        }
+    }
+    /// Sign the request with the shared key.
+    ///
+    /// See <https://learn.microsoft.com/en-us/rest/api/storageservices/authorize-with-shared-key>.
+    fn signature(
+        &self,
+        access_key: &str,
+        method: &Method,
+        headers: &HeaderMap,
+        resource_path: &str,
+        query: &[(String, String)],
+    ) -> Result<String> {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .unwrap_or_default()
+                .to_string()
+        };
+
+        let content_length = match header("content-length").as_str() {
+            "0" => String::default(),
+            content_length => content_length.to_string(),
+        };
+
+        let mut canonicalized_headers: Vec<(String, String)> = headers
+            .iter()
+            .filter(|(name, _)| name.as_str().starts_with("x-ms-"))
+            .map(|(name, value)| {
+                (
+                    name.as_str().to_string(),
+                    value.to_str().unwrap_or_default().trim().to_string(),
+                )
+            })
+            .collect();
+        canonicalized_headers.sort();
+
+        let mut canonicalized_resource = format!("/{}{}", self.account(), resource_path);
+        let mut params: BTreeMap<String, Vec<String>> = BTreeMap::default();
+        for (key, value) in query {
+            params
+                .entry(key.to_lowercase())
+                .or_default()
+                .push(value.clone());
+        }
+        for (key, values) in params {
+            canonicalized_resource += &format!("\n{}:{}", key, values.join(","));
+        }
+
+        let string_to_sign = format!(
+            "{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}{}",
+            method.as_str(),
+            header("content-encoding"),
+            header("content-language"),
+            content_length,
+            header("content-md5"),
+            header("content-type"),
+            header("date"),
+            header("if-modified-since"),
+            header("if-match"),
+            header("if-none-match"),
+            header("if-unmodified-since"),
+            header("range"),
+            canonicalized_headers
+                .iter()
+                .map(|(name, value)| format!("{}:{}\n", name, value))
+                .collect::<String>(),
+            canonicalized_resource
+        );
+
+        let key = base64::engine::general_purpose::STANDARD
+            .decode(access_key)
+            .map_err(|e| Error::new(ErrorKind::InvalidInput, e))?;
+        let mut mac = Hmac::<Sha256>::new_from_slice(&key)
+            .map_err(|e| Error::new(ErrorKind::InvalidInput, e))?;
+        mac.update(string_to_sign.as_bytes());
+
+        Ok(base64::engine::general_purpose::STANDARD.encode(mac.finalize().into_bytes()))
+    }
+    /// Call the Blob service and return the status code with the response headers and body.
+    async fn call(
+        &self,
+        method: Method,
+        blob: Option<&str>,
+        query: Vec<(String, String)>,
+        extra_headers: Vec<(&str, String)>,
+        body: Vec<u8>,
+    ) -> Result<(u16, HashMap<String, String>, Vec<u8>)> {
+        let resource_path = self.resource_path(blob);
+
+        let mut headers = HeaderMap::default();
+        insert_header(
+            &mut headers,
+            "x-ms-date",
+            &chrono::Utc::now()
+                .format("%a, %d %b %Y %H:%M:%S GMT")
+                .to_string(),
+        )?;
+        insert_header(&mut headers, "x-ms-version", API_VERSION)?;
+        for (name, value) in extra_headers {
+            insert_header(&mut headers, name, &value)?;
+        }
+        if method == Method::PUT || method == Method::POST {
+            insert_header(&mut headers, "content-length", &body.len().to_string())?;
+        }
+
+        let mut query_string = query
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, percent_encode(value, false)))
+            .collect::<Vec<String>>();
+
+        match (self.sas_token(), self.access_key()) {
+            (Some(sas_token), _) => query_string.push(sas_token),
+            (None, Some(access_key)) => {
+                let signature =
+                    self.signature(&access_key, &method, &headers, &resource_path, &query)?;
+                insert_header(
+                    &mut headers,
+                    "authorization",
+                    &format!("SharedKey {}:{}", self.account(), signature),
+                )?;
+            }
+            (None, None) => (),
+        };
+
+        let path = match query_string.is_empty() {
+            true => resource_path.clone(),
+            false => format!("{}?{}", resource_path, query_string.join("&")),
+        };
+
+        let endpoint = self.endpoint();
+        let mut curl = Curl::default();
+        curl.endpoint = endpoint
+            .split_once("://")
+            .and_then(|(scheme, rest)| {
+                rest.split('/')
+                    .next()
+                    .map(|host| format!("{}://{}", scheme, host))
+            })
+            .unwrap_or(endpoint.clone());
+        curl.headers = headers;
+        if self.timeout.is_some() {
+            curl.timeout = self.timeout;
+        }
+
+        curl.call_with_headers(&method, &path, Bytes::from(body))
+            .await
+    }
+    /// Get the properties of a blob. Return None if the blob doesn't exist.
+    pub async fn properties(&self) -> Result<Option<BlobProperties>> {
+        let path = self.path();
+        let query = match &self.version {
+            Some(version) => vec![("versionid".to_string(), version.clone())],
+            None => Vec::default(),
+        };
+
+        let (status, headers, _) = self
+            .call(
+                Method::HEAD,
+                Some(&path),
+                query,
+                Vec::default(),
+                Vec::default(),
+            )
+            .await?;
+
+        match status {
+            404 => Ok(None),
+            status if status >= 400 => Err(Error::new(
+                ErrorKind::Interrupted,
+                format!(
+                    "Can't get the properties of the blob '{}', status '{}'",
+                    path, status
+                ),
+            )),
+            _ => Ok(Some(BlobProperties {
+                len: headers
+                    .get("content-length")
+                    .and_then(|len| len.parse::<usize>().ok())
+                    .unwrap_or_default(),
+                blob_type: headers.get("x-ms-blob-type").cloned().unwrap_or_default(),
+            })),
+        }
+    }
+    /// Get the content of a blob. Return an empty content if the blob doesn't exist.
+    async fn get(&self, path: &str) -> Result<Vec<u8>> {
+        let query = match &self.version {
+            Some(version) => vec![("versionid".to_string(), version.clone())],
+            None => Vec::default(),
+        };
+
+        let (status, _, data) = self
+            .call(
+                Method::GET,
+                Some(path),
+                query,
+                Vec::default(),
+                Vec::default(),
+            )
+            .await?;
+
+        match status {
+            404 => Ok(Vec::default()),
+            status if status >= 400 => Err(Error::new(
+                ErrorKind::Interrupted,
+                format!(
+                    "Can't get the blob '{}', status '{}': {}",
+                    path,
+                    status,
+                    String::from_utf8_lossy(&data)
+                ),
+            )),
+            _ => Ok(data),
+        }
+    }
+    /// Headers with the metadata, the tags and the properties of the blob to write.
+    fn write_headers(&self, blob_type: &str) -> Vec<(&str, String)> {
+        let metadata = self.metadata();
+        let mut headers = vec![
+            ("x-ms-blob-type", blob_type.to_string()),
+            ("x-ms-tags", self.tagging()),
+        ];
+
+        if !metadata.content_type().is_empty() {
+            headers.push(("x-ms-blob-content-type", metadata.content_type()));
+        }
+        if !metadata.content_language().is_empty() {
+            headers.push(("x-ms-blob-content-language", metadata.content_language()));
+        }
+        if let Some(cache_control) = &self.cache_control {
+            headers.push(("x-ms-blob-cache-control", cache_control.clone()));
+        }
+
+        headers
+    }
+    /// Metadata of the blob in the form `x-ms-meta-<name>: <value>`.
+    fn write_metadata(&self) -> Vec<(String, String)> {
+        self.metadata()
+            .to_hashmap()
+            .into_iter()
+            .filter(|(_, value)| value.is_ascii())
+            .map(|(key, value)| {
+                (
+                    format!(
+                        "x-ms-meta-{}",
+                        key.replace(|c: char| !c.is_ascii_alphanumeric(), "_")
+                    ),
+                    value.replace('\n', "\\n"),
+                )
+            })
+            .collect()
+    }
+    /// Write a block blob and overwrite the existing one.
+    async fn put_block_blob(&self, path: &str, body: Vec<u8>) -> Result<()> {
+        let metadata = self.write_metadata();
+        let mut headers = self.write_headers("BlockBlob");
+        headers.extend(metadata.iter().map(|(k, v)| (k.as_str(), v.clone())));
+
+        let (status, _, data) = self
+            .call(Method::PUT, Some(path), Vec::default(), headers, body)
+            .await?;
+
+        check_status(status, &data, "write", path)
+    }
+    /// Create an empty append blob.
+    async fn create_append_blob(&self, path: &str) -> Result<()> {
+        let metadata = self.write_metadata();
+        let mut headers = self.write_headers(APPEND_BLOB);
+        headers.extend(metadata.iter().map(|(k, v)| (k.as_str(), v.clone())));
+
+        let (status, _, data) = self
+            .call(
+                Method::PUT,
+                Some(path),
+                Vec::default(),
+                headers,
+                Vec::default(),
+            )
+            .await?;
+
+        check_status(status, &data, "create", path)
+    }
+    /// Append blocks at the end of an append blob.
+    async fn append_block(&self, path: &str, body: Vec<u8>) -> Result<()> {
+        for chunk in body.chunks(APPEND_BLOCK_MAX_SIZE) {
+            let (status, _, data) = self
+                .call(
+                    Method::PUT,
+                    Some(path),
+                    vec![("comp".to_string(), "appendblock".to_string())],
+                    Vec::default(),
+                    chunk.to_vec(),
+                )
+                .await?;
+
+            check_status(status, &data, "append", path)?;
+        }
+
+        Ok(())
+    }
+    /// List the blob names that start with the prefix.
+    pub async fn list(&self, prefix: &str) -> Result<Vec<String>> {
+        let mut names = Vec::default();
+        let mut marker: Option<String> = None;
+
+        loop {
+            let mut query = vec![
+                ("comp".to_string(), "list".to_string()),
+                ("prefix".to_string(), prefix.to_string()),
+                ("restype".to_string(), "container".to_string()),
+            ];
+            if let Some(marker) = &marker {
+                query.push(("marker".to_string(), marker.clone()));
+            }
+
+            let (status, _, data) = self
+                .call(Method::GET, None, query, Vec::default(), Vec::default())
+                .await?;
+            check_status(status, &data, "list", &self.container)?;
+
+            let results: EnumerationResults = quick_xml::de::from_str(
+                String::from_utf8_lossy(&data).trim_start_matches('\u{feff}'),
+            )
+            .map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+
+            names.extend(results.blobs.blobs.into_iter().map(|blob| blob.name));
+
+            marker = results.next_marker.filter(|marker| !marker.is_empty());
+            if marker.is_none() {
+                break;
+            }
+        }
+
+        Ok(names)
+    }
+}
+
+fn insert_header(headers: &mut HeaderMap, name: &str, value: &str) -> Result<()> {
+    headers.insert(
+        HeaderName::from_bytes(name.as_bytes())
+            .map_err(|e| Error::new(ErrorKind::InvalidInput, e))?,
+        HeaderValue::from_str(value).map_err(|e| Error::new(ErrorKind::InvalidInput, e))?,
+    );
+
+    Ok(())
+}
+
+fn check_status(status: u16, data: &[u8], action: &str, path: &str) -> Result<()> {
+    if status >= 400 {
+        return Err(Error::new(
+            ErrorKind::Interrupted,
+            format!(
+                "Can't {} the blob '{}', status '{}': {}",
+                action,
+                path,
+                status,
+                String::from_utf8_lossy(data)
+            ),
+        ));
+    }
+
+    Ok(())
+}
+
+#[async_trait]
+impl Connector for AzureBlob {
+    /// See [`Connector::set_document`] for more details.
+    fn set_document(&mut self, document: Box<dyn Document>) -> Result<()> {
+        self.document = Some(document.clone());
+
+        Ok(())
+    }
+    /// See [`Connector::document`] for more details.
+    fn document(&self) -> Result<&dyn Document> {
+        self.document.as_deref().ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                "The document has not been set in the connector",
+            )
+        })
+    }
+    /// See [`Connector::set_parameters`] for more details.
+    fn set_parameters(&mut self, parameters: Value) {
+        *self.parameters = parameters
+    }
+    /// See [`Connector::is_variable`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::azure_blob::AzureBlob;
+    /// use chewdata::connector::Connector;
+    ///
+    /// let mut connector = AzureBlob::default();
+    /// assert_eq!(false, connector.is_variable());
+    /// connector.path = "/dir/filename_{{ field }}.ext".to_string();
+    /// assert_eq!(true, connector.is_variable());
+    /// ```
+    fn is_variable(&self) -> bool {
+        self.path.has_mustache()
+    }
+    /// See [`Connector::is_resource_will_change`] for more details.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use chewdata::connector::{azure_blob::AzureBlob, Connector};
+    /// use serde_json::Value;
+    ///
+    /// let mut connector = AzureBlob::default();
+    /// let params = serde_json::from_str(r#"{"field":"test"}"#).unwrap();
+    /// assert_eq!(false, connector.is_resource_will_change(Value::Null).unwrap());
+    /// connector.path = "/dir/static.ext".to_string();
+    /// assert_eq!(false, connector.is_resource_will_change(Value::Null).unwrap());
+    /// connector.path = "/dir/dynamic_{{ field }}.ext".to_string();
+    /// assert_eq!(true, connector.is_resource_will_change(params).unwrap());
+    /// ```
+    fn is_resource_will_change(&self, new_parameters: Value) -> Result<bool> {
+        if !self.is_variable() {
+            trace!("Stay link to the same resource");
+            return Ok(false);
+        }
+
+        let mut metadata_kv = Map::default();
+        metadata_kv.insert("metadata".to_string(), self.metadata().into());
+        let metadata = Value::Object(metadata_kv);
+
+        let mut new_parameters = new_parameters;
+        new_parameters.merge(&metadata);
+        let mut old_parameters = *self.parameters.clone();
+        old_parameters.merge(&metadata);
+
+        let mut previous_path = self.path.clone();
+        previous_path.replace_mustache(old_parameters);
+
+        let mut new_path = self.path.clone();
+        new_path.replace_mustache(new_parameters);
+
+        if previous_path == new_path {
+            trace!(path = previous_path, "Path didn't change");
+            return Ok(false);
+        }
+
+        info!(
+            previous_path = previous_path,
+            new_path = new_path,
+            "Will use another resource, regarding the new parameters"
+        );
+        Ok(true)
+    }
+    /// See [`Connector::path`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::azure_blob::AzureBlob;
+    /// use chewdata::connector::Connector;
+    /// use serde_json::Value;
+    ///
+    /// let mut connector = AzureBlob::default();
+    /// connector.path = "/dir/filename_{{ field }}.ext".to_string();
+    /// let params: Value = serde_json::from_str(r#"{"field":"value"}"#).unwrap();
+    /// connector.set_parameters(params);
+    /// assert_eq!("/dir/filename_value.ext", connector.path());
+    /// ```
+    fn path(&self) -> String {
+        if !self.is_variable() {
+            return self.path.clone();
+        }
+
+        let mut params = *self.parameters.clone();
+        params.merge(&serde_json::json!({
+            "metadata": self.metadata()
+        }));
+
+        let mut path = self.path.clone();
+        path.replace_mustache(params);
+        path
+    }
+    /// See [`Connector::len`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::azure_blob::AzureBlob;
+    /// use chewdata::connector::Connector;
+    /// use std::io;
+    ///
+    /// use macro_rules_attribute::apply;
+    /// use smol_macros::main;
+    ///
+    /// #[apply(main!)]
+    /// async fn main() -> io::Result<()> {
+    ///     let mut connector = AzureBlob::default();
+    ///     connector.container = "my-container".to_string();
+    ///     connector.path = "data/not-found-file".to_string();
+    ///     assert_eq!(0, connector.len().await?);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[instrument(name = "azure_blob::len")]
+    async fn len(&self) -> Result<usize> {
+        if self.path.contains('*') {
+            return Err(Error::new(
+                ErrorKind::NotFound,
+                "len() method not available for wildcard path",
+            ));
+        }
+
+        let len = match self.properties().await {
+            Ok(properties) => properties
+                .map(|properties| properties.len)
+                .unwrap_or_default(),
+            Err(e) => {
+                warn!(
+                    error = format!("{:?}", e.to_string()).as_str(),
+                    "Can't find the length of the resource"
+                );
+                0_usize
+            }
+        };
+
+        info!(len, "Find length of the resource");
+
+        Ok(len)
+    }
+    /// See [`Connector::fetch`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::{azure_blob::AzureBlob, Connector};
+    /// use chewdata::document::json::Json;
+    /// use chewdata::DataResult;
+    /// use smol::stream::StreamExt;
+    /// use std::io;
+    ///
+    /// use macro_rules_attribute::apply;
+    /// use smol_macros::main;
+    ///
+    /// #[apply(main!)]
+    /// async fn main() -> io::Result<()> {
+    ///     let mut connector = AzureBlob::default();
+    ///     connector.container = "my-container".to_string();
+    ///     connector.path = "data/fetch_doc.json".to_string();
+    ///     connector.set_document(Box::new(Json::default()))?;
+    ///     connector.erase().await?;
+    ///     connector.send(&vec![DataResult::Ok(serde_json::from_str(r#"{"column1":"value1"}"#)?)]).await?;
+    ///
+    ///     let datastream = connector.fetch().await?.unwrap();
+    ///     assert!(
+    ///         0 < datastream.count().await,
+    ///         "The inner connector should have a size upper than zero"
+    ///     );
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[instrument(name = "azure_blob::fetch")]
+    async fn fetch(&mut self) -> Result<Option<DataStream>> {
+        let document = self.document()?;
+        let path = self.path();
+
+        if path.has_mustache() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("This path '{}' is not fully resolved", path),
+            ));
+        }
+
+        let buffer = self.get(&path).await?;
+
+        info!(path = path, "Fetch data with success");
+
+        if !document.has_data(&buffer)? {
+            return Ok(None);
+        }
+
+        let dataset = document.read(&buffer)?;
+
+        Ok(Some(Box::pin(stream! {
+            for data in dataset {
+                yield data;
+            }
+        })))
+    }
+    /// See [`Connector::send`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::azure_blob::AzureBlob;
+    /// use chewdata::connector::Connector;
+    /// use chewdata::document::json::Json;
+    /// use chewdata::DataResult;
+    /// use smol::prelude::*;
+    /// use std::io;
+    ///
+    /// use macro_rules_attribute::apply;
+    /// use smol_macros::main;
+    ///
+    /// #[apply(main!)]
+    /// async fn main() -> io::Result<()> {
+    ///     let mut connector = AzureBlob::default();
+    ///     connector.container = "my-container".to_string();
+    ///     connector.path = "data/send_doc.json".to_string();
+    ///     connector.set_document(Box::new(Json::default()))?;
+    ///     connector.erase().await?;
+    ///
+    ///     let expected_result1 =
+    ///         DataResult::Ok(serde_json::from_str(r#"{"column1":"value1"}"#)?);
+    ///     let expected_result2 =
+    ///         DataResult::Ok(serde_json::from_str(r#"{"column1":"value2"}"#)?);
+    ///     connector.send(&vec![expected_result1.clone()]).await?;
+    ///     connector.send(&vec![expected_result2.clone()]).await?;
+    ///
+    ///     let mut datastream = connector.fetch().await?.unwrap();
+    ///     assert_eq!(expected_result1, datastream.next().await.unwrap());
+    ///     assert_eq!(expected_result2, datastream.next().await.unwrap());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[instrument(skip(dataset), name = "azure_blob::send")]
+    async fn send(&mut self, dataset: &DataSet) -> std::io::Result<Option<DataStream>> {
+        let document = self.document()?;
+        let path = self.path();
+        let terminator = document.terminator()?;
+        let footer = document.footer(dataset)?;
+        let header = document.header(dataset)?;
+        let body = document.write(dataset)?;
+        let can_append = document.can_append();
+
+        if path.has_mustache() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("This path '{}' is not fully resolved", path),
+            ));
+        }
+
+        let properties = match can_append {
+            true => self.properties().await?,
+            false => None,
+        };
+
+        // Append the data at the end of the append blob.
+        if can_append && footer.is_empty() {
+            let file_len = match &properties {
+                Some(properties) if properties.blob_type == APPEND_BLOB => Some(properties.len),
+                Some(_) => None,
+                None => {
+                    self.create_append_blob(&path).await?;
+                    Some(0)
+                }
+            };
+
+            if let Some(file_len) = file_len {
+                let mut buffer = Vec::default();
+
+                if 0 == file_len {
+                    buffer.write_all(&header)?;
+                }
+                if 0 < file_len && file_len > header.len() {
+                    buffer.write_all(&terminator)?;
+                }
+                buffer.write_all(&body)?;
+
+                self.append_block(&path, buffer).await?;
+
+                info!(path = path, "Send data with success");
+                return Ok(None);
+            }
+        }
+
+        let position = match can_append {
+            true => Some(-(footer.len() as isize)),
+            false => None,
+        };
+
+        let content_file = match properties {
+            Some(properties) if properties.len > 0 => {
+                info!(path = path.to_string().as_str(), "Fetch existing data");
+                self.get(&path).await?
+            }
+            _ => Vec::default(),
+        };
+
+        let file_len = content_file.len();
+        let mut cursor = Cursor::new(content_file);
+
+        match position {
+            Some(pos) => match file_len as isize + pos {
+                start if start > 0 => cursor.seek(SeekFrom::Start(start as u64)),
+                _ => cursor.seek(SeekFrom::Start(0)),
+            },
+            None => cursor.seek(SeekFrom::Start(0)),
+        }?;
+
+        if 0 == file_len {
+            cursor.write_all(&header)?;
+        }
+        if 0 < file_len && file_len > (header.len() + footer.len()) {
+            cursor.write_all(&terminator)?;
+        }
+        cursor.write_all(&body)?;
+        cursor.write_all(&footer)?;
+
+        self.put_block_blob(&path, cursor.into_inner()).await?;
+
+        info!(path = path, "Send data with success");
+        Ok(None)
+    }
+    /// See [`Connector::metadata`] for more details.
+    fn metadata(&self) -> Metadata {
+        match &self.document {
+            Some(document) => self.metadata.clone().merge(&document.metadata()),
+            None => self.metadata.clone(),
+        }
+    }
+    /// See [`Connector::erase`] for more details.
+    #[instrument(name = "azure_blob::erase")]
+    async fn erase(&mut self) -> Result<()> {
+        let path = self.path();
+
+        if path.has_mustache() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("This path '{}' is not fully resolved", path),
+            ));
+        }
+
+        let paths = match path.contains('*') {
+            true => AzureBlobPaginator::new(self).await?.paths.collect(),
+            false => vec![path],
+        };
+
+        for path in paths {
+            self.put_block_blob(&path, Vec::default()).await?;
+        }
+
+        info!("Erase data with success");
+        Ok(())
+    }
+    /// See [`Connector::paginate`] for more details.
+    async fn paginate(
+        &self,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<Box<dyn Connector>>> + Send>>> {
+        AzureBlobPaginator::new(self).await?.paginate(self).await
+    }
+}
+
+#[derive(Debug)]
+pub struct AzureBlobPaginator {
+    pub paths: IntoIter<String>,
+    pub skip: usize,
+}
+
+impl AzureBlobPaginator {
+    pub async fn new(connector: &AzureBlob) -> Result<Self> {
+        let path = connector.path();
+
+        let mut paths = match path.contains('*') {
+            true => {
+                let prefix = path
+                    .split('*')
+                    .next()
+                    .unwrap_or_default()
+                    .trim_start_matches('/');
+                let pattern = glob::Pattern::new(path.trim_start_matches('/'))
+                    .map_err(|e| Error::new(ErrorKind::InvalidInput, e))?;
+
+                match connector.list(prefix).await {
+                    Ok(names) => names
+                        .into_iter()
+                        .filter(|name| pattern.matches(name))
+                        .collect(),
+                    Err(e) => {
+                        warn!(
+                            error = e.to_string().as_str(),
+                            "Can't fetch the list of blobs"
+                        );
+                        Vec::default()
+                    }
+                }
+            }
+            false => vec![path],
+        };
+
+        if let Some(limit) = connector.limit {
+            let paths_range_start = paths.len().min(connector.skip);
+            let paths_range_end = paths.len().min(connector.skip + limit);
+
+            paths = paths[paths_range_start..paths_range_end].to_vec();
+        }
+
+        Ok(AzureBlobPaginator {
+            skip: connector.skip,
+            paths: paths.into_iter(),
+        })
+    }
+}
+
+impl AzureBlobPaginator {
+    /// Paginate through the container.
+    /// Wildcard is allowed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::azure_blob::{AzureBlob, AzureBlobPaginator};
+    /// use chewdata::connector::Connector;
+    /// use smol::prelude::*;
+    /// use std::io;
+    ///
+    /// use macro_rules_attribute::apply;
+    /// use smol_macros::main;
+    ///
+    /// #[apply(main!)]
+    /// async fn main() -> io::Result<()> {
+    ///     let mut connector = AzureBlob::default();
+    ///     connector.container = "my-container".to_string();
+    ///     connector.path = "data/one_line.json".to_string();
+    ///
+    ///     let paginator = AzureBlobPaginator::new(&connector).await?;
+    ///     let mut paging = paginator.paginate(&connector).await?;
+    ///     assert!(paging.next().await.transpose()?.is_some(), "Can't get the first reader.");
+    ///     assert!(paging.next().await.transpose()?.is_none(), "Should not have more readers.");
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[instrument(name = "azure_blob::paginate")]
+    pub async fn paginate(&self, connector: &AzureBlob) -> Result<ConnectorStream> {
+        let mut paths = self.paths.clone();
+        let connector = connector.clone();
+
+        Ok(Box::pin(stream! {
+            for path in &mut paths {
+                trace!(next_path = path.as_str(), "Next path");
+
+                let mut new_connector = connector.clone();
+                new_connector.path = path;
+
+                trace!(connector = format!("{:?}", new_connector).as_str(), "The stream yields a new connector");
+                yield Ok(Box::new(new_connector) as Box<dyn Connector>);
+            }
+            trace!("The stream stops yielding new connectors");
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::json::Json;
+    use crate::document::jsonl::Jsonl;
+    use crate::DataResult;
+    use macro_rules_attribute::apply;
+    use smol::stream::StreamExt;
+    use smol_macros::test;
+
+    const AZURITE_KEY: &str =
+        "Eby8vdM02xNOcqFlqUwJPLlmEtlCDXJ1OUzFT50uSRZ6IFsuFq2UVErCz4I6tq/K1SZFPTOtr/KBHBeksoGMGw==";
+
+    fn connector(path: &str) -> AzureBlob {
+        let mut connector = AzureBlob::default();
+        connector.endpoint = Some(DEFAULT_ENDPOINT.to_string());
+        connector.access_key = Some(AZURITE_KEY.to_string());
+        connector.container = "my-container".to_string();
+        connector.path = path.to_string();
+        connector
+    }
+
+    #[test]
+    fn account() {
+        let mut connector = AzureBlob::default();
+        connector.endpoint = Some("https://myaccount.blob.core.windows.net".to_string());
+        assert_eq!("myaccount", connector.account());
+        connector.account = Some("other".to_string());
+        assert_eq!("other", connector.account());
+    }
+    #[test]
+    fn resource_path() {
+        let mut connector = connector("data/my file.json");
+        assert_eq!(
+            "/devstoreaccount1/my-container/data/my%20file.json",
+            connector.resource_path(Some(&connector.path()))
+        );
+        connector.endpoint = Some("https://myaccount.blob.core.windows.net".to_string());
+        assert_eq!("/my-container", connector.resource_path(None));
+    }
+    #[test]
+    fn tagging() {
+        let mut connector = AzureBlob::default();
+        connector
+            .tags
+            .insert("service:writer:env".to_string(), "dev".to_string());
+        assert_eq!(
+            "service%3Awriter%3Aenv=dev&service%3Awriter%3Aname=chewdata",
+            connector.tagging()
+        );
+    }
+    #[test]
+    fn is_resource_will_change() {
+        let mut connector = AzureBlob::default();
+        let params = serde_json::from_str(r#"{"field":"test"}"#).unwrap();
+        assert_eq!(
+            false,
+            connector.is_resource_will_change(Value::Null).unwrap()
+        );
+        connector.path = "/dir/dynamic_{{ field }}.ext".to_string();
+        assert_eq!(true, connector.is_resource_will_change(params).unwrap());
+    }
+    #[apply(test!)]
+    async fn len() {
+        let mut connector = connector("data/len.json");
+        connector.set_document(Box::new(Json::default())).unwrap();
+        connector.erase().await.unwrap();
+        assert_eq!(0, connector.len().await.unwrap());
+        connector
+            .send(&vec![DataResult::Ok(
+                serde_json::from_str(r#"{"column1":"value1"}"#).unwrap(),
+            )])
+            .await
+            .unwrap();
+        assert!(0 < connector.len().await.unwrap());
+    }
+    #[apply(test!)]
+    async fn send_and_fetch() {
+        let expected_result1 =
+            DataResult::Ok(serde_json::from_str(r#"{"column1":"value1"}"#).unwrap());
+        let expected_result2 =
+            DataResult::Ok(serde_json::from_str(r#"{"column1":"value2"}"#).unwrap());
+        let mut connector = connector("data/send.json");
+        connector.set_document(Box::new(Json::default())).unwrap();
+        connector.erase().await.unwrap();
+        connector
+            .send(&vec![expected_result1.clone()])
+            .await
+            .unwrap();
+        connector
+            .send(&vec![expected_result2.clone()])
+            .await
+            .unwrap();
+
+        let mut datastream = connector.fetch().await.unwrap().unwrap();
+        assert_eq!(expected_result1, datastream.next().await.unwrap());
+        assert_eq!(expected_result2, datastream.next().await.unwrap());
+    }
+    #[apply(test!)]
+    async fn send_into_append_blob() {
+        let expected_result1 =
+            DataResult::Ok(serde_json::from_str(r#"{"column1":"value1"}"#).unwrap());
+        let expected_result2 =
+            DataResult::Ok(serde_json::from_str(r#"{"column1":"value2"}"#).unwrap());
+        let mut connector = connector("data/append.jsonl");
+        connector.set_document(Box::new(Jsonl::default())).unwrap();
+        // Remove the previous append blob in order to create a new one.
+        connector
+            .call(
+                Method::DELETE,
+                Some("data/append.jsonl"),
+                Vec::default(),
+                Vec::default(),
+                Vec::default(),
+            )
+            .await
+            .unwrap();
+        connector
+            .send(&vec![expected_result1.clone()])
+            .await
+            .unwrap();
+        connector
+            .send(&vec![expected_result2.clone()])
+            .await
+            .unwrap();
+
+        assert_eq!(
+            APPEND_BLOB,
+            connector.properties().await.unwrap().unwrap().blob_type
+        );
+        let mut datastream = connector.fetch().await.unwrap().unwrap();
+        assert_eq!(expected_result1, datastream.next().await.unwrap());
+        assert_eq!(expected_result2, datastream.next().await.unwrap());
+    }
+    #[apply(test!)]
+    async fn paginator_paginate_with_wildcard_limit_skip() {
+        let mut connector = connector("data/paginate_{{ number }}.json");
+        connector.set_document(Box::new(Json::default())).unwrap();
+        for number in 1..4 {
+            connector.set_parameters(serde_json::json!({ "number": number }));
+            connector.erase().await.unwrap();
+            connector
+                .send(&vec![DataResult::Ok(
+                    serde_json::json!({ "number": number }),
+                )])
+                .await
+                .unwrap();
+        }
+        connector.path = "data/paginate_*.json".to_string();
+        connector.limit = Some(2);
+        connector.skip = 1;
+
+        let paginator = AzureBlobPaginator::new(&connector).await.unwrap();
+        let mut paging = paginator.paginate(&connector).await.unwrap();
+
+        let connector = paging.next().await.transpose().unwrap().unwrap();
+        assert_eq!("data/paginate_2.json", connector.path());
+        let connector = paging.next().await.transpose().unwrap().unwrap();
+        assert_eq!("data/paginate_3.json", connector.path());
+        assert!(paging.next().await.is_none());
+    }
+}
@@ -48,6 +48,7 @@
 //! ```
 use crate::connector::Connector;
 use crate::document::Document;
+use crate::helper::aws::credentials_provider;
 use crate::helper::mustache::Mustache;
 use crate::helper::string::DisplayOnlyForDebugging;
 use crate::{ConnectorStream, DataSet, DataStream, Metadata};
@@ -55,7 +56,6 @@ use async_compat::CompatExt;
 use async_lock::OnceCell;
 use async_stream::stream;
 use async_trait::async_trait;
-use aws_config::meta::credentials::CredentialsProviderChain;
 use aws_sdk_s3::config::Region;
 use aws_sdk_s3::primitives::DateTime;
 use aws_sdk_s3::Client;
@@ -232,14 +232,7 @@ async fn get_or_create_client(endpoint: String, region: String) -> Result<Client
         .get_or_try_init(|| async {
             trace!(key = ?key, "storing client in shared container");
 
-            if let Ok(key) = env::var("BUCKET_ACCESS_KEY_ID") {
-                env::set_var("AWS_ACCESS_KEY_ID", key);
-            }
-            if let Ok(secret) = env::var("BUCKET_SECRET_ACCESS_KEY") {
-                env::set_var("AWS_SECRET_ACCESS_KEY", secret);
-            }
-
-            let provider = CredentialsProviderChain::default_provider().await;
+            let provider = credentials_provider("BUCKET").await;
             let config = aws_sdk_s3::Config::builder()
                 .endpoint_url(endpoint)
                 .region(Region::new(region))
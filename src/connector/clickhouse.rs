@@ -0,0 +1,1296 @@
+//! Read and write data into a ClickHouse table with the HTTP interface.
+//!
+//! The records are inserted by batch in one `INSERT` statement with the `JSONEachRow` or the `RowBinary` format.
+//! If the server rejects a batch, each record of the batch is returned in error to the writer.
+//! With the `RowBinary` format, the columns of the table are read with `DESCRIBE TABLE` in order to encode the records.
+//! The records that can't be encoded are returned in error and are not sent.
+//!
+//! The result of the query is read line by line with the `JSONEachRow` format without loading the response in memory.
+//! The query can use the ClickHouse query parameters `{name:Type}`. The values come from the parameters of the connector
+//! and are sent apart from the query.
+//!
+//! ### Configuration
+//!
+//! | key           | alias                | Description                                                          | Default Value           | Possible Values                                  |
+//! | ------------- | -------------------- | -------------------------------------------------------------------- | ----------------------- | ------------------------------------------------ |
+//! | type          | -                    | Required in order to use this connector                              | `clickhouse`            | `clickhouse` / `ch`                              |
+//! | endpoint      | `url`                | Endpoint of the HTTP interface                                       | `http://localhost:8123` | String                                           |
+//! | database      | `db`                 | The database name                                                    | `default`               | String                                           |
+//! | table         | `col` / `collection` | The table name or `database.table`. Can use mustache variables       | ``                      | String                                           |
+//! | authenticator | `auth`               | Define the authentification that secure the http(s) call             | `null`                  | [`crate::connector::authenticator::basic::Basic`] / [`crate::connector::authenticator::bearer::Bearer`] |
+//! | certificate   | `crt`                | Path to a local certificate file used to trust the HTTPS connection  | `null`                  | Local path of a .crt file                        |
+//! | timeout       | -                    | Time in secound before to abort the call                             | `5`                     | Unsigned number                                  |
+//! | query         | -                    | Query used to read the data. Read all the table by default           | `null`                  | String                                           |
+//! | format        | -                    | Format used to insert the records                                    | `json_each_row`         | `json_each_row` / `row_binary`                   |
+//! | settings      | -                    | ClickHouse settings applied to each query                            | `{}`                    | List of Key/Value                                |
+//! | parameters    | `params`             | Parameters used to inject into the table and the query parameters    | `null`                  | Json structure                                   |
+//!
+//! ### Examples
+//!
+//! ```json
+//! [
+//!     {
+//!         "type": "r",
+//!         "connector":{
+//!             "type": "clickhouse",
+//!             "endpoint": "{{ CLICKHOUSE_ENDPOINT }}",
+//!             "database": "default",
+//!             "query": "SELECT * FROM orders WHERE number > {number:UInt32}",
+//!             "parameters": {
+//!                 "number": 100
+//!             }
+//!         }
+//!     },
+//!     {
+//!         "type": "w",
+//!         "connector":{
+//!             "type": "clickhouse",
+//!             "endpoint": "{{ CLICKHOUSE_ENDPOINT }}",
+//!             "table": "orders_copy",
+//!             "format": "row_binary",
+//!             "settings": {
+//!                 "async_insert": 1
+//!             }
+//!         },
+//!         "record_limit": 10000
+//!     }
+//! ]
+//! ```
+use super::authenticator::AuthenticatorType;
+use super::curl::{ByteStream, Curl};
+use super::Connector;
+use crate::connector::paginator::once::Once;
+use crate::helper::mustache::Mustache;
+use crate::helper::string::{percent_encode, DisplayOnlyForDebugging, Obfuscate};
+use crate::{DataResult, DataSet, DataStream, Metadata};
+use async_stream::stream;
+use async_trait::async_trait;
+use bytes::Bytes;
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use futures::{Stream, StreamExt};
+use http::Method;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::pin::Pin;
+use std::{
+    fmt,
+    io::{Error, ErrorKind, Result},
+};
+
+const DEFAULT_ENDPOINT: &str = "http://localhost:8123";
+const DEFAULT_DATABASE: &str = "default";
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Format {
+    #[default]
+    #[serde(alias = "jsoneachrow")]
+    JsonEachRow,
+    #[serde(alias = "rowbinary")]
+    RowBinary,
+}
+
+impl Format {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Format::JsonEachRow => "JSONEachRow",
+            Format::RowBinary => "RowBinary",
+        }
+    }
+}
+
+/// A column of the table used to encode the records in `RowBinary`.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct Column {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(default)]
+    pub default_type: String,
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct Clickhouse {
+    #[serde(alias = "url")]
+    pub endpoint: String,
+    #[serde(alias = "db")]
+    pub database: String,
+    #[serde(alias = "col")]
+    #[serde(alias = "collection")]
+    pub table: String,
+    #[serde(alias = "auth")]
+    #[serde(rename = "authenticator")]
+    pub authenticator_type: Option<Box<AuthenticatorType>>,
+    #[serde(alias = "crt")]
+    pub certificate: Option<String>,
+    pub timeout: Option<u64>,
+    pub query: Option<String>,
+    pub format: Format,
+    pub settings: Map<String, Value>,
+    #[serde(alias = "params")]
+    pub parameters: Value,
+    // Columns of the last table described, used to encode the records in RowBinary.
+    #[serde(skip)]
+    columns: Option<(String, Vec<Column>)>,
+}
+
+impl Default for Clickhouse {
+    fn default() -> Self {
+        Clickhouse {
+            endpoint: DEFAULT_ENDPOINT.to_string(),
+            database: DEFAULT_DATABASE.to_string(),
+            table: Default::default(),
+            authenticator_type: None,
+            certificate: None,
+            timeout: None,
+            query: None,
+            format: Format::default(),
+            settings: Map::default(),
+            parameters: Value::Null,
+            columns: None,
+        }
+    }
+}
+
+impl fmt::Debug for Clickhouse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Clickhouse")
+            .field("endpoint", &self.endpoint.to_obfuscate())
+            .field("database", &self.database)
+            .field("table", &self.table)
+            .field(
+                "authenticator_type",
+                &self.authenticator_type.display_only_for_debugging(),
+            )
+            .field("certificate", &self.certificate)
+            .field("timeout", &self.timeout)
+            .field("query", &self.query)
+            .field("format", &self.format)
+            .field("settings", &self.settings)
+            // Can contain sensitive data
+            .field("parameters", &self.parameters.display_only_for_debugging())
+            .finish()
+    }
+}
+
+impl Clickhouse {
+    /// Get the table name resolved with the parameters.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::clickhouse::Clickhouse;
+    /// use chewdata::connector::Connector;
+    /// use serde_json::Value;
+    ///
+    /// let mut connector = Clickhouse::default();
+    /// connector.table = "table_{{ field }}".to_string();
+    /// let params: Value = serde_json::from_str(r#"{"field":"value"}"#).unwrap();
+    /// connector.set_parameters(params);
+    /// assert_eq!("table_value", connector.table().unwrap());
+    /// ```
+    pub fn table(&self) -> Result<String> {
+        let mut table = self.table.clone();
+        table.replace_mustache(self.parameters.clone());
+
+        if table.has_mustache() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("This table '{}' is not fully resolved", table),
+            ));
+        }
+
+        if table.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The table name is required",
+            ));
+        }
+
+        Ok(table)
+    }
+    /// Get the query used to read the data. Select all the table by default.
+    pub fn query(&self) -> Result<String> {
+        match &self.query {
+            Some(query) => Ok(query.clone()),
+            None => Ok(format!("SELECT * FROM {}", quote_table(&self.table()?))),
+        }
+    }
+    /// Get the path with the url arguments of a call: the database, the settings,
+    /// the query parameters used by the query and the extra arguments.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::clickhouse::Clickhouse;
+    /// use chewdata::connector::Connector;
+    ///
+    /// let mut connector = Clickhouse::default();
+    /// connector.settings.insert("max_threads".to_string(), 2.into());
+    /// connector.set_parameters(serde_json::json!({"name": "a&b", "unused": 1}));
+    /// assert_eq!(
+    ///     "/?database=default&max_threads=2&param_name=a%26b",
+    ///     connector.path_with_arguments("SELECT {name:String}", &[])
+    /// );
+    /// ```
+    pub fn path_with_arguments(&self, query: &str, arguments: &[(&str, &str)]) -> String {
+        let mut pairs: Vec<(String, String)> =
+            vec![("database".to_string(), self.database.clone())];
+
+        for (key, value) in &self.settings {
+            pairs.push((key.clone(), argument_value(value)));
+        }
+
+        if let Value::Object(parameters) = &self.parameters {
+            for (key, value) in parameters {
+                if query.contains(&format!("{{{}:", key)) {
+                    pairs.push((format!("param_{}", key), argument_value(value)));
+                }
+            }
+        }
+
+        for (key, value) in arguments {
+            pairs.push((key.to_string(), value.to_string()));
+        }
+
+        let arguments: Vec<String> = pairs
+            .iter()
+            .map(|(key, value)| {
+                format!(
+                    "{}={}",
+                    percent_encode(key, false),
+                    percent_encode(value, false)
+                )
+            })
+            .collect();
+
+        format!("/?{}", arguments.join("&"))
+    }
+    fn curl(&self, mime_subtype: &str) -> Curl {
+        let mut curl = Curl::default();
+        curl.endpoint = self.endpoint.clone();
+        curl.authenticator_type = self.authenticator_type.clone();
+        curl.certificate = self.certificate.clone();
+        if self.timeout.is_some() {
+            curl.timeout = self.timeout;
+        }
+        curl.metadata = Metadata {
+            mime_type: Some(
+                match mime_subtype {
+                    "plain" => "text",
+                    _ => "application",
+                }
+                .to_string(),
+            ),
+            mime_subtype: Some(mime_subtype.to_string()),
+            ..Default::default()
+        };
+        curl
+    }
+    /// Execute a statement and return the response body.
+    pub async fn execute(&self, query: &str) -> Result<Vec<u8>> {
+        let path = self.path_with_arguments(query, &[]);
+        let (status, data) = self
+            .curl("plain")
+            .call(&Method::POST, &path, Bytes::from(query.to_string()))
+            .await?;
+
+        if status >= 400 {
+            return Err(Error::new(
+                ErrorKind::Interrupted,
+                format!(
+                    "The query failed with the status '{}': {}",
+                    status,
+                    String::from_utf8_lossy(&data).trim()
+                ),
+            ));
+        }
+
+        Ok(data)
+    }
+    /// Get the columns of the table that can be inserted.
+    pub async fn describe(&mut self, table: &str) -> Result<Vec<Column>> {
+        if let Some((described_table, columns)) = &self.columns {
+            if described_table == table {
+                return Ok(columns.clone());
+            }
+        }
+
+        let data = self
+            .execute(&format!(
+                "DESCRIBE TABLE {} FORMAT JSONEachRow",
+                quote_table(table)
+            ))
+            .await?;
+
+        let mut columns = Vec::default();
+        for line in data.split(|byte| b'\n' == *byte) {
+            if line.iter().all(u8::is_ascii_whitespace) {
+                continue;
+            }
+
+            let column: Column =
+                serde_json::from_slice(line).map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+
+            // These columns are computed by the server.
+            if matches!(column.default_type.as_str(), "MATERIALIZED" | "ALIAS") {
+                continue;
+            }
+
+            columns.push(column);
+        }
+
+        self.columns = Some((table.to_string(), columns.clone()));
+
+        Ok(columns)
+    }
+}
+
+/// Quote an identifier with backticks.
+///
+/// # Examples
+///
+/// ```
+/// use chewdata::connector::clickhouse::quote_identifier;
+///
+/// assert_eq!("`my_table`", quote_identifier("my_table"));
+/// assert_eq!("`my\\`table`", quote_identifier("my`table"));
+/// ```
+pub fn quote_identifier(identifier: &str) -> String {
+    format!("`{}`", identifier.replace('\\', "\\\\").replace('`', "\\`"))
+}
+
+/// Quote each part of a table name `database.table` with backticks.
+///
+/// # Examples
+///
+/// ```
+/// use chewdata::connector::clickhouse::quote_table;
+///
+/// assert_eq!("`my_table`", quote_table("my_table"));
+/// assert_eq!("`my_db`.`my_table`", quote_table("my_db.my_table"));
+/// ```
+pub fn quote_table(table: &str) -> String {
+    table
+        .split('.')
+        .map(quote_identifier)
+        .collect::<Vec<String>>()
+        .join(".")
+}
+
+/// Value of an url argument. The null is sent with the ClickHouse escape sequence.
+fn argument_value(value: &Value) -> String {
+    match value {
+        Value::String(value) => value.clone(),
+        Value::Null => "\\N".to_string(),
+        _ => value.to_string(),
+    }
+}
+
+/// Transform a stream of bytes into records. Each line is a json record that can be split between chunks.
+///
+/// The lines that are not json, like an exception raised by the server during the streaming, are returned in error.
+fn json_lines(mut chunks: ByteStream) -> DataStream {
+    Box::pin(stream! {
+        let mut buffer: Vec<u8> = Vec::default();
+
+        loop {
+            let chunk = chunks.next().await;
+            let is_end = chunk.is_none();
+
+            match chunk {
+                Some(Ok(chunk)) => buffer.extend_from_slice(&chunk),
+                Some(Err(e)) => {
+                    yield DataResult::Err((Value::Null, e));
+                    break;
+                }
+                None => buffer.push(b'\n'),
+            }
+
+            while let Some(position) = buffer.iter().position(|byte| b'\n' == *byte) {
+                let line: Vec<u8> = buffer.drain(..=position).collect();
+                let line = &line[..position];
+
+                if line.iter().all(u8::is_ascii_whitespace) {
+                    continue;
+                }
+
+                yield match serde_json::from_slice::<Value>(line) {
+                    Ok(value) => DataResult::Ok(value),
+                    Err(_) => {
+                        let line = String::from_utf8_lossy(line).trim().to_string();
+                        DataResult::Err((
+                            Value::String(line.clone()),
+                            Error::new(ErrorKind::InvalidData, line),
+                        ))
+                    }
+                };
+            }
+
+            if is_end {
+                break;
+            }
+        }
+    })
+}
+
+/// Write an unsigned number with the LEB128 encoding.
+fn write_varint(mut number: u64, buffer: &mut Vec<u8>) {
+    loop {
+        let byte = (number & 0x7f) as u8;
+        number >>= 7;
+
+        if 0 == number {
+            buffer.push(byte);
+            break;
+        }
+
+        buffer.push(byte | 0x80);
+    }
+}
+
+fn invalid_value(kind: &str, value: &Value) -> Error {
+    Error::new(
+        ErrorKind::InvalidData,
+        format!("The value '{}' can't be encoded in '{}'", value, kind),
+    )
+}
+
+fn integer(kind: &str, value: &Value) -> Result<i128> {
+    match value {
+        Value::Number(number) => number
+            .as_i64()
+            .map(i128::from)
+            .or_else(|| number.as_u64().map(i128::from)),
+        Value::String(string) => string.trim().parse().ok(),
+        Value::Bool(boolean) => Some(i128::from(*boolean)),
+        _ => None,
+    }
+    .ok_or_else(|| invalid_value(kind, value))
+}
+
+fn float(kind: &str, value: &Value) -> Result<f64> {
+    match value {
+        Value::Number(number) => number.as_f64(),
+        Value::String(string) => string.trim().parse().ok(),
+        _ => None,
+    }
+    .ok_or_else(|| invalid_value(kind, value))
+}
+
+fn days(kind: &str, value: &Value) -> Result<i128> {
+    match value {
+        Value::String(string) => NaiveDate::parse_from_str(string.trim(), "%Y-%m-%d")
+            .map(|date| i128::from(date.signed_duration_since(NaiveDate::default()).num_days()))
+            .map_err(|_| invalid_value(kind, value)),
+        _ => integer(kind, value),
+    }
+}
+
+fn seconds(kind: &str, value: &Value) -> Result<i128> {
+    match value {
+        Value::String(string) => {
+            let string = string.trim();
+            DateTime::parse_from_rfc3339(string)
+                .map(|datetime| datetime.timestamp())
+                .or_else(|_| {
+                    NaiveDateTime::parse_from_str(string, "%Y-%m-%d %H:%M:%S")
+                        .map(|datetime| datetime.and_utc().timestamp())
+                })
+                .map(i128::from)
+                .or_else(|_| integer(kind, value))
+        }
+        _ => integer(kind, value),
+    }
+}
+
+fn inner_type<'a>(kind: &'a str, wrapper: &str) -> Option<&'a str> {
+    kind.strip_prefix(wrapper)
+        .and_then(|kind| kind.strip_prefix('('))
+        .and_then(|kind| kind.strip_suffix(')'))
+}
+
+macro_rules! write_integer {
+    ($type:ty, $number:expr, $kind:expr, $value:expr, $buffer:expr) => {
+        $buffer.extend_from_slice(
+            &<$type>::try_from($number)
+                .map_err(|_| invalid_value($kind, $value))?
+                .to_le_bytes(),
+        )
+    };
+}
+
+/// Encode a value in `RowBinary` for a ClickHouse type.
+///
+/// The DateTime without timezone in the value are read in UTC.
+///
+/// # Examples
+///
+/// ```
+/// use chewdata::connector::clickhouse::encode;
+/// use serde_json::json;
+///
+/// let mut buffer = Vec::default();
+/// encode("Nullable(UInt16)", &json!(258), &mut buffer).unwrap();
+/// encode("Array(String)", &json!(["a"]), &mut buffer).unwrap();
+/// assert_eq!(vec![0, 2, 1, 1, 1, b'a'], buffer);
+///
+/// assert!(encode("UInt8", &json!(256), &mut buffer).is_err());
+/// assert!(encode("Decimal(10, 2)", &json!(1.5), &mut buffer).is_err());
+/// ```
+pub fn encode(kind: &str, value: &Value, buffer: &mut Vec<u8>) -> Result<()> {
+    let kind = kind.trim();
+
+    if let Some(inner) = inner_type(kind, "LowCardinality") {
+        return encode(inner, value, buffer);
+    }
+
+    if let Some(inner) = inner_type(kind, "Nullable") {
+        if value.is_null() {
+            buffer.push(1);
+            return Ok(());
+        }
+        buffer.push(0);
+        return encode(inner, value, buffer);
+    }
+
+    if let Some(inner) = inner_type(kind, "Array") {
+        let items = match value {
+            Value::Array(items) => items,
+            _ => return Err(invalid_value(kind, value)),
+        };
+        write_varint(items.len() as u64, buffer);
+        for item in items {
+            encode(inner, item, buffer)?;
+        }
+        return Ok(());
+    }
+
+    match kind {
+        "Bool" => match value {
+            Value::Bool(boolean) => buffer.push(u8::from(*boolean)),
+            _ => write_integer!(u8, integer(kind, value)?, kind, value, buffer),
+        },
+        "UInt8" => write_integer!(u8, integer(kind, value)?, kind, value, buffer),
+        "UInt16" => write_integer!(u16, integer(kind, value)?, kind, value, buffer),
+        "UInt32" => write_integer!(u32, integer(kind, value)?, kind, value, buffer),
+        "UInt64" => write_integer!(u64, integer(kind, value)?, kind, value, buffer),
+        "Int8" => write_integer!(i8, integer(kind, value)?, kind, value, buffer),
+        "Int16" => write_integer!(i16, integer(kind, value)?, kind, value, buffer),
+        "Int32" => write_integer!(i32, integer(kind, value)?, kind, value, buffer),
+        "Int64" => write_integer!(i64, integer(kind, value)?, kind, value, buffer),
+        "Float32" => buffer.extend_from_slice(&(float(kind, value)? as f32).to_le_bytes()),
+        "Float64" => buffer.extend_from_slice(&float(kind, value)?.to_le_bytes()),
+        "String" => {
+            let string = match value {
+                Value::String(string) => string.clone(),
+                Value::Null => return Err(invalid_value(kind, value)),
+                _ => value.to_string(),
+            };
+            write_varint(string.len() as u64, buffer);
+            buffer.extend_from_slice(string.as_bytes());
+        }
+        "Date" => write_integer!(u16, days(kind, value)?, kind, value, buffer),
+        "Date32" => write_integer!(i32, days(kind, value)?, kind, value, buffer),
+        _ if "DateTime" == kind || inner_type(kind, "DateTime").is_some() => {
+            write_integer!(u32, seconds(kind, value)?, kind, value, buffer)
+        }
+        _ => {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "The type '{}' is not supported with the RowBinary format",
+                    kind
+                ),
+            ))
+        }
+    };
+
+    Ok(())
+}
+
+/// Encode a record in `RowBinary` with the columns of the table.
+fn encode_row(columns: &[Column], value: &Value, buffer: &mut Vec<u8>) -> Result<()> {
+    for column in columns {
+        let field = value.get(&column.name).unwrap_or(&Value::Null);
+
+        encode(&column.kind, field, buffer)
+            .map_err(|e| Error::new(e.kind(), format!("Column '{}': {}", column.name, e)))?;
+    }
+
+    Ok(())
+}
+
+#[async_trait]
+impl Connector for Clickhouse {
+    /// See [`Connector::path`] for more details.
+    fn path(&self) -> String {
+        let mut table = self.table.clone();
+        table.replace_mustache(self.parameters.clone());
+
+        format!("{}/{}.{}", self.endpoint, self.database, table)
+    }
+    /// See [`Connector::set_parameters`] for more details.
+    fn set_parameters(&mut self, parameters: Value) {
+        self.parameters = parameters;
+    }
+    /// See [`Connector::is_variable`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::{clickhouse::Clickhouse, Connector};
+    ///
+    /// let mut connector = Clickhouse::default();
+    /// connector.table = "my_table".to_string();
+    /// assert_eq!(false, connector.is_variable());
+    /// connector.table = "my_table_{{ field }}".to_string();
+    /// assert_eq!(true, connector.is_variable());
+    /// ```
+    fn is_variable(&self) -> bool {
+        self.table.has_mustache()
+    }
+    /// See [`Connector::is_resource_will_change`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::{clickhouse::Clickhouse, Connector};
+    /// use serde_json::Value;
+    ///
+    /// let mut connector = Clickhouse::default();
+    /// let params: Value = serde_json::from_str(r#"{"field":"test"}"#).unwrap();
+    /// connector.table = "my_table".to_string();
+    /// assert_eq!(false, connector.is_resource_will_change(params.clone()).unwrap());
+    /// connector.table = "my_table_{{ field }}".to_string();
+    /// assert_eq!(true, connector.is_resource_will_change(params).unwrap());
+    /// ```
+    fn is_resource_will_change(&self, new_parameters: Value) -> Result<bool> {
+        if !self.is_variable() {
+            trace!("Stay link to the same resource");
+            return Ok(false);
+        }
+
+        let mut previous_table = self.table.clone();
+        previous_table.replace_mustache(self.parameters.clone());
+
+        let mut new_table = self.table.clone();
+        new_table.replace_mustache(new_parameters);
+
+        if previous_table == new_table {
+            trace!(table = previous_table, "The table has not changed");
+            return Ok(false);
+        }
+
+        info!(
+            previous_table = previous_table,
+            new_table = new_table,
+            "Will use another table based the new parameters"
+        );
+        Ok(true)
+    }
+    /// See [`Connector::len`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::{clickhouse::Clickhouse, Connector};
+    /// use chewdata::DataResult;
+    /// use std::io;
+    ///
+    /// use macro_rules_attribute::apply;
+    /// use smol_macros::main;
+    ///
+    /// #[apply(main!)]
+    /// async fn main() -> io::Result<()> {
+    ///     let mut connector = Clickhouse::default();
+    ///     connector.table = "orders".into();
+    ///     connector
+    ///         .send(&vec![DataResult::Ok(serde_json::json!({"number": 100}))])
+    ///         .await?;
+    ///
+    ///     let len = connector.len().await?;
+    ///     assert!(0 < len, "The connector should have a size upper than zero");
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[instrument(name = "clickhouse::len")]
+    async fn len(&self) -> Result<usize> {
+        let query = format!(
+            "SELECT count() FROM ({}) FORMAT TabSeparated",
+            self.query()?
+        );
+
+        let len = match self.execute(&query).await {
+            Ok(data) => String::from_utf8_lossy(&data)
+                .trim()
+                .parse::<usize>()
+                .unwrap_or_default(),
+            Err(e) => {
+                warn!(
+                    error = e.to_string(),
+                    "Can't count the number of element, return 0"
+                );
+                return Ok(0);
+            }
+        };
+
+        info!(len = len, "The connector found data in the table");
+        Ok(len)
+    }
+    /// See [`Connector::fetch`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::{clickhouse::Clickhouse, Connector};
+    /// use smol::stream::StreamExt;
+    /// use std::io;
+    ///
+    /// use macro_rules_attribute::apply;
+    /// use smol_macros::main;
+    ///
+    /// #[apply(main!)]
+    /// async fn main() -> io::Result<()> {
+    ///     let mut connector = Clickhouse::default();
+    ///     connector.query = Some("SELECT number FROM system.numbers LIMIT 10".into());
+    ///     let datastream = connector.fetch().await?.unwrap();
+    ///     assert_eq!(10, datastream.count().await);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[instrument(name = "clickhouse::fetch")]
+    async fn fetch(&mut self) -> std::io::Result<Option<DataStream>> {
+        let query = self.query()?;
+        let path = self.path_with_arguments(&query, &[("default_format", "JSONEachRow")]);
+
+        let (status, mut chunks) = self
+            .curl("plain")
+            .call_stream(&Method::POST, &path, Bytes::from(query))
+            .await?;
+
+        if status >= 400 {
+            let mut data = Vec::default();
+            while let Some(Ok(chunk)) = chunks.next().await {
+                data.extend_from_slice(&chunk);
+            }
+
+            return Err(Error::new(
+                ErrorKind::Interrupted,
+                format!(
+                    "The query failed with the status '{}': {}",
+                    status,
+                    String::from_utf8_lossy(&data).trim()
+                ),
+            ));
+        }
+
+        info!("Fetch data with success");
+
+        Ok(Some(json_lines(chunks)))
+    }
+    /// See [`Connector::send`] for more details.
+    ///
+    /// The stream returned contains the result of each record sent.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::{clickhouse::Clickhouse, Connector};
+    /// use chewdata::DataResult;
+    /// use smol::stream::StreamExt;
+    /// use std::io;
+    ///
+    /// use macro_rules_attribute::apply;
+    /// use smol_macros::main;
+    ///
+    /// #[apply(main!)]
+    /// async fn main() -> io::Result<()> {
+    ///     let mut connector = Clickhouse::default();
+    ///     connector.table = "orders".into();
+    ///
+    ///     let dataset = vec![DataResult::Ok(serde_json::from_str(r#"{"number":110}"#)?)];
+    ///     let results = connector.send(&dataset).await?.unwrap();
+    ///     assert_eq!(1, results.count().await);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[instrument(skip(dataset), name = "clickhouse::send")]
+    async fn send(&mut self, dataset: &DataSet) -> std::io::Result<Option<DataStream>> {
+        let table = self.table()?;
+        let mut results: Vec<Option<DataResult>> = Vec::default();
+        let mut positions: Vec<usize> = Vec::default();
+        let mut body: Vec<u8> = Vec::default();
+
+        let (query, mime_subtype) = match self.format {
+            Format::JsonEachRow => {
+                for (position, data) in dataset.iter().enumerate() {
+                    body.extend_from_slice(data.to_value().to_string().as_bytes());
+                    body.push(b'\n');
+                    results.push(None);
+                    positions.push(position);
+                }
+
+                (
+                    format!(
+                        "INSERT INTO {} FORMAT {}",
+                        quote_table(&table),
+                        self.format.as_str()
+                    ),
+                    "x-ndjson",
+                )
+            }
+            Format::RowBinary => {
+                let columns = self.describe(&table).await?;
+
+                for (position, data) in dataset.iter().enumerate() {
+                    let value = data.to_value();
+                    let mut row = Vec::default();
+
+                    match encode_row(&columns, &value, &mut row) {
+                        Ok(()) => {
+                            body.extend_from_slice(&row);
+                            results.push(None);
+                            positions.push(position);
+                        }
+                        Err(e) => {
+                            warn!(error = e.to_string(), "Can't encode the record");
+                            results.push(Some(DataResult::Err((value, e))));
+                        }
+                    }
+                }
+
+                let names: Vec<String> = columns
+                    .iter()
+                    .map(|column| quote_identifier(&column.name))
+                    .collect();
+
+                (
+                    format!(
+                        "INSERT INTO {} ({}) FORMAT {}",
+                        quote_table(&table),
+                        names.join(", "),
+                        self.format.as_str()
+                    ),
+                    "octet-stream",
+                )
+            }
+        };
+
+        if !positions.is_empty() {
+            let path = self.path_with_arguments(&query, &[("query", &query)]);
+
+            let error = match self
+                .curl(mime_subtype)
+                .call(&Method::POST, &path, Bytes::from(body))
+                .await
+            {
+                Ok((status, _)) if status < 400 => None,
+                Ok((status, data)) => Some(Error::new(
+                    ErrorKind::Interrupted,
+                    format!(
+                        "The insert failed with the status '{}': {}",
+                        status,
+                        String::from_utf8_lossy(&data).trim()
+                    ),
+                )),
+                Err(e) => Some(e),
+            };
+
+            if let Some(error) = &error {
+                warn!(
+                    error = error.to_string(),
+                    records = positions.len(),
+                    "Can't insert the batch"
+                );
+            }
+
+            for position in positions {
+                let value = dataset[position].to_value();
+
+                results[position] = Some(match &error {
+                    Some(error) => {
+                        DataResult::Err((value, Error::new(error.kind(), error.to_string())))
+                    }
+                    None => DataResult::Ok(value),
+                });
+            }
+        }
+
+        info!("Send data with success");
+
+        let results: Vec<DataResult> = results.into_iter().flatten().collect();
+
+        Ok(Some(Box::pin(stream! {
+            for data in results {
+                yield data;
+            }
+        })))
+    }
+    /// See [`Connector::has_send_results`] for more details.
+    fn has_send_results(&self) -> bool {
+        true
+    }
+    /// See [`Connector::erase`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::{clickhouse::Clickhouse, Connector};
+    /// use std::io;
+    ///
+    /// use macro_rules_attribute::apply;
+    /// use smol_macros::main;
+    ///
+    /// #[apply(main!)]
+    /// async fn main() -> io::Result<()> {
+    ///     let mut connector = Clickhouse::default();
+    ///     connector.table = "orders_copy".into();
+    ///     connector.erase().await?;
+    ///     assert_eq!(0, connector.len().await?);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[instrument(name = "clickhouse::erase")]
+    async fn erase(&mut self) -> Result<()> {
+        self.execute(&format!(
+            "TRUNCATE TABLE IF EXISTS {}",
+            quote_table(&self.table()?)
+        ))
+        .await?;
+
+        info!("Erase data with success");
+        Ok(())
+    }
+    /// See [`Connector::paginate`] for more details.
+    async fn paginate(
+        &self,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<Box<dyn Connector>>> + Send>>> {
+        let paginator = Once {};
+        paginator.paginate(self).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_channel::Receiver;
+    use macro_rules_attribute::apply;
+    use smol::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+    use smol::net::TcpListener;
+    use smol_macros::test;
+    use std::sync::Arc;
+
+    type Handler = Arc<dyn Fn(&str, &[u8]) -> (u16, Vec<String>) + Send + Sync>;
+
+    /// Start a ClickHouse server that answers each request with the handler in chunks and returns the requests received.
+    async fn clickhouse_server(handler: Handler) -> (String, Receiver<(String, Vec<u8>)>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        let (sender, receiver) = async_channel::unbounded();
+
+        smol::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let (sender, handler) = (sender.clone(), handler.clone());
+                smol::spawn(async move {
+                    let mut reader = BufReader::new(stream.clone());
+                    let mut writer = stream;
+                    loop {
+                        let mut request_line = String::default();
+                        if 0 == reader.read_line(&mut request_line).await.unwrap_or(0) {
+                            return;
+                        }
+                        let uri = request_line.split_whitespace().nth(1).unwrap_or_default();
+                        let path = match uri.split_once("://") {
+                            Some((_, uri)) => uri.find('/').map(|position| &uri[position..]).unwrap_or("/"),
+                            None => uri,
+                        }
+                        .to_string();
+                        let mut length = 0;
+                        loop {
+                            let mut line = String::default();
+                            if 0 == reader.read_line(&mut line).await.unwrap_or(0) {
+                                return;
+                            }
+                            match line.trim_end().split_once(':') {
+                                Some((name, value)) if name.eq_ignore_ascii_case("content-length") => {
+                                    length = value.trim().parse().unwrap()
+                                }
+                                Some(_) => (),
+                                None => break,
+                            }
+                        }
+                        let mut body = vec![0; length];
+                        reader.read_exact(&mut body).await.unwrap();
+                        let (status, chunks) = handler(&path, &body);
+                        sender.send((path, body)).await.unwrap();
+
+                        let mut response = format!(
+                            "HTTP/1.1 {} OK\r\nContent-Type: text/plain\r\nTransfer-Encoding: chunked\r\n\r\n",
+                            status
+                        );
+                        for chunk in chunks.iter().filter(|chunk| !chunk.is_empty()) {
+                            response.push_str(&format!("{:x}\r\n{}\r\n", chunk.len(), chunk));
+                        }
+                        response.push_str("0\r\n\r\n");
+                        writer.write_all(response.as_bytes()).await.unwrap();
+                    }
+                })
+                .detach();
+            }
+        })
+        .detach();
+
+        (endpoint, receiver)
+    }
+
+    fn ok() -> Handler {
+        Arc::new(|_, _| (200, Vec::default()))
+    }
+
+    fn connector(endpoint: String) -> Clickhouse {
+        Clickhouse {
+            endpoint,
+            table: "orders".to_string(),
+            timeout: Some(1),
+            ..Default::default()
+        }
+    }
+
+    fn dataset() -> DataSet {
+        vec![
+            DataResult::Ok(
+                serde_json::json!({"number": 1, "name": "a", "tags": ["x"], "score": null}),
+            ),
+            DataResult::Ok(serde_json::json!({"number": 2, "name": "b", "tags": [], "score": 1.5})),
+        ]
+    }
+
+    #[apply(test!)]
+    async fn send_json_each_row() {
+        let (endpoint, requests) = clickhouse_server(ok()).await;
+        let mut connector = connector(endpoint);
+        connector
+            .settings
+            .insert("async_insert".to_string(), 1.into());
+
+        let results: Vec<DataResult> = connector
+            .send(&dataset())
+            .await
+            .unwrap()
+            .unwrap()
+            .collect()
+            .await;
+        assert_eq!(dataset(), results);
+
+        let (path, body) = requests.recv().await.unwrap();
+        assert_eq!(
+            format!(
+                "/?database=default&async_insert=1&query={}",
+                percent_encode("INSERT INTO `orders` FORMAT JSONEachRow", false)
+            ),
+            path
+        );
+        let expected: String = dataset()
+            .iter()
+            .map(|data| format!("{}\n", data.to_value()))
+            .collect();
+        assert_eq!(expected, String::from_utf8(body).unwrap());
+    }
+    #[apply(test!)]
+    async fn send_with_batch_error() {
+        let (endpoint, _requests) = clickhouse_server(Arc::new(|_, _| {
+            (
+                500,
+                vec!["Code: 60. DB::Exception: Table default.orders does not exist.".to_string()],
+            )
+        }))
+        .await;
+        let mut connector = connector(endpoint);
+
+        let results: Vec<DataResult> = connector
+            .send(&dataset())
+            .await
+            .unwrap()
+            .unwrap()
+            .collect()
+            .await;
+        assert_eq!(2, results.len());
+        for result in results {
+            match result {
+                DataResult::Err((_, e)) => assert!(e.to_string().contains("Code: 60."), "{}", e),
+                DataResult::Ok(_) => panic!("The batch should be in error"),
+            }
+        }
+    }
+    #[apply(test!)]
+    async fn send_row_binary() {
+        let (endpoint, requests) =
+            clickhouse_server(Arc::new(|_, body| match body.starts_with(b"DESCRIBE") {
+                true => (
+                    200,
+                    vec![[
+                        r#"{"name":"number","type":"UInt32","default_type":""}"#,
+                        r#"{"name":"name","type":"LowCardinality(String)","default_type":""}"#,
+                        r#"{"name":"tags","type":"Array(String)","default_type":""}"#,
+                        r#"{"name":"score","type":"Nullable(Float64)","default_type":""}"#,
+                        r#"{"name":"total","type":"Float64","default_type":"MATERIALIZED"}"#,
+                    ]
+                    .join("\n")],
+                ),
+                false => (200, Vec::default()),
+            }))
+            .await;
+        let mut connector = connector(endpoint);
+        connector.format = Format::RowBinary;
+
+        let mut dataset = dataset();
+        dataset.push(DataResult::Ok(
+            serde_json::json!({"number": -1, "name": "c", "tags": []}),
+        ));
+        let results: Vec<DataResult> = connector
+            .send(&dataset)
+            .await
+            .unwrap()
+            .unwrap()
+            .collect()
+            .await;
+        assert!(results[0].is_type(DataResult::OK));
+        assert!(results[1].is_type(DataResult::OK));
+        assert!(results[2].is_type(DataResult::ERR));
+
+        let (_, body) = requests.recv().await.unwrap();
+        assert_eq!(
+            "DESCRIBE TABLE `orders` FORMAT JSONEachRow",
+            String::from_utf8(body).unwrap()
+        );
+        let (path, body) = requests.recv().await.unwrap();
+        assert!(path.contains(&percent_encode(
+            "INSERT INTO `orders` (`number`, `name`, `tags`, `score`) FORMAT RowBinary",
+            false
+        )));
+        let mut expected = vec![1, 0, 0, 0, 1, b'a', 1, 1, b'x', 1];
+        expected.extend_from_slice(&[2, 0, 0, 0, 1, b'b', 0, 0]);
+        expected.extend_from_slice(&1.5_f64.to_le_bytes());
+        assert_eq!(expected, body);
+
+        // The columns are described once per table.
+        connector.send(&dataset).await.unwrap();
+        let (_, body) = requests.recv().await.unwrap();
+        assert!(body.starts_with(&[1, 0, 0, 0]));
+    }
+    #[apply(test!)]
+    async fn fetch() {
+        let (endpoint, requests) = clickhouse_server(Arc::new(|_, _| {
+            (
+                200,
+                vec![
+                    "{\"number\":1}\n{\"num".to_string(),
+                    "ber\":2}\n".to_string(),
+                    "Code: 241. DB::Exception: Memory limit exceeded.\n".to_string(),
+                ],
+            )
+        }))
+        .await;
+        let mut connector = connector(endpoint);
+        connector.query = Some("SELECT number FROM orders WHERE number > {min:UInt32}".into());
+        connector.set_parameters(serde_json::json!({"min": 0, "other": "value"}));
+
+        let results: Vec<DataResult> = connector.fetch().await.unwrap().unwrap().collect().await;
+        assert_eq!(3, results.len());
+        assert_eq!(r#"{"number":1}"#, results[0].to_value().to_string());
+        assert_eq!(r#"{"number":2}"#, results[1].to_value().to_string());
+        assert!(results[2].is_type(DataResult::ERR));
+
+        let (path, body) = requests.recv().await.unwrap();
+        assert_eq!(
+            "/?database=default&param_min=0&default_format=JSONEachRow",
+            path
+        );
+        assert_eq!(
+            "SELECT number FROM orders WHERE number > {min:UInt32}",
+            String::from_utf8(body).unwrap()
+        );
+    }
+    #[apply(test!)]
+    async fn fetch_with_error() {
+        let (endpoint, _requests) = clickhouse_server(Arc::new(|_, _| {
+            (
+                404,
+                vec!["Code: 60. DB::Exception: Table default.orders does not exist.".to_string()],
+            )
+        }))
+        .await;
+        let mut connector = connector(endpoint);
+
+        match connector.fetch().await {
+            Err(e) => assert!(e.to_string().contains("Code: 60."), "{}", e),
+            Ok(_) => panic!("The fetch should fail"),
+        }
+    }
+    #[apply(test!)]
+    async fn json_lines_split_between_chunks() {
+        let chunks: ByteStream = Box::pin(futures::stream::iter(vec![
+            Ok(Bytes::from("{\"a\"")),
+            Ok(Bytes::from(":1}\n\n{\"a\":2")),
+            Ok(Bytes::from("}")),
+        ]));
+
+        let values: Vec<String> = json_lines(chunks)
+            .map(|data| data.to_value().to_string())
+            .collect()
+            .await;
+        assert_eq!(vec![r#"{"a":1}"#, r#"{"a":2}"#], values);
+    }
+    #[apply(test!)]
+    async fn len() {
+        let (endpoint, requests) =
+            clickhouse_server(Arc::new(|_, _| (200, vec!["2\n".to_string()]))).await;
+        let connector = connector(endpoint);
+
+        assert_eq!(2, connector.len().await.unwrap());
+        let (_, body) = requests.recv().await.unwrap();
+        assert_eq!(
+            "SELECT count() FROM (SELECT * FROM `orders`) FORMAT TabSeparated",
+            String::from_utf8(body).unwrap()
+        );
+    }
+    #[apply(test!)]
+    async fn erase() {
+        let (endpoint, requests) = clickhouse_server(ok()).await;
+        let mut connector = connector(endpoint);
+
+        connector.erase().await.unwrap();
+        let (_, body) = requests.recv().await.unwrap();
+        assert_eq!(
+            "TRUNCATE TABLE IF EXISTS `orders`",
+            String::from_utf8(body).unwrap()
+        );
+    }
+    #[test]
+    fn encode_types() {
+        let mut buffer = Vec::default();
+        encode("Date", &serde_json::json!("1970-01-11"), &mut buffer).unwrap();
+        encode(
+            "DateTime('UTC')",
+            &serde_json::json!("1970-01-01 00:01:00"),
+            &mut buffer,
+        )
+        .unwrap();
+        encode("Bool", &serde_json::json!(true), &mut buffer).unwrap();
+        encode("Int16", &serde_json::json!("-2"), &mut buffer).unwrap();
+        assert_eq!(vec![10, 0, 60, 0, 0, 0, 1, 254, 255], buffer);
+
+        let mut buffer = Vec::default();
+        write_varint(300, &mut buffer);
+        assert_eq!(vec![0xac, 0x02], buffer);
+
+        assert!(encode("String", &Value::Null, &mut buffer).is_err());
+        assert!(encode("UInt32", &serde_json::json!(-1), &mut buffer).is_err());
+    }
+}
@@ -26,6 +26,14 @@
 //! * Storage: OS temp directory (`cache/http`)
 //! * Cache is bypassed if response is stale
 //!
+//! ## Server-Sent Events
+//!
+//! With `is_event_stream`, the response is read incrementally on a dedicated connection and each event
+//! is parsed with the `Document`. The type of the event is added in the field `_event` and its id in the field `_event_id`.
+//! When the connection is lost, the connector waits the `retry` delay sent by the server, reconnects with
+//! the `Last-Event-ID` header and stops after 3 failed attempts or if the server answers `204 No Content`.
+//! The cache, the redirections and the paginator are not used in this mode.
+//!
 //! ### Configuration
 //!
 //! | key           | alias | Description                                              | Default Value | Possible Values                                                         |
@@ -45,6 +53,8 @@
 //! | version    | - | HTTP version|    `1`    | `1` / `2` |
 //! | is_cached  | cache | Enable the cache management. |    `false`    | `true` / `false` |
 //! | certificate | crt | Path to a local certificate file used to trust the HTTPS connection. | `null` | Local path of a .crt file |
+//! | is_event_stream | sse | Read the response as a `text/event-stream` and yield the records of each event. | `false` | `true` / `false` |
+//! | event_limit | - | Stop to read the event stream after N events. Read without limit if not set. | `null` | Unsigned number |
 //!
 //! ### Examples
 //!
@@ -83,6 +93,7 @@
 use super::authenticator::AuthenticatorType;
 use super::counter::curl::CounterType;
 use super::paginator::curl::PaginatorType;
+use super::paginator::once::Once;
 use super::Connector;
 use crate::document::Document;
 use crate::helper::mustache::Mustache;
@@ -108,7 +119,7 @@ use http::{
 };
 use http_body_util::{BodyExt, Empty, Full};
 use http_cache_semantics::{BeforeRequest, CachePolicy};
-use hyper::body::Body;
+use hyper::body::{Body, Incoming};
 use hyper::client::conn::http1::{Connection as ConnectionHttp1, SendRequest as SendRequestHttp1};
 use hyper::client::conn::http2::SendRequest as SendRequestHttp2;
 use json_value_merge::Merge;
@@ -144,8 +155,15 @@ const REDIRECT_CODES: &[StatusCode; 5] = &[
 const DEFAULT_TIMEOUT: u64 = 5;
 const DEFAULT_CACHE_DIR: &str = "cache/http";
 const DEFAULT_HOSTNAME: &str = "localhost";
+const DEFAULT_EVENT_STREAM_RETRY: Duration = Duration::from_secs(3);
+const EVENT_STREAM_CONTENT_TYPE: &str = "text/event-stream";
+const LAST_EVENT_ID: &str = "last-event-id";
+pub const FIELD_EVENT: &str = "_event";
+pub const FIELD_EVENT_ID: &str = "_event_id";
 
 type DynBody = Pin<Box<dyn Body<Data = Bytes, Error = io::Error> + Send + Sync>>;
+#[cfg(feature = "clickhouse")]
+pub(crate) type ByteStream = Pin<Box<dyn Stream<Item = io::Result<Bytes>> + Send>>;
 type SharedClients = DashMap<SharedClientKey, Arc<OnceCell<ClientType>>>;
 
 static CLIENTS: OnceLock<SharedClients> = OnceLock::new();
@@ -183,6 +201,10 @@ pub struct Curl {
     pub is_cached: bool,
     #[serde(alias = "crt")]
     pub certificate: Option<String>,
+    #[serde(alias = "sse")]
+    #[serde(alias = "event_stream")]
+    pub is_event_stream: bool,
+    pub event_limit: Option<usize>,
     #[serde(skip)]
     #[serde(default)]
     client: Option<ClientType>,
@@ -252,6 +274,8 @@ impl Clone for Curl {
             version: self.version,
             is_cached: self.is_cached,
             certificate: None,
+            is_event_stream: self.is_event_stream,
+            event_limit: self.event_limit,
             client: None,
         }
     }
@@ -286,6 +310,8 @@ impl fmt::Debug for Curl {
             .field("version", &self.version)
             .field("is_cached", &self.is_cached)
             .field("certificate", &self.certificate)
+            .field("is_event_stream", &self.is_event_stream)
+            .field("event_limit", &self.event_limit)
             .finish()
     }
 }
@@ -308,13 +334,15 @@ impl Default for Curl {
             version: Version::default(),
             is_cached: false,
             certificate: None,
+            is_event_stream: false,
+            event_limit: None,
             client: None,
         }
     }
 }
 
 /// A TCP or TCP+TLS connection.
-enum SmolStream {
+pub(crate) enum SmolStream {
     /// A plain TCP connection.
     Plain(TcpStream),
 
@@ -402,7 +430,7 @@ impl RetryPolicy {
     }
 }
 
-async fn backoff(attempt: usize, base_delay: Duration) {
+pub(crate) async fn backoff(attempt: usize, base_delay: Duration) {
     let max_delay = Duration::from_secs(30);
 
     let delay = base_delay
@@ -575,7 +603,154 @@ impl Curl {
             .map(|(key, value)| (key.to_string().clone(), value.as_bytes().to_vec()))
             .collect())
     }
+    /// Send a request on a path of the endpoint and return the status code with the response body.
+    ///
+    /// Used by the connectors that call an http api through this client.
+    #[cfg(any(
+        feature = "elasticsearch",
+        feature = "clickhouse",
+        all(test, feature = "http_server")
+    ))]
+    #[instrument(skip(body), name = "curl::call")]
+    pub(crate) async fn call(
+        &mut self,
+        method: &Method,
+        path: &str,
+        body: Bytes,
+    ) -> io::Result<(u16, Vec<u8>)> {
+        let (status, _, data) = self.call_with_headers(method, path, body).await?;
+
+        Ok((status, data))
+    }
+    /// Send a request on a path of the endpoint and return the status code with the response headers and body.
+    #[cfg(any(
+        feature = "elasticsearch",
+        feature = "clickhouse",
+        feature = "azure_blob",
+        feature = "gcs",
+        all(test, feature = "http_server")
+    ))]
+    #[instrument(skip(body), name = "curl::call_with_headers")]
+    pub(crate) async fn call_with_headers(
+        &mut self,
+        method: &Method,
+        path: &str,
+        body: Bytes,
+    ) -> io::Result<(u16, HashMap<String, String>, Vec<u8>)> {
+        let uri = format!("{}{}", self.endpoint, path);
+        let request_builder = self
+            .request_builder(Some(&uri), Some(method), Some(&body))
+            .await?;
+        let entry = self.follow_redirects(request_builder, &body).await?;
+
+        Ok((entry.status, entry.resp_headers, entry.data))
+    }
+    /// Send a request on a path of the endpoint with a dedicated connection and return the status code with the response body read chunk by chunk.
+    ///
+    /// Used by the connectors that read large responses without loading them in memory.
+    #[cfg(feature = "clickhouse")]
+    #[instrument(skip(body), name = "curl::call_stream")]
+    pub(crate) async fn call_stream(
+        &mut self,
+        method: &Method,
+        path: &str,
+        body: Bytes,
+    ) -> io::Result<(u16, ByteStream)> {
+        let uri = format!("{}{}", self.endpoint, path);
+        let request_builder = self
+            .request_builder(Some(&uri), Some(method), Some(&body))
+            .await?;
+        let (client, response) = self
+            .send_with_dedicated_connection(request_builder, &body)
+            .await?;
+        let status = response.status().as_u16();
+        let mut response_body = response.into_body();
+
+        Ok((
+            status,
+            Box::pin(stream! {
+                // Keep the client alive until the end of the body.
+                let _client = client;
+
+                while let Some(frame) = response_body.frame().await {
+                    match frame {
+                        Ok(frame) => {
+                            if let Ok(chunk) = frame.into_data() {
+                                yield Ok(chunk);
+                            }
+                        }
+                        Err(e) => {
+                            yield Err(Error::new(ErrorKind::Interrupted, e));
+                            break;
+                        }
+                    }
+                }
+            }),
+        ))
+    }
     /// Return parameter's values without context.
+    /// Send a request on a path of the endpoint, block the current thread until the response and return the status code with the response body.
+    ///
+    /// The connection is dedicated to the request and driven by the current thread instead of a task of the executor,
+    /// so the call doesn't wait the executor blocked by the caller. Used by the synchronous documents called in the steps.
+    #[cfg(feature = "avro")]
+    #[instrument(skip(body), name = "curl::call_blocking")]
+    pub(crate) fn call_blocking(
+        &mut self,
+        method: &Method,
+        path: &str,
+        body: Bytes,
+    ) -> io::Result<(u16, Vec<u8>)> {
+        use hyper::client::conn::http1;
+
+        let timeout = self.timeout.unwrap_or(DEFAULT_TIMEOUT);
+        let uri = format!("{}{}", self.endpoint, path);
+
+        smol::block_on(
+            async {
+                let request_builder = self
+                    .request_builder(Some(&uri), Some(method), Some(&body))
+                    .await?;
+                let request = build_request(request_builder, &body)?;
+                let stream = connect(&self.endpoint, timeout, self.certificate.clone()).await?;
+                let (mut sender, connection) = http1::Builder::new()
+                    .handshake::<_, DynBody>(FuturesIo::new(stream))
+                    .await
+                    .map_err(|e| Error::new(ErrorKind::ConnectionAborted, e))?;
+
+                let response = async {
+                    let response = sender
+                        .send_request(request)
+                        .await
+                        .map_err(|e| Error::new(ErrorKind::Interrupted, e))?;
+                    let status = response.status().as_u16();
+                    let data = response
+                        .into_body()
+                        .collect()
+                        .await
+                        .map_err(|e| Error::new(ErrorKind::Interrupted, e))?
+                        .to_bytes();
+
+                    Ok((status, data.to_vec()))
+                };
+                // Drive the connection until the end of the response.
+                let connection = async {
+                    connection
+                        .await
+                        .map_err(|e| Error::new(ErrorKind::ConnectionAborted, e))?;
+
+                    Err(Error::new(
+                        ErrorKind::ConnectionAborted,
+                        "The connection is closed before the end of the response",
+                    ))
+                };
+
+                smol::future::or(response, connection).await
+            }
+            .timeout(Duration::from_secs(timeout)),
+        )
+        .ok_or_else(|| Error::new(ErrorKind::TimedOut, "request timeout"))?
+    }
     fn parameters_without_context(&self) -> Result<Value> {
         Ok(match self.parameters.clone().search("/input")? {
             Some(input) => input,
@@ -647,10 +822,19 @@ impl Curl {
                 return Ok(entry_to_cache);
             }
 
-            let location = entry_to_cache
-                .resp_headers
-                .get(header::LOCATION.as_str())
-                .ok_or_else(|| Error::new(ErrorKind::InvalidData, "Missing Location header"))?;
+            // A '308 Resume Incomplete' of a resumable upload has no Location header and is not a redirection.
+            let location = match entry_to_cache.resp_headers.get(header::LOCATION.as_str()) {
+                Some(location) => location,
+                None if StatusCode::PERMANENT_REDIRECT.as_u16() == entry_to_cache.status => {
+                    return Ok(entry_to_cache)
+                }
+                None => {
+                    return Err(Error::new(
+                        ErrorKind::InvalidData,
+                        "Missing Location header",
+                    ))
+                }
+            };
 
             current_uri = if location.to_string().starts_with("/") {
                 format!("{}{}", endpoint, location)
@@ -668,11 +852,11 @@ impl Curl {
                     current_method = Method::GET;
                     bytes = Bytes::new(); // drop body
                 }
-                hyper::StatusCode::MOVED_PERMANENTLY | hyper::StatusCode::FOUND => {
-                    if current_method == Method::POST {
-                        current_method = Method::GET;
-                        bytes = Bytes::new();
-                    }
+                hyper::StatusCode::MOVED_PERMANENTLY | hyper::StatusCode::FOUND
+                    if current_method == Method::POST =>
+                {
+                    current_method = Method::GET;
+                    bytes = Bytes::new();
                 }
                 hyper::StatusCode::TEMPORARY_REDIRECT | hyper::StatusCode::PERMANENT_REDIRECT => {
                     // keep method + body
@@ -770,6 +954,320 @@ impl Curl {
 
         Err(Error::new(ErrorKind::TimedOut, "retry limit exceeded"))
     }
+    /// Send a request with a dedicated connection and return the client with the response.
+    ///
+    /// The response body keeps the connection busy, so it can't be shared with the other requests.
+    async fn send_with_dedicated_connection(
+        &self,
+        request_builder: Builder,
+        body: &Bytes,
+    ) -> io::Result<(ClientType, Response<Incoming>)> {
+        let timeout = self.timeout.unwrap_or(DEFAULT_TIMEOUT);
+        let client = match self.version {
+            Version::HTTP_2 => ClientType::Http2(
+                http2(self.endpoint.clone(), timeout, self.certificate.clone()).await?,
+            ),
+            _ => ClientType::Http1(Arc::new(Mutex::new(
+                http1(self.endpoint.clone(), timeout, self.certificate.clone()).await?,
+            ))),
+        };
+
+        let request = build_request(request_builder, body)?;
+        let response = match &client {
+            ClientType::Http1(sender) => {
+                let mut sender = sender.lock().await;
+                sender
+                    .send_request(request)
+                    .timeout(Duration::from_secs(timeout))
+                    .await
+            }
+            ClientType::Http2(sender) => {
+                sender
+                    .clone()
+                    .send_request(request)
+                    .timeout(Duration::from_secs(timeout))
+                    .await
+            }
+        }
+        .ok_or_else(|| Error::new(ErrorKind::TimedOut, "request timeout"))?
+        .map_err(|e| Error::new(ErrorKind::Interrupted, e))?;
+
+        Ok((client, response))
+    }
+    /// Open a dedicated connection on a `text/event-stream` resource and return the client with the response body.
+    ///
+    /// Return `None` if the server answers `204 No Content` in order to stop the reconnections.
+    async fn open_event_stream(
+        &mut self,
+        body: &Bytes,
+        last_event_id: Option<&str>,
+    ) -> io::Result<Option<(ClientType, Incoming)>> {
+        let mut request_builder = self.request_builder(None, None, Some(body)).await?;
+
+        if let Some(headers) = request_builder.headers_mut() {
+            headers.insert(
+                header::ACCEPT,
+                HeaderValue::from_static(EVENT_STREAM_CONTENT_TYPE),
+            );
+            headers.insert(header::CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+
+            if let Some(last_event_id) = last_event_id {
+                headers.insert(
+                    HeaderName::from_static(LAST_EVENT_ID),
+                    HeaderValue::from_str(last_event_id)
+                        .map_err(|e| Error::new(ErrorKind::InvalidData, e))?,
+                );
+            }
+        }
+
+        let (client, response) = self
+            .send_with_dedicated_connection(request_builder, body)
+            .await?;
+
+        if StatusCode::NO_CONTENT == response.status() {
+            info!("The server asks to stop the event stream");
+            return Ok(None);
+        }
+
+        if !response.status().is_success() {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "The server answers with the status '{}' for the event stream",
+                    response.status()
+                ),
+            ));
+        }
+
+        let content_type = response
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default();
+        if !content_type.starts_with(EVENT_STREAM_CONTENT_TYPE) {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("The content type '{}' is not an event stream", content_type),
+            ));
+        }
+
+        Ok(Some((client, response.into_body())))
+    }
+    /// Read the response as an event stream and yield the records of each event.
+    #[instrument(name = "curl::fetch_event_stream", skip(self))]
+    async fn fetch_event_stream(&mut self) -> io::Result<Option<DataStream>> {
+        let mut parameters_without_context = self.parameters_without_context()?;
+        parameters_without_context.replace_mustache(self.parameters.clone());
+        let body = self
+            .body(&vec![DataResult::Ok(parameters_without_context)])
+            .await?;
+        let document = self.document()?.clone_box();
+        let event_limit = self.event_limit;
+        let max_attempts = RetryPolicy::default().max_attempts;
+        let mut connector = self.clone();
+
+        let mut connection = self.open_event_stream(&body, None).await?;
+
+        info!("Open the event stream with success");
+
+        Ok(Some(Box::pin(stream! {
+            let mut parser = EventStreamParser::default();
+            let mut events = 0;
+            let mut attempt = 0;
+
+            'connection: loop {
+                // Keep the client alive until the end of the body.
+                let (_client, mut response_body) = match connection.take() {
+                    Some(connection) => connection,
+                    None => break,
+                };
+
+                while let Some(frame) = response_body.frame().await {
+                    let chunk = match frame {
+                        Ok(frame) => match frame.into_data() {
+                            Ok(chunk) => chunk,
+                            Err(_) => continue,
+                        },
+                        Err(e) => {
+                            warn!(error = e.to_string().as_str(), "The event stream is interrupted");
+                            break;
+                        }
+                    };
+
+                    for event in parser.push(&chunk) {
+                        if event_limit.is_some_and(|limit| events >= limit) {
+                            break 'connection;
+                        }
+                        events += 1;
+
+                        match document.has_data(event.data.as_bytes()) {
+                            Ok(false) => continue,
+                            Ok(true) => (),
+                            Err(e) => {
+                                yield with_event(DataResult::Err((Value::String(event.data.clone()), e)), &event);
+                                continue;
+                            }
+                        }
+
+                        match document.read(event.data.as_bytes()) {
+                            Ok(dataset) => {
+                                for data in dataset {
+                                    yield with_event(data, &event);
+                                }
+                            }
+                            Err(e) => {
+                                yield with_event(DataResult::Err((Value::String(event.data.clone()), e)), &event);
+                            }
+                        }
+                    }
+
+                    if event_limit.is_some_and(|limit| events >= limit) {
+                        break 'connection;
+                    }
+                }
+
+                info!(last_event_id = parser.last_event_id, "The event stream is closed, reconnect");
+
+                connection = loop {
+                    backoff(attempt, parser.retry).await;
+
+                    match connector.open_event_stream(&body, parser.last_event_id.as_deref()).await {
+                        Ok(connection) => {
+                            attempt = 0;
+                            break connection;
+                        }
+                        Err(e) if attempt + 1 < max_attempts => {
+                            warn!(error = e.to_string().as_str(), attempt, "Can't reconnect to the event stream, retry");
+                            attempt += 1;
+                        }
+                        Err(e) => {
+                            warn!(error = e.to_string().as_str(), "Can't reconnect to the event stream");
+                            break 'connection;
+                        }
+                    }
+                };
+            }
+
+            info!(events, "Stop to read the event stream");
+        })))
+    }
+}
+
+/// An event received from a `text/event-stream` response.
+#[derive(Debug, Default, Clone, PartialEq)]
+struct ServerSentEvent {
+    id: Option<String>,
+    event: Option<String>,
+    data: String,
+}
+
+/// Parse incrementally the frames of a `text/event-stream` response.
+///
+/// See <https://html.spec.whatwg.org/multipage/server-sent-events.html#event-stream-interpretation>.
+#[derive(Debug)]
+struct EventStreamParser {
+    buffer: Vec<u8>,
+    event: ServerSentEvent,
+    last_event_id: Option<String>,
+    retry: Duration,
+}
+
+impl Default for EventStreamParser {
+    fn default() -> Self {
+        EventStreamParser {
+            buffer: Vec::default(),
+            event: ServerSentEvent::default(),
+            last_event_id: None,
+            retry: DEFAULT_EVENT_STREAM_RETRY,
+        }
+    }
+}
+
+impl EventStreamParser {
+    /// Add a chunk of the body and return the events completed.
+    fn push(&mut self, chunk: &[u8]) -> Vec<ServerSentEvent> {
+        self.buffer.extend_from_slice(chunk);
+
+        let mut events = Vec::default();
+        while let Some(position) = self.buffer.iter().position(|byte| b'\n' == *byte) {
+            let mut line: Vec<u8> = self.buffer.drain(..=position).collect();
+            line.pop();
+            if line.last() == Some(&b'\r') {
+                line.pop();
+            }
+
+            if let Some(event) = self.parse_line(&String::from_utf8_lossy(&line)) {
+                events.push(event);
+            }
+        }
+
+        events
+    }
+    /// Apply a line on the current event and return it if the line ends it.
+    fn parse_line(&mut self, line: &str) -> Option<ServerSentEvent> {
+        if line.is_empty() {
+            let mut event = std::mem::take(&mut self.event);
+            if event.data.is_empty() {
+                return None;
+            }
+            event.data.pop();
+            event.id = self.last_event_id.clone();
+            return Some(event);
+        }
+
+        // A line starting with a colon is a comment.
+        if line.starts_with(':') {
+            return None;
+        }
+
+        let (field, value) = match line.split_once(':') {
+            Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+            None => (line, ""),
+        };
+
+        match field {
+            "data" => {
+                self.event.data.push_str(value);
+                self.event.data.push('\n');
+            }
+            "event" => self.event.event = Some(value.to_string()),
+            "id" if !value.contains('\0') => {
+                self.last_event_id = match value.is_empty() {
+                    true => None,
+                    false => Some(value.to_string()),
+                }
+            }
+            "retry" => {
+                if let Ok(retry) = value.parse::<u64>() {
+                    self.retry = Duration::from_millis(retry);
+                }
+            }
+            _ => (),
+        }
+
+        None
+    }
+}
+
+/// Add the type and the id of the event in the record.
+fn with_event(data: DataResult, event: &ServerSentEvent) -> DataResult {
+    let add_event = |mut value: Value| {
+        if let Value::Object(map) = &mut value {
+            map.insert(
+                FIELD_EVENT.to_string(),
+                Value::String(event.event.clone().unwrap_or("message".to_string())),
+            );
+            if let Some(id) = &event.id {
+                map.insert(FIELD_EVENT_ID.to_string(), Value::String(id.clone()));
+            }
+        }
+        value
+    };
+
+    match data {
+        DataResult::Ok(value) => DataResult::Ok(add_event(value)),
+        DataResult::Err((value, e)) => DataResult::Err((add_event(value), e)),
+    }
 }
 
 async fn get_or_create_client(
@@ -1014,6 +1512,10 @@ impl Connector for Curl {
     /// ```
     #[instrument(name = "curl::fetch", skip(self))]
     async fn fetch(&mut self) -> std::io::Result<Option<DataStream>> {
+        if self.is_event_stream {
+            return self.fetch_event_stream().await;
+        }
+
         let mut parameters_without_context = self.parameters_without_context()?;
         parameters_without_context.replace_mustache(self.parameters.clone());
         let dataset = vec![DataResult::Ok(parameters_without_context)];
@@ -1172,6 +1674,11 @@ impl Connector for Curl {
     async fn paginate(
         &self,
     ) -> Result<Pin<Box<dyn Stream<Item = Result<Box<dyn Connector>>> + Send>>> {
+        // The event stream is read without end on the same resource.
+        if self.is_event_stream {
+            return Once {}.paginate(self).await;
+        }
+
         self.paginator_type.paginate(self).await
     }
 }
@@ -1325,35 +1832,36 @@ impl SharedClientKey {
     }
 }
 
-async fn http1(
-    endpoint: String,
+/// Open a TCP connection to the endpoint, secured by TLS when the scheme is `https` or `wss`.
+pub(crate) async fn connect(
+    endpoint: &str,
     timeout: u64,
     has_certificate: Option<String>,
-) -> io::Result<SendRequestHttp1<DynBody>> {
-    use hyper::client::conn::http1;
-
+) -> io::Result<SmolStream> {
     let base = endpoint
         .parse::<hyper::Uri>()
         .map_err(|e| io::Error::new(ErrorKind::InvalidData, e))?;
 
-    let scheme: Scheme = base
+    let is_secure = match base
         .scheme_str()
         .ok_or_else(|| io::Error::new(ErrorKind::InvalidInput, "missing scheme"))?
-        .try_into()
-        .map_err(|_| io::Error::new(ErrorKind::InvalidInput, "unsupported scheme"))?;
+    {
+        "http" | "ws" => false,
+        "https" | "wss" => true,
+        _ => {
+            return Err(io::Error::new(
+                ErrorKind::InvalidInput,
+                "unsupported scheme",
+            ))
+        }
+    };
 
     let host: String = base
         .host()
         .ok_or_else(|| io::Error::new(ErrorKind::InvalidInput, "missing host"))?
         .to_owned();
 
-    let port = base.port_u16().unwrap_or(if scheme == Scheme::HTTP {
-        80
-    } else if scheme == Scheme::HTTPS {
-        443
-    } else {
-        return Err(io::Error::new(ErrorKind::InvalidInput, "unsupported port"));
-    });
+    let port = base.port_u16().unwrap_or(if is_secure { 443 } else { 80 });
 
     let tcp = match TcpStream::connect((host.clone(), port))
         .timeout(Duration::from_secs(timeout))
@@ -1366,39 +1874,47 @@ async fn http1(
 
     tcp.set_nodelay(true)?;
 
-    let stream = if scheme == Scheme::HTTP {
-        SmolStream::Plain(tcp)
-    } else if scheme == Scheme::HTTPS {
-        let mut roots = RootCertStore::empty();
-        roots.extend(TLS_SERVER_ROOTS.iter().cloned());
+    if !is_secure {
+        return Ok(SmolStream::Plain(tcp));
+    }
 
-        if let Some(certificate_path) = has_certificate {
-            let iter = CertificateDer::pem_file_iter(certificate_path)
-                .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+    let mut roots = RootCertStore::empty();
+    roots.extend(TLS_SERVER_ROOTS.iter().cloned());
 
-            let certs: Vec<CertificateDer<'_>> = iter.filter_map(|res| res.ok()).collect();
+    if let Some(certificate_path) = has_certificate {
+        let iter = CertificateDer::pem_file_iter(certificate_path)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
 
-            roots.add_parsable_certificates(certs.into_iter());
-        }
+        let certs: Vec<CertificateDer<'_>> = iter.filter_map(|res| res.ok()).collect();
 
-        let mut config = ClientConfig::builder()
-            .with_root_certificates(roots)
-            .with_no_client_auth();
+        roots.add_parsable_certificates(certs);
+    }
 
-        config.alpn_protocols.clear();
+    let mut config = ClientConfig::builder()
+        .with_root_certificates(roots)
+        .with_no_client_auth();
 
-        let connector = TlsConnector::from(Arc::new(config));
-        let server_name = rustls::pki_types::ServerName::try_from(host)
-            .map_err(|_| io::Error::new(ErrorKind::InvalidInput, "invalid DNS name"))?;
+    config.alpn_protocols.clear();
 
-        let tls = connector.connect(server_name, tcp).await?;
-        SmolStream::Tls(Box::new(futures_rustls::TlsStream::Client(tls)))
-    } else {
-        return Err(io::Error::new(
-            ErrorKind::InvalidInput,
-            "unsupported scheme",
-        ));
-    };
+    let connector = TlsConnector::from(Arc::new(config));
+    let server_name = rustls::pki_types::ServerName::try_from(host)
+        .map_err(|_| io::Error::new(ErrorKind::InvalidInput, "invalid DNS name"))?;
+
+    let tls = connector.connect(server_name, tcp).await?;
+
+    Ok(SmolStream::Tls(Box::new(
+        futures_rustls::TlsStream::Client(tls),
+    )))
+}
+
+async fn http1(
+    endpoint: String,
+    timeout: u64,
+    has_certificate: Option<String>,
+) -> io::Result<SendRequestHttp1<DynBody>> {
+    use hyper::client::conn::http1;
+
+    let stream = connect(&endpoint, timeout, has_certificate).await?;
 
     let (sender, connection): (
         SendRequestHttp1<DynBody>,
@@ -1467,7 +1983,7 @@ async fn http2(
 
         let certs: Vec<CertificateDer<'_>> = iter.filter_map(|res| res.ok()).collect();
 
-        roots.add_parsable_certificates(certs.into_iter());
+        roots.add_parsable_certificates(certs);
     }
 
     let mut config = ClientConfig::builder()
@@ -1776,4 +2292,130 @@ mod tests {
             result
         );
     }
+    #[test]
+    fn event_stream_parser() {
+        let mut parser = EventStreamParser::default();
+        assert_eq!(
+            Vec::<ServerSentEvent>::default(),
+            parser.push(b": comment\nretry: 10\nid: 1\nevent: update\ndata: {\"number\":")
+        );
+        assert_eq!(Duration::from_millis(10), parser.retry);
+        assert_eq!(
+            vec![
+                ServerSentEvent {
+                    id: Some("1".to_string()),
+                    event: Some("update".to_string()),
+                    data: "{\"number\":1}".to_string(),
+                },
+                ServerSentEvent {
+                    id: Some("1".to_string()),
+                    event: None,
+                    data: "line1\nline2".to_string(),
+                }
+            ],
+            parser.push(b"1}\r\n\r\ndata:line1\ndata: line2\n\nid\n\n")
+        );
+        assert_eq!(None, parser.last_event_id);
+    }
+    fn event_stream_connector(listener: &smol::net::TcpListener) -> Curl {
+        let mut connector = Curl {
+            endpoint: format!("http://{}", listener.local_addr().unwrap()),
+            path: "/events".to_string(),
+            is_event_stream: true,
+            ..Default::default()
+        };
+        connector.set_document(Box::new(Json::default())).unwrap();
+        connector
+    }
+    async fn event_stream_server(
+        listener: smol::net::TcpListener,
+        responses: Vec<&'static str>,
+    ) -> Vec<String> {
+        use futures::AsyncReadExt;
+
+        let mut requests = Vec::default();
+        for response in responses {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = Vec::default();
+            let mut buffer = [0; 1024];
+            while !request.ends_with(b"\r\n\r\n") {
+                let size = stream.read(&mut buffer).await.unwrap();
+                request.extend_from_slice(&buffer[..size]);
+            }
+            requests.push(String::from_utf8(request).unwrap().to_lowercase());
+            stream.write_all(response.as_bytes()).await.unwrap();
+            stream.close().await.unwrap();
+        }
+        requests
+    }
+    #[apply(test!)]
+    async fn fetch_event_stream_and_reconnect() {
+        let listener = smol::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let mut connector = event_stream_connector(&listener);
+
+        let server = smol::spawn(event_stream_server(
+            listener,
+            vec![
+                "HTTP/1.1 200 OK\r\ncontent-type: text/event-stream\r\nconnection: close\r\n\r\nretry: 10\n\nid: 1\nevent: update\ndata: {\"number\":1}\n\n",
+                "HTTP/1.1 200 OK\r\ncontent-type: text/event-stream\r\nconnection: close\r\n\r\nid: 2\ndata: {\"number\":2}\n\n",
+                "HTTP/1.1 204 No Content\r\ncontent-length: 0\r\n\r\n",
+            ],
+        ));
+
+        let dataset: Vec<Value> = connector
+            .fetch()
+            .await
+            .unwrap()
+            .unwrap()
+            .map(|data| data.to_value())
+            .collect()
+            .await;
+        assert_eq!(
+            vec![
+                serde_json::json!({"number":1,"_event":"update","_event_id":"1"}),
+                serde_json::json!({"number":2,"_event":"message","_event_id":"2"}),
+            ],
+            dataset
+        );
+
+        let requests = server.await;
+        assert!(requests[0].contains("accept: text/event-stream"));
+        assert!(!requests[0].contains("last-event-id"));
+        assert!(requests[1].contains("last-event-id: 1"));
+        assert!(requests[2].contains("last-event-id: 2"));
+    }
+    #[apply(test!)]
+    async fn fetch_event_stream_with_limit() {
+        let listener = smol::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let mut connector = event_stream_connector(&listener);
+        connector.event_limit = Some(2);
+
+        let server = smol::spawn(event_stream_server(
+            listener,
+            vec![
+                "HTTP/1.1 200 OK\r\ncontent-type: text/event-stream\r\nconnection: close\r\n\r\ndata: {\"number\":1}\n\ndata: {\"number\":2}\n\ndata: {\"number\":3}\n\n",
+            ],
+        ));
+
+        let dataset: Vec<DataResult> = connector.fetch().await.unwrap().unwrap().collect().await;
+        assert_eq!(2, dataset.len());
+
+        server.await;
+    }
+    #[apply(test!)]
+    async fn fetch_event_stream_with_wrong_content_type() {
+        let listener = smol::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let mut connector = event_stream_connector(&listener);
+
+        let server = smol::spawn(event_stream_server(
+            listener,
+            vec![
+                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: 2\r\n\r\n{}",
+            ],
+        ));
+
+        assert!(connector.fetch().await.is_err());
+
+        server.await;
+    }
 }
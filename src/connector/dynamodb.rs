@@ -0,0 +1,1247 @@
+//! Read and write data into an Amazon DynamoDB table.
+//!
+//! The records are read with a `Query` if a `key_condition` is set, otherwise with a `Scan` of the table or the `index`.
+//! The pages are read through the paginator with the `LastEvaluatedKey` of the previous page.
+//!
+//! The records are written by batch of twenty five with `BatchWriteItem` and the unprocessed items are sent again with an exponential backoff up to `retry` times.
+//! If a `condition` is set, each record is written with a conditional `PutItem` and the records that don't match the condition are returned in error.
+//! The erase removes all the items of the table.
+//!
+//! The expressions use the `names` and the `values` placeholders. The `values` can use mustache variables rendered with the parameters of the connector
+//! and, in a condition, with the record in `input`.
+//!
+//! The json values are mapped to the DynamoDB types like the psql connector: a number is a `N`, a string is a `S`, a boolean is a `BOOL`, a null is a `NULL`,
+//! an array is a `L` and an object is a `M`. The sets are read as arrays and the binaries as strings.
+//!
+//! ### Configuration
+//!
+//! | key             | alias            | Description                                                                                | Default Value | Possible Values       |
+//! | --------------- | ---------------- | ------------------------------------------------------------------------------------------ | ------------- | --------------------- |
+//! | type            | -                | Required in order to use this connector                                                    | `dynamodb`    | `dynamodb` / `dynamo` |
+//! | endpoint        | -                | Endpoint of the service. Use `DYNAMODB_ENDPOINT` or `AWS_ENDPOINT_URL_DYNAMODB` if not set | `null`        | String                |
+//! | region          | -                | The region of the table. Use `AWS_REGION` or `AWS_DEFAULT_REGION` if not set               | `us-east-1`   | String                |
+//! | table           | col / collection | The table name                                                                             | ``            | String                |
+//! | index           | idx              | The secondary index to read                                                                | `null`        | String                |
+//! | key_condition   | query            | The key condition expression. Query the table if set, scan it otherwise                    | `null`        | String                |
+//! | filter          | -                | The filter expression applied on the items read                                            | `null`        | String                |
+//! | condition       | -                | The condition expression to put each record. Write by batch if not set                     | `null`        | String                |
+//! | names           | -                | The placeholders of the attribute names used in the expressions                            | `null`        | List of Key/Value     |
+//! | values          | -                | The placeholders of the attribute values used in the expressions. Can use mustache         | `null`        | Object                |
+//! | consistent_read | -                | Read the items with a strongly consistent read                                             | `false`       | `true` / `false`      |
+//! | retry           | -                | Number of attempts to write the unprocessed items                                          | `5`           | Unsigned number       |
+//! | parameters      | params           | Parameters used to render the values                                                       | `null`        | Json structure        |
+//! | paginator       | -                | Paginator parameters                                                                       | [`crate::connector::paginator::dynamodb::last_evaluated_key::LastEvaluatedKey`] | [`crate::connector::paginator::dynamodb::last_evaluated_key::LastEvaluatedKey`] |
+//!
+//! Credentials are read from the `DYNAMODB_ACCESS_KEY_ID` and `DYNAMODB_SECRET_ACCESS_KEY` variables if set, then like the other AWS clients.
+//!
+//! ### Examples
+//!
+//! ```json
+//! [
+//!     {
+//!         "type": "r",
+//!         "connector":{
+//!             "type": "dynamodb",
+//!             "table": "orders",
+//!             "key_condition": "customer = :customer",
+//!             "filter": "#status <> :status",
+//!             "names": { "#status": "status" },
+//!             "values": { ":customer": "{{ customer }}", ":status": "cancelled" },
+//!             "paginator": {
+//!                 "type": "last_evaluated_key",
+//!                 "limit": 100
+//!             }
+//!         }
+//!     },
+//!     {
+//!         "type": "w",
+//!         "connector":{
+//!             "type": "dynamodb",
+//!             "table": "orders_archive",
+//!             "condition": "attribute_not_exists(id) OR version < :version",
+//!             "values": { ":version": "{{ input.version }}" }
+//!         }
+//!     }
+//! ]
+//! ```
+use super::paginator::dynamodb::PaginatorType;
+use super::Connector;
+use crate::helper::aws::shared_client;
+use crate::helper::mustache::Mustache;
+use crate::helper::string::DisplayOnlyForDebugging;
+use crate::{DataResult, DataSet, DataStream};
+use async_compat::Compat;
+use async_stream::stream;
+use async_trait::async_trait;
+use aws_sdk_dynamodb::error::DisplayErrorContext;
+use aws_sdk_dynamodb::primitives::Blob;
+use aws_sdk_dynamodb::types::{AttributeValue, DeleteRequest, PutRequest, Select, WriteRequest};
+use aws_sdk_dynamodb::Client;
+use futures::Stream;
+use json_value_merge::Merge;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Number, Value};
+use std::collections::HashMap;
+use std::env;
+use std::pin::Pin;
+use std::time::Duration;
+use std::{
+    fmt,
+    io::{Error, ErrorKind, Result},
+};
+
+const DEFAULT_REGION: &str = "us-east-1";
+const DEFAULT_RETRY: usize = 5;
+const RETRY_DELAY: Duration = Duration::from_millis(100);
+const MAX_BATCH_SIZE: usize = 25;
+
+type Item = HashMap<String, AttributeValue>;
+
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct Dynamodb {
+    pub endpoint: Option<String>,
+    pub region: Option<String>,
+    #[serde(alias = "col")]
+    #[serde(alias = "collection")]
+    pub table: String,
+    #[serde(alias = "idx")]
+    pub index: Option<String>,
+    #[serde(alias = "query")]
+    pub key_condition: Option<String>,
+    pub filter: Option<String>,
+    pub condition: Option<String>,
+    pub names: Option<HashMap<String, String>>,
+    pub values: Option<Value>,
+    pub consistent_read: bool,
+    pub retry: usize,
+    #[serde(alias = "params")]
+    pub parameters: Value,
+    #[serde(alias = "paginator")]
+    pub paginator_type: PaginatorType,
+    // Items already fetched by the paginator.
+    #[serde(skip)]
+    pub(crate) items: Option<Vec<Value>>,
+}
+
+impl Default for Dynamodb {
+    fn default() -> Self {
+        Dynamodb {
+            endpoint: None,
+            region: None,
+            table: String::default(),
+            index: None,
+            key_condition: None,
+            filter: None,
+            condition: None,
+            names: None,
+            values: None,
+            consistent_read: false,
+            retry: DEFAULT_RETRY,
+            parameters: Value::Null,
+            paginator_type: PaginatorType::default(),
+            items: None,
+        }
+    }
+}
+
+impl fmt::Debug for Dynamodb {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Dynamodb")
+            .field("endpoint", &self.endpoint)
+            .field("region", &self.region)
+            .field("table", &self.table)
+            .field("index", &self.index)
+            .field("key_condition", &self.key_condition)
+            .field("filter", &self.filter)
+            .field("condition", &self.condition)
+            .field("names", &self.names)
+            // Can contain sensitive data
+            .field("values", &self.values.display_only_for_debugging())
+            .field("consistent_read", &self.consistent_read)
+            .field("retry", &self.retry)
+            // Can contain sensitive data
+            .field("parameters", &self.parameters.display_only_for_debugging())
+            .field(
+                "paginator_type",
+                &self.paginator_type.display_only_for_debugging(),
+            )
+            .finish()
+    }
+}
+
+/// Convert a json value into a DynamoDB attribute value.
+///
+/// # Examples
+///
+/// ```
+/// use aws_sdk_dynamodb::types::AttributeValue;
+/// use chewdata::connector::dynamodb::to_attribute_value;
+///
+/// let value = serde_json::json!({"number":10.5,"list":[true,null]});
+/// let attribute = to_attribute_value(&value);
+/// let map = attribute.as_m().unwrap();
+/// assert_eq!(&AttributeValue::N("10.5".to_string()), map.get("number").unwrap());
+/// assert_eq!(
+///     &AttributeValue::L(vec![AttributeValue::Bool(true), AttributeValue::Null(true)]),
+///     map.get("list").unwrap()
+/// );
+/// ```
+pub fn to_attribute_value(value: &Value) -> AttributeValue {
+    match value {
+        Value::Null => AttributeValue::Null(true),
+        Value::Bool(boolean) => AttributeValue::Bool(*boolean),
+        Value::Number(number) => AttributeValue::N(number.to_string()),
+        Value::String(string) => AttributeValue::S(string.clone()),
+        Value::Array(vec) => AttributeValue::L(vec.iter().map(to_attribute_value).collect()),
+        Value::Object(map) => AttributeValue::M(
+            map.iter()
+                .map(|(key, value)| (key.clone(), to_attribute_value(value)))
+                .collect(),
+        ),
+    }
+}
+
+/// Convert a DynamoDB attribute value into a json value.
+///
+/// # Examples
+///
+/// ```
+/// use aws_sdk_dynamodb::types::AttributeValue;
+/// use chewdata::connector::dynamodb::from_attribute_value;
+///
+/// let attribute = AttributeValue::Ns(vec!["1".to_string(), "1.5".to_string()]);
+/// assert_eq!(serde_json::json!([1, 1.5]), from_attribute_value(&attribute));
+/// ```
+pub fn from_attribute_value(attribute: &AttributeValue) -> Value {
+    match attribute {
+        AttributeValue::Null(_) => Value::Null,
+        AttributeValue::Bool(boolean) => Value::Bool(*boolean),
+        AttributeValue::N(number) => from_number(number),
+        AttributeValue::S(string) => Value::String(string.clone()),
+        AttributeValue::B(blob) => from_blob(blob),
+        AttributeValue::Ss(vec) => Value::Array(vec.iter().cloned().map(Value::String).collect()),
+        AttributeValue::Ns(vec) => {
+            Value::Array(vec.iter().map(|number| from_number(number)).collect())
+        }
+        AttributeValue::Bs(vec) => Value::Array(vec.iter().map(from_blob).collect()),
+        AttributeValue::L(vec) => Value::Array(vec.iter().map(from_attribute_value).collect()),
+        AttributeValue::M(map) => Value::Object(
+            map.iter()
+                .map(|(key, attribute)| (key.clone(), from_attribute_value(attribute)))
+                .collect(),
+        ),
+        _ => Value::Null,
+    }
+}
+
+fn from_number(number: &str) -> Value {
+    if let Ok(number) = number.parse::<i64>() {
+        return Value::Number(Number::from(number));
+    }
+    if let Ok(number) = number.parse::<u64>() {
+        return Value::Number(Number::from(number));
+    }
+    match number.parse::<f64>().ok().and_then(Number::from_f64) {
+        Some(number) => Value::Number(number),
+        None => Value::String(number.to_string()),
+    }
+}
+
+fn from_blob(blob: &Blob) -> Value {
+    Value::String(String::from_utf8_lossy(blob.as_ref()).to_string())
+}
+
+fn to_item(value: &Value) -> Result<Item> {
+    match to_attribute_value(value) {
+        AttributeValue::M(item) => Ok(item),
+        _ => Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "The record '{}' must be an object to be written in a table",
+                value
+            ),
+        )),
+    }
+}
+
+fn from_item(item: &Item) -> Value {
+    Value::Object(
+        item.iter()
+            .map(|(key, attribute)| (key.clone(), from_attribute_value(attribute)))
+            .collect::<Map<String, Value>>(),
+    )
+}
+
+impl Dynamodb {
+    fn region(&self) -> String {
+        match (
+            self.region.clone(),
+            env::var("AWS_REGION"),
+            env::var("AWS_DEFAULT_REGION"),
+        ) {
+            (Some(region), _, _) => region,
+            (None, Ok(region), _) => region,
+            (None, Err(_), Ok(region)) => region,
+            (None, Err(_), Err(_)) => DEFAULT_REGION.to_string(),
+        }
+    }
+    fn endpoint(&self) -> Option<String> {
+        self.endpoint
+            .clone()
+            .or_else(|| env::var("DYNAMODB_ENDPOINT").ok())
+            .or_else(|| env::var("AWS_ENDPOINT_URL_DYNAMODB").ok())
+    }
+    /// Get the client shared by the connectors with the same endpoint and region.
+    #[instrument(name = "dynamodb::client")]
+    pub async fn client(&self) -> Result<Client> {
+        Ok(shared_client(
+            "DYNAMODB",
+            self.endpoint(),
+            self.region(),
+            |provider, region, endpoint| {
+                let mut builder = aws_sdk_dynamodb::Config::builder()
+                    .region(region)
+                    .credentials_provider(provider);
+                if let Some(endpoint) = endpoint {
+                    builder = builder.endpoint_url(endpoint);
+                }
+
+                Client::from_conf(builder.build())
+            },
+        )
+        .await)
+    }
+    /// Get the values of the expressions rendered with the parameters and the record in `input`.
+    fn values(&self, input: Option<&Value>) -> Result<Option<Item>> {
+        let mut values = match &self.values {
+            Some(values) => values.clone(),
+            None => return Ok(None),
+        };
+
+        let mut params = self.parameters.clone();
+        if let Some(input) = input {
+            params.merge(&serde_json::json!({ "input": input }));
+        }
+        values.replace_mustache(params);
+
+        to_item(&values).map(Some)
+    }
+    /// Read a page of items with a query or a scan and return the key of the last item evaluated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::dynamodb::Dynamodb;
+    /// use std::io;
+    ///
+    /// use macro_rules_attribute::apply;
+    /// use smol_macros::main;
+    ///
+    /// #[apply(main!)]
+    /// async fn main() -> io::Result<()> {
+    ///     let mut connector = Dynamodb::default();
+    ///     connector.endpoint = Some("http://localhost:8000".into());
+    ///     connector.table = "orders".into();
+    ///
+    ///     let (items, last_evaluated_key) = connector.page(Some(10), None).await?;
+    ///     assert!(items.len() <= 10);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn page(
+        &self,
+        limit: Option<i32>,
+        exclusive_start_key: Option<&Value>,
+    ) -> Result<(Vec<Value>, Option<Value>)> {
+        let client = self.client().await?;
+        let exclusive_start_key = exclusive_start_key.map(to_item).transpose()?;
+        let values = self.values(None)?;
+
+        let (items, last_evaluated_key) = match &self.key_condition {
+            Some(key_condition) => {
+                let output = Compat::new(
+                    client
+                        .query()
+                        .table_name(self.table.as_str())
+                        .set_index_name(self.index.clone())
+                        .key_condition_expression(key_condition)
+                        .set_filter_expression(self.filter.clone())
+                        .set_expression_attribute_names(self.names.clone())
+                        .set_expression_attribute_values(values)
+                        .set_exclusive_start_key(exclusive_start_key)
+                        .set_limit(limit)
+                        .consistent_read(self.consistent_read)
+                        .send(),
+                )
+                .await
+                .map_err(|e| {
+                    Error::new(ErrorKind::Interrupted, DisplayErrorContext(e).to_string())
+                })?;
+
+                (output.items, output.last_evaluated_key)
+            }
+            None => {
+                let output = Compat::new(
+                    client
+                        .scan()
+                        .table_name(self.table.as_str())
+                        .set_index_name(self.index.clone())
+                        .set_filter_expression(self.filter.clone())
+                        .set_expression_attribute_names(self.names.clone())
+                        .set_expression_attribute_values(values)
+                        .set_exclusive_start_key(exclusive_start_key)
+                        .set_limit(limit)
+                        .consistent_read(self.consistent_read)
+                        .send(),
+                )
+                .await
+                .map_err(|e| {
+                    Error::new(ErrorKind::Interrupted, DisplayErrorContext(e).to_string())
+                })?;
+
+                (output.items, output.last_evaluated_key)
+            }
+        };
+
+        Ok((
+            items.unwrap_or_default().iter().map(from_item).collect(),
+            last_evaluated_key.as_ref().map(from_item),
+        ))
+    }
+    /// Write the items by batch and send again the unprocessed items. Return the error of each item not written.
+    async fn batch_write(
+        &self,
+        client: &Client,
+        requests: Vec<WriteRequest>,
+    ) -> Vec<Option<Error>> {
+        let mut errors: Vec<Option<Error>> = requests.iter().map(|_| None).collect();
+        let mut pending: Vec<(usize, WriteRequest)> = requests.into_iter().enumerate().collect();
+        let mut attempt = 0;
+
+        while !pending.is_empty() {
+            if 0 < attempt {
+                let delay = RETRY_DELAY
+                    .checked_mul(2u32.saturating_pow(attempt as u32 - 1))
+                    .unwrap_or(Duration::from_secs(30))
+                    .min(Duration::from_secs(30));
+                smol::Timer::after(delay).await;
+            }
+
+            let output = Compat::new(
+                client
+                    .batch_write_item()
+                    .request_items(
+                        self.table.as_str(),
+                        pending.iter().map(|(_, request)| request.clone()).collect(),
+                    )
+                    .send(),
+            )
+            .await;
+
+            let unprocessed = match output {
+                Ok(output) => output
+                    .unprocessed_items
+                    .and_then(|mut items| items.remove(self.table.as_str()))
+                    .unwrap_or_default(),
+                Err(e) => {
+                    let error = DisplayErrorContext(e).to_string();
+                    warn!(error = error.as_str(), "Can't write the batch of items");
+                    for (position, _) in &pending {
+                        errors[*position] = Some(Error::new(ErrorKind::Interrupted, error.clone()));
+                    }
+                    break;
+                }
+            };
+
+            pending.retain(|(_, request)| unprocessed.contains(request));
+            attempt += 1;
+
+            if !pending.is_empty() && self.retry < attempt {
+                warn!(
+                    unprocessed = pending.len(),
+                    attempts = attempt,
+                    "Can't write the unprocessed items"
+                );
+                for (position, _) in &pending {
+                    errors[*position] = Some(Error::new(
+                        ErrorKind::Interrupted,
+                        format!("The item is still unprocessed after {} attempts", attempt),
+                    ));
+                }
+                break;
+            }
+        }
+
+        errors
+    }
+    /// Put an item if it matches the condition.
+    async fn conditional_put(&self, client: &Client, condition: &str, value: &Value) -> Result<()> {
+        Compat::new(
+            client
+                .put_item()
+                .table_name(self.table.as_str())
+                .set_item(Some(to_item(value)?))
+                .condition_expression(condition)
+                .set_expression_attribute_names(self.names.clone())
+                .set_expression_attribute_values(self.values(Some(value))?)
+                .send(),
+        )
+        .await
+        .map(|_| ())
+        .map_err(|e| {
+            let kind = match e
+                .as_service_error()
+                .is_some_and(|e| e.is_conditional_check_failed_exception())
+            {
+                true => ErrorKind::AlreadyExists,
+                false => ErrorKind::Interrupted,
+            };
+            Error::new(kind, DisplayErrorContext(e).to_string())
+        })
+    }
+    /// Get the names of the attributes of the primary key.
+    async fn key_names(&self, client: &Client) -> Result<Vec<String>> {
+        let output = Compat::new(
+            client
+                .describe_table()
+                .table_name(self.table.as_str())
+                .send(),
+        )
+        .await
+        .map_err(|e| Error::new(ErrorKind::NotFound, DisplayErrorContext(e).to_string()))?;
+
+        Ok(output
+            .table()
+            .map(|table| {
+                table
+                    .key_schema()
+                    .iter()
+                    .map(|key| key.attribute_name().to_string())
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+}
+
+#[async_trait]
+impl Connector for Dynamodb {
+    /// See [`Connector::path`] for more details.
+    fn path(&self) -> String {
+        format!("{}/{}", self.endpoint().unwrap_or_default(), self.table)
+    }
+    /// See [`Connector::set_parameters`] for more details.
+    fn set_parameters(&mut self, parameters: Value) {
+        self.parameters = parameters;
+    }
+    /// See [`Connector::is_variable`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::dynamodb::Dynamodb;
+    /// use chewdata::connector::Connector;
+    ///
+    /// let mut connector = Dynamodb::default();
+    /// assert_eq!(false, connector.is_variable());
+    /// connector.values = Some(serde_json::json!({":customer":"{{ customer }}"}));
+    /// assert_eq!(true, connector.is_variable());
+    /// ```
+    fn is_variable(&self) -> bool {
+        self.values
+            .as_ref()
+            .is_some_and(|values| values.has_mustache())
+    }
+    /// See [`Connector::is_resource_will_change`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::dynamodb::Dynamodb;
+    /// use chewdata::connector::Connector;
+    ///
+    /// let mut connector = Dynamodb::default();
+    /// connector.values = Some(serde_json::json!({":customer":"{{ customer }}"}));
+    /// connector.set_parameters(serde_json::json!({"customer":"a"}));
+    /// assert_eq!(false, connector.is_resource_will_change(serde_json::json!({"customer":"a"})).unwrap());
+    /// assert_eq!(true, connector.is_resource_will_change(serde_json::json!({"customer":"b"})).unwrap());
+    /// ```
+    fn is_resource_will_change(&self, new_parameters: Value) -> Result<bool> {
+        if !self.is_variable() {
+            trace!("Stay link to the same resource");
+            return Ok(false);
+        }
+
+        let mut previous_values = self.values.clone().unwrap_or_default();
+        previous_values.replace_mustache(self.parameters.clone());
+
+        let mut new_values = self.values.clone().unwrap_or_default();
+        new_values.replace_mustache(new_parameters);
+
+        if previous_values == new_values {
+            trace!("The values didn't change");
+            return Ok(false);
+        }
+
+        info!("The values will change");
+        Ok(true)
+    }
+    /// See [`Connector::len`] for more details.
+    ///
+    /// Count the items that match the key condition and the filter.
+    #[instrument(name = "dynamodb::len")]
+    async fn len(&self) -> Result<usize> {
+        let client = self.client().await?;
+        let values = self.values(None)?;
+        let mut exclusive_start_key: Option<Item> = None;
+        let mut count = 0;
+
+        loop {
+            let (page_count, last_evaluated_key) = match &self.key_condition {
+                Some(key_condition) => {
+                    let output = Compat::new(
+                        client
+                            .query()
+                            .table_name(self.table.as_str())
+                            .set_index_name(self.index.clone())
+                            .key_condition_expression(key_condition)
+                            .set_filter_expression(self.filter.clone())
+                            .set_expression_attribute_names(self.names.clone())
+                            .set_expression_attribute_values(values.clone())
+                            .set_exclusive_start_key(exclusive_start_key)
+                            .select(Select::Count)
+                            .send(),
+                    )
+                    .await
+                    .map_err(|e| {
+                        Error::new(ErrorKind::Interrupted, DisplayErrorContext(e).to_string())
+                    })?;
+
+                    (output.count, output.last_evaluated_key)
+                }
+                None => {
+                    let output = Compat::new(
+                        client
+                            .scan()
+                            .table_name(self.table.as_str())
+                            .set_index_name(self.index.clone())
+                            .set_filter_expression(self.filter.clone())
+                            .set_expression_attribute_names(self.names.clone())
+                            .set_expression_attribute_values(values.clone())
+                            .set_exclusive_start_key(exclusive_start_key)
+                            .select(Select::Count)
+                            .send(),
+                    )
+                    .await
+                    .map_err(|e| {
+                        Error::new(ErrorKind::Interrupted, DisplayErrorContext(e).to_string())
+                    })?;
+
+                    (output.count, output.last_evaluated_key)
+                }
+            };
+
+            count += page_count as usize;
+            exclusive_start_key = last_evaluated_key;
+            if exclusive_start_key.is_none() {
+                break;
+            }
+        }
+
+        info!(len = count, "Number of items found");
+
+        Ok(count)
+    }
+    /// See [`Connector::fetch`] for more details.
+    ///
+    /// Read the items of the page fetched by the paginator, or the first page if the connector is not paginated.
+    #[instrument(name = "dynamodb::fetch")]
+    async fn fetch(&mut self) -> std::io::Result<Option<DataStream>> {
+        let items = match self.items.take() {
+            Some(items) => items,
+            None => self.page(None, None).await?.0,
+        };
+
+        info!("Fetch data with success");
+
+        if items.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(Box::pin(stream! {
+            for item in items {
+                yield DataResult::Ok(item);
+            }
+        })))
+    }
+    /// See [`Connector::send`] for more details.
+    ///
+    /// Return the result of each record written.
+    #[instrument(skip(dataset), name = "dynamodb::send")]
+    async fn send(&mut self, dataset: &DataSet) -> std::io::Result<Option<DataStream>> {
+        let client = self.client().await?;
+        let mut results = Vec::default();
+
+        match &self.condition {
+            Some(condition) => {
+                for data in dataset {
+                    let value = data.to_value();
+                    results.push(
+                        match self.conditional_put(&client, condition, &value).await {
+                            Ok(()) => DataResult::Ok(value),
+                            Err(e) => {
+                                warn!(error = e.to_string().as_str(), "Can't put the record");
+                                DataResult::Err((value, e))
+                            }
+                        },
+                    );
+                }
+            }
+            None => {
+                for records in dataset.chunks(MAX_BATCH_SIZE) {
+                    let mut batch: Vec<Option<DataResult>> = Vec::default();
+                    let mut requests = Vec::default();
+
+                    for data in records {
+                        let value = data.to_value();
+                        match to_item(&value).and_then(|item| {
+                            PutRequest::builder()
+                                .set_item(Some(item))
+                                .build()
+                                .map_err(|e| Error::new(ErrorKind::InvalidInput, e))
+                        }) {
+                            Ok(request) => {
+                                requests.push(WriteRequest::builder().put_request(request).build());
+                                batch.push(None);
+                            }
+                            Err(e) => batch.push(Some(DataResult::Err((value, e)))),
+                        };
+                    }
+
+                    // Only the records with a request have a result in the batch.
+                    let mut errors = self.batch_write(&client, requests).await.into_iter();
+                    for (data, result) in records.iter().zip(batch) {
+                        results.push(match result {
+                            Some(result) => result,
+                            None => match errors.next().flatten() {
+                                Some(e) => DataResult::Err((data.to_value(), e)),
+                                None => DataResult::Ok(data.to_value()),
+                            },
+                        });
+                    }
+                }
+            }
+        };
+
+        info!(
+            table = self.table.as_str(),
+            items = results
+                .iter()
+                .filter(|data| data.is_type(DataResult::OK))
+                .count(),
+            errors = results
+                .iter()
+                .filter(|data| data.is_type(DataResult::ERR))
+                .count(),
+            "Send data with success"
+        );
+
+        Ok(Some(Box::pin(stream! {
+            for data in results {
+                yield data;
+            }
+        })))
+    }
+    /// See [`Connector::has_send_results`] for more details.
+    fn has_send_results(&self) -> bool {
+        true
+    }
+    /// See [`Connector::erase`] for more details.
+    ///
+    /// Delete all the items of the table by batch.
+    #[instrument(name = "dynamodb::erase")]
+    async fn erase(&mut self) -> Result<()> {
+        let client = self.client().await?;
+        let key_names = self.key_names(&client).await?;
+        let mut exclusive_start_key: Option<Item> = None;
+        let mut count = 0;
+
+        loop {
+            let output = Compat::new(
+                client
+                    .scan()
+                    .table_name(self.table.as_str())
+                    .set_exclusive_start_key(exclusive_start_key)
+                    .send(),
+            )
+            .await
+            .map_err(|e| Error::new(ErrorKind::Interrupted, DisplayErrorContext(e).to_string()))?;
+
+            let requests = output
+                .items()
+                .iter()
+                .map(|item| {
+                    let key = item
+                        .iter()
+                        .filter(|(name, _)| key_names.contains(name))
+                        .map(|(name, attribute)| (name.clone(), attribute.clone()))
+                        .collect();
+                    DeleteRequest::builder()
+                        .set_key(Some(key))
+                        .build()
+                        .map(|request| WriteRequest::builder().delete_request(request).build())
+                        .map_err(|e| Error::new(ErrorKind::InvalidInput, e))
+                })
+                .collect::<Result<Vec<WriteRequest>>>()?;
+
+            for requests in requests.chunks(MAX_BATCH_SIZE) {
+                count += requests.len();
+                if let Some(e) = self
+                    .batch_write(&client, requests.to_vec())
+                    .await
+                    .into_iter()
+                    .flatten()
+                    .next()
+                {
+                    return Err(e);
+                }
+            }
+
+            exclusive_start_key = output.last_evaluated_key;
+            if exclusive_start_key.is_none() {
+                break;
+            }
+        }
+
+        info!(items = count, "Erase data with success");
+        Ok(())
+    }
+    /// See [`Connector::paginate`] for more details.
+    async fn paginate(
+        &self,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<Box<dyn Connector>>> + Send>>> {
+        self.paginator_type.paginate(self).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_channel::Receiver;
+    use futures::StreamExt;
+    use macro_rules_attribute::apply;
+    use smol::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+    use smol::net::TcpListener;
+    use smol_macros::test;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    type Handler = Arc<dyn Fn(&str, &Value) -> (u16, Value) + Send + Sync>;
+
+    /// Start a DynamoDB server that answers each action with the handler and returns the actions received.
+    async fn dynamodb_server(handler: Handler) -> (String, Receiver<(String, Value)>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        let (sender, receiver) = async_channel::unbounded();
+
+        smol::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let (sender, handler) = (sender.clone(), handler.clone());
+                smol::spawn(async move {
+                    let mut reader = BufReader::new(stream.clone());
+                    let mut writer = stream;
+                    loop {
+                        let (mut action, mut length) = (String::default(), 0);
+                        loop {
+                            let mut line = String::default();
+                            if 0 == reader.read_line(&mut line).await.unwrap_or(0) {
+                                return;
+                            }
+                            match line.trim_end().split_once(':') {
+                                Some((name, value)) if name.eq_ignore_ascii_case("x-amz-target") => {
+                                    action = value
+                                        .trim()
+                                        .trim_start_matches("DynamoDB_20120810.")
+                                        .to_string()
+                                }
+                                Some((name, value)) if name.eq_ignore_ascii_case("content-length") => {
+                                    length = value.trim().parse().unwrap()
+                                }
+                                Some(_) => (),
+                                None if line.trim_end().is_empty() => break,
+                                None => (),
+                            }
+                        }
+                        let mut body = vec![0; length];
+                        reader.read_exact(&mut body).await.unwrap();
+                        let request: Value = serde_json::from_slice(&body).unwrap_or_default();
+                        let (status, response) = handler(&action, &request);
+                        let response = response.to_string();
+                        sender.send((action, request)).await.unwrap();
+                        writer
+                            .write_all(
+                                format!(
+                                    "HTTP/1.1 {} OK\r\nContent-Type: application/x-amz-json-1.0\r\nContent-Length: {}\r\n\r\n{}",
+                                    status,
+                                    response.len(),
+                                    response
+                                )
+                                .as_bytes(),
+                            )
+                            .await
+                            .unwrap();
+                    }
+                })
+                .detach();
+            }
+        })
+        .detach();
+
+        (endpoint, receiver)
+    }
+
+    fn connector(endpoint: String) -> Dynamodb {
+        std::env::set_var("AWS_ACCESS_KEY_ID", "test");
+        std::env::set_var("AWS_SECRET_ACCESS_KEY", "test");
+
+        Dynamodb {
+            endpoint: Some(endpoint),
+            region: Some("us-east-1".to_string()),
+            table: "orders".to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn requests(receiver: &Receiver<(String, Value)>, action: &str) -> Vec<Value> {
+        std::iter::from_fn(|| receiver.try_recv().ok())
+            .filter(|(name, _)| action == name)
+            .map(|(_, request)| request)
+            .collect()
+    }
+
+    #[test]
+    fn attribute_value() {
+        let value = serde_json::json!({
+            "id": "order-1",
+            "number": 10,
+            "negative": -1,
+            "float": 10.5,
+            "boolean": true,
+            "null": null,
+            "list": [1, "a"],
+            "object": {"field": "value"}
+        });
+        assert_eq!(value, from_attribute_value(&to_attribute_value(&value)));
+        assert_eq!(
+            serde_json::json!(["a", "b"]),
+            from_attribute_value(&AttributeValue::Ss(vec!["a".to_string(), "b".to_string()]))
+        );
+        assert_eq!(
+            serde_json::json!("value"),
+            from_attribute_value(&AttributeValue::B(Blob::new("value")))
+        );
+    }
+    #[apply(test!)]
+    async fn fetch() {
+        let (endpoint, receiver) = dynamodb_server(Arc::new(|_, _| {
+            (
+                200,
+                serde_json::json!({"Items": [
+                    {"id": {"S": "order-1"}, "number": {"N": "10"}, "tags": {"SS": ["a"]}}
+                ], "Count": 1}),
+            )
+        }))
+        .await;
+        let mut connector = connector(endpoint);
+        connector.filter = Some("#status = :status".to_string());
+        connector.names = Some(HashMap::from([(
+            "#status".to_string(),
+            "status".to_string(),
+        )]));
+        connector.values = Some(serde_json::json!({":status": "{{ status }}"}));
+        connector.set_parameters(serde_json::json!({"status": "paid"}));
+
+        let dataset: Vec<DataResult> = connector.fetch().await.unwrap().unwrap().collect().await;
+        assert_eq!(
+            serde_json::json!({"id":"order-1","number":10,"tags":["a"]}),
+            dataset[0].to_value()
+        );
+
+        let request = requests(&receiver, "Scan").remove(0);
+        assert_eq!("orders", request["TableName"]);
+        assert_eq!("#status = :status", request["FilterExpression"]);
+        assert_eq!(
+            serde_json::json!({":status": {"S": "paid"}}),
+            request["ExpressionAttributeValues"]
+        );
+    }
+    #[apply(test!)]
+    async fn fetch_with_key_condition() {
+        let (endpoint, receiver) = dynamodb_server(Arc::new(|_, _| {
+            (200, serde_json::json!({"Items": [], "Count": 0}))
+        }))
+        .await;
+        let mut connector = connector(endpoint);
+        connector.index = Some("by_customer".to_string());
+        connector.key_condition = Some("customer = :customer".to_string());
+        connector.values = Some(serde_json::json!({":customer": 1}));
+
+        assert!(connector.fetch().await.unwrap().is_none());
+
+        let request = requests(&receiver, "Query").remove(0);
+        assert_eq!("by_customer", request["IndexName"]);
+        assert_eq!("customer = :customer", request["KeyConditionExpression"]);
+        assert_eq!(
+            serde_json::json!({":customer": {"N": "1"}}),
+            request["ExpressionAttributeValues"]
+        );
+    }
+    #[apply(test!)]
+    async fn paginate() {
+        let (endpoint, receiver) = dynamodb_server(Arc::new(|_, request| {
+            match request.get("ExclusiveStartKey") {
+                None => (
+                    200,
+                    serde_json::json!({
+                        "Items": [{"id": {"S": "order-1"}}],
+                        "LastEvaluatedKey": {"id": {"S": "order-1"}}
+                    }),
+                ),
+                Some(_) => (
+                    200,
+                    serde_json::json!({"Items": [{"id": {"S": "order-2"}}]}),
+                ),
+            }
+        }))
+        .await;
+        let connector = connector(endpoint);
+
+        let mut paging = connector.paginate().await.unwrap();
+        let mut ids = Vec::default();
+        while let Some(connector) = paging.next().await {
+            let dataset: Vec<DataResult> = connector
+                .unwrap()
+                .fetch()
+                .await
+                .unwrap()
+                .unwrap()
+                .collect()
+                .await;
+            ids.extend(dataset.iter().map(|data| data.to_value()["id"].clone()));
+        }
+        assert_eq!(vec!["order-1", "order-2"], ids);
+
+        let scans = requests(&receiver, "Scan");
+        assert_eq!(100, scans[0]["Limit"]);
+        assert_eq!(
+            serde_json::json!({"id": {"S": "order-1"}}),
+            scans[1]["ExclusiveStartKey"]
+        );
+    }
+    #[apply(test!)]
+    async fn len() {
+        let (endpoint, receiver) = dynamodb_server(Arc::new(|_, _| {
+            (200, serde_json::json!({"Count": 3, "ScannedCount": 3}))
+        }))
+        .await;
+        let connector = connector(endpoint);
+
+        assert_eq!(3, connector.len().await.unwrap());
+        assert_eq!("COUNT", requests(&receiver, "Scan")[0]["Select"]);
+    }
+    #[apply(test!)]
+    async fn send() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let handler_calls = calls.clone();
+        let (endpoint, receiver) = dynamodb_server(Arc::new(move |_, request| {
+            match handler_calls.fetch_add(1, Ordering::SeqCst) {
+                // The second item is unprocessed by the first call.
+                0 => (
+                    200,
+                    serde_json::json!({"UnprocessedItems": {
+                        "orders": [request["RequestItems"]["orders"][1].clone()]
+                    }}),
+                ),
+                _ => (200, serde_json::json!({"UnprocessedItems": {}})),
+            }
+        }))
+        .await;
+        let mut connector = connector(endpoint);
+
+        let results: Vec<DataResult> = connector
+            .send(&vec![
+                DataResult::Ok(serde_json::json!({"id":"order-1","number":10})),
+                DataResult::Ok(serde_json::json!({"id":"order-2","number":20})),
+                DataResult::Ok(serde_json::json!("not an object")),
+            ])
+            .await
+            .unwrap()
+            .unwrap()
+            .collect()
+            .await;
+
+        assert!(results[0].is_type(DataResult::OK));
+        assert!(results[1].is_type(DataResult::OK));
+        match &results[2] {
+            DataResult::Err((_, e)) => assert_eq!(ErrorKind::InvalidInput, e.kind()),
+            _ => panic!("The record must be rejected"),
+        };
+
+        let batches = requests(&receiver, "BatchWriteItem");
+        assert_eq!(2, batches.len());
+        assert_eq!(
+            serde_json::json!([
+                {"PutRequest": {"Item": {"id": {"S": "order-1"}, "number": {"N": "10"}}}},
+                {"PutRequest": {"Item": {"id": {"S": "order-2"}, "number": {"N": "20"}}}}
+            ]),
+            batches[0]["RequestItems"]["orders"]
+        );
+        assert_eq!(
+            serde_json::json!([
+                {"PutRequest": {"Item": {"id": {"S": "order-2"}, "number": {"N": "20"}}}}
+            ]),
+            batches[1]["RequestItems"]["orders"]
+        );
+    }
+    #[apply(test!)]
+    async fn send_with_unprocessed_items() {
+        let (endpoint, receiver) = dynamodb_server(Arc::new(|_, request| {
+            (
+                200,
+                serde_json::json!({"UnprocessedItems": request["RequestItems"].clone()}),
+            )
+        }))
+        .await;
+        let mut connector = connector(endpoint);
+        connector.retry = 1;
+
+        let results: Vec<DataResult> = connector
+            .send(&vec![DataResult::Ok(serde_json::json!({"id":"order-1"}))])
+            .await
+            .unwrap()
+            .unwrap()
+            .collect()
+            .await;
+
+        match &results[0] {
+            DataResult::Err((_, e)) => assert_eq!(ErrorKind::Interrupted, e.kind()),
+            _ => panic!("The record must be in error"),
+        };
+        assert_eq!(2, requests(&receiver, "BatchWriteItem").len());
+    }
+    #[apply(test!)]
+    async fn send_with_invalid_record_before_unprocessed_item() {
+        let (endpoint, receiver) = dynamodb_server(Arc::new(|_, request| {
+            let items = request["RequestItems"]["orders"].as_array().unwrap();
+            (
+                200,
+                serde_json::json!({"UnprocessedItems": {"orders": [items.last().unwrap().clone()]}}),
+            )
+        }))
+        .await;
+        let mut connector = connector(endpoint);
+        connector.retry = 0;
+
+        let results: Vec<DataResult> = connector
+            .send(&vec![
+                DataResult::Ok(serde_json::json!("not an object")),
+                DataResult::Ok(serde_json::json!({"id":"order-1"})),
+                DataResult::Ok(serde_json::json!({"id":"order-2"})),
+            ])
+            .await
+            .unwrap()
+            .unwrap()
+            .collect()
+            .await;
+
+        match &results[0] {
+            DataResult::Err((_, e)) => assert_eq!(ErrorKind::InvalidInput, e.kind()),
+            _ => panic!("The record must be rejected"),
+        };
+        assert_eq!(
+            DataResult::Ok(serde_json::json!({"id":"order-1"})),
+            results[1]
+        );
+        match &results[2] {
+            DataResult::Err((value, e)) => {
+                assert_eq!(serde_json::json!({"id":"order-2"}), *value);
+                assert_eq!(ErrorKind::Interrupted, e.kind());
+            }
+            _ => panic!("The unprocessed item must be in error"),
+        };
+        assert_eq!(1, requests(&receiver, "BatchWriteItem").len());
+    }
+    #[apply(test!)]
+    async fn send_with_condition() {
+        let (endpoint, receiver) = dynamodb_server(Arc::new(|_, request| {
+            match request["Item"]["id"]["S"].as_str() {
+                Some("order-2") => (
+                    400,
+                    serde_json::json!({
+                        "__type": "com.amazonaws.dynamodb.v20120810#ConditionalCheckFailedException",
+                        "message": "The conditional request failed"
+                    }),
+                ),
+                _ => (200, serde_json::json!({})),
+            }
+        }))
+        .await;
+        let mut connector = connector(endpoint);
+        connector.condition = Some("attribute_not_exists(id) OR version < :version".to_string());
+        connector.values = Some(serde_json::json!({":version": "{{ input.version }}"}));
+
+        let results: Vec<DataResult> = connector
+            .send(&vec![
+                DataResult::Ok(serde_json::json!({"id":"order-1","version":2})),
+                DataResult::Ok(serde_json::json!({"id":"order-2","version":1})),
+            ])
+            .await
+            .unwrap()
+            .unwrap()
+            .collect()
+            .await;
+
+        assert!(results[0].is_type(DataResult::OK));
+        match &results[1] {
+            DataResult::Err((_, e)) => assert_eq!(ErrorKind::AlreadyExists, e.kind()),
+            _ => panic!("The record must not match the condition"),
+        };
+
+        let puts = requests(&receiver, "PutItem");
+        assert_eq!(
+            "attribute_not_exists(id) OR version < :version",
+            puts[0]["ConditionExpression"]
+        );
+        assert_eq!(
+            serde_json::json!({":version": {"N": "2"}}),
+            puts[0]["ExpressionAttributeValues"]
+        );
+    }
+    #[apply(test!)]
+    async fn erase() {
+        let (endpoint, receiver) = dynamodb_server(Arc::new(|action, _| match action {
+            "DescribeTable" => (
+                200,
+                serde_json::json!({"Table": {
+                    "TableName": "orders",
+                    "KeySchema": [{"AttributeName": "id", "KeyType": "HASH"}]
+                }}),
+            ),
+            "Scan" => (
+                200,
+                serde_json::json!({"Items": [{"id": {"S": "order-1"}, "number": {"N": "10"}}]}),
+            ),
+            _ => (200, serde_json::json!({})),
+        }))
+        .await;
+        let mut connector = connector(endpoint);
+
+        connector.erase().await.unwrap();
+
+        assert_eq!(
+            serde_json::json!([{"DeleteRequest": {"Key": {"id": {"S": "order-1"}}}}]),
+            requests(&receiver, "BatchWriteItem")[0]["RequestItems"]["orders"]
+        );
+    }
+}
@@ -0,0 +1,875 @@
+//! Read and write data into an Elasticsearch or OpenSearch index.
+//!
+//! The records are written with the `_bulk` api and the result of each record is returned to the writer.
+//! The records are read with a point in time and `search_after` through the paginator.
+//! The erase removes the documents that match the query with the `_delete_by_query` api.
+//!
+//! The `_id` of a record read is added into the record. The `_id` of a record written is taken from the `id` template or,
+//! if not set, from the `_id` field of the record that is removed from the source.
+//!
+//! ### Configuration
+//!
+//! | key           | alias        | Description                                                          | Default Value | Possible Values                                  |
+//! | ------------- | ------------ | -------------------------------------------------------------------- | ------------- | ------------------------------------------------ |
+//! | type          | -            | Required in order to use this connector                              | `elasticsearch` | `elasticsearch` / `opensearch` / `es`          |
+//! | endpoint      | `url`        | Endpoint of the cluster                                              | ``            | String                                           |
+//! | index         | `idx`        | The index name. Can use mustache to write into different indexes     | ``            | String                                           |
+//! | authenticator | `auth`       | Define the authentification that secure the http(s) call             | `null`        | [`crate::connector::authenticator::basic::Basic`] / [`crate::connector::authenticator::bearer::Bearer`] / [`crate::connector::authenticator::jwt::Jwt`] |
+//! | certificate   | `crt`        | Path to a local certificate file used to trust the HTTPS connection  | `null`        | Local path of a .crt file                        |
+//! | timeout       | -            | Time in secound before to abort the call                             | `5`           | Unsigned number                                  |
+//! | query         | -            | Query DSL used to read or erase the documents                        | `match_all`   | Json structure                                   |
+//! | id            | -            | Template of the document id used to write the records                | `null`        | String                                           |
+//! | action        | -            | Bulk action used to write the records                                | `index`       | `index` / `create` / `update` / `delete`         |
+//! | refresh       | -            | Refresh policy applied on writes                                     | `null`        | `true` / `false` / `wait_for`                    |
+//! | is_upsert     | `doc_as_upsert` | Create the document if it doesn't exist with the `update` action  | `false`       | `true` / `false`                                 |
+//! | parameters    | `params`     | Parameters used to inject into the index, the query and the id       | `null`        | Json structure                                   |
+//! | paginator     | -            | Paginator parameters                                                 | [`crate::connector::paginator::elasticsearch::search_after::SearchAfter`] | [`crate::connector::paginator::elasticsearch::search_after::SearchAfter`] |
+//!
+//! ### Examples
+//!
+//! ```json
+//! [
+//!     {
+//!         "type": "r",
+//!         "connector":{
+//!             "type": "elasticsearch",
+//!             "endpoint": "{{ ELASTICSEARCH_ENDPOINT }}",
+//!             "index": "my_index",
+//!             "query": {
+//!                 "term": { "group": "{{ group }}" }
+//!             },
+//!             "paginator": {
+//!                 "type": "search_after",
+//!                 "limit": 1000,
+//!                 "keep_alive": "1m"
+//!             }
+//!         }
+//!     },
+//!     {
+//!         "type": "w",
+//!         "connector":{
+//!             "type": "elasticsearch",
+//!             "endpoint": "{{ ELASTICSEARCH_ENDPOINT }}",
+//!             "index": "my_index_{{ input.group }}",
+//!             "id": "{{ number }}",
+//!             "action": "update",
+//!             "refresh": "wait_for"
+//!         },
+//!         "record_limit": 1000
+//!     }
+//! ]
+//! ```
+use super::authenticator::AuthenticatorType;
+use super::curl::Curl;
+use super::paginator::elasticsearch::PaginatorType;
+use super::Connector;
+use crate::helper::mustache::Mustache;
+use crate::helper::string::{DisplayOnlyForDebugging, Obfuscate};
+use crate::{DataResult, DataSet, DataStream, Metadata};
+use async_stream::stream;
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::Stream;
+use http::Method;
+use json_value_merge::Merge;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::pin::Pin;
+use std::{
+    fmt,
+    io::{Error, ErrorKind, Result},
+};
+
+const FIELD_ID: &str = "_id";
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Action {
+    #[default]
+    Index,
+    Create,
+    Update,
+    Delete,
+}
+
+impl Action {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Action::Index => "index",
+            Action::Create => "create",
+            Action::Update => "update",
+            Action::Delete => "delete",
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct Elasticsearch {
+    #[serde(alias = "url")]
+    pub endpoint: String,
+    #[serde(alias = "idx")]
+    pub index: String,
+    #[serde(alias = "auth")]
+    #[serde(rename = "authenticator")]
+    pub authenticator_type: Option<Box<AuthenticatorType>>,
+    #[serde(alias = "crt")]
+    pub certificate: Option<String>,
+    pub timeout: Option<u64>,
+    pub query: Option<Value>,
+    pub id: Option<String>,
+    pub action: Action,
+    pub refresh: Option<String>,
+    #[serde(alias = "doc_as_upsert")]
+    pub is_upsert: bool,
+    #[serde(alias = "params")]
+    pub parameters: Value,
+    #[serde(alias = "paginator")]
+    pub paginator_type: PaginatorType,
+    // Hits already fetched by the paginator.
+    #[serde(skip)]
+    pub(crate) hits: Option<Vec<Value>>,
+}
+
+impl Default for Elasticsearch {
+    fn default() -> Self {
+        Elasticsearch {
+            endpoint: Default::default(),
+            index: Default::default(),
+            authenticator_type: None,
+            certificate: None,
+            timeout: None,
+            query: None,
+            id: None,
+            action: Action::default(),
+            refresh: None,
+            is_upsert: false,
+            parameters: Value::Null,
+            paginator_type: PaginatorType::default(),
+            hits: None,
+        }
+    }
+}
+
+impl fmt::Debug for Elasticsearch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Elasticsearch")
+            .field("endpoint", &self.endpoint.to_obfuscate())
+            .field("index", &self.index)
+            .field(
+                "authenticator_type",
+                &self.authenticator_type.display_only_for_debugging(),
+            )
+            .field("certificate", &self.certificate)
+            .field("timeout", &self.timeout)
+            // Can contain sensitive data
+            .field("query", &self.query.display_only_for_debugging())
+            .field("id", &self.id)
+            .field("action", &self.action)
+            .field("refresh", &self.refresh)
+            .field("is_upsert", &self.is_upsert)
+            // Can contain sensitive data
+            .field("parameters", &self.parameters.display_only_for_debugging())
+            .field(
+                "paginator_type",
+                &self.paginator_type.display_only_for_debugging(),
+            )
+            .finish()
+    }
+}
+
+impl Elasticsearch {
+    /// Get the index name resolved with the parameters.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::elasticsearch::Elasticsearch;
+    /// use chewdata::connector::Connector;
+    /// use serde_json::Value;
+    ///
+    /// let mut connector = Elasticsearch::default();
+    /// connector.index = "index_{{ field }}".to_string();
+    /// let params: Value = serde_json::from_str(r#"{"field":"value"}"#).unwrap();
+    /// connector.set_parameters(params);
+    /// assert_eq!("index_value", connector.index().unwrap());
+    /// ```
+    pub fn index(&self) -> Result<String> {
+        let mut index = self.index.clone();
+        index.replace_mustache(self.parameters.clone());
+
+        if index.has_mustache() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("This index '{}' is not fully resolved", index),
+            ));
+        }
+
+        Ok(index)
+    }
+    /// Get the query resolved with the parameters. Match all the documents by default.
+    pub fn query(&self) -> Value {
+        match &self.query {
+            Some(query) => {
+                let mut query = query.clone();
+                query.replace_mustache(self.parameters.clone());
+                query
+            }
+            None => serde_json::json!({"match_all": {}}),
+        }
+    }
+    /// Call the cluster and return the response body.
+    ///
+    /// The body is sent in json if it's an object and in ndjson if it's a string.
+    pub async fn call(&self, method: Method, path: &str, body: Option<Value>) -> Result<Value> {
+        let mut curl = Curl::default();
+        curl.endpoint = self.endpoint.clone();
+        curl.authenticator_type = self.authenticator_type.clone();
+        curl.certificate = self.certificate.clone();
+        if self.timeout.is_some() {
+            curl.timeout = self.timeout;
+        }
+
+        let bytes = match body {
+            Some(Value::String(lines)) => {
+                curl.metadata = Metadata {
+                    mime_type: Some("application".to_string()),
+                    mime_subtype: Some("x-ndjson".to_string()),
+                    ..Default::default()
+                };
+                Bytes::from(lines)
+            }
+            Some(value) => {
+                curl.metadata = Metadata {
+                    mime_type: Some("application".to_string()),
+                    mime_subtype: Some("json".to_string()),
+                    ..Default::default()
+                };
+                Bytes::from(serde_json::to_vec(&value)?)
+            }
+            None => Bytes::new(),
+        };
+
+        let (status, data) = curl.call(&method, path, bytes).await?;
+
+        if status >= 400 {
+            return Err(Error::new(
+                ErrorKind::Interrupted,
+                format!(
+                    "The call '{} {}' failed with the status '{}': {}",
+                    method,
+                    path,
+                    status,
+                    String::from_utf8_lossy(&data)
+                ),
+            ));
+        }
+
+        if data.is_empty() {
+            return Ok(Value::Null);
+        }
+
+        serde_json::from_slice(&data).map_err(|e| Error::new(ErrorKind::InvalidData, e))
+    }
+    /// Search documents with a search body and return the response.
+    ///
+    /// If the body contains a point in time, the search is not bound to the index.
+    pub async fn search(&self, body: &Value) -> Result<Value> {
+        let path = match body.get("pit") {
+            Some(_) => "/_search".to_string(),
+            None => format!("/{}/_search", self.index()?),
+        };
+
+        self.call(Method::POST, &path, Some(body.clone())).await
+    }
+    /// Open a point in time on the index and return its id.
+    pub async fn open_pit(&self, keep_alive: &str) -> Result<String> {
+        let response = self
+            .call(
+                Method::POST,
+                &format!("/{}/_pit?keep_alive={}", self.index()?, keep_alive),
+                None,
+            )
+            .await?;
+
+        // Elasticsearch returns 'id' and OpenSearch returns 'pit_id'.
+        match response.get("id").or_else(|| response.get("pit_id")) {
+            Some(Value::String(id)) => Ok(id.clone()),
+            _ => Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "Can't find the point in time id in the response: {}",
+                    response
+                ),
+            )),
+        }
+    }
+    /// Close a point in time.
+    pub async fn close_pit(&self, pit_id: &str) -> Result<()> {
+        self.call(
+            Method::DELETE,
+            "/_pit",
+            Some(serde_json::json!({ "id": pit_id })),
+        )
+        .await?;
+
+        Ok(())
+    }
+    /// Transform a hit into a record with its `_id`.
+    fn hit_to_value(hit: &Value) -> Value {
+        let mut value = match hit.get("_source") {
+            Some(source) => source.clone(),
+            None => Value::Object(Map::default()),
+        };
+
+        if let (Value::Object(map), Some(id)) = (&mut value, hit.get(FIELD_ID)) {
+            map.insert(FIELD_ID.to_string(), id.clone());
+        }
+
+        value
+    }
+    /// Get the document id of a record and remove the `_id` field from the record.
+    fn document_id(&self, value: &mut Value) -> Option<String> {
+        let id_from_value = match value {
+            Value::Object(map) => map.remove(FIELD_ID),
+            _ => None,
+        };
+
+        if let Some(id) = &self.id {
+            let mut id = id.clone();
+            id.replace_mustache(value.clone());
+
+            if !id.has_mustache() && !id.is_empty() {
+                return Some(id);
+            }
+        }
+
+        match id_from_value {
+            Some(Value::String(id)) => Some(id),
+            Some(Value::Null) | None => None,
+            Some(id) => Some(id.to_string()),
+        }
+    }
+}
+
+#[async_trait]
+impl Connector for Elasticsearch {
+    /// See [`Connector::path`] for more details.
+    fn path(&self) -> String {
+        let mut index = self.index.clone();
+        index.replace_mustache(self.parameters.clone());
+
+        format!("{}/{}", self.endpoint, index)
+    }
+    /// See [`Connector::set_parameters`] for more details.
+    fn set_parameters(&mut self, parameters: Value) {
+        self.parameters = parameters;
+    }
+    /// See [`Connector::is_variable`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::{elasticsearch::Elasticsearch, Connector};
+    ///
+    /// let mut connector = Elasticsearch::default();
+    /// connector.index = "my_index".to_string();
+    /// assert_eq!(false, connector.is_variable());
+    /// connector.index = "my_index_{{ field }}".to_string();
+    /// assert_eq!(true, connector.is_variable());
+    /// ```
+    fn is_variable(&self) -> bool {
+        self.index.has_mustache()
+    }
+    /// See [`Connector::is_resource_will_change`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::{elasticsearch::Elasticsearch, Connector};
+    /// use serde_json::Value;
+    ///
+    /// let mut connector = Elasticsearch::default();
+    /// let params: Value = serde_json::from_str(r#"{"field":"test"}"#).unwrap();
+    /// connector.index = "my_index".to_string();
+    /// assert_eq!(false, connector.is_resource_will_change(params.clone()).unwrap());
+    /// connector.index = "my_index_{{ field }}".to_string();
+    /// assert_eq!(true, connector.is_resource_will_change(params).unwrap());
+    /// ```
+    fn is_resource_will_change(&self, new_parameters: Value) -> Result<bool> {
+        if !self.is_variable() {
+            trace!("Stay link to the same resource");
+            return Ok(false);
+        }
+
+        let mut previous_index = self.index.clone();
+        previous_index.replace_mustache(self.parameters.clone());
+
+        let mut new_index = self.index.clone();
+        new_index.replace_mustache(new_parameters);
+
+        if previous_index == new_index {
+            trace!(index = previous_index, "The index has not changed");
+            return Ok(false);
+        }
+
+        info!(
+            previous_index = previous_index,
+            new_index = new_index,
+            "Will use another index based the new parameters"
+        );
+        Ok(true)
+    }
+    /// See [`Connector::len`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::{elasticsearch::Elasticsearch, Connector};
+    /// use std::io;
+    ///
+    /// use macro_rules_attribute::apply;
+    /// use smol_macros::main;
+    ///
+    /// #[apply(main!)]
+    /// async fn main() -> io::Result<()> {
+    ///     let mut connector = Elasticsearch::default();
+    ///     connector.endpoint = "http://localhost:9200".into();
+    ///     connector.index = "read".into();
+    ///     let len = connector.len().await?;
+    ///     assert!(0 < len, "The connector should have a size upper than zero");
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[instrument(name = "elasticsearch::len")]
+    async fn len(&self) -> Result<usize> {
+        let response = match self
+            .call(
+                Method::POST,
+                &format!("/{}/_count", self.index()?),
+                Some(serde_json::json!({ "query": self.query() })),
+            )
+            .await
+        {
+            Ok(response) => response,
+            Err(e) => {
+                warn!(
+                    error = e.to_string(),
+                    "Can't count the number of element, return 0"
+                );
+                return Ok(0);
+            }
+        };
+
+        let len = response
+            .get("count")
+            .and_then(Value::as_u64)
+            .unwrap_or_default() as usize;
+
+        info!(len = len, "The connector found data in the index");
+        Ok(len)
+    }
+    /// See [`Connector::fetch`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::{elasticsearch::Elasticsearch, Connector};
+    /// use smol::stream::StreamExt;
+    /// use std::io;
+    ///
+    /// use macro_rules_attribute::apply;
+    /// use smol_macros::main;
+    ///
+    /// #[apply(main!)]
+    /// async fn main() -> io::Result<()> {
+    ///     let mut connector = Elasticsearch::default();
+    ///     connector.endpoint = "http://localhost:9200".into();
+    ///     connector.index = "read".into();
+    ///     let datastream = connector.fetch().await?.unwrap();
+    ///     assert!(0 < datastream.count().await, "The inner connector should have a size upper than zero");
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[instrument(name = "elasticsearch::fetch")]
+    async fn fetch(&mut self) -> std::io::Result<Option<DataStream>> {
+        let hits = match self.hits.take() {
+            Some(hits) => hits,
+            None => {
+                let response = self
+                    .search(&serde_json::json!({ "query": self.query() }))
+                    .await?;
+
+                match response.pointer("/hits/hits") {
+                    Some(Value::Array(hits)) => hits.clone(),
+                    _ => Vec::default(),
+                }
+            }
+        };
+
+        info!("Fetch data with success");
+
+        if hits.is_empty() {
+            return Ok(None);
+        }
+
+        let dataset: Vec<DataResult> = hits
+            .iter()
+            .map(|hit| DataResult::Ok(Elasticsearch::hit_to_value(hit)))
+            .collect();
+
+        Ok(Some(Box::pin(stream! {
+            for data in dataset {
+                yield data;
+            }
+        })))
+    }
+    /// See [`Connector::send`] for more details.
+    ///
+    /// The stream returned contains the result of each record sent.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::{elasticsearch::Elasticsearch, Connector};
+    /// use chewdata::DataResult;
+    /// use smol::stream::StreamExt;
+    /// use std::io;
+    ///
+    /// use macro_rules_attribute::apply;
+    /// use smol_macros::main;
+    ///
+    /// #[apply(main!)]
+    /// async fn main() -> io::Result<()> {
+    ///     let mut connector = Elasticsearch::default();
+    ///     connector.endpoint = "http://localhost:9200".into();
+    ///     connector.index = "send".into();
+    ///     connector.refresh = Some("true".into());
+    ///
+    ///     let dataset = vec![DataResult::Ok(serde_json::from_str(r#"{"_id":"1","number":110}"#)?)];
+    ///     let results = connector.send(&dataset).await?.unwrap();
+    ///     assert_eq!(1, results.count().await);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[instrument(skip(dataset), name = "elasticsearch::send")]
+    async fn send(&mut self, dataset: &DataSet) -> std::io::Result<Option<DataStream>> {
+        let index = self.index()?;
+        let mut lines = String::default();
+        let mut results: Vec<Option<DataResult>> = Vec::default();
+        let mut positions: Vec<usize> = Vec::default();
+
+        for (position, data) in dataset.iter().enumerate() {
+            let original_value = data.to_value();
+            let mut value = original_value.clone();
+            let id = self.document_id(&mut value);
+
+            if id.is_none() && matches!(self.action, Action::Update | Action::Delete) {
+                results.push(Some(DataResult::Err((
+                    original_value,
+                    Error::new(
+                        ErrorKind::InvalidInput,
+                        format!(
+                            "The action '{}' requires a document id",
+                            self.action.as_str()
+                        ),
+                    ),
+                ))));
+                continue;
+            }
+
+            let mut metadata = Map::default();
+            metadata.insert("_index".to_string(), Value::String(index.clone()));
+            if let Some(id) = id {
+                metadata.insert(FIELD_ID.to_string(), Value::String(id));
+            }
+            let mut action = Map::default();
+            action.insert(self.action.as_str().to_string(), Value::Object(metadata));
+
+            lines.push_str(&Value::Object(action).to_string());
+            lines.push('\n');
+
+            match self.action {
+                Action::Index | Action::Create => {
+                    lines.push_str(&value.to_string());
+                    lines.push('\n');
+                }
+                Action::Update => {
+                    lines.push_str(
+                        &serde_json::json!({ "doc": value, "doc_as_upsert": self.is_upsert })
+                            .to_string(),
+                    );
+                    lines.push('\n');
+                }
+                Action::Delete => (),
+            };
+
+            results.push(None);
+            positions.push(position);
+        }
+
+        if !positions.is_empty() {
+            let path = match &self.refresh {
+                Some(refresh) => format!("/_bulk?refresh={}", refresh),
+                None => "/_bulk".to_string(),
+            };
+
+            let response = self
+                .call(Method::POST, &path, Some(Value::String(lines)))
+                .await?;
+
+            let items = match response.get("items") {
+                Some(Value::Array(items)) => items.clone(),
+                _ => Vec::default(),
+            };
+
+            if items.len() != positions.len() {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!(
+                        "The bulk response contains '{}' items for '{}' records sent",
+                        items.len(),
+                        positions.len()
+                    ),
+                ));
+            }
+
+            for (item, position) in items.iter().zip(positions) {
+                let value = dataset[position].to_value();
+                let item_result = item
+                    .as_object()
+                    .and_then(|map| map.values().next())
+                    .cloned()
+                    .unwrap_or_default();
+
+                results[position] = Some(match item_result.get("error") {
+                    Some(error) => {
+                        let reason = error
+                            .get("reason")
+                            .and_then(Value::as_str)
+                            .map(|reason| reason.to_string())
+                            .unwrap_or_else(|| error.to_string());
+
+                        let status = item_result
+                            .get("status")
+                            .and_then(Value::as_u64)
+                            .unwrap_or_default();
+
+                        warn!(status, reason = reason.as_str(), "Can't send the record");
+
+                        DataResult::Err((value, Error::new(ErrorKind::InvalidData, reason)))
+                    }
+                    None => DataResult::Ok(value),
+                });
+            }
+        }
+
+        info!("Send data with success");
+
+        let results: Vec<DataResult> = results.into_iter().flatten().collect();
+
+        Ok(Some(Box::pin(stream! {
+            for data in results {
+                yield data;
+            }
+        })))
+    }
+    /// See [`Connector::has_send_results`] for more details.
+    fn has_send_results(&self) -> bool {
+        true
+    }
+    /// See [`Connector::erase`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::{elasticsearch::Elasticsearch, Connector};
+    /// use std::io;
+    ///
+    /// use macro_rules_attribute::apply;
+    /// use smol_macros::main;
+    ///
+    /// #[apply(main!)]
+    /// async fn main() -> io::Result<()> {
+    ///     let mut connector = Elasticsearch::default();
+    ///     connector.endpoint = "http://localhost:9200".into();
+    ///     connector.index = "erase".into();
+    ///     connector.erase().await?;
+    ///     assert_eq!(0, connector.len().await?);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[instrument(name = "elasticsearch::erase")]
+    async fn erase(&mut self) -> Result<()> {
+        let mut body = Value::default();
+        body.merge_in("/query", &self.query())?;
+
+        self.call(
+            Method::POST,
+            &format!(
+                "/{}/_delete_by_query?conflicts=proceed&refresh=true&ignore_unavailable=true",
+                self.index()?
+            ),
+            Some(body),
+        )
+        .await?;
+
+        info!("Erase data with success");
+        Ok(())
+    }
+    /// See [`Connector::paginate`] for more details.
+    async fn paginate(
+        &self,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<Box<dyn Connector>>> + Send>>> {
+        self.paginator_type.paginate(self).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use macro_rules_attribute::apply;
+    use smol::stream::StreamExt;
+    use smol_macros::test;
+
+    fn connector(index: &str) -> Elasticsearch {
+        let mut connector = Elasticsearch::default();
+        connector.endpoint = "http://localhost:9200".into();
+        connector.index = index.into();
+        connector.refresh = Some("true".into());
+        connector
+    }
+
+    #[apply(test!)]
+    async fn send_and_fetch() {
+        let mut connector = connector("send_and_fetch");
+        connector.erase().await.unwrap();
+
+        let dataset = vec![
+            DataResult::Ok(serde_json::from_str(r#"{"_id":"1","number":110}"#).unwrap()),
+            DataResult::Ok(serde_json::from_str(r#"{"_id":"2","number":111}"#).unwrap()),
+        ];
+        let results: Vec<DataResult> = connector
+            .send(&dataset)
+            .await
+            .unwrap()
+            .unwrap()
+            .collect()
+            .await;
+        assert_eq!(dataset, results);
+
+        let mut connector_read = connector.clone();
+        connector_read.query = Some(serde_json::from_str(r#"{"term":{"number":111}}"#).unwrap());
+        let mut datastream = connector_read.fetch().await.unwrap().unwrap();
+        assert_eq!(
+            r#"{"_id":"2","number":111}"#,
+            datastream.next().await.unwrap().to_value().to_string()
+        );
+    }
+    #[apply(test!)]
+    async fn send_with_item_errors() {
+        let mut connector = connector("send_with_item_errors");
+        connector.erase().await.unwrap();
+        connector.action = Action::Create;
+
+        let dataset = vec![
+            DataResult::Ok(serde_json::from_str(r#"{"_id":"1","number":110}"#).unwrap()),
+            DataResult::Ok(serde_json::from_str(r#"{"_id":"1","number":111}"#).unwrap()),
+        ];
+        let results: Vec<DataResult> = connector
+            .send(&dataset)
+            .await
+            .unwrap()
+            .unwrap()
+            .collect()
+            .await;
+        assert!(results[0].is_type(DataResult::OK));
+        assert!(results[1].is_type(DataResult::ERR));
+    }
+    #[apply(test!)]
+    async fn send_update_without_id() {
+        let mut connector = connector("send_update_without_id");
+        connector.action = Action::Update;
+
+        let dataset = vec![DataResult::Ok(
+            serde_json::from_str(r#"{"number":110}"#).unwrap(),
+        )];
+        let results: Vec<DataResult> = connector
+            .send(&dataset)
+            .await
+            .unwrap()
+            .unwrap()
+            .collect()
+            .await;
+        assert!(results[0].is_type(DataResult::ERR));
+    }
+    #[apply(test!)]
+    async fn send_update_missing_document() {
+        let mut connector = connector("send_update_missing_document");
+        connector.erase().await.unwrap();
+        connector.action = Action::Update;
+
+        let dataset = vec![DataResult::Ok(
+            serde_json::from_str(r#"{"_id":"1","number":110}"#).unwrap(),
+        )];
+        let results: Vec<DataResult> = connector
+            .send(&dataset)
+            .await
+            .unwrap()
+            .unwrap()
+            .collect()
+            .await;
+        assert!(results[0].is_type(DataResult::ERR));
+
+        connector.is_upsert = true;
+        let results: Vec<DataResult> = connector
+            .send(&dataset)
+            .await
+            .unwrap()
+            .unwrap()
+            .collect()
+            .await;
+        assert!(results[0].is_type(DataResult::OK));
+    }
+    #[apply(test!)]
+    async fn len() {
+        let mut connector = connector("len");
+        connector.erase().await.unwrap();
+        let dataset = vec![DataResult::Ok(
+            serde_json::from_str(r#"{"number":110}"#).unwrap(),
+        )];
+        connector.send(&dataset).await.unwrap();
+        assert_eq!(1, connector.len().await.unwrap());
+    }
+    #[apply(test!)]
+    async fn erase() {
+        let mut connector = connector("erase");
+        let dataset = vec![DataResult::Ok(
+            serde_json::from_str(r#"{"number":110}"#).unwrap(),
+        )];
+        connector.send(&dataset).await.unwrap();
+        connector.erase().await.unwrap();
+
+        let mut connector_read = connector.clone();
+        let datastream = connector_read.fetch().await.unwrap();
+        assert!(datastream.is_none(), "The datastream should be empty.");
+    }
+    #[test]
+    fn document_id() {
+        let mut connector = Elasticsearch::default();
+        let mut value: Value = serde_json::from_str(r#"{"_id":1,"number":110}"#).unwrap();
+        assert_eq!(Some("1".to_string()), connector.document_id(&mut value));
+        assert_eq!(r#"{"number":110}"#, value.to_string());
+
+        connector.id = Some("number_{{ number }}".to_string());
+        let mut value: Value = serde_json::from_str(r#"{"_id":1,"number":110}"#).unwrap();
+        assert_eq!(
+            Some("number_110".to_string()),
+            connector.document_id(&mut value)
+        );
+    }
+}
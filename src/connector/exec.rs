@@ -0,0 +1,646 @@
+//! Read and write data through an external command.
+//!
+//! When the connector reads, it runs the command and parses its standard output with the [`crate::document`].
+//!
+//! When the connector writes, it runs the command, writes the dataset serialized by the [`crate::document`] in its standard input
+//! and parses its standard output. The records of the standard output replace the records sent, so the command can be used to transform the data.
+//! The command must write one record per record sent, in the same order. Otherwise, each record sent is returned as an error.
+//! If the command writes nothing in its standard output, the records sent are kept.
+//!
+//! If the command exits with an error, each record sent is returned as an error with the exit code and the standard error.
+//!
+//! ### Configuration
+//!
+//! | key         | alias         | Description                                              | Default Value | Possible Values                |
+//! | ----------- | ------------- | -------------------------------------------------------- | ------------- | ------------------------------ |
+//! | type        | -             | Required in order to use this connector                  | `exec`        | `exec` / `command` / `process` |
+//! | metadata    | meta          | Override metadata information                            | `null`        | [`crate::Metadata`]            |
+//! | command     | cmd / program | The program to run. Can use mustache variables           | `null`        | String                         |
+//! | args        | arguments     | The arguments of the program. Can use mustache variables | `[]`          | List of string                 |
+//! | envs        | env           | The environment variables added to the program           | `{}`          | Object                         |
+//! | current_dir | dir / cwd     | The working directory of the program                     | `null`        | String                         |
+//! | timeout     | -             | Time in second before to kill the program                | `null`        | Unsigned number                |
+//! | parameters  | params        | Variables used to render the `command` and the `args`    | `null`        | Object                         |
+//!
+//! ### Examples
+//!
+//! ```json
+//! [
+//!     {
+//!         "type": "r",
+//!         "connector":{
+//!             "type": "exec",
+//!             "command": "cat",
+//!             "args": ["./data/multi_lines.json"]
+//!         }
+//!     },
+//!     {
+//!         "type": "w",
+//!         "connector":{
+//!             "type": "exec",
+//!             "command": "jq",
+//!             "args": ["-c", "map(.number * 10)"]
+//!         }
+//!     }
+//! ]
+//! ```
+use super::Connector;
+use crate::connector::paginator::once::Once;
+use crate::document::Document;
+use crate::helper::mustache::Mustache;
+use crate::helper::string::DisplayOnlyForDebugging;
+use crate::{DataResult, DataSet, DataStream, Metadata};
+use async_process::{Command, Output, Stdio};
+use async_stream::stream;
+use async_trait::async_trait;
+use futures::{AsyncWriteExt, Stream};
+use json_value_merge::Merge;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use smol_timeout::TimeoutExt;
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::time::Duration;
+use std::{
+    fmt,
+    io::{Error, ErrorKind, Result},
+};
+
+#[derive(Deserialize, Serialize, Clone, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct Exec {
+    #[serde(skip)]
+    document: Option<Box<dyn Document>>,
+    #[serde(rename = "metadata")]
+    #[serde(alias = "meta")]
+    pub metadata: Metadata,
+    #[serde(alias = "cmd")]
+    #[serde(alias = "program")]
+    pub command: String,
+    #[serde(alias = "arguments")]
+    pub args: Vec<String>,
+    #[serde(alias = "env")]
+    pub envs: HashMap<String, String>,
+    #[serde(alias = "dir")]
+    #[serde(alias = "cwd")]
+    pub current_dir: Option<String>,
+    pub timeout: Option<u64>,
+    #[serde(alias = "params")]
+    pub parameters: Box<Value>,
+}
+
+impl fmt::Debug for Exec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Exec")
+            .field("document", &self.document.display_only_for_debugging())
+            .field("metadata", &self.metadata.display_only_for_debugging())
+            .field("command", &self.command)
+            .field("args", &self.args)
+            // Can contain sensitive data
+            .field("envs", &self.envs.display_only_for_debugging())
+            .field("current_dir", &self.current_dir)
+            .field("timeout", &self.timeout)
+            .field("parameters", &self.parameters.display_only_for_debugging())
+            .finish()
+    }
+}
+
+impl Exec {
+    /// Get the command and its arguments rendered with the parameters.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::exec::Exec;
+    /// use chewdata::connector::Connector;
+    ///
+    /// let mut connector = Exec::default();
+    /// connector.command = "echo".to_string();
+    /// connector.args = vec!["-n".to_string(), "{{ input.field }}".to_string()];
+    /// connector.set_parameters(serde_json::from_str(r#"{"input":{"field":"value"}}"#).unwrap());
+    /// assert_eq!(("echo".to_string(), vec!["-n".to_string(), "value".to_string()]), connector.command_line());
+    /// ```
+    pub fn command_line(&self) -> (String, Vec<String>) {
+        command_line(&self.command, &self.args, self.parameters())
+    }
+    fn parameters(&self) -> Value {
+        let mut params = *self.parameters.clone();
+        params.merge(&serde_json::json!({
+            "metadata": self.metadata()
+        }));
+        params
+    }
+    /// Run the command, write the input in its stdin and wait the end of the program.
+    async fn run(&self, input: Option<Vec<u8>>) -> Result<Output> {
+        let (program, args) = self.command_line();
+
+        if program.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The command is required in order to run a program",
+            ));
+        }
+
+        let mut command = Command::new(&program);
+        command
+            .args(&args)
+            .envs(&self.envs)
+            .stdin(match input {
+                Some(_) => Stdio::piped(),
+                None => Stdio::null(),
+            })
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+
+        if let Some(current_dir) = &self.current_dir {
+            command.current_dir(current_dir);
+        }
+
+        let mut child = command.spawn().map_err(|e| {
+            Error::new(
+                e.kind(),
+                format!("Can't run the command '{}': {}", program, e),
+            )
+        })?;
+
+        let stdin = child.stdin.take();
+        // Write the stdin in parallel to avoid a deadlock if the program fills its stdout before to read all its stdin.
+        let write = async move {
+            if let (Some(mut stdin), Some(input)) = (stdin, input) {
+                let written = async {
+                    stdin.write_all(&input).await?;
+                    stdin.close().await
+                };
+                match written.await {
+                    // The program can exit without reading its stdin.
+                    Err(e) if ErrorKind::BrokenPipe == e.kind() => (),
+                    result => result?,
+                }
+            }
+            Ok::<(), Error>(())
+        };
+
+        let execution = futures::future::join(write, child.output());
+        let (written, output) = match self.timeout {
+            Some(timeout) => execution
+                .timeout(Duration::from_secs(timeout))
+                .await
+                .ok_or_else(|| {
+                    Error::new(
+                        ErrorKind::TimedOut,
+                        format!("The command '{}' didn't end before the timeout", program),
+                    )
+                })?,
+            None => execution.await,
+        };
+        written?;
+        let output = output?;
+
+        if !output.stderr.is_empty() {
+            warn!(
+                command = program,
+                stderr = String::from_utf8_lossy(&output.stderr).trim_end(),
+                "The command writes in the stderr"
+            );
+        }
+
+        Ok(output)
+    }
+}
+
+/// Render the program and its arguments with the parameters.
+fn command_line(command: &str, args: &[String], parameters: Value) -> (String, Vec<String>) {
+    let render = |value: &str| {
+        let mut value = value.to_string();
+        if value.has_mustache() {
+            value.replace_mustache(parameters.clone());
+        }
+        value
+    };
+
+    (
+        render(command),
+        args.iter().map(|arg| render(arg)).collect(),
+    )
+}
+
+/// Get the error of a program that fails.
+fn exit_error(output: &Output) -> Option<Error> {
+    if output.status.success() {
+        return None;
+    }
+
+    Some(Error::other(format!(
+        "The command exits with the code '{}': {}",
+        output
+            .status
+            .code()
+            .map_or("unknown".to_string(), |code| code.to_string()),
+        String::from_utf8_lossy(&output.stderr).trim_end()
+    )))
+}
+
+#[async_trait]
+impl Connector for Exec {
+    /// See [`Connector::set_document`] for more details.
+    fn set_document(&mut self, document: Box<dyn Document>) -> Result<()> {
+        self.document = Some(document.clone());
+
+        Ok(())
+    }
+    /// See [`Connector::document`] for more details.
+    fn document(&self) -> Result<&dyn Document> {
+        self.document.as_deref().ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                "The document has not been set in the connector",
+            )
+        })
+    }
+    /// See [`Connector::set_parameters`] for more details.
+    fn set_parameters(&mut self, parameters: Value) {
+        *self.parameters = parameters;
+    }
+    /// See [`Connector::is_variable`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::exec::Exec;
+    /// use chewdata::connector::Connector;
+    ///
+    /// let mut connector = Exec::default();
+    /// connector.command = "cat".to_string();
+    /// connector.args = vec!["./data/one_line.json".to_string()];
+    /// assert_eq!(false, connector.is_variable());
+    /// connector.args = vec!["./data/{{ file }}.json".to_string()];
+    /// assert_eq!(true, connector.is_variable());
+    /// ```
+    fn is_variable(&self) -> bool {
+        self.command.has_mustache() || self.args.iter().any(|arg| arg.has_mustache())
+    }
+    /// See [`Connector::is_resource_will_change`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::exec::Exec;
+    /// use chewdata::connector::Connector;
+    /// use serde_json::Value;
+    ///
+    /// let mut connector = Exec::default();
+    /// let params = serde_json::from_str(r#"{"file":"one_line"}"#).unwrap();
+    /// connector.command = "cat".to_string();
+    /// connector.args = vec!["./data/one_line.json".to_string()];
+    /// assert_eq!(false, connector.is_resource_will_change(Value::Null).unwrap());
+    /// connector.args = vec!["./data/{{ file }}.json".to_string()];
+    /// assert_eq!(true, connector.is_resource_will_change(params).unwrap());
+    /// ```
+    fn is_resource_will_change(&self, new_parameters: Value) -> Result<bool> {
+        if !self.is_variable() {
+            trace!("Stay link to the same resource");
+            return Ok(false);
+        }
+
+        let metadata = serde_json::json!({
+            "metadata": self.metadata()
+        });
+
+        let mut new_parameters = new_parameters;
+        new_parameters.merge(&metadata);
+
+        let previous_command_line = self.command_line();
+        let new_command_line = command_line(&self.command, &self.args, new_parameters);
+
+        if previous_command_line == new_command_line {
+            trace!(
+                command_line = format!("{:?}", previous_command_line),
+                "Command line didn't change"
+            );
+            return Ok(false);
+        }
+
+        info!(
+            previous_command_line = format!("{:?}", previous_command_line),
+            new_command_line = format!("{:?}", new_command_line),
+            "Command line will change"
+        );
+
+        Ok(true)
+    }
+    /// See [`Connector::path`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::exec::Exec;
+    /// use chewdata::connector::Connector;
+    ///
+    /// let mut connector = Exec::default();
+    /// connector.command = "cat".to_string();
+    /// connector.args = vec!["./data/{{ file }}.json".to_string()];
+    /// connector.set_parameters(serde_json::from_str(r#"{"file":"one_line"}"#).unwrap());
+    /// assert_eq!("cat ./data/one_line.json", connector.path());
+    /// ```
+    fn path(&self) -> String {
+        let (program, args) = self.command_line();
+
+        std::iter::once(program)
+            .chain(args)
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+    /// See [`Connector::metadata`] for more details.
+    fn metadata(&self) -> Metadata {
+        match &self.document {
+            Some(document) => self.metadata.clone().merge(&document.metadata()),
+            None => self.metadata.clone(),
+        }
+    }
+    /// See [`Connector::fetch`] for more details.
+    ///
+    /// Run the command and yield the records of its stdout.
+    #[instrument(name = "exec::fetch")]
+    async fn fetch(&mut self) -> Result<Option<DataStream>> {
+        let document = self.document()?;
+        let output = self.run(None).await?;
+
+        if let Some(e) = exit_error(&output) {
+            warn!(
+                error = e.to_string().as_str(),
+                "The command exits with an error"
+            );
+
+            let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+            return Ok(Some(Box::pin(stream! {
+                yield DataResult::Err((Value::String(stderr), e));
+            })));
+        }
+
+        if !document.has_data(&output.stdout)? {
+            info!("No data found");
+            return Ok(None);
+        }
+
+        let dataset = document.read(&output.stdout)?;
+
+        info!("Fetch data with success");
+
+        Ok(Some(Box::pin(stream! {
+            for data in dataset {
+                yield data;
+            }
+        })))
+    }
+    /// See [`Connector::send`] for more details.
+    ///
+    /// Run the command with the dataset in its stdin and yield the records of its stdout.
+    #[instrument(name = "exec::send", skip(dataset))]
+    async fn send(&mut self, dataset: &DataSet) -> Result<Option<DataStream>> {
+        let document = self.document()?;
+
+        let mut buffer = Vec::default();
+        buffer.append(&mut document.header(dataset)?);
+        buffer.append(&mut document.write(dataset)?);
+        buffer.append(&mut document.footer(dataset)?);
+
+        let output = self.run(Some(buffer)).await?;
+
+        if let Some(e) = exit_error(&output) {
+            warn!(
+                error = e.to_string().as_str(),
+                "The command exits with an error"
+            );
+
+            let dataset = dataset.clone();
+            return Ok(Some(Box::pin(stream! {
+                for data in dataset {
+                    yield DataResult::Err((data.to_value(), Error::new(e.kind(), e.to_string())));
+                }
+            })));
+        }
+
+        info!("Send data with success");
+
+        let dataset = dataset.clone();
+        if !document.has_data(&output.stdout)? {
+            return Ok(Some(Box::pin(stream! {
+                for data in dataset {
+                    yield data;
+                }
+            })));
+        }
+
+        let results = document.read(&output.stdout)?;
+        if results.len() != dataset.len() {
+            let message = format!(
+                "The command returns {} records for {} records sent",
+                results.len(),
+                dataset.len()
+            );
+            warn!(
+                message,
+                "The records of the command can't replace the records sent"
+            );
+
+            return Ok(Some(Box::pin(stream! {
+                for data in dataset {
+                    yield DataResult::Err((data.to_value(), Error::new(ErrorKind::InvalidData, message.clone())));
+                }
+            })));
+        }
+
+        Ok(Some(Box::pin(stream! {
+            for data in results {
+                yield data;
+            }
+        })))
+    }
+    /// See [`Connector::has_send_results`] for more details.
+    fn has_send_results(&self) -> bool {
+        true
+    }
+    /// See [`Connector::paginate`] for more details.
+    async fn paginate(
+        &self,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<Box<dyn Connector>>> + Send>>> {
+        let paginator = Once {};
+        paginator.paginate(self).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::json::Json;
+    use crate::document::jsonl::Jsonl;
+    use macro_rules_attribute::apply;
+    use smol::stream::StreamExt;
+    use smol_macros::test;
+
+    fn connector(command: &str, args: &[&str]) -> Exec {
+        let mut connector = Exec {
+            command: command.to_string(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            timeout: Some(5),
+            ..Default::default()
+        };
+        connector.set_document(Box::new(Json::default())).unwrap();
+        connector
+    }
+
+    #[apply(test!)]
+    async fn fetch() {
+        let mut connector = connector("cat", &["./data/one_line.json"]);
+        let dataset: Vec<DataResult> = connector.fetch().await.unwrap().unwrap().collect().await;
+        assert_eq!(1, dataset.len());
+        assert_eq!(Value::Number(10.into()), dataset[0].to_value()["number"]);
+    }
+    #[apply(test!)]
+    async fn fetch_with_env_and_current_dir() {
+        let mut connector = connector(
+            "sh",
+            &[
+                "-c",
+                "echo \"{\\\"dir\\\":\\\"$(basename $PWD)\\\",\\\"env\\\":\\\"$MY_ENV\\\"}\"",
+            ],
+        );
+        connector.envs = HashMap::from([("MY_ENV".to_string(), "my_value".to_string())]);
+        connector.current_dir = Some("./data".to_string());
+        let dataset: Vec<Value> = connector
+            .fetch()
+            .await
+            .unwrap()
+            .unwrap()
+            .map(|data| data.to_value())
+            .collect()
+            .await;
+        assert_eq!(
+            vec![serde_json::json!({"dir":"data","env":"my_value"})],
+            dataset
+        );
+    }
+    #[apply(test!)]
+    async fn fetch_with_error() {
+        let mut connector = connector("sh", &["-c", "echo 'my error' >&2; exit 3"]);
+        let dataset: Vec<DataResult> = connector.fetch().await.unwrap().unwrap().collect().await;
+        assert_eq!(1, dataset.len());
+        match &dataset[0] {
+            DataResult::Err((value, e)) => {
+                assert_eq!(Value::String("my error\n".to_string()), *value);
+                assert_eq!(
+                    "The command exits with the code '3': my error",
+                    e.to_string()
+                );
+            }
+            DataResult::Ok(_) => panic!("The command should fail"),
+        }
+    }
+    #[apply(test!)]
+    async fn fetch_with_timeout() {
+        let mut connector = connector("sleep", &["5"]);
+        connector.timeout = Some(1);
+        let error = connector.fetch().await.err().unwrap();
+        assert_eq!(ErrorKind::TimedOut, error.kind());
+    }
+    #[apply(test!)]
+    async fn fetch_unknown_command() {
+        let mut connector = connector("chewdata_unknown_command", &[]);
+        let error = connector.fetch().await.err().unwrap();
+        assert_eq!(ErrorKind::NotFound, error.kind());
+    }
+    #[apply(test!)]
+    async fn send_and_transform() {
+        let mut connector = connector("sed", &["s/value/{{ input.field }}/"]);
+        connector.set_document(Box::new(Jsonl::default())).unwrap();
+        connector.set_parameters(serde_json::json!({"input":{"field":"new"}}));
+        let dataset: Vec<Value> = connector
+            .send(&vec![
+                DataResult::Ok(serde_json::json!({"field":"value1"})),
+                DataResult::Ok(serde_json::json!({"field":"value2"})),
+            ])
+            .await
+            .unwrap()
+            .unwrap()
+            .map(|data| data.to_value())
+            .collect()
+            .await;
+        assert_eq!(
+            vec![
+                serde_json::json!({"field":"new1"}),
+                serde_json::json!({"field":"new2"})
+            ],
+            dataset
+        );
+    }
+    #[apply(test!)]
+    async fn send_without_output() {
+        let mut connector = connector("sh", &["-c", "cat > /dev/null"]);
+        let dataset: Vec<Value> = connector
+            .send(&vec![DataResult::Ok(serde_json::json!({"field":"value"}))])
+            .await
+            .unwrap()
+            .unwrap()
+            .map(|data| data.to_value())
+            .collect()
+            .await;
+        assert_eq!(vec![serde_json::json!({"field":"value"})], dataset);
+    }
+    #[apply(test!)]
+    async fn send_with_missing_records() {
+        let mut connector = connector("head", &["-n", "1"]);
+        connector.set_document(Box::new(Jsonl::default())).unwrap();
+        let dataset: Vec<DataResult> = connector
+            .send(&vec![
+                DataResult::Ok(serde_json::json!({"field":"value1"})),
+                DataResult::Ok(serde_json::json!({"field":"value2"})),
+            ])
+            .await
+            .unwrap()
+            .unwrap()
+            .collect()
+            .await;
+        assert_eq!(2, dataset.len());
+        for (data, expected) in dataset.iter().zip(["value1", "value2"]) {
+            match data {
+                DataResult::Err((value, e)) => {
+                    assert_eq!(serde_json::json!({"field": expected}), *value);
+                    assert_eq!(ErrorKind::InvalidData, e.kind());
+                }
+                DataResult::Ok(_) => panic!("The command should return an error"),
+            }
+        }
+    }
+    #[apply(test!)]
+    async fn send_with_error() {
+        let mut connector = connector("sh", &["-c", "echo 'my error' >&2; exit 1"]);
+        let dataset: Vec<DataResult> = connector
+            .send(&vec![
+                DataResult::Ok(serde_json::json!({"field":"value1"})),
+                DataResult::Ok(serde_json::json!({"field":"value2"})),
+            ])
+            .await
+            .unwrap()
+            .unwrap()
+            .collect()
+            .await;
+        assert_eq!(2, dataset.len());
+        for (data, expected) in dataset.iter().zip(["value1", "value2"]) {
+            match data {
+                DataResult::Err((value, e)) => {
+                    assert_eq!(serde_json::json!({"field": expected}), *value);
+                    assert_eq!(ErrorKind::Other, e.kind());
+                }
+                DataResult::Ok(_) => panic!("The command should fail"),
+            }
+        }
+    }
+    #[apply(test!)]
+    async fn paginate() {
+        let connector = connector("cat", &[]);
+        let mut paging = connector.paginate().await.unwrap();
+        assert!(paging.next().await.transpose().unwrap().is_some());
+        assert!(paging.next().await.transpose().unwrap().is_none());
+    }
+}
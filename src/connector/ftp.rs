@@ -0,0 +1,977 @@
+//! FTP / SFTP file connector
+//!
+//! This connector reads from and writes to **remote files** through FTP or SFTP.
+//!
+//! It supports:
+//! - Reading **one or multiple files** using glob wildcards (`*`) in the file name
+//! - Dynamic paths using **Mustache templates**
+//! - Optional **checksum verification**
+//! - Password or private key authentication
+//!
+//! The protocol is defined by the scheme of the endpoint, `ftp://` or `sftp://`.
+//! If the document can be appended, the data are appended at the end of the remote file, otherwise the file is overwritten.
+//!
+//! ---
+//!
+//! ## Configuration
+//!
+//! | Key | Alias | Description | Default | Possible Values |
+//! |-----|-------|-------------|---------|-----------------|
+//! | `type` | – | Required to select this connector | `ftp` | `ftp` / `sftp` |
+//! | `metadata` | `meta` | Override or enrich resource metadata | `null` | [`crate::Metadata`] |
+//! | `endpoint` | `url` | Endpoint of the server with the protocol | `null` | `ftp://host:21` / `sftp://host:22` |
+//! | `path` | – | File path or glob pattern. Supports `*` in the file name and Mustache variables | `null` | `String` |
+//! | `parameters` | `params` | Variables injected into the path template | `null` | JSON object |
+//! | `username` | `user` | User used to authenticate | `anonymous` | `String` |
+//! | `password` | `pass` | Password used to authenticate | `null` | `String` |
+//! | `private_key` | `key` | Path of the private key used to authenticate with SFTP | `null` | `String` |
+//! | `public_key` | – | Path of the public key used with the private key | `null` | `String` |
+//! | `passphrase` | – | Passphrase of the private key | `null` | `String` |
+//! | `algo_with_checksum` | `checksum` | Checksum validation in the form `algorithm:checksum` | `null` | `sha224`, `sha256`, `sha384`, `sha512`, `sha3_*` |
+//! | `timeout` | – | Time in secound before to abort the connection | `5` | Unsigned number |
+//!
+//! ---
+//!
+//! ## Example
+//!
+//! ```json
+//! [
+//!   {
+//!     "type": "reader",
+//!     "connector": {
+//!       "type": "sftp",
+//!       "endpoint": "sftp://localhost:2222",
+//!       "path": "/upload/{{ folder }}/*.json",
+//!       "username": "{{ SFTP_USERNAME }}",
+//!       "private_key": "./.config/id_rsa",
+//!       "parameters": {
+//!         "folder": "my_folder"
+//!       }
+//!     }
+//!   },
+//!   {
+//!     "type": "writer",
+//!     "connector": {
+//!       "type": "ftp",
+//!       "endpoint": "ftp://localhost:21",
+//!       "path": "data/out/file.json",
+//!       "username": "{{ FTP_USERNAME }}",
+//!       "password": "{{ FTP_PASSWORD }}"
+//!     }
+//!   }
+//! ]
+//! ```
+use super::paginator::ftp::wildcard::Wildcard;
+use super::Connector;
+use crate::document::Document;
+use crate::helper::checksum::{hasher, str_to_algorithm_name_with_checksum};
+use crate::helper::mustache::Mustache;
+use crate::helper::string::{DisplayOnlyForDebugging, Obfuscate};
+use crate::{DataSet, DataStream, Metadata};
+use async_stream::stream;
+use async_trait::async_trait;
+use futures::Stream;
+use json_value_merge::Merge;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use ssh2::{OpenFlags, OpenType, Sftp};
+use std::io::{Cursor, Read, Seek, SeekFrom, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::Path;
+use std::pin::Pin;
+use std::time::Duration;
+use std::{
+    fmt,
+    io::{Error, ErrorKind, Result},
+};
+use suppaftp::types::FileType;
+use suppaftp::FtpStream;
+
+const DEFAULT_TIMEOUT: u64 = 5;
+const DEFAULT_USERNAME: &str = "anonymous";
+
+#[derive(Deserialize, Serialize, Clone, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct Ftp {
+    #[serde(skip)]
+    document: Option<Box<dyn Document>>,
+    #[serde(rename = "metadata")]
+    #[serde(alias = "meta")]
+    pub metadata: Metadata,
+    #[serde(alias = "url")]
+    pub endpoint: String,
+    pub path: String,
+    #[serde(alias = "params")]
+    pub parameters: Value,
+    #[serde(alias = "user")]
+    pub username: Option<String>,
+    #[serde(alias = "pass")]
+    pub password: Option<String>,
+    #[serde(alias = "key")]
+    pub private_key: Option<String>,
+    pub public_key: Option<String>,
+    pub passphrase: Option<String>,
+    #[serde(alias = "checksum")]
+    pub algo_with_checksum: Option<String>,
+    pub timeout: Option<u64>,
+}
+
+impl fmt::Debug for Ftp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Ftp")
+            .field("metadata", &self.metadata.display_only_for_debugging())
+            .field("endpoint", &self.endpoint.to_obfuscate())
+            .field("path", &self.path)
+            .field("parameters", &self.parameters.display_only_for_debugging())
+            .field("username", &self.username)
+            // Can contain sensitive data
+            .field("password", &self.password.display_only_for_debugging())
+            .field(
+                "private_key",
+                &self.private_key.display_only_for_debugging(),
+            )
+            .field("public_key", &self.public_key)
+            .field("passphrase", &self.passphrase.display_only_for_debugging())
+            .field("algo_with_checksum", &self.algo_with_checksum)
+            .field("timeout", &self.timeout)
+            .finish()
+    }
+}
+
+/// Blocking session opened on the remote server.
+enum Session {
+    Ftp(FtpStream),
+    Sftp(Sftp),
+}
+
+impl Session {
+    /// Get the size of a remote file. Return zero if the file doesn't exist.
+    fn len(&mut self, path: &str) -> Result<usize> {
+        match self {
+            Session::Ftp(ftp) => Ok(ftp.size(path).unwrap_or_default()),
+            Session::Sftp(sftp) => Ok(match sftp.stat(Path::new(path)) {
+                Ok(stat) => stat.size.unwrap_or_default() as usize,
+                Err(_) => 0,
+            }),
+        }
+    }
+    /// Read the content of a remote file. Return an empty content if the file doesn't exist.
+    fn read(&mut self, path: &str) -> Result<Vec<u8>> {
+        if 0 == self.len(path)? {
+            return Ok(Vec::default());
+        }
+
+        match self {
+            Session::Ftp(ftp) => Ok(ftp
+                .retr_as_buffer(path)
+                .map_err(|e| Error::new(ErrorKind::Interrupted, e))?
+                .into_inner()),
+            Session::Sftp(sftp) => {
+                let mut buff = Vec::default();
+                sftp.open(Path::new(path))?.read_to_end(&mut buff)?;
+                Ok(buff)
+            }
+        }
+    }
+    /// Overwrite the remote file with the content.
+    fn write(&mut self, path: &str, content: &[u8]) -> Result<()> {
+        match self {
+            Session::Ftp(ftp) => {
+                ftp.put_file(path, &mut Cursor::new(content))
+                    .map_err(|e| Error::new(ErrorKind::Interrupted, e))?;
+            }
+            Session::Sftp(sftp) => {
+                let mut file = sftp.create(Path::new(path))?;
+                file.write_all(content)?;
+                file.flush()?;
+            }
+        };
+
+        Ok(())
+    }
+    /// Append the content at the end of the remote file.
+    fn append(&mut self, path: &str, content: &[u8]) -> Result<()> {
+        match self {
+            Session::Ftp(ftp) => {
+                ftp.append_file(path, &mut Cursor::new(content))
+                    .map_err(|e| Error::new(ErrorKind::Interrupted, e))?;
+            }
+            Session::Sftp(sftp) => {
+                let mut file = sftp.open_mode(
+                    Path::new(path),
+                    OpenFlags::WRITE | OpenFlags::APPEND | OpenFlags::CREATE,
+                    0o644,
+                    OpenType::File,
+                )?;
+                file.write_all(content)?;
+                file.flush()?;
+            }
+        };
+
+        Ok(())
+    }
+    /// List the file names of a remote directory.
+    fn list(&mut self, directory: &str) -> Result<Vec<String>> {
+        let names = match self {
+            Session::Ftp(ftp) => ftp
+                .nlst(Some(directory))
+                .map_err(|e| Error::new(ErrorKind::Interrupted, e))?,
+            Session::Sftp(sftp) => sftp
+                .readdir(Path::new(directory))?
+                .into_iter()
+                .filter(|(_, stat)| stat.is_file())
+                .map(|(path, _)| path.display().to_string())
+                .collect(),
+        };
+
+        Ok(names
+            .into_iter()
+            .filter_map(|name| name.rsplit('/').next().map(|name| name.to_string()))
+            .filter(|name| !name.is_empty())
+            .collect())
+    }
+    fn close(self) {
+        if let Session::Ftp(mut ftp) = self {
+            let _ = ftp.quit();
+        }
+    }
+}
+
+impl Ftp {
+    /// Get the protocol and the address of the server from the endpoint.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::ftp::Ftp;
+    ///
+    /// let mut connector = Ftp::default();
+    /// connector.endpoint = "sftp://localhost".to_string();
+    /// assert_eq!(("sftp".to_string(), "localhost:22".to_string()), connector.address().unwrap());
+    /// connector.endpoint = "ftp://localhost:2121".to_string();
+    /// assert_eq!(("ftp".to_string(), "localhost:2121".to_string()), connector.address().unwrap());
+    /// ```
+    pub fn address(&self) -> Result<(String, String)> {
+        let (protocol, host) = match self.endpoint.split_once("://") {
+            Some((protocol, host)) => (protocol.to_lowercase(), host.trim_end_matches('/')),
+            None => {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!(
+                        "The endpoint '{}' must start with 'ftp://' or 'sftp://'",
+                        self.endpoint.to_obfuscate()
+                    ),
+                ))
+            }
+        };
+
+        let default_port = match protocol.as_str() {
+            "ftp" => 21,
+            "sftp" => 22,
+            _ => {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("The protocol '{}' is not supported", protocol),
+                ))
+            }
+        };
+
+        let address = match host.contains(':') {
+            true => host.to_string(),
+            false => format!("{}:{}", host, default_port),
+        };
+
+        Ok((protocol, address))
+    }
+    /// Open a blocking session on the remote server.
+    fn session(&self) -> Result<Session> {
+        let (protocol, address) = self.address()?;
+        let timeout = Duration::from_secs(self.timeout.unwrap_or(DEFAULT_TIMEOUT));
+        let socket_address = address.to_socket_addrs()?.next().ok_or_else(|| {
+            Error::new(
+                ErrorKind::NotFound,
+                format!("Can't resolve the address '{}'", address),
+            )
+        })?;
+        let username = self
+            .username
+            .clone()
+            .unwrap_or_else(|| DEFAULT_USERNAME.to_string());
+
+        match protocol.as_str() {
+            "sftp" => {
+                let tcp = TcpStream::connect_timeout(&socket_address, timeout)?;
+                let mut session = ssh2::Session::new()?;
+                session.set_timeout(timeout.as_millis() as u32);
+                session.set_tcp_stream(tcp);
+                session.handshake()?;
+
+                match (&self.private_key, &self.password) {
+                    (Some(private_key), _) => session.userauth_pubkey_file(
+                        &username,
+                        self.public_key.as_deref().map(Path::new),
+                        Path::new(private_key),
+                        self.passphrase.as_deref(),
+                    )?,
+                    (None, Some(password)) => session.userauth_password(&username, password)?,
+                    (None, None) => session.userauth_agent(&username)?,
+                };
+
+                if !session.authenticated() {
+                    return Err(Error::new(
+                        ErrorKind::PermissionDenied,
+                        format!("Can't authenticate the user '{}'", username),
+                    ));
+                }
+
+                Ok(Session::Sftp(session.sftp()?))
+            }
+            _ => {
+                let mut ftp = FtpStream::connect_timeout(socket_address, timeout)
+                    .map_err(|e| Error::new(ErrorKind::Interrupted, e))?;
+                ftp.login(
+                    username.as_str(),
+                    self.password.as_deref().unwrap_or_default(),
+                )
+                .map_err(|e| Error::new(ErrorKind::PermissionDenied, e))?;
+                ftp.transfer_type(FileType::Binary)
+                    .map_err(|e| Error::new(ErrorKind::Interrupted, e))?;
+
+                Ok(Session::Ftp(ftp))
+            }
+        }
+    }
+    /// List the remote files that match the path pattern.
+    ///
+    /// The wildcard is only supported in the file name.
+    pub async fn list(&self) -> Result<Vec<String>> {
+        let path = self.path();
+
+        let (directory, pattern) = match path.rsplit_once('/') {
+            Some((directory, pattern)) => (directory.to_string(), pattern.to_string()),
+            None => (".".to_string(), path.clone()),
+        };
+
+        if directory.contains('*') {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "The wildcard is only supported in the file name of the path '{}'",
+                    path
+                ),
+            ));
+        }
+
+        let matcher =
+            glob::Pattern::new(&pattern).map_err(|e| Error::new(ErrorKind::InvalidInput, e))?;
+        let connector = self.clone();
+
+        smol::unblock(move || {
+            let mut session = connector.session()?;
+            let names = session.list(&directory)?;
+            session.close();
+
+            Ok(names
+                .into_iter()
+                .filter(|name| matcher.matches(name))
+                .map(|name| match directory.as_str() {
+                    "." => name,
+                    _ => format!("{}/{}", directory, name),
+                })
+                .collect())
+        })
+        .await
+    }
+}
+
+/// Compute the checksum of a content.
+fn compute_checksum(algorithm_name: &str, buff: &[u8]) -> Result<String> {
+    let mut hasher = hasher(algorithm_name)?;
+    hasher.update(buff);
+
+    Ok(base16ct::lower::encode_string(&hasher.finalize()))
+}
+
+#[async_trait]
+impl Connector for Ftp {
+    /// See [`Connector::set_document`] for more details.
+    fn set_document(&mut self, document: Box<dyn Document>) -> Result<()> {
+        self.document = Some(document);
+
+        Ok(())
+    }
+    /// See [`Connector::document`] for more details.
+    fn document(&self) -> Result<&dyn Document> {
+        self.document.as_deref().ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                "The document has not been set in the connector",
+            )
+        })
+    }
+    /// See [`Connector::path`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::ftp::Ftp;
+    /// use chewdata::connector::Connector;
+    /// use serde_json::Value;
+    ///
+    /// let mut connector = Ftp::default();
+    /// connector.path = "/dir/filename_{{ field }}.ext".to_string();
+    /// let params: Value = serde_json::from_str(r#"{"field":"value"}"#).unwrap();
+    /// connector.set_parameters(params);
+    /// assert_eq!("/dir/filename_value.ext", connector.path());
+    /// ```
+    fn path(&self) -> String {
+        if !self.is_variable() {
+            return self.path.clone();
+        }
+
+        let mut params = self.parameters.clone();
+        params.merge(&serde_json::json!({
+            "metadata": self.metadata()
+        }));
+
+        let mut path = self.path.clone();
+        path.replace_mustache(params);
+        path
+    }
+    /// See [`Connector::len`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::ftp::Ftp;
+    /// use chewdata::connector::Connector;
+    /// use std::io;
+    ///
+    /// use macro_rules_attribute::apply;
+    /// use smol_macros::main;
+    ///
+    /// #[apply(main!)]
+    /// async fn main() -> io::Result<()> {
+    ///     let mut connector = Ftp::default();
+    ///     connector.endpoint = "sftp://localhost:2222".to_string();
+    ///     connector.username = Some("chewdata".to_string());
+    ///     connector.password = Some("chewdata".to_string());
+    ///     connector.path = "/upload/not_found_file".to_string();
+    ///     assert_eq!(0, connector.len().await?);
+    ///     Ok(())
+    /// }
+    /// ```
+    #[instrument(name = "ftp::len")]
+    async fn len(&self) -> Result<usize> {
+        if self.path.contains('*') {
+            return Err(Error::other("len() method not available for wildcard path"));
+        }
+
+        let path = self.path();
+        let connector = self.clone();
+
+        let len = smol::unblock(move || {
+            let mut session = connector.session()?;
+            let len = session.len(&path)?;
+            session.close();
+            Ok::<usize, Error>(len)
+        })
+        .await?;
+
+        info!(len = len, "Find the length");
+        Ok(len)
+    }
+    /// See [`Connector::set_parameters`] for more details.
+    fn set_parameters(&mut self, parameters: Value) {
+        self.parameters = parameters;
+    }
+    /// See [`Connector::is_variable`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::ftp::Ftp;
+    /// use chewdata::connector::Connector;
+    ///
+    /// let mut connector = Ftp::default();
+    /// assert_eq!(false, connector.is_variable());
+    /// connector.path = "/dir/filename_{{ field }}.ext".to_string();
+    /// assert_eq!(true, connector.is_variable());
+    /// ```
+    fn is_variable(&self) -> bool {
+        self.path.has_mustache()
+    }
+    /// See [`Connector::is_resource_will_change`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::ftp::Ftp;
+    /// use chewdata::connector::Connector;
+    /// use serde_json::Value;
+    ///
+    /// let mut connector = Ftp::default();
+    /// let params = serde_json::from_str(r#"{"field":"test"}"#).unwrap();
+    /// assert_eq!(false, connector.is_resource_will_change(Value::Null).unwrap());
+    /// connector.path = "/dir/static.ext".to_string();
+    /// assert_eq!(false, connector.is_resource_will_change(Value::Null).unwrap());
+    /// connector.path = "/dir/dynamic_{{ field }}.ext".to_string();
+    /// assert_eq!(true, connector.is_resource_will_change(params).unwrap());
+    /// ```
+    fn is_resource_will_change(&self, new_parameters: Value) -> Result<bool> {
+        if !self.is_variable() {
+            trace!("Stay link to the same resource");
+            return Ok(false);
+        }
+
+        let mut metadata_kv = Map::default();
+        metadata_kv.insert("metadata".to_string(), self.metadata().into());
+        let metadata = Value::Object(metadata_kv);
+
+        let mut new_parameters = new_parameters;
+        new_parameters.merge(&metadata);
+        let mut old_parameters = self.parameters.clone();
+        old_parameters.merge(&metadata);
+
+        let mut previous_path = self.path.clone();
+        previous_path.replace_mustache(old_parameters);
+
+        let mut new_path = self.path.clone();
+        new_path.replace_mustache(new_parameters);
+
+        if previous_path == new_path {
+            trace!(path = previous_path, "Stay link to the same resource");
+            return Ok(false);
+        }
+
+        info!(
+            previous_path = previous_path,
+            new_path = new_path,
+            "Will use another resource, regarding the new parameters"
+        );
+        Ok(true)
+    }
+    /// See [`Connector::metadata`] for more details.
+    fn metadata(&self) -> Metadata {
+        match &self.document {
+            Some(document) => self.metadata.clone().merge(&document.metadata()),
+            None => self.metadata.clone(),
+        }
+    }
+    /// See [`Connector::fetch`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::ftp::Ftp;
+    /// use chewdata::connector::Connector;
+    /// use chewdata::document::json::Json;
+    /// use chewdata::DataResult;
+    /// use smol::stream::StreamExt;
+    /// use std::io;
+    ///
+    /// use macro_rules_attribute::apply;
+    /// use smol_macros::main;
+    ///
+    /// #[apply(main!)]
+    /// async fn main() -> io::Result<()> {
+    ///     let mut connector = Ftp::default();
+    ///     connector.endpoint = "ftp://localhost:21".to_string();
+    ///     connector.username = Some("chewdata".to_string());
+    ///     connector.password = Some("chewdata".to_string());
+    ///     connector.path = "test_ftp_fetch.json".to_string();
+    ///     connector.set_document(Box::new(Json::default()))?;
+    ///     connector.erase().await?;
+    ///     connector.send(&vec![DataResult::Ok(serde_json::from_str(r#"{"column1":"value1"}"#)?)]).await?;
+    ///
+    ///     let datastream = connector.fetch().await?.unwrap();
+    ///     assert!(
+    ///         0 < datastream.count().await,
+    ///         "The inner connector should have a size upper than zero"
+    ///     );
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[instrument(name = "ftp::fetch")]
+    async fn fetch(&mut self) -> std::io::Result<Option<DataStream>> {
+        let document = self.document()?;
+        let path = self.path();
+        let algo_with_checksum_opt = self.algo_with_checksum.clone();
+
+        if path.has_mustache() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("This path '{}' is not fully resolved", path),
+            ));
+        }
+
+        let connector = self.clone();
+        let path_to_read = path.clone();
+        let buff = smol::unblock(move || {
+            let mut session = connector.session()?;
+            let buff = session.read(&path_to_read)?;
+            session.close();
+            Ok::<Vec<u8>, Error>(buff)
+        })
+        .await?;
+
+        info!(path = path, "Fetch data with success");
+
+        if !document.has_data(&buff)? {
+            return Ok(None);
+        }
+
+        if let Some(algorithm_name_with_checksum) = &algo_with_checksum_opt {
+            if let (algorithm_name, Some(expected_checksum)) =
+                str_to_algorithm_name_with_checksum(algorithm_name_with_checksum)?
+            {
+                let digest = compute_checksum(algorithm_name, &buff)?;
+
+                if !digest.eq(expected_checksum) {
+                    return Err(Error::new(
+                        ErrorKind::PermissionDenied,
+                        format!(
+                            "Checksum verification failed. {}({}) != configuration({})",
+                            path, digest, expected_checksum
+                        ),
+                    ));
+                }
+            };
+        }
+
+        let dataset = document.read(&buff)?;
+
+        Ok(Some(Box::pin(stream! {
+            for data in dataset {
+                yield data;
+            }
+        })))
+    }
+    /// See [`Connector::send`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::ftp::Ftp;
+    /// use chewdata::connector::Connector;
+    /// use chewdata::document::json::Json;
+    /// use chewdata::DataResult;
+    /// use smol::prelude::*;
+    /// use std::io;
+    ///
+    /// use macro_rules_attribute::apply;
+    /// use smol_macros::main;
+    ///
+    /// #[apply(main!)]
+    /// async fn main() -> io::Result<()> {
+    ///     let mut connector = Ftp::default();
+    ///     connector.endpoint = "sftp://localhost:2222".to_string();
+    ///     connector.username = Some("chewdata".to_string());
+    ///     connector.password = Some("chewdata".to_string());
+    ///     connector.path = "/upload/test_sftp_send_doc.json".to_string();
+    ///     connector.set_document(Box::new(Json::default()))?;
+    ///     connector.erase().await?;
+    ///
+    ///     let expected_result1 =
+    ///         DataResult::Ok(serde_json::from_str(r#"{"column1":"value1"}"#)?);
+    ///     connector.send(&vec![expected_result1.clone()]).await?;
+    ///
+    ///     let mut connector_read = connector.clone();
+    ///     let mut datastream = connector_read.fetch().await?.unwrap();
+    ///     assert_eq!(expected_result1, datastream.next().await.unwrap());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[instrument(skip(dataset), name = "ftp::send")]
+    async fn send(&mut self, dataset: &DataSet) -> std::io::Result<Option<DataStream>> {
+        let document = self.document()?;
+        let terminator = document.terminator()?;
+        let footer = document.footer(dataset)?;
+        let header = document.header(dataset)?;
+        let body = document.write(dataset)?;
+        let can_append = document.can_append();
+        let path = self.path();
+
+        if path.has_mustache() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("This path '{}' is not fully resolved", path),
+            ));
+        }
+
+        let connector = self.clone();
+        let path_to_write = path.clone();
+        let algo_with_checksum_opt = self.algo_with_checksum.clone();
+
+        let checksum = smol::unblock(move || {
+            let path = path_to_write;
+            let mut session = connector.session()?;
+
+            match (can_append, footer.is_empty()) {
+                // Append the data at the end of the remote file.
+                (true, true) => {
+                    let file_len = session.len(&path)?;
+                    let mut buffer = Vec::default();
+
+                    if 0 == file_len {
+                        buffer.write_all(&header)?;
+                    }
+                    if 0 < file_len && file_len > header.len() {
+                        buffer.write_all(&terminator)?;
+                    }
+                    buffer.write_all(&body)?;
+
+                    session.append(&path, &buffer)?;
+                }
+                // Insert the data before the footer and rewrite the remote file.
+                (true, false) => {
+                    let content_file = session.read(&path)?;
+                    let file_len = content_file.len();
+                    let mut cursor = Cursor::new(content_file);
+
+                    match file_len as isize - footer.len() as isize {
+                        start if start > 0 => cursor.seek(SeekFrom::Start(start as u64)),
+                        _ => cursor.seek(SeekFrom::Start(0)),
+                    }?;
+
+                    if 0 == file_len {
+                        cursor.write_all(&header)?;
+                    }
+                    if 0 < file_len && file_len > (header.len() + footer.len()) {
+                        cursor.write_all(&terminator)?;
+                    }
+                    cursor.write_all(&body)?;
+                    cursor.write_all(&footer)?;
+
+                    session.write(&path, &cursor.into_inner())?;
+                }
+                // Overwrite the remote file.
+                (false, _) => {
+                    let mut buffer = Vec::default();
+                    buffer.write_all(&header)?;
+                    buffer.write_all(&body)?;
+                    buffer.write_all(&footer)?;
+
+                    session.write(&path, &buffer)?;
+                }
+            };
+
+            let checksum = match &algo_with_checksum_opt {
+                Some(algorithm_name_with_checksum) => {
+                    let (algorithm_name, _) =
+                        str_to_algorithm_name_with_checksum(algorithm_name_with_checksum)?;
+                    compute_checksum(algorithm_name, &session.read(&path)?)?
+                }
+                None => "algorithm undefined".to_string(),
+            };
+
+            session.close();
+            Ok::<String, Error>(checksum)
+        })
+        .await?;
+
+        info!(path = path, checksum = checksum, "Send data with success");
+
+        Ok(None)
+    }
+    /// See [`Connector::erase`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::ftp::Ftp;
+    /// use chewdata::connector::Connector;
+    /// use chewdata::document::json::Json;
+    /// use chewdata::DataResult;
+    /// use std::io;
+    ///
+    /// use macro_rules_attribute::apply;
+    /// use smol_macros::main;
+    ///
+    /// #[apply(main!)]
+    /// async fn main() -> io::Result<()> {
+    ///     let mut connector = Ftp::default();
+    ///     connector.endpoint = "ftp://localhost:21".to_string();
+    ///     connector.username = Some("chewdata".to_string());
+    ///     connector.password = Some("chewdata".to_string());
+    ///     connector.path = "test_ftp_erase_doc.json".to_string();
+    ///     connector.set_document(Box::new(Json::default()))?;
+    ///
+    ///     connector.send(&vec![DataResult::Ok(serde_json::from_str(r#"{"column1":"value1"}"#)?)]).await?;
+    ///     connector.erase().await?;
+    ///     let datastream = connector.fetch().await?;
+    ///     assert!(datastream.is_none(), "No datastream with empty body");
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[instrument(name = "ftp::erase")]
+    async fn erase(&mut self) -> Result<()> {
+        let path = self.path();
+
+        if path.has_mustache() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("This path '{}' is not fully resolved", path),
+            ));
+        }
+
+        let paths = match path.contains('*') {
+            true => self.list().await?,
+            false => vec![path.clone()],
+        };
+
+        let connector = self.clone();
+        smol::unblock(move || {
+            let mut session = connector.session()?;
+            for path in paths {
+                session.write(&path, &[])?;
+            }
+            session.close();
+            Ok::<(), Error>(())
+        })
+        .await?;
+
+        info!(path = path, "Erase data with success");
+        Ok(())
+    }
+    /// See [`Connector::paginate`] for more details.
+    async fn paginate(
+        &self,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<Box<dyn Connector>>> + Send>>> {
+        Wildcard::new(self).await?.paginate(self).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use macro_rules_attribute::apply;
+    use smol::stream::StreamExt;
+    use smol_macros::test;
+
+    use super::*;
+    use crate::document::json::Json;
+    use crate::document::jsonl::Jsonl;
+    use crate::DataResult;
+
+    fn sftp(path: &str) -> Ftp {
+        let mut connector = Ftp::default();
+        connector.endpoint = "sftp://localhost:2222".to_string();
+        connector.username = Some("chewdata".to_string());
+        connector.password = Some("chewdata".to_string());
+        connector.path = path.to_string();
+        connector
+    }
+
+    fn ftp(path: &str) -> Ftp {
+        let mut connector = Ftp::default();
+        connector.endpoint = "ftp://localhost:21".to_string();
+        connector.username = Some("chewdata".to_string());
+        connector.password = Some("chewdata".to_string());
+        connector.path = path.to_string();
+        connector
+    }
+
+    #[test]
+    fn address() {
+        let mut connector = Ftp::default();
+        connector.endpoint = "localhost".to_string();
+        assert!(connector.address().is_err());
+        connector.endpoint = "http://localhost".to_string();
+        assert!(connector.address().is_err());
+        connector.endpoint = "SFTP://localhost/".to_string();
+        assert_eq!(
+            ("sftp".to_string(), "localhost:22".to_string()),
+            connector.address().unwrap()
+        );
+    }
+    #[test]
+    fn is_resource_will_change() {
+        let mut connector = Ftp::default();
+        let params = serde_json::from_str(r#"{"field":"test"}"#).unwrap();
+        assert_eq!(
+            false,
+            connector.is_resource_will_change(Value::Null).unwrap()
+        );
+        connector.path = "/dir/dynamic_{{ field }}.ext".to_string();
+        assert_eq!(true, connector.is_resource_will_change(params).unwrap());
+    }
+    #[apply(test!)]
+    async fn sftp_send_and_fetch() {
+        let expected_result1 =
+            DataResult::Ok(serde_json::from_str(r#"{"column1":"value1"}"#).unwrap());
+        let expected_result2 =
+            DataResult::Ok(serde_json::from_str(r#"{"column1":"value2"}"#).unwrap());
+        let mut connector = sftp("/upload/test_sftp_send.json");
+        connector.set_document(Box::new(Json::default())).unwrap();
+        connector.erase().await.unwrap();
+        connector
+            .send(&vec![expected_result1.clone()])
+            .await
+            .unwrap();
+        connector
+            .send(&vec![expected_result2.clone()])
+            .await
+            .unwrap();
+
+        let mut connector_read = connector.clone();
+        let mut datastream = connector_read.fetch().await.unwrap().unwrap();
+        assert_eq!(expected_result1, datastream.next().await.unwrap());
+        assert_eq!(expected_result2, datastream.next().await.unwrap());
+    }
+    #[apply(test!)]
+    async fn ftp_append_and_fetch() {
+        let expected_result1 =
+            DataResult::Ok(serde_json::from_str(r#"{"column1":"value1"}"#).unwrap());
+        let expected_result2 =
+            DataResult::Ok(serde_json::from_str(r#"{"column1":"value2"}"#).unwrap());
+        let mut connector = ftp("test_ftp_append.jsonl");
+        connector.set_document(Box::new(Jsonl::default())).unwrap();
+        connector.erase().await.unwrap();
+        connector
+            .send(&vec![expected_result1.clone()])
+            .await
+            .unwrap();
+        connector
+            .send(&vec![expected_result2.clone()])
+            .await
+            .unwrap();
+
+        let mut connector_read = connector.clone();
+        let mut datastream = connector_read.fetch().await.unwrap().unwrap();
+        assert_eq!(expected_result1, datastream.next().await.unwrap());
+        assert_eq!(expected_result2, datastream.next().await.unwrap());
+    }
+    #[apply(test!)]
+    async fn fetch_with_wrong_checksum() {
+        let mut connector = sftp("/upload/test_sftp_checksum.json");
+        connector.set_document(Box::new(Json::default())).unwrap();
+        connector.erase().await.unwrap();
+        connector
+            .send(&vec![DataResult::Ok(
+                serde_json::from_str(r#"{"column1":"value1"}"#).unwrap(),
+            )])
+            .await
+            .unwrap();
+
+        connector.algo_with_checksum = Some("sha256:wrong".to_string());
+        let error = connector.fetch().await.err().unwrap();
+        assert_eq!(ErrorKind::PermissionDenied, error.kind());
+    }
+    #[apply(test!)]
+    async fn erase() {
+        let mut connector = ftp("test_ftp_erase.json");
+        connector.set_document(Box::new(Json::default())).unwrap();
+        connector
+            .send(&vec![DataResult::Ok(
+                serde_json::from_str(r#"{"column1":"value1"}"#).unwrap(),
+            )])
+            .await
+            .unwrap();
+        connector.erase().await.unwrap();
+        let datastream = connector.fetch().await.unwrap();
+        assert!(datastream.is_none(), "No datastream with empty body.");
+    }
+}
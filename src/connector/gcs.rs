@@ -0,0 +1,1258 @@
+//! Read and write data into Google Cloud Storage buckets.
+//!
+//! The connector authenticates with a service account JSON key, signed with [`crate::connector::authenticator::jwt::Jwt`],
+//! or with an OAuth2 access token. Without credentials, the calls are anonymous, which is useful with an emulator like fake-gcs-server.
+//! The objects upper than `resumable_threshold` are sent with a resumable upload.
+//!
+//! ### Configuration
+//!
+//! | key                 | alias                      | Description                                                              | Default Value                    | Possible Values            |
+//! | ------------------- | -------------------------- | ------------------------------------------------------------------------ | -------------------------------- | -------------------------- |
+//! | type                | -                          | Required in order to use this connector                                  | `gcs`                            | `gcs`                      |
+//! | metadata            | meta                       | Override metadata information                                            | `null`                           | [`crate::Metadata`]        |
+//! | endpoint            | -                          | Endpoint of the storage                                                  | `https://storage.googleapis.com` | String                     |
+//! | bucket              | -                          | The bucket name                                                          | `null`                           | String                     |
+//! | path                | key / object               | The path of the object. Can use `*` in order to read multiple objects    | `null`                           | String                     |
+//! | parameters          | params                     | The parameters used to remplace variables in the path                    | `null`                           | Object or Array of objects |
+//! | limit               | -                          | Limit the number of objects to read                                      | `null`                           | Unsigned number            |
+//! | skip                | -                          | Skip N objects before to start to read the next objects                  | `0`                              | Unsigned number            |
+//! | generation          | version                    | Read a specific generation of an object                                  | `null`                           | String                     |
+//! | tags                | -                          | Custom metadata to apply on the object                                   | `(service:writer:name,chewdata)` | List of Key/Value          |
+//! | cache_control       | -                          | Override the object cache control                                        | `null`                           | String                     |
+//! | credentials         | service_account / key_file | Path or content of the service account JSON key                          | `null`                           | String                     |
+//! | token               | access_token               | OAuth2 access token used instead of the service account                  | `null`                           | String                     |
+//! | resumable_threshold | -                          | Size in bytes from which the object is sent with a resumable upload      | `8388608`                        | Unsigned number            |
+//! | chunk_size          | -                          | Size in bytes of each chunk of a resumable upload. Multiple of 256 KiB   | `8388608`                        | Unsigned number            |
+//! | timeout             | -                          | Time in secound before to abort the call                                 | `5`                              | Unsigned number            |
+//!
+//! If not set, the `endpoint`, `credentials` and `token` are read from the environment variables
+//! `GCS_ENDPOINT`, `GOOGLE_APPLICATION_CREDENTIALS` and `GCS_TOKEN`.
+//!
+//! ### Examples
+//!
+//! ```json
+//! [
+//!     {
+//!         "type": "r",
+//!         "connector": {
+//!             "type": "gcs",
+//!             "endpoint": "{{ GCS_ENDPOINT }}",
+//!             "bucket": "my-bucket",
+//!             "path": "data/*.json*",
+//!             "credentials": "./service_account.json",
+//!             "limit": 10,
+//!             "skip": 0
+//!         }
+//!     }
+//! ]
+//! ```
+use super::authenticator::jwt::{Jwt, SigningType};
+use super::curl::Curl;
+use super::Connector;
+use crate::document::Document;
+use crate::helper::mustache::Mustache;
+use crate::helper::string::{percent_encode, DisplayOnlyForDebugging};
+use crate::{ConnectorStream, DataSet, DataStream, Metadata};
+use async_lock::Mutex;
+use async_stream::stream;
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::Stream;
+use http::{HeaderMap, HeaderName, HeaderValue, Method};
+use json_value_merge::Merge;
+use jsonwebtoken::Algorithm;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::env;
+use std::pin::Pin;
+use std::sync::OnceLock;
+use std::vec::IntoIter;
+use std::{
+    fmt,
+    io::{Cursor, Error, ErrorKind, Result, Seek, SeekFrom, Write},
+};
+
+// Access tokens by service account with their expiration timestamp.
+static TOKENS: OnceLock<Mutex<HashMap<String, (String, i64)>>> = OnceLock::new();
+
+const DEFAULT_TAG_SERVICE_WRITER_NAME: (&str, &str) = ("service:writer:name", "chewdata");
+const DEFAULT_ENDPOINT: &str = "https://storage.googleapis.com";
+const DEFAULT_TOKEN_URI: &str = "https://oauth2.googleapis.com/token";
+const DEFAULT_SCOPE: &str = "https://www.googleapis.com/auth/devstorage.read_write";
+const DEFAULT_RESUMABLE_SIZE: usize = 8 * 1024 * 1024;
+const RESUMABLE_CHUNK_MULTIPLE: usize = 256 * 1024;
+// Refresh the access token before its expiration.
+const TOKEN_EXPIRATION_MARGIN: i64 = 60;
+
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct Gcs {
+    #[serde(skip)]
+    document: Option<Box<dyn Document>>,
+    #[serde(rename = "metadata")]
+    #[serde(alias = "meta")]
+    pub metadata: Metadata,
+    pub endpoint: Option<String>,
+    pub bucket: String,
+    #[serde(alias = "key")]
+    #[serde(alias = "object")]
+    pub path: String,
+    #[serde(alias = "params")]
+    pub parameters: Box<Value>,
+    pub limit: Option<usize>,
+    pub skip: usize,
+    #[serde(alias = "version")]
+    pub generation: Option<String>,
+    pub tags: HashMap<String, String>,
+    pub cache_control: Option<String>,
+    #[serde(alias = "service_account")]
+    #[serde(alias = "key_file")]
+    pub credentials: Option<String>,
+    #[serde(alias = "access_token")]
+    pub token: Option<String>,
+    pub resumable_threshold: usize,
+    pub chunk_size: usize,
+    pub timeout: Option<u64>,
+}
+
+impl fmt::Debug for Gcs {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Gcs")
+            .field("document", &self.document.display_only_for_debugging())
+            .field("metadata", &self.metadata.display_only_for_debugging())
+            .field("endpoint", &self.endpoint)
+            .field("bucket", &self.bucket)
+            .field("path", &self.path)
+            .field("parameters", &self.parameters.display_only_for_debugging())
+            .field("limit", &self.limit)
+            .field("skip", &self.skip)
+            .field("generation", &self.generation)
+            .field("tags", &self.tags)
+            .field("cache_control", &self.cache_control)
+            // Can contain sensitive data
+            .field(
+                "credentials",
+                &self.credentials.display_only_for_debugging(),
+            )
+            .field("token", &self.token.display_only_for_debugging())
+            .field("resumable_threshold", &self.resumable_threshold)
+            .field("chunk_size", &self.chunk_size)
+            .field("timeout", &self.timeout)
+            .finish()
+    }
+}
+
+impl Default for Gcs {
+    fn default() -> Self {
+        let mut tags = HashMap::default();
+        tags.insert(
+            DEFAULT_TAG_SERVICE_WRITER_NAME.0.to_string(),
+            DEFAULT_TAG_SERVICE_WRITER_NAME.1.to_string(),
+        );
+
+        Gcs {
+            document: None,
+            metadata: Metadata::default(),
+            endpoint: None,
+            bucket: String::default(),
+            path: String::default(),
+            parameters: Box::<Value>::default(),
+            limit: None,
+            skip: 0,
+            generation: None,
+            tags,
+            cache_control: None,
+            credentials: None,
+            token: None,
+            resumable_threshold: DEFAULT_RESUMABLE_SIZE,
+            chunk_size: DEFAULT_RESUMABLE_SIZE,
+            timeout: None,
+        }
+    }
+}
+
+/// Service account JSON key.
+#[derive(Deserialize, Clone)]
+struct ServiceAccount {
+    client_email: String,
+    private_key: String,
+    token_uri: Option<String>,
+}
+
+impl Gcs {
+    /// Get the endpoint of the storage.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::gcs::Gcs;
+    ///
+    /// let mut connector = Gcs::default();
+    /// connector.endpoint = Some("http://localhost:4443/".to_string());
+    /// assert_eq!("http://localhost:4443", connector.endpoint());
+    /// ```
+    pub fn endpoint(&self) -> String {
+        match (self.endpoint.clone(), env::var("GCS_ENDPOINT")) {
+            (Some(endpoint), _) => endpoint,
+            (None, Ok(endpoint)) => endpoint,
+            (None, Err(_)) => DEFAULT_ENDPOINT.to_string(),
+        }
+        .trim_end_matches('/')
+        .to_string()
+    }
+    /// Get the service account from a path or from its JSON content.
+    fn service_account(&self) -> Result<Option<ServiceAccount>> {
+        let credentials = match self
+            .credentials
+            .clone()
+            .or_else(|| env::var("GOOGLE_APPLICATION_CREDENTIALS").ok())
+        {
+            Some(credentials) => credentials,
+            None => return Ok(None),
+        };
+
+        let content = match credentials.trim_start().starts_with('{') {
+            true => credentials,
+            false => std::fs::read_to_string(&credentials)?,
+        };
+
+        Ok(Some(serde_json::from_str(&content).map_err(|e| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                format!("The service account is not valid: {}", e),
+            )
+        })?))
+    }
+    /// Get an access token. Exchange a JWT signed by the service account if no token is defined.
+    async fn access_token(&self) -> Result<Option<String>> {
+        if let Some(token) = self.token.clone().or_else(|| env::var("GCS_TOKEN").ok()) {
+            return Ok(Some(token));
+        }
+
+        let service_account = match self.service_account()? {
+            Some(service_account) => service_account,
+            None => return Ok(None),
+        };
+
+        let now = chrono::Utc::now().timestamp();
+        let tokens = TOKENS.get_or_init(|| Mutex::new(HashMap::default()));
+        let mut tokens = tokens.lock().await;
+
+        if let Some((token, expire_at)) = tokens.get(&service_account.client_email) {
+            if now + TOKEN_EXPIRATION_MARGIN < *expire_at {
+                return Ok(Some(token.clone()));
+            }
+        }
+
+        let token_uri = service_account
+            .token_uri
+            .clone()
+            .unwrap_or_else(|| DEFAULT_TOKEN_URI.to_string());
+
+        let jwt = Jwt {
+            algorithm: Algorithm::RS256,
+            signing_type: Some(SigningType::RsaPem),
+            key: service_account.private_key.clone(),
+            ..Default::default()
+        };
+        let assertion = jwt
+            .encode(&serde_json::json!({
+                "iss": service_account.client_email,
+                "scope": DEFAULT_SCOPE,
+                "aud": token_uri,
+                "iat": now,
+                "exp": now + 3600,
+            }))
+            .map_err(|e| Error::new(ErrorKind::InvalidInput, e))?;
+
+        let mut curl = Curl::default();
+        curl.metadata = Metadata {
+            mime_type: Some("application".to_string()),
+            mime_subtype: Some("x-www-form-urlencoded".to_string()),
+            ..Default::default()
+        };
+        if self.timeout.is_some() {
+            curl.timeout = self.timeout;
+        }
+
+        let body = format!(
+            "grant_type={}&assertion={}",
+            percent_encode("urn:ietf:params:oauth:grant-type:jwt-bearer", false),
+            assertion
+        );
+        let (status, _, data) = curl
+            .call_with_headers(&Method::POST, &token_uri, Bytes::from(body))
+            .await?;
+        check_status(
+            status,
+            &data,
+            "authenticate with",
+            &service_account.client_email,
+        )?;
+
+        let response: Value =
+            serde_json::from_slice(&data).map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+        let token = response
+            .get("access_token")
+            .and_then(Value::as_str)
+            .ok_or_else(|| {
+                Error::new(
+                    ErrorKind::InvalidData,
+                    "The access token is missing in the response",
+                )
+            })?
+            .to_string();
+        let expires_in = response
+            .get("expires_in")
+            .and_then(Value::as_i64)
+            .unwrap_or(3600);
+
+        info!(
+            client_email = service_account.client_email,
+            "Access token refreshed with success"
+        );
+
+        tokens.insert(
+            service_account.client_email.clone(),
+            (token.clone(), now + expires_in),
+        );
+
+        Ok(Some(token))
+    }
+    /// Call the storage and return the status code with the response headers and body.
+    ///
+    /// The uri can be a path of the endpoint or an absolute url.
+    async fn call(
+        &self,
+        method: Method,
+        uri: &str,
+        extra_headers: Vec<(&str, String)>,
+        body: Vec<u8>,
+    ) -> Result<(u16, HashMap<String, String>, Vec<u8>)> {
+        let mut headers = HeaderMap::default();
+
+        if let Some(token) = self.access_token().await? {
+            insert_header(&mut headers, "authorization", &format!("Bearer {}", token))?;
+        }
+        for (name, value) in extra_headers {
+            insert_header(&mut headers, name, &value)?;
+        }
+        if Method::POST == method || Method::PUT == method {
+            insert_header(&mut headers, "content-length", &body.len().to_string())?;
+        }
+
+        let mut curl = Curl::default();
+        curl.endpoint = match uri.starts_with("http") {
+            true => String::default(),
+            false => self.endpoint(),
+        };
+        curl.headers = headers;
+        if self.timeout.is_some() {
+            curl.timeout = self.timeout;
+        }
+
+        curl.call_with_headers(&method, uri, Bytes::from(body))
+            .await
+    }
+    fn object_uri(&self, path: &str) -> String {
+        let mut uri = format!(
+            "/storage/v1/b/{}/o/{}",
+            percent_encode(&self.bucket, false),
+            percent_encode(path.trim_start_matches('/'), false)
+        );
+
+        if let Some(generation) = &self.generation {
+            uri += &format!("?generation={}", percent_encode(generation, false));
+        }
+
+        uri
+    }
+    /// Get the metadata of an object. Return None if the object doesn't exist.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::gcs::Gcs;
+    /// use std::io;
+    ///
+    /// use macro_rules_attribute::apply;
+    /// use smol_macros::main;
+    ///
+    /// #[apply(main!)]
+    /// async fn main() -> io::Result<()> {
+    ///     let mut connector = Gcs::default();
+    ///     connector.endpoint = Some("http://localhost:4443".to_string());
+    ///     connector.bucket = "my-bucket".to_string();
+    ///     connector.path = "data/not_found.json".to_string();
+    ///     assert!(connector.object().await?.is_none());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn object(&self) -> Result<Option<Value>> {
+        let path = self.path();
+        let (status, _, data) = self
+            .call(
+                Method::GET,
+                &self.object_uri(&path),
+                Vec::default(),
+                Vec::default(),
+            )
+            .await?;
+
+        if 404 == status {
+            return Ok(None);
+        }
+        check_status(status, &data, "get", &path)?;
+
+        Ok(Some(
+            serde_json::from_slice(&data).map_err(|e| Error::new(ErrorKind::InvalidData, e))?,
+        ))
+    }
+    /// Get the content of an object. Return an empty content if the object doesn't exist.
+    async fn download(&self, path: &str) -> Result<Vec<u8>> {
+        let uri = self.object_uri(path);
+        let uri = match uri.contains('?') {
+            true => format!("{}&alt=media", uri),
+            false => format!("{}?alt=media", uri),
+        };
+
+        let (status, _, data) = self
+            .call(Method::GET, &uri, Vec::default(), Vec::default())
+            .await?;
+
+        if 404 == status {
+            return Ok(Vec::default());
+        }
+        check_status(status, &data, "download", path)?;
+
+        Ok(data)
+    }
+    /// Resource of the object to upload with its metadata.
+    fn object_resource(&self, path: &str) -> Value {
+        let metadata = self.metadata();
+
+        let mut custom_metadata: HashMap<String, String> = metadata
+            .to_hashmap()
+            .into_iter()
+            .map(|(key, value)| (key, value.replace('\n', "\\n")))
+            .collect();
+        custom_metadata.extend(Gcs::default().tags);
+        custom_metadata.extend(self.tags.clone());
+
+        let mut resource = serde_json::json!({
+            "name": path.trim_start_matches('/'),
+            "metadata": custom_metadata,
+        });
+
+        if !metadata.content_type().is_empty() {
+            resource
+                .merge_in("/contentType", &Value::String(metadata.content_type()))
+                .ok();
+        }
+        if !metadata.content_language().is_empty() {
+            resource
+                .merge_in(
+                    "/contentLanguage",
+                    &Value::String(metadata.content_language()),
+                )
+                .ok();
+        }
+        if let Some(cache_control) = &self.cache_control {
+            resource
+                .merge_in("/cacheControl", &Value::String(cache_control.clone()))
+                .ok();
+        }
+
+        resource
+    }
+    /// Upload an object with its metadata in one call.
+    async fn upload_multipart(&self, path: &str, content: Vec<u8>) -> Result<()> {
+        let boundary = format!("chewdata_{}", uuid::Uuid::new_v4().simple());
+        let content_type = match self.metadata().content_type().is_empty() {
+            true => "application/octet-stream".to_string(),
+            false => self.metadata().content_type(),
+        };
+
+        let mut body = Vec::default();
+        write!(
+            body,
+            "--{}\r\nContent-Type: application/json; charset=UTF-8\r\n\r\n{}\r\n--{}\r\nContent-Type: {}\r\n\r\n",
+            boundary,
+            self.object_resource(path),
+            boundary,
+            content_type
+        )?;
+        body.write_all(&content)?;
+        write!(body, "\r\n--{}--", boundary)?;
+
+        let uri = format!(
+            "/upload/storage/v1/b/{}/o?uploadType=multipart",
+            percent_encode(&self.bucket, false)
+        );
+        let (status, _, data) = self
+            .call(
+                Method::POST,
+                &uri,
+                vec![(
+                    "content-type",
+                    format!("multipart/related; boundary={}", boundary),
+                )],
+                body,
+            )
+            .await?;
+
+        check_status(status, &data, "upload", path)
+    }
+    /// Upload an object by chunks with a resumable session.
+    async fn upload_resumable(&self, path: &str, content: Vec<u8>) -> Result<()> {
+        let uri = format!(
+            "/upload/storage/v1/b/{}/o?uploadType=resumable",
+            percent_encode(&self.bucket, false)
+        );
+        let mut headers = vec![
+            (
+                "content-type",
+                "application/json; charset=UTF-8".to_string(),
+            ),
+            ("x-upload-content-length", content.len().to_string()),
+        ];
+        if !self.metadata().content_type().is_empty() {
+            headers.push(("x-upload-content-type", self.metadata().content_type()));
+        }
+
+        let (status, response_headers, data) = self
+            .call(
+                Method::POST,
+                &uri,
+                headers,
+                serde_json::to_vec(&self.object_resource(path))?,
+            )
+            .await?;
+        check_status(status, &data, "start the resumable upload of", path)?;
+
+        let session_uri = response_headers.get("location").cloned().ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidData,
+                "The resumable upload session is missing in the response",
+            )
+        })?;
+
+        // The size of the chunks must be a multiple of 256 KiB, except for the last one.
+        let chunk_size =
+            (self.chunk_size / RESUMABLE_CHUNK_MULTIPLE).max(1) * RESUMABLE_CHUNK_MULTIPLE;
+        let total = content.len();
+        let mut start = 0;
+
+        loop {
+            let end = (start + chunk_size).min(total);
+            let content_range = match total {
+                0 => "bytes */0".to_string(),
+                _ => format!("bytes {}-{}/{}", start, end - 1, total),
+            };
+
+            let (status, _, data) = self
+                .call(
+                    Method::PUT,
+                    &session_uri,
+                    vec![("content-range", content_range)],
+                    content[start..end].to_vec(),
+                )
+                .await?;
+
+            match status {
+                // The chunk is received, the upload is not completed.
+                308 => (),
+                200 | 201 => break,
+                _ => check_status(status, &data, "upload", path)?,
+            };
+
+            trace!(start, end, total, "Chunk uploaded with success");
+            start = end;
+
+            if start >= total {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+    /// Upload an object. The object is sent with a resumable upload if its size is upper than the threshold.
+    async fn upload(&self, path: &str, content: Vec<u8>) -> Result<()> {
+        match content.len() > self.resumable_threshold {
+            true => self.upload_resumable(path, content).await,
+            false => self.upload_multipart(path, content).await,
+        }
+    }
+    /// List the object names that start with the prefix.
+    pub async fn list(&self, prefix: &str) -> Result<Vec<String>> {
+        let mut names = Vec::default();
+        let mut page_token: Option<String> = None;
+
+        loop {
+            let mut uri = format!(
+                "/storage/v1/b/{}/o?fields=items(name),nextPageToken&prefix={}",
+                percent_encode(&self.bucket, false),
+                percent_encode(prefix, false)
+            );
+            if let Some(page_token) = &page_token {
+                uri += &format!("&pageToken={}", percent_encode(page_token, false));
+            }
+
+            let (status, _, data) = self
+                .call(Method::GET, &uri, Vec::default(), Vec::default())
+                .await?;
+            check_status(status, &data, "list", &self.bucket)?;
+
+            let response: Value =
+                serde_json::from_slice(&data).map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+
+            if let Some(Value::Array(items)) = response.get("items") {
+                names.extend(
+                    items
+                        .iter()
+                        .filter_map(|item| item.get("name").and_then(Value::as_str))
+                        .map(|name| name.to_string()),
+                );
+            }
+
+            page_token = response
+                .get("nextPageToken")
+                .and_then(Value::as_str)
+                .filter(|page_token| !page_token.is_empty())
+                .map(|page_token| page_token.to_string());
+
+            if page_token.is_none() {
+                break;
+            }
+        }
+
+        Ok(names)
+    }
+}
+
+fn insert_header(headers: &mut HeaderMap, name: &str, value: &str) -> Result<()> {
+    headers.insert(
+        HeaderName::from_bytes(name.as_bytes())
+            .map_err(|e| Error::new(ErrorKind::InvalidInput, e))?,
+        HeaderValue::from_str(value).map_err(|e| Error::new(ErrorKind::InvalidInput, e))?,
+    );
+
+    Ok(())
+}
+
+fn check_status(status: u16, data: &[u8], action: &str, path: &str) -> Result<()> {
+    if status >= 400 {
+        return Err(Error::new(
+            ErrorKind::Interrupted,
+            format!(
+                "Can't {} the object '{}', status '{}': {}",
+                action,
+                path,
+                status,
+                String::from_utf8_lossy(data)
+            ),
+        ));
+    }
+
+    Ok(())
+}
+
+#[async_trait]
+impl Connector for Gcs {
+    /// See [`Connector::set_document`] for more details.
+    fn set_document(&mut self, document: Box<dyn Document>) -> Result<()> {
+        self.document = Some(document.clone());
+
+        Ok(())
+    }
+    /// See [`Connector::document`] for more details.
+    fn document(&self) -> Result<&dyn Document> {
+        self.document.as_deref().ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                "The document has not been set in the connector",
+            )
+        })
+    }
+    /// See [`Connector::set_parameters`] for more details.
+    fn set_parameters(&mut self, parameters: Value) {
+        *self.parameters = parameters
+    }
+    /// See [`Connector::is_variable`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::gcs::Gcs;
+    /// use chewdata::connector::Connector;
+    ///
+    /// let mut connector = Gcs::default();
+    /// assert_eq!(false, connector.is_variable());
+    /// connector.path = "/dir/filename_{{ field }}.ext".to_string();
+    /// assert_eq!(true, connector.is_variable());
+    /// ```
+    fn is_variable(&self) -> bool {
+        self.path.has_mustache()
+    }
+    /// See [`Connector::is_resource_will_change`] for more details.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use chewdata::connector::{gcs::Gcs, Connector};
+    /// use serde_json::Value;
+    ///
+    /// let mut connector = Gcs::default();
+    /// let params = serde_json::from_str(r#"{"field":"test"}"#).unwrap();
+    /// assert_eq!(false, connector.is_resource_will_change(Value::Null).unwrap());
+    /// connector.path = "/dir/static.ext".to_string();
+    /// assert_eq!(false, connector.is_resource_will_change(Value::Null).unwrap());
+    /// connector.path = "/dir/dynamic_{{ field }}.ext".to_string();
+    /// assert_eq!(true, connector.is_resource_will_change(params).unwrap());
+    /// ```
+    fn is_resource_will_change(&self, new_parameters: Value) -> Result<bool> {
+        if !self.is_variable() {
+            trace!("Stay link to the same resource");
+            return Ok(false);
+        }
+
+        let mut metadata_kv = Map::default();
+        metadata_kv.insert("metadata".to_string(), self.metadata().into());
+        let metadata = Value::Object(metadata_kv);
+
+        let mut new_parameters = new_parameters;
+        new_parameters.merge(&metadata);
+        let mut old_parameters = *self.parameters.clone();
+        old_parameters.merge(&metadata);
+
+        let mut previous_path = self.path.clone();
+        previous_path.replace_mustache(old_parameters);
+
+        let mut new_path = self.path.clone();
+        new_path.replace_mustache(new_parameters);
+
+        if previous_path == new_path {
+            trace!(path = previous_path, "Path didn't change");
+            return Ok(false);
+        }
+
+        info!(
+            previous_path = previous_path,
+            new_path = new_path,
+            "Will use another resource, regarding the new parameters"
+        );
+        Ok(true)
+    }
+    /// See [`Connector::path`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::gcs::Gcs;
+    /// use chewdata::connector::Connector;
+    /// use serde_json::Value;
+    ///
+    /// let mut connector = Gcs::default();
+    /// connector.path = "/dir/filename_{{ field }}.ext".to_string();
+    /// let params: Value = serde_json::from_str(r#"{"field":"value"}"#).unwrap();
+    /// connector.set_parameters(params);
+    /// assert_eq!("/dir/filename_value.ext", connector.path());
+    /// ```
+    fn path(&self) -> String {
+        if !self.is_variable() {
+            return self.path.clone();
+        }
+
+        let mut params = *self.parameters.clone();
+        params.merge(&serde_json::json!({
+            "metadata": self.metadata()
+        }));
+
+        let mut path = self.path.clone();
+        path.replace_mustache(params);
+        path
+    }
+    /// See [`Connector::len`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::gcs::Gcs;
+    /// use chewdata::connector::Connector;
+    /// use std::io;
+    ///
+    /// use macro_rules_attribute::apply;
+    /// use smol_macros::main;
+    ///
+    /// #[apply(main!)]
+    /// async fn main() -> io::Result<()> {
+    ///     let mut connector = Gcs::default();
+    ///     connector.endpoint = Some("http://localhost:4443".to_string());
+    ///     connector.bucket = "my-bucket".to_string();
+    ///     connector.path = "data/not-found-file".to_string();
+    ///     assert_eq!(0, connector.len().await?);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[instrument(name = "gcs::len")]
+    async fn len(&self) -> Result<usize> {
+        if self.path.contains('*') {
+            return Err(Error::new(
+                ErrorKind::NotFound,
+                "len() method not available for wildcard path",
+            ));
+        }
+
+        let len = match self.object().await {
+            Ok(object) => object
+                .and_then(|object| match object.get("size") {
+                    // The size is a string in the JSON API.
+                    Some(Value::String(size)) => size.parse::<usize>().ok(),
+                    Some(Value::Number(size)) => size.as_u64().map(|size| size as usize),
+                    _ => None,
+                })
+                .unwrap_or_default(),
+            Err(e) => {
+                warn!(
+                    error = format!("{:?}", e.to_string()).as_str(),
+                    "Can't find the length of the resource"
+                );
+                0_usize
+            }
+        };
+
+        info!(len, "Find length of the resource");
+
+        Ok(len)
+    }
+    /// See [`Connector::fetch`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::{gcs::Gcs, Connector};
+    /// use chewdata::document::json::Json;
+    /// use smol::stream::StreamExt;
+    /// use std::io;
+    ///
+    /// use macro_rules_attribute::apply;
+    /// use smol_macros::main;
+    ///
+    /// #[apply(main!)]
+    /// async fn main() -> io::Result<()> {
+    ///     let mut connector = Gcs::default();
+    ///     connector.endpoint = Some("http://localhost:4443".to_string());
+    ///     connector.bucket = "my-bucket".to_string();
+    ///     connector.path = "data/one_line.json".to_string();
+    ///     connector.set_document(Box::new(Json::default()))?;
+    ///
+    ///     let datastream = connector.fetch().await?.unwrap();
+    ///     assert!(
+    ///         0 < datastream.count().await,
+    ///         "The inner connector should have a size upper than zero"
+    ///     );
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[instrument(name = "gcs::fetch")]
+    async fn fetch(&mut self) -> Result<Option<DataStream>> {
+        let document = self.document()?;
+        let path = self.path();
+
+        if path.has_mustache() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("This path '{}' is not fully resolved", path),
+            ));
+        }
+
+        let buffer = self.download(&path).await?;
+
+        info!(path = path, "Fetch data with success");
+
+        if !document.has_data(&buffer)? {
+            return Ok(None);
+        }
+
+        let dataset = document.read(&buffer)?;
+
+        Ok(Some(Box::pin(stream! {
+            for data in dataset {
+                yield data;
+            }
+        })))
+    }
+    /// See [`Connector::send`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::gcs::Gcs;
+    /// use chewdata::connector::Connector;
+    /// use chewdata::document::json::Json;
+    /// use chewdata::DataResult;
+    /// use smol::prelude::*;
+    /// use std::io;
+    ///
+    /// use macro_rules_attribute::apply;
+    /// use smol_macros::main;
+    ///
+    /// #[apply(main!)]
+    /// async fn main() -> io::Result<()> {
+    ///     let mut connector = Gcs::default();
+    ///     connector.endpoint = Some("http://localhost:4443".to_string());
+    ///     connector.bucket = "my-bucket".to_string();
+    ///     connector.path = "data/out/send_doc.json".to_string();
+    ///     connector.set_document(Box::new(Json::default()))?;
+    ///     connector.erase().await?;
+    ///
+    ///     let expected_result1 =
+    ///         DataResult::Ok(serde_json::from_str(r#"{"column1":"value1"}"#)?);
+    ///     let expected_result2 =
+    ///         DataResult::Ok(serde_json::from_str(r#"{"column1":"value2"}"#)?);
+    ///     connector.send(&vec![expected_result1.clone()]).await?;
+    ///     connector.send(&vec![expected_result2.clone()]).await?;
+    ///
+    ///     let mut datastream = connector.fetch().await?.unwrap();
+    ///     assert_eq!(expected_result1, datastream.next().await.unwrap());
+    ///     assert_eq!(expected_result2, datastream.next().await.unwrap());
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[instrument(skip(dataset), name = "gcs::send")]
+    async fn send(&mut self, dataset: &DataSet) -> std::io::Result<Option<DataStream>> {
+        let document = self.document()?;
+        let path = self.path();
+        let terminator = document.terminator()?;
+        let footer = document.footer(dataset)?;
+        let header = document.header(dataset)?;
+        let body = document.write(dataset)?;
+
+        if path.has_mustache() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("This path '{}' is not fully resolved", path),
+            ));
+        }
+
+        let position = match document.can_append() {
+            true => Some(-(footer.len() as isize)),
+            false => None,
+        };
+
+        // The objects are immutable, the existing content is rewritten with the new data.
+        let content_file = match position {
+            Some(_) if !self.is_empty().await? => {
+                info!(path = path.to_string().as_str(), "Fetch existing data");
+                self.download(&path).await?
+            }
+            _ => Vec::default(),
+        };
+
+        let file_len = content_file.len();
+        let mut cursor = Cursor::new(content_file);
+
+        match position {
+            Some(pos) => match file_len as isize + pos {
+                start if start > 0 => cursor.seek(SeekFrom::Start(start as u64)),
+                _ => cursor.seek(SeekFrom::Start(0)),
+            },
+            None => cursor.seek(SeekFrom::Start(0)),
+        }?;
+
+        if 0 == file_len {
+            cursor.write_all(&header)?;
+        }
+        if 0 < file_len && file_len > (header.len() + footer.len()) {
+            cursor.write_all(&terminator)?;
+        }
+        cursor.write_all(&body)?;
+        cursor.write_all(&footer)?;
+
+        self.upload(&path, cursor.into_inner()).await?;
+
+        info!(path = path, "Send data with success");
+        Ok(None)
+    }
+    /// See [`Connector::metadata`] for more details.
+    fn metadata(&self) -> Metadata {
+        match &self.document {
+            Some(document) => self.metadata.clone().merge(&document.metadata()),
+            None => self.metadata.clone(),
+        }
+    }
+    /// See [`Connector::erase`] for more details.
+    #[instrument(name = "gcs::erase")]
+    async fn erase(&mut self) -> Result<()> {
+        let path = self.path();
+
+        if path.has_mustache() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("This path '{}' is not fully resolved", path),
+            ));
+        }
+
+        let paths = match path.contains('*') {
+            true => GcsPaginator::new(self).await?.paths.collect(),
+            false => vec![path],
+        };
+
+        for path in paths {
+            self.upload_multipart(&path, Vec::default()).await?;
+        }
+
+        info!("Erase data with success");
+        Ok(())
+    }
+    /// See [`Connector::paginate`] for more details.
+    async fn paginate(
+        &self,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<Box<dyn Connector>>> + Send>>> {
+        GcsPaginator::new(self).await?.paginate(self).await
+    }
+}
+
+#[derive(Debug)]
+pub struct GcsPaginator {
+    pub paths: IntoIter<String>,
+    pub skip: usize,
+}
+
+impl GcsPaginator {
+    pub async fn new(connector: &Gcs) -> Result<Self> {
+        let path = connector.path();
+
+        let mut paths = match path.contains('*') {
+            true => {
+                let prefix = path
+                    .split('*')
+                    .next()
+                    .unwrap_or_default()
+                    .trim_start_matches('/');
+                let pattern = glob::Pattern::new(path.trim_start_matches('/'))
+                    .map_err(|e| Error::new(ErrorKind::InvalidInput, e))?;
+
+                match connector.list(prefix).await {
+                    Ok(names) => names
+                        .into_iter()
+                        .filter(|name| pattern.matches(name))
+                        .collect(),
+                    Err(e) => {
+                        warn!(
+                            error = e.to_string().as_str(),
+                            "Can't fetch the list of objects"
+                        );
+                        Vec::default()
+                    }
+                }
+            }
+            false => vec![path],
+        };
+
+        if let Some(limit) = connector.limit {
+            let paths_range_start = paths.len().min(connector.skip);
+            let paths_range_end = paths.len().min(connector.skip + limit);
+
+            paths = paths[paths_range_start..paths_range_end].to_vec();
+        }
+
+        Ok(GcsPaginator {
+            skip: connector.skip,
+            paths: paths.into_iter(),
+        })
+    }
+}
+
+impl GcsPaginator {
+    /// Paginate through the bucket.
+    /// Wildcard is allowed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::gcs::{Gcs, GcsPaginator};
+    /// use chewdata::connector::Connector;
+    /// use smol::prelude::*;
+    /// use std::io;
+    ///
+    /// use macro_rules_attribute::apply;
+    /// use smol_macros::main;
+    ///
+    /// #[apply(main!)]
+    /// async fn main() -> io::Result<()> {
+    ///     let mut connector = Gcs::default();
+    ///     connector.endpoint = Some("http://localhost:4443".to_string());
+    ///     connector.bucket = "my-bucket".to_string();
+    ///     connector.path = "data/one_line.*".to_string();
+    ///
+    ///     let paginator = GcsPaginator::new(&connector).await?;
+    ///     let mut paging = paginator.paginate(&connector).await?;
+    ///     assert!(paging.next().await.transpose()?.is_some(), "Can't get the first reader.");
+    ///     assert!(paging.next().await.transpose()?.is_some(), "Can't get the second reader.");
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[instrument(name = "gcs::paginate")]
+    pub async fn paginate(&self, connector: &Gcs) -> Result<ConnectorStream> {
+        let mut paths = self.paths.clone();
+        let connector = connector.clone();
+
+        Ok(Box::pin(stream! {
+            for path in &mut paths {
+                trace!(next_path = path.as_str(), "Next path");
+
+                let mut new_connector = connector.clone();
+                new_connector.path = path;
+
+                trace!(connector = format!("{:?}", new_connector).as_str(), "The stream yields a new connector");
+                yield Ok(Box::new(new_connector) as Box<dyn Connector>);
+            }
+            trace!("The stream stops yielding new connectors");
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::json::Json;
+    use crate::DataResult;
+    use macro_rules_attribute::apply;
+    use smol::stream::StreamExt;
+    use smol_macros::test;
+
+    fn connector(path: &str) -> Gcs {
+        let mut connector = Gcs::default();
+        connector.endpoint = Some("http://localhost:4443".to_string());
+        connector.bucket = "my-bucket".to_string();
+        connector.path = path.to_string();
+        connector
+    }
+
+    #[test]
+    fn object_uri() {
+        let mut connector = connector("data/my file.json");
+        assert_eq!(
+            "/storage/v1/b/my-bucket/o/data%2Fmy%20file.json",
+            connector.object_uri(&connector.path())
+        );
+        connector.generation = Some("1234".to_string());
+        assert_eq!(
+            "/storage/v1/b/my-bucket/o/data%2Fmy%20file.json?generation=1234",
+            connector.object_uri(&connector.path())
+        );
+    }
+    #[test]
+    fn object_resource() {
+        let mut connector = connector("/data/file.json");
+        connector.set_document(Box::new(Json::default())).unwrap();
+        connector.cache_control = Some("no-cache".to_string());
+        let resource = connector.object_resource(&connector.path());
+        assert_eq!("data/file.json", resource["name"]);
+        assert_eq!("application/json; charset=utf-8", resource["contentType"]);
+        assert_eq!("no-cache", resource["cacheControl"]);
+        assert_eq!("chewdata", resource["metadata"]["service:writer:name"]);
+    }
+    #[test]
+    fn service_account() {
+        let mut connector = Gcs::default();
+        connector.credentials = Some(
+            r#"{"client_email":"test@chewdata.iam.gserviceaccount.com","private_key":"key"}"#
+                .to_string(),
+        );
+        let service_account = connector.service_account().unwrap().unwrap();
+        assert_eq!(
+            "test@chewdata.iam.gserviceaccount.com",
+            service_account.client_email
+        );
+        connector.credentials = Some("{}".to_string());
+        assert!(connector.service_account().is_err());
+    }
+    #[apply(test!)]
+    async fn len() {
+        let mut connector = connector("data/one_line.json");
+        assert!(0 < connector.len().await.unwrap());
+        connector.path = "data/not_found.json".to_string();
+        assert_eq!(0, connector.len().await.unwrap());
+    }
+    #[apply(test!)]
+    async fn send_and_fetch() {
+        let expected_result1 =
+            DataResult::Ok(serde_json::from_str(r#"{"column1":"value1"}"#).unwrap());
+        let expected_result2 =
+            DataResult::Ok(serde_json::from_str(r#"{"column1":"value2"}"#).unwrap());
+        let mut connector = connector("data/out/send.json");
+        connector.set_document(Box::new(Json::default())).unwrap();
+        connector.erase().await.unwrap();
+        connector
+            .send(&vec![expected_result1.clone()])
+            .await
+            .unwrap();
+        connector
+            .send(&vec![expected_result2.clone()])
+            .await
+            .unwrap();
+
+        let mut datastream = connector.fetch().await.unwrap().unwrap();
+        assert_eq!(expected_result1, datastream.next().await.unwrap());
+        assert_eq!(expected_result2, datastream.next().await.unwrap());
+    }
+    #[apply(test!)]
+    async fn send_with_resumable_upload() {
+        let dataset: DataSet = (0..20000)
+            .map(|number| DataResult::Ok(serde_json::json!({ "number": number })))
+            .collect();
+        let mut connector = connector("data/out/resumable.json");
+        connector.resumable_threshold = 0;
+        connector.chunk_size = RESUMABLE_CHUNK_MULTIPLE;
+        connector.set_document(Box::new(Json::default())).unwrap();
+        connector.erase().await.unwrap();
+        connector.send(&dataset).await.unwrap();
+
+        assert!(RESUMABLE_CHUNK_MULTIPLE < connector.len().await.unwrap());
+        let datastream = connector.fetch().await.unwrap().unwrap();
+        assert_eq!(20000, datastream.count().await);
+    }
+    #[apply(test!)]
+    async fn fetch_with_generation() {
+        let mut connector = connector("data/out/generation.json");
+        connector.set_document(Box::new(Json::default())).unwrap();
+        connector.erase().await.unwrap();
+        connector
+            .send(&vec![DataResult::Ok(serde_json::json!({"version": 1}))])
+            .await
+            .unwrap();
+        let generation = connector.object().await.unwrap().unwrap()["generation"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        connector.generation = Some(generation);
+        let mut datastream = connector.fetch().await.unwrap().unwrap();
+        assert_eq!(
+            DataResult::Ok(serde_json::json!({"version": 1})),
+            datastream.next().await.unwrap()
+        );
+
+        connector.generation = Some("1".to_string());
+        assert!(connector.fetch().await.unwrap().is_none());
+    }
+    #[apply(test!)]
+    async fn paginator_paginate_with_wildcard_limit_skip() {
+        let mut connector = connector("data/one_line.*");
+        connector.limit = Some(1);
+        connector.skip = 1;
+
+        let paginator = GcsPaginator::new(&connector).await.unwrap();
+        let mut paging = paginator.paginate(&connector).await.unwrap();
+        assert!(paging.next().await.transpose().unwrap().is_some());
+        assert!(paging.next().await.is_none());
+    }
+}
@@ -0,0 +1,677 @@
+//! Listen HTTP requests and read the data pushed in their body, like webhooks.
+//!
+//! Each `POST` request received on the `path` is parsed with the [`crate::document`] and its records are pushed into the pipeline.
+//! In `async` mode, the server returns a `202 Accepted` as soon as the records are pushed.
+//! In `sync` mode, the server waits the result of the writer for each record and returns it in the response:
+//!
+//! * `200 OK` if all the records are written.
+//! * `207 Multi-Status` if some records are written and others are in error.
+//! * `422 Unprocessable Entity` if no records are written.
+//! * `500 Internal Server Error` if the pipeline stops before the writer returns the result of each record.
+//! * `504 Gateway Timeout` if the writer didn't return the results before the timeout.
+//!
+//! In `sync` mode, the writer sends the records by batch. Use a small `record_limit` in the writer in order to respond quickly.
+//! The records dropped by a step or skipped by the writers are considered as written.
+//!
+//! ### Configuration
+//!
+//! | key           | alias            | Description                                                          | Default Value    | Possible Values            |
+//! | ------------- | ---------------- | -------------------------------------------------------------------- | ---------------- | -------------------------- |
+//! | type          | -                | Required in order to use this connector                              | `http_server`    | `http_server` / `webhook`  |
+//! | metadata      | meta             | Override metadata information                                        | `null`           | [`crate::Metadata`]        |
+//! | endpoint      | address / addr   | The local address to listen                                          | `127.0.0.1:8686` | String                     |
+//! | path          | route            | The path of the requests to handle                                   | `/`              | String                     |
+//! | mode          | -                | Wait or not the result of the writer before to respond               | `async`          | `async` / `sync`           |
+//! | timeout       | -                | Time in second to wait the result of the writer in `sync` mode       | `30`             | Unsigned number            |
+//! | request_limit | limit            | Stop the server after N requests with data. Listen without limit if not set | `null`    | Unsigned number            |
+//! | max_body_size | -                | Maximum size in bytes of the request body. Respond `413 Payload Too Large` if exceeded | `10485760` | Unsigned number  |
+//!
+//! ### Examples
+//!
+//! ```json
+//! [
+//!     {
+//!         "type": "r",
+//!         "connector": {
+//!             "type": "http_server",
+//!             "endpoint": "0.0.0.0:8686",
+//!             "path": "/webhook",
+//!             "mode": "sync"
+//!         },
+//!         "document": {
+//!             "type": "json"
+//!         }
+//!     },
+//!     {
+//!         "type": "w",
+//!         "record_limit": 1
+//!     }
+//! ]
+//! ```
+use super::Connector;
+use crate::document::Document;
+use crate::helper::string::DisplayOnlyForDebugging;
+use crate::{ConnectorStream, DataResult, DataSet, DataStream, Metadata};
+use async_channel::{Receiver, Sender};
+use async_stream::stream;
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::Stream;
+use http::{header, Method, Request, Response, StatusCode};
+use http_body_util::{BodyExt, Full, LengthLimitError, Limited};
+use hyper::body::Incoming;
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use smol::future::FutureExt;
+use smol::net::TcpListener;
+use smol::Timer;
+use smol_hyper::rt::FuturesIo;
+use smol_timeout::TimeoutExt;
+use std::convert::Infallible;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use std::{
+    fmt,
+    io::{Error, ErrorKind, Result},
+};
+
+const DEFAULT_ENDPOINT: &str = "127.0.0.1:8686";
+const DEFAULT_TIMEOUT: u64 = 30;
+const DEFAULT_MAX_BODY_SIZE: usize = 10 * 1024 * 1024;
+const ACCEPT_RETRY_DELAY: Duration = Duration::from_millis(100);
+const ACCEPT_RETRY_MAX: u32 = 50;
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+pub enum Mode {
+    #[serde(rename = "sync")]
+    #[serde(alias = "synchronous")]
+    Sync,
+    #[default]
+    #[serde(rename = "async")]
+    #[serde(alias = "asynchronous")]
+    Async,
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct HttpServer {
+    #[serde(skip)]
+    document: Option<Box<dyn Document>>,
+    #[serde(rename = "metadata")]
+    #[serde(alias = "meta")]
+    pub metadata: Metadata,
+    #[serde(alias = "address")]
+    #[serde(alias = "addr")]
+    pub endpoint: String,
+    #[serde(alias = "route")]
+    pub path: String,
+    pub mode: Mode,
+    pub timeout: Option<u64>,
+    #[serde(alias = "limit")]
+    pub request_limit: Option<usize>,
+    pub max_body_size: usize,
+    #[serde(alias = "params")]
+    pub parameters: Box<Value>,
+    // Records received by the request.
+    #[serde(skip)]
+    dataset: Option<DataSet>,
+    #[serde(skip)]
+    acknowledgement: Option<Sender<DataResult>>,
+}
+
+impl fmt::Debug for HttpServer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HttpServer")
+            .field("document", &self.document.display_only_for_debugging())
+            .field("metadata", &self.metadata.display_only_for_debugging())
+            .field("endpoint", &self.endpoint)
+            .field("path", &self.path)
+            .field("mode", &self.mode)
+            .field("timeout", &self.timeout)
+            .field("request_limit", &self.request_limit)
+            .field("max_body_size", &self.max_body_size)
+            .field("parameters", &self.parameters.display_only_for_debugging())
+            .field("dataset", &self.dataset.display_only_for_debugging())
+            .finish()
+    }
+}
+
+impl Default for HttpServer {
+    fn default() -> Self {
+        HttpServer {
+            document: None,
+            metadata: Metadata::default(),
+            endpoint: DEFAULT_ENDPOINT.to_string(),
+            path: "/".to_string(),
+            mode: Mode::default(),
+            timeout: None,
+            request_limit: None,
+            max_body_size: DEFAULT_MAX_BODY_SIZE,
+            parameters: Box::<Value>::default(),
+            dataset: None,
+            acknowledgement: None,
+        }
+    }
+}
+
+impl HttpServer {
+    /// Accept the connections until the limit of requests and push a new connector for each request.
+    async fn listen(self, listener: TcpListener, sender: Sender<Box<dyn Connector>>) {
+        let requests = Arc::new(AtomicUsize::default());
+        // Closed by the request that reaches the limit.
+        let (stop, stopped) = async_channel::bounded::<()>(1);
+        let mut errors: u32 = 0;
+
+        loop {
+            let connection = async { Some(listener.accept().await) }
+                .or(async {
+                    let _ = stopped.recv().await;
+                    None
+                })
+                .await;
+
+            let (stream, address) = match connection {
+                Some(Ok(connection)) => {
+                    errors = 0;
+                    connection
+                }
+                Some(Err(e)) => {
+                    // Wait before to retry, the error can persist like with too many open files.
+                    errors = errors.saturating_add(1);
+                    warn!(
+                        error = e.to_string().as_str(),
+                        retry = errors,
+                        "Can't accept the connection"
+                    );
+                    Timer::after(ACCEPT_RETRY_DELAY * errors.min(ACCEPT_RETRY_MAX)).await;
+                    continue;
+                }
+                None => break,
+            };
+
+            trace!(
+                address = address.to_string().as_str(),
+                "Accept a new connection"
+            );
+
+            // One request by connection, the sender is released as soon as the request is handled.
+            let connector = self.clone();
+            let sender = Arc::new(Mutex::new(Some(sender.clone())));
+            let requests = requests.clone();
+            let stop = stop.clone();
+            let service = service_fn(move |request: Request<Incoming>| {
+                let connector = connector.clone();
+                let sender = sender.lock().ok().and_then(|mut sender| sender.take());
+                let requests = requests.clone();
+                let stop = stop.clone();
+                async move {
+                    Ok::<_, Infallible>(connector.handle(request, sender, requests, stop).await)
+                }
+            });
+
+            smol::spawn(async move {
+                if let Err(e) = http1::Builder::new()
+                    .keep_alive(false)
+                    .serve_connection(FuturesIo::new(stream), service)
+                    .await
+                {
+                    warn!(error = e.to_string().as_str(), "Can't serve the connection");
+                }
+            })
+            .detach();
+        }
+
+        info!(requests = requests.load(Ordering::SeqCst), "Stop listening");
+    }
+    /// Parse the request body and push the records into the pipeline.
+    async fn handle(
+        &self,
+        request: Request<Incoming>,
+        sender: Option<Sender<Box<dyn Connector>>>,
+        requests: Arc<AtomicUsize>,
+        stop: Sender<()>,
+    ) -> Response<Full<Bytes>> {
+        if request.uri().path() != self.path {
+            return response(StatusCode::NOT_FOUND, "Path not found");
+        }
+        if Method::POST != request.method() {
+            return response(
+                StatusCode::METHOD_NOT_ALLOWED,
+                "Only the POST method is allowed",
+            );
+        }
+
+        let body = match Limited::new(request.into_body(), self.max_body_size)
+            .collect()
+            .await
+        {
+            Ok(body) => body.to_bytes(),
+            Err(e) if e.is::<LengthLimitError>() => {
+                return response(
+                    StatusCode::PAYLOAD_TOO_LARGE,
+                    &format!(
+                        "The request body exceeds the maximum size of {} bytes",
+                        self.max_body_size
+                    ),
+                )
+            }
+            Err(e) => return response(StatusCode::BAD_REQUEST, &e.to_string()),
+        };
+
+        let dataset = match self.read(&body) {
+            Ok(dataset) => dataset,
+            Err(e) => return response(StatusCode::BAD_REQUEST, &e.to_string()),
+        };
+        let dataset_len = dataset.len();
+
+        let mut connector = self.clone();
+        connector.dataset = Some(dataset);
+
+        let receiver = match self.mode {
+            Mode::Sync => {
+                let (acknowledgement, receiver) = async_channel::unbounded();
+                connector.acknowledgement = Some(acknowledgement);
+                Some(receiver)
+            }
+            Mode::Async => None,
+        };
+
+        // Only the requests with data count in the limit.
+        let request = requests.fetch_add(1, Ordering::SeqCst) + 1;
+        let is_over_limit = self.request_limit.is_some_and(|limit| request > limit);
+
+        match sender {
+            Some(sender) if !is_over_limit && sender.send(Box::new(connector)).await.is_ok() => {
+                if Some(request) == self.request_limit {
+                    sender.close();
+                    stop.close();
+                }
+            }
+            _ => {
+                return response(
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    "The server doesn't read the requests anymore",
+                )
+            }
+        };
+
+        info!(records = dataset_len, "Receive data with success");
+
+        match receiver {
+            Some(receiver) => self.wait_results(receiver, dataset_len).await,
+            None => response(StatusCode::ACCEPTED, ""),
+        }
+    }
+    fn read(&self, body: &[u8]) -> Result<DataSet> {
+        let document = self.document()?;
+
+        if !document.has_data(body)? {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "No data found in the request body",
+            ));
+        }
+
+        document.read(body)
+    }
+    /// Wait the result of the writer for each record and return them in the response.
+    async fn wait_results(
+        &self,
+        receiver: Receiver<DataResult>,
+        dataset_len: usize,
+    ) -> Response<Full<Bytes>> {
+        let results = async {
+            let mut results = Vec::default();
+            // Stop if all the contexts are dropped, the missing records never reach the writer.
+            while results.len() < dataset_len {
+                match receiver.recv().await {
+                    Ok(result) => results.push(result),
+                    Err(_) => break,
+                }
+            }
+            results
+        }
+        .timeout(Duration::from_secs(self.timeout.unwrap_or(DEFAULT_TIMEOUT)))
+        .await;
+
+        let results = match results {
+            Some(results) => results,
+            None => {
+                warn!("The writer didn't return the results before the timeout");
+                return response(
+                    StatusCode::GATEWAY_TIMEOUT,
+                    "The writer didn't return the results before the timeout",
+                );
+            }
+        };
+        if results.len() < dataset_len {
+            warn!(
+                results = results.len(),
+                records = dataset_len,
+                "The pipeline stopped before the writer returned the result of each record"
+            );
+            return response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "The pipeline stopped before the writer returned the result of each record",
+            );
+        }
+
+        let written = results
+            .iter()
+            .filter(|result| result.is_type(DataResult::OK))
+            .count();
+        let status = match written {
+            0 => StatusCode::UNPROCESSABLE_ENTITY,
+            written if written == dataset_len => StatusCode::OK,
+            _ => StatusCode::MULTI_STATUS,
+        };
+        let body = Value::Array(results.iter().map(DataResult::to_value).collect());
+
+        let mut response = response(status, &body.to_string());
+        response.headers_mut().insert(
+            header::CONTENT_TYPE,
+            header::HeaderValue::from_static("application/json"),
+        );
+        response
+    }
+}
+
+fn response(status: StatusCode, body: &str) -> Response<Full<Bytes>> {
+    let mut response = Response::new(Full::new(Bytes::from(body.to_string())));
+    *response.status_mut() = status;
+    response
+}
+
+#[async_trait]
+impl Connector for HttpServer {
+    /// See [`Connector::set_document`] for more details.
+    fn set_document(&mut self, document: Box<dyn Document>) -> Result<()> {
+        self.document = Some(document.clone());
+
+        Ok(())
+    }
+    /// See [`Connector::document`] for more details.
+    fn document(&self) -> Result<&dyn Document> {
+        self.document.as_deref().ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                "The document has not been set in the connector",
+            )
+        })
+    }
+    /// See [`Connector::set_parameters`] for more details.
+    fn set_parameters(&mut self, parameters: Value) {
+        *self.parameters = parameters;
+    }
+    /// See [`Connector::is_variable`] for more details.
+    fn is_variable(&self) -> bool {
+        false
+    }
+    /// See [`Connector::is_resource_will_change`] for more details.
+    fn is_resource_will_change(&self, _new_parameters: Value) -> Result<bool> {
+        Ok(false)
+    }
+    /// See [`Connector::path`] for more details.
+    fn path(&self) -> String {
+        self.path.clone()
+    }
+    /// See [`Connector::metadata`] for more details.
+    fn metadata(&self) -> Metadata {
+        match &self.document {
+            Some(document) => self.metadata.clone().merge(&document.metadata()),
+            None => self.metadata.clone(),
+        }
+    }
+    /// See [`Connector::len`] for more details.
+    async fn len(&self) -> Result<usize> {
+        Ok(self.dataset.as_ref().map_or(0, |dataset| dataset.len()))
+    }
+    /// See [`Connector::acknowledgement`] for more details.
+    fn acknowledgement(&self) -> Option<Sender<DataResult>> {
+        self.acknowledgement.clone()
+    }
+    /// See [`Connector::fetch`] for more details.
+    #[instrument(name = "http_server::fetch")]
+    async fn fetch(&mut self) -> Result<Option<DataStream>> {
+        let dataset = match self.dataset.take() {
+            Some(dataset) => dataset,
+            None => return Ok(None),
+        };
+
+        Ok(Some(Box::pin(stream! {
+            for data in dataset {
+                yield data;
+            }
+        })))
+    }
+    /// See [`Connector::send`] for more details.
+    async fn send(&mut self, _dataset: &DataSet) -> Result<Option<DataStream>> {
+        Err(Error::new(
+            ErrorKind::Unsupported,
+            "Can't send data with the http_server connector. Use it only to read data",
+        ))
+    }
+    /// See [`Connector::paginate`] for more details.
+    ///
+    /// Listen the endpoint and return a new connector for each request received.
+    #[instrument(name = "http_server::paginate")]
+    async fn paginate(
+        &self,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<Box<dyn Connector>>> + Send>>> {
+        let listener = TcpListener::bind(&self.endpoint).await?;
+        let (sender, receiver) = async_channel::unbounded();
+
+        info!(
+            endpoint = self.endpoint,
+            path = self.path,
+            "Start listening"
+        );
+
+        smol::spawn(self.clone().listen(listener, sender)).detach();
+
+        let stream: ConnectorStream = Box::pin(stream! {
+            while let Ok(connector) = receiver.recv().await {
+                trace!("The stream yields a new connector");
+                yield Ok(connector);
+            }
+            trace!("The stream stops yielding new connectors");
+        });
+
+        Ok(stream)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::connector::curl::Curl;
+    use crate::document::json::Json;
+    use crate::step::StepType;
+    use macro_rules_attribute::apply;
+    use smol::stream::StreamExt;
+    use smol_macros::test;
+
+    async fn post(endpoint: &str, path: &str, body: &str) -> (u16, Vec<u8>) {
+        let mut curl = Curl::default();
+        curl.endpoint = format!("http://{}", endpoint);
+        curl.metadata = Json::default().metadata();
+
+        // Wait the server to listen.
+        for _ in 0..50 {
+            if let Ok(result) = curl
+                .call(&Method::POST, path, Bytes::from(body.to_string()))
+                .await
+            {
+                return result;
+            }
+            smol::Timer::after(Duration::from_millis(100)).await;
+        }
+
+        panic!("The server is not reachable");
+    }
+
+    #[apply(test!)]
+    async fn paginate_in_async_mode() {
+        let mut connector = HttpServer::default();
+        connector.endpoint = "127.0.0.1:8691".to_string();
+        connector.path = "/webhook".to_string();
+        connector.request_limit = Some(1);
+        connector.set_document(Box::new(Json::default())).unwrap();
+
+        let mut paging = connector.paginate().await.unwrap();
+        let client = smol::spawn(post(
+            "127.0.0.1:8691",
+            "/webhook",
+            r#"[{"field":"value1"},{"field":"value2"}]"#,
+        ));
+
+        let mut connector = paging.next().await.transpose().unwrap().unwrap();
+        assert_eq!(2, connector.len().await.unwrap());
+        let datastream = connector.fetch().await.unwrap().unwrap();
+        assert_eq!(2, datastream.count().await);
+        assert!(paging.next().await.is_none());
+
+        let (status, _) = client.await;
+        assert_eq!(202, status);
+    }
+    #[apply(test!)]
+    async fn paginate_with_wrong_path() {
+        let mut connector = HttpServer::default();
+        connector.endpoint = "127.0.0.1:8692".to_string();
+        connector.path = "/webhook".to_string();
+        connector.request_limit = Some(1);
+        connector.set_document(Box::new(Json::default())).unwrap();
+
+        let mut paging = connector.paginate().await.unwrap();
+        let (status, _) = post("127.0.0.1:8692", "/other", r#"{"field":"value"}"#).await;
+        assert_eq!(404, status);
+
+        // The request without data doesn't count in the limit.
+        // The client keeps one connection by endpoint and the server closes it after each request, so use another host name.
+        let (status, _) = post("localhost:8692", "/webhook", r#"{"field":"value"}"#).await;
+        assert_eq!(202, status);
+        let mut connector = paging.next().await.transpose().unwrap().unwrap();
+        assert_eq!(1, connector.fetch().await.unwrap().unwrap().count().await);
+        assert!(paging.next().await.is_none());
+    }
+    #[apply(test!)]
+    async fn paginate_with_body_too_large() {
+        let mut connector = HttpServer::default();
+        connector.endpoint = "127.0.0.1:8695".to_string();
+        connector.path = "/webhook".to_string();
+        connector.request_limit = Some(1);
+        connector.max_body_size = 10;
+        connector.set_document(Box::new(Json::default())).unwrap();
+
+        let _paging = connector.paginate().await.unwrap();
+        let (status, _) = post(
+            "127.0.0.1:8695",
+            "/webhook",
+            r#"[{"field":"value1"},{"field":"value2"}]"#,
+        )
+        .await;
+        assert_eq!(413, status);
+    }
+    #[apply(test!)]
+    async fn wait_results_with_missing_results() {
+        let connector = HttpServer::default();
+        let (sender, receiver) = async_channel::unbounded();
+        sender
+            .send(DataResult::Ok(serde_json::json!({"field":"value1"})))
+            .await
+            .unwrap();
+        drop(sender);
+
+        let response = connector.wait_results(receiver, 2).await;
+        assert_eq!(StatusCode::INTERNAL_SERVER_ERROR, response.status());
+    }
+    #[apply(test!)]
+    async fn exec_in_sync_mode() {
+        let config = r#"[
+            {
+                "type": "r",
+                "connector": {
+                    "type": "http_server",
+                    "endpoint": "127.0.0.1:8693",
+                    "path": "/webhook",
+                    "mode": "sync",
+                    "request_limit": 1
+                }
+            },
+            {
+                "type": "w",
+                "connector": {
+                    "type": "in_memory"
+                },
+                "record_limit": 1
+            }
+        ]"#;
+        let steps: Vec<StepType> = serde_json::from_str(config).unwrap();
+        let (result, (status, body)) = futures::future::join(
+            crate::exec(steps, None, None),
+            post(
+                "127.0.0.1:8693",
+                "/webhook",
+                r#"[{"field":"value1"},{"field":"value2"}]"#,
+            ),
+        )
+        .await;
+        result.unwrap();
+        assert_eq!(200, status);
+        let results: Vec<Value> = serde_json::from_slice(&body).unwrap();
+        assert_eq!(2, results.len());
+        assert!(results.contains(&serde_json::json!({"field":"value1"})));
+        assert!(results.contains(&serde_json::json!({"field":"value2"})));
+    }
+    #[apply(test!)]
+    async fn exec_in_sync_mode_with_filtered_record() {
+        let config = r#"[
+            {
+                "type": "r",
+                "connector": {
+                    "type": "http_server",
+                    "endpoint": "127.0.0.1:8694",
+                    "path": "/webhook",
+                    "mode": "sync",
+                    "request_limit": 1
+                }
+            },
+            {
+                "type": "t",
+                "actions": [
+                    {
+                        "field": "/",
+                        "pattern": "{% if input.field == 'value2' %}null{% else %}{{ input | json_encode() }}{% endif %}"
+                    }
+                ]
+            },
+            {
+                "type": "w",
+                "connector": {
+                    "type": "in_memory"
+                },
+                "record_limit": 1
+            }
+        ]"#;
+        let steps: Vec<StepType> = serde_json::from_str(config).unwrap();
+        let (result, (status, body)) = futures::future::join(
+            crate::exec(steps, None, None),
+            post(
+                "127.0.0.1:8694",
+                "/webhook",
+                r#"[{"field":"value1"},{"field":"value2"}]"#,
+            ),
+        )
+        .await;
+        result.unwrap();
+        assert_eq!(200, status);
+        let results: Vec<Value> = serde_json::from_slice(&body).unwrap();
+        assert_eq!(2, results.len());
+        assert!(results.contains(&serde_json::json!({"field":"value1"})));
+        assert!(results.contains(&serde_json::json!({"field":"value2"})));
+    }
+}
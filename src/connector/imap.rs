@@ -0,0 +1,779 @@
+//! Read the attachments of the emails stored on an IMAP server.
+//!
+//! When the connector reads, it selects the `folder`, searches the messages with the `search` query and parses each attachment matching the `attachment` pattern with the [`crate::document`].
+//! The information of the email is added in each record in the field `_email`.
+//! The messages are read without changing their flags, except if `mark_as_seen` is enabled.
+//!
+//! When the connector erases, it deletes the messages matching the `search` query from the `folder`.
+//!
+//! The connector can't write data. Use the [`crate::connector::smtp`] connector to send emails.
+//!
+//! ### Configuration
+//!
+//! | key           | alias       | Description                                                                      | Default Value           | Possible Values                                                         |
+//! | ------------- | ----------- | -------------------------------------------------------------------------------- | ----------------------- | ----------------------------------------------------------------------- |
+//! | type          | -           | Required in order to use this connector                                          | `imap`                  | `imap`                                                                  |
+//! | metadata      | meta        | Override metadata information                                                    | `null`                  | [`crate::Metadata`]                                                     |
+//! | endpoint      | -           | The address of the server. Use `imaps://` to connect with TLS                    | `imap://localhost:143`  | String                                                                  |
+//! | username      | user        | The username used to login on the server                                         | `null`                  | String                                                                  |
+//! | password      | pass        | The password used to login on the server                                         | `null`                  | String                                                                  |
+//! | folder        | path        | The folder to read. Can use mustache variables                                   | `INBOX`                 | String                                                                  |
+//! | search        | query       | The search criteria used to select the messages. Can use mustache variables      | `ALL`                   | [IMAP search](https://www.rfc-editor.org/rfc/rfc3501#section-6.4.4)     |
+//! | attachment    | -           | Read only the attachments with a file name matching this glob pattern            | `*`                     | String                                                                  |
+//! | mark_as_seen  | seen        | Flag the messages read as seen                                                   | `false`                 | `true` / `false`                                                        |
+//! | message_limit | limit       | Read only the N oldest messages found                                            | `null`                  | Unsigned number                                                         |
+//! | timeout       | -           | Time in second to connect to the server                                          | `30`                    | Unsigned number                                                         |
+//! | parameters    | params      | Variables used to render the `folder` and the `search`                           | `null`                  | Object                                                                  |
+//!
+//! ### Examples
+//!
+//! ```json
+//! [
+//!     {
+//!         "type": "r",
+//!         "document": {
+//!             "type": "csv"
+//!         },
+//!         "connector": {
+//!             "type": "imap",
+//!             "endpoint": "imaps://imap.example.com",
+//!             "username": "{{ IMAP_USERNAME }}",
+//!             "password": "{{ IMAP_PASSWORD }}",
+//!             "folder": "INBOX/partners",
+//!             "search": "UNSEEN FROM \"partner@example.com\"",
+//!             "attachment": "*.csv",
+//!             "mark_as_seen": true
+//!         }
+//!     }
+//! ]
+//! ```
+use super::Connector;
+use crate::connector::paginator::once::Once;
+use crate::document::Document;
+use crate::helper::mustache::Mustache;
+use crate::helper::string::DisplayOnlyForDebugging;
+use crate::{DataResult, DataSet, DataStream, Metadata};
+use async_imap::{Client, Session};
+use async_stream::stream;
+use async_trait::async_trait;
+use futures::{AsyncRead, AsyncWrite, Stream, TryStreamExt};
+use futures_rustls::TlsConnector;
+use mail_parser::{MessageParser, MimeHeaders};
+use rustls::{ClientConfig, RootCertStore};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use smol::net::TcpStream;
+use smol_timeout::TimeoutExt;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+use std::{
+    fmt,
+    io::{Error, ErrorKind, Result},
+};
+use webpki_roots::TLS_SERVER_ROOTS;
+
+const DEFAULT_ENDPOINT: &str = "imap://localhost:143";
+const DEFAULT_PORT: u16 = 143;
+const DEFAULT_TLS_PORT: u16 = 993;
+const DEFAULT_FOLDER: &str = "INBOX";
+const DEFAULT_SEARCH: &str = "ALL";
+const DEFAULT_ATTACHMENT: &str = "*";
+const DEFAULT_TIMEOUT: u64 = 30;
+pub const FIELD_EMAIL: &str = "_email";
+
+/// A TCP or TCP+TLS connection to the server.
+trait ImapStream: AsyncRead + AsyncWrite + Unpin + Send + fmt::Debug {}
+impl<T: AsyncRead + AsyncWrite + Unpin + Send + fmt::Debug> ImapStream for T {}
+
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct Imap {
+    #[serde(skip)]
+    document: Option<Box<dyn Document>>,
+    #[serde(rename = "metadata")]
+    #[serde(alias = "meta")]
+    pub metadata: Metadata,
+    pub endpoint: String,
+    #[serde(alias = "user")]
+    pub username: Option<String>,
+    #[serde(alias = "pass")]
+    pub password: Option<String>,
+    #[serde(alias = "path")]
+    pub folder: String,
+    #[serde(alias = "query")]
+    pub search: String,
+    pub attachment: String,
+    #[serde(alias = "seen")]
+    pub mark_as_seen: bool,
+    #[serde(alias = "limit")]
+    pub message_limit: Option<usize>,
+    pub timeout: u64,
+    #[serde(alias = "params")]
+    pub parameters: Box<Value>,
+}
+
+impl fmt::Debug for Imap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Imap")
+            .field("document", &self.document.display_only_for_debugging())
+            .field("metadata", &self.metadata.display_only_for_debugging())
+            .field("endpoint", &self.endpoint)
+            .field("username", &self.username)
+            .field("folder", &self.folder)
+            .field("search", &self.search)
+            .field("attachment", &self.attachment)
+            .field("mark_as_seen", &self.mark_as_seen)
+            .field("message_limit", &self.message_limit)
+            .field("timeout", &self.timeout)
+            .field("parameters", &self.parameters.display_only_for_debugging())
+            .finish()
+    }
+}
+
+impl Default for Imap {
+    fn default() -> Self {
+        Imap {
+            document: None,
+            metadata: Metadata::default(),
+            endpoint: DEFAULT_ENDPOINT.to_string(),
+            username: None,
+            password: None,
+            folder: DEFAULT_FOLDER.to_string(),
+            search: DEFAULT_SEARCH.to_string(),
+            attachment: DEFAULT_ATTACHMENT.to_string(),
+            mark_as_seen: false,
+            message_limit: None,
+            timeout: DEFAULT_TIMEOUT,
+            parameters: Box::<Value>::default(),
+        }
+    }
+}
+
+impl Imap {
+    /// Get the host, the port and if the connection use TLS from the endpoint.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::imap::Imap;
+    ///
+    /// let mut connector = Imap::default();
+    /// assert_eq!(("localhost".to_string(), 143, false), connector.address().unwrap());
+    /// connector.endpoint = "imaps://imap.example.com".to_string();
+    /// assert_eq!(("imap.example.com".to_string(), 993, true), connector.address().unwrap());
+    /// connector.endpoint = "imap.example.com:3143".to_string();
+    /// assert_eq!(("imap.example.com".to_string(), 3143, false), connector.address().unwrap());
+    /// connector.endpoint = "pop3://pop.example.com".to_string();
+    /// assert!(connector.address().is_err());
+    /// ```
+    pub fn address(&self) -> Result<(String, u16, bool)> {
+        let (address, is_secure) = match self.endpoint.split_once("://") {
+            Some(("imap", address)) => (address, false),
+            Some(("imaps", address)) => (address, true),
+            Some((scheme, _)) => {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!(
+                        "The scheme '{}' is not supported by the imap connector",
+                        scheme
+                    ),
+                ))
+            }
+            None => (self.endpoint.as_str(), false),
+        };
+        let address = address.trim_end_matches('/');
+
+        match address.rsplit_once(':') {
+            Some((host, port)) => Ok((
+                host.to_string(),
+                port.parse()
+                    .map_err(|e| Error::new(ErrorKind::InvalidInput, e))?,
+                is_secure,
+            )),
+            None => Ok((
+                address.to_string(),
+                match is_secure {
+                    true => DEFAULT_TLS_PORT,
+                    false => DEFAULT_PORT,
+                },
+                is_secure,
+            )),
+        }
+    }
+    /// Get the search query rendered with the parameters.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::imap::Imap;
+    /// use chewdata::connector::Connector;
+    ///
+    /// let mut connector = Imap::default();
+    /// connector.search = "SINCE {{ date }}".to_string();
+    /// connector.set_parameters(serde_json::from_str(r#"{"date":"1-Feb-2024"}"#).unwrap());
+    /// assert_eq!("SINCE 1-Feb-2024", connector.search());
+    /// ```
+    pub fn search(&self) -> String {
+        let mut search = self.search.clone();
+        search.replace_mustache(*self.parameters.clone());
+        search
+    }
+    /// Connect, login and select the folder.
+    async fn session(&self) -> Result<Session<Box<dyn ImapStream>>> {
+        let (host, port, is_secure) = self.address()?;
+
+        let tcp = TcpStream::connect((host.clone(), port))
+            .timeout(Duration::from_secs(self.timeout))
+            .await
+            .ok_or_else(|| {
+                Error::new(
+                    ErrorKind::TimedOut,
+                    "The server didn't respond before the timeout",
+                )
+            })??;
+
+        let stream: Box<dyn ImapStream> = match is_secure {
+            false => Box::new(tcp),
+            true => {
+                let mut roots = RootCertStore::empty();
+                roots.extend(TLS_SERVER_ROOTS.iter().cloned());
+                let config = ClientConfig::builder()
+                    .with_root_certificates(roots)
+                    .with_no_client_auth();
+                let server_name = rustls::pki_types::ServerName::try_from(host)
+                    .map_err(|e| Error::new(ErrorKind::InvalidInput, e))?;
+
+                Box::new(
+                    TlsConnector::from(Arc::new(config))
+                        .connect(server_name, tcp)
+                        .await?,
+                )
+            }
+        };
+
+        let mut client = Client::new(stream);
+        client.read_response().await?.ok_or_else(|| {
+            Error::new(
+                ErrorKind::ConnectionAborted,
+                "The server closed the connection before the greeting",
+            )
+        })?;
+
+        let mut session = client
+            .login(
+                self.username.clone().unwrap_or_default(),
+                self.password.clone().unwrap_or_default(),
+            )
+            .await
+            .map_err(|(e, _)| Error::new(ErrorKind::PermissionDenied, e))?;
+
+        session.select(self.path()).await.map_err(imap_error)?;
+
+        Ok(session)
+    }
+    /// Get the uids of the messages matching the search query, the oldest first.
+    async fn uids(&self, session: &mut Session<Box<dyn ImapStream>>) -> Result<Vec<u32>> {
+        let mut uids: Vec<u32> = session
+            .uid_search(self.search())
+            .await
+            .map_err(imap_error)?
+            .into_iter()
+            .collect();
+        uids.sort_unstable();
+
+        if let Some(limit) = self.message_limit {
+            uids.truncate(limit);
+        }
+
+        Ok(uids)
+    }
+    /// Parse the message and read the attachments matching the pattern with the document.
+    fn read_message(&self, uid: u32, raw: &[u8]) -> Result<DataSet> {
+        let document = self.document()?;
+        let pattern = glob::Pattern::new(&self.attachment)
+            .map_err(|e| Error::new(ErrorKind::InvalidInput, e))?;
+        let message = MessageParser::default().parse(raw).ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("The message '{}' can't be parsed", uid),
+            )
+        })?;
+
+        let mut dataset = DataSet::default();
+        for part in message.attachments() {
+            let name = part.attachment_name().unwrap_or_default();
+            if !pattern.matches(name) {
+                trace!(uid, attachment = name, "Attachment skipped");
+                continue;
+            }
+            if !document.has_data(part.contents())? {
+                continue;
+            }
+
+            let mut email = Map::default();
+            email.insert("uid".to_string(), Value::from(uid));
+            email.insert("subject".to_string(), message.subject().into());
+            email.insert(
+                "from".to_string(),
+                message
+                    .from()
+                    .and_then(|from| from.first())
+                    .and_then(|from| from.address())
+                    .into(),
+            );
+            email.insert(
+                "date".to_string(),
+                message.date().map(|date| date.to_rfc3339()).into(),
+            );
+            email.insert("attachment".to_string(), Value::String(name.to_string()));
+            let email = Value::Object(email);
+
+            for data in document.read(part.contents())? {
+                dataset.push(with_email(data, &email));
+            }
+        }
+
+        Ok(dataset)
+    }
+}
+
+fn imap_error(e: async_imap::error::Error) -> Error {
+    match e {
+        async_imap::error::Error::Io(e) => e,
+        e => Error::new(ErrorKind::InvalidInput, e),
+    }
+}
+
+/// Add the information of the email in the record.
+fn with_email(data: DataResult, email: &Value) -> DataResult {
+    let add_email = |mut value: Value| {
+        if let Value::Object(map) = &mut value {
+            map.insert(FIELD_EMAIL.to_string(), email.clone());
+        }
+        value
+    };
+
+    match data {
+        DataResult::Ok(value) => DataResult::Ok(add_email(value)),
+        DataResult::Err((value, e)) => DataResult::Err((add_email(value), e)),
+    }
+}
+
+/// Join the uids in a sequence set.
+fn uid_set(uids: &[u32]) -> String {
+    uids.iter()
+        .map(|uid| uid.to_string())
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+#[async_trait]
+impl Connector for Imap {
+    /// See [`Connector::set_document`] for more details.
+    fn set_document(&mut self, document: Box<dyn Document>) -> Result<()> {
+        self.document = Some(document.clone());
+
+        Ok(())
+    }
+    /// See [`Connector::document`] for more details.
+    fn document(&self) -> Result<&dyn Document> {
+        self.document.as_deref().ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                "The document has not been set in the connector",
+            )
+        })
+    }
+    /// See [`Connector::set_parameters`] for more details.
+    fn set_parameters(&mut self, parameters: Value) {
+        *self.parameters = parameters;
+    }
+    /// See [`Connector::is_variable`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::imap::Imap;
+    /// use chewdata::connector::Connector;
+    ///
+    /// let mut connector = Imap::default();
+    /// assert_eq!(false, connector.is_variable());
+    /// connector.folder = "INBOX/{{ partner }}".to_string();
+    /// assert_eq!(true, connector.is_variable());
+    /// ```
+    fn is_variable(&self) -> bool {
+        self.folder.has_mustache() || self.search.has_mustache()
+    }
+    /// See [`Connector::is_resource_will_change`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::imap::Imap;
+    /// use chewdata::connector::Connector;
+    /// use serde_json::Value;
+    ///
+    /// let mut connector = Imap::default();
+    /// let params = serde_json::from_str(r#"{"partner":"acme"}"#).unwrap();
+    /// assert_eq!(false, connector.is_resource_will_change(Value::Null).unwrap());
+    /// connector.folder = "INBOX/{{ partner }}".to_string();
+    /// assert_eq!(true, connector.is_resource_will_change(params).unwrap());
+    /// ```
+    fn is_resource_will_change(&self, new_parameters: Value) -> Result<bool> {
+        if !self.is_variable() {
+            trace!("Stay link to the same resource");
+            return Ok(false);
+        }
+
+        let mut previous = format!("{} {}", self.folder, self.search);
+        previous.replace_mustache(*self.parameters.clone());
+
+        let mut new = format!("{} {}", self.folder, self.search);
+        new.replace_mustache(new_parameters);
+
+        if previous == new {
+            trace!(folder = previous, "Folder and search didn't change");
+            return Ok(false);
+        }
+
+        info!(
+            previous = previous,
+            new = new,
+            "Folder or search will change"
+        );
+
+        Ok(true)
+    }
+    /// See [`Connector::path`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::imap::Imap;
+    /// use chewdata::connector::Connector;
+    ///
+    /// let mut connector = Imap::default();
+    /// connector.folder = "INBOX/{{ partner }}".to_string();
+    /// connector.set_parameters(serde_json::from_str(r#"{"partner":"acme"}"#).unwrap());
+    /// assert_eq!("INBOX/acme", connector.path());
+    /// ```
+    fn path(&self) -> String {
+        let mut folder = self.folder.clone();
+        folder.replace_mustache(*self.parameters.clone());
+        folder
+    }
+    /// See [`Connector::metadata`] for more details.
+    fn metadata(&self) -> Metadata {
+        match &self.document {
+            Some(document) => self.metadata.clone().merge(&document.metadata()),
+            None => self.metadata.clone(),
+        }
+    }
+    /// See [`Connector::fetch`] for more details.
+    ///
+    /// Download the messages found and yield the records of their attachments.
+    #[instrument(name = "imap::fetch")]
+    async fn fetch(&mut self) -> Result<Option<DataStream>> {
+        let mut session = self.session().await?;
+        let uids = self.uids(&mut session).await?;
+
+        if uids.is_empty() {
+            info!(folder = self.path(), "No message found");
+            session.logout().await.map_err(imap_error)?;
+            return Ok(None);
+        }
+
+        let messages: Vec<(u32, Vec<u8>)> = session
+            .uid_fetch(uid_set(&uids), "(UID BODY.PEEK[])")
+            .await
+            .map_err(imap_error)?
+            .map_err(imap_error)
+            .try_filter_map(|fetch| async move {
+                Ok(fetch.uid.zip(fetch.body().map(|body| body.to_vec())))
+            })
+            .try_collect()
+            .await?;
+
+        if self.mark_as_seen {
+            session
+                .uid_store(uid_set(&uids), "+FLAGS (\\Seen)")
+                .await
+                .map_err(imap_error)?
+                .map_err(imap_error)
+                .try_collect::<Vec<_>>()
+                .await?;
+        }
+        session.logout().await.map_err(imap_error)?;
+
+        info!(
+            folder = self.path(),
+            messages = messages.len(),
+            "Fetch messages with success"
+        );
+
+        let mut dataset = DataSet::default();
+        for (uid, raw) in messages {
+            dataset.append(&mut self.read_message(uid, &raw)?);
+        }
+
+        Ok(Some(Box::pin(stream! {
+            for data in dataset {
+                yield data;
+            }
+        })))
+    }
+    /// See [`Connector::send`] for more details.
+    async fn send(&mut self, _dataset: &DataSet) -> Result<Option<DataStream>> {
+        Err(Error::new(
+            ErrorKind::Unsupported,
+            "Can't send data with the imap connector. Use the smtp connector to send emails",
+        ))
+    }
+    /// See [`Connector::erase`] for more details.
+    ///
+    /// Delete the messages matching the search query.
+    #[instrument(name = "imap::erase")]
+    async fn erase(&mut self) -> Result<()> {
+        let mut session = self.session().await?;
+        let uids = self.uids(&mut session).await?;
+
+        if !uids.is_empty() {
+            session
+                .uid_store(uid_set(&uids), "+FLAGS (\\Deleted)")
+                .await
+                .map_err(imap_error)?
+                .map_err(imap_error)
+                .try_collect::<Vec<_>>()
+                .await?;
+            session
+                .expunge()
+                .await
+                .map_err(imap_error)?
+                .map_err(imap_error)
+                .try_collect::<Vec<_>>()
+                .await?;
+        }
+        session.logout().await.map_err(imap_error)?;
+
+        info!(
+            folder = self.path(),
+            messages = uids.len(),
+            "Messages deleted with success"
+        );
+
+        Ok(())
+    }
+    /// See [`Connector::paginate`] for more details.
+    async fn paginate(
+        &self,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<Box<dyn Connector>>> + Send>>> {
+        let paginator = Once {};
+        paginator.paginate(self).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::jsonl::Jsonl;
+    use futures::StreamExt;
+    use macro_rules_attribute::apply;
+    use serde_json::json;
+    use smol::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use smol::net::TcpListener;
+    use smol_macros::test;
+
+    const MESSAGE: &str = "From: Partner <partner@example.com>\r\n\
+To: data@example.com\r\n\
+Subject: Daily export\r\n\
+Date: Mon, 5 Feb 2024 10:00:00 +0000\r\n\
+MIME-Version: 1.0\r\n\
+Content-Type: multipart/mixed; boundary=\"boundary\"\r\n\
+\r\n\
+--boundary\r\n\
+Content-Type: text/plain\r\n\
+\r\n\
+Find the export in attachment.\r\n\
+--boundary\r\n\
+Content-Type: application/x-ndjson\r\n\
+Content-Disposition: attachment; filename=\"export.jsonl\"\r\n\
+\r\n\
+{\"column1\":\"value1\"}\r\n\
+{\"column1\":\"value2\"}\r\n\
+--boundary\r\n\
+Content-Type: image/png\r\n\
+Content-Disposition: attachment; filename=\"logo.png\"\r\n\
+Content-Transfer-Encoding: base64\r\n\
+\r\n\
+iVBORw0KGgo=\r\n\
+--boundary--\r\n";
+
+    /// Start an IMAP server with one message in the INBOX and return the commands received.
+    async fn imap_server() -> (String, async_channel::Receiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("imap://{}", listener.local_addr().unwrap());
+        let (sender, receiver) = async_channel::unbounded();
+
+        smol::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut reader = BufReader::new(stream.clone());
+            let mut writer = stream;
+
+            writer.write_all(b"* OK IMAP4rev1 ready\r\n").await.unwrap();
+            loop {
+                let mut line = String::default();
+                if 0 == reader.read_line(&mut line).await.unwrap() {
+                    break;
+                }
+                let (tag, command) = line.trim_end().split_once(' ').unwrap();
+                let command = command.to_string();
+                let response = match command.to_uppercase() {
+                    command if command.starts_with("LOGIN \"USER\"") => {
+                        format!("{} OK LOGIN completed\r\n", tag)
+                    }
+                    command if command.starts_with("LOGIN") => {
+                        format!("{} NO LOGIN failed\r\n", tag)
+                    }
+                    command if command.starts_with("SELECT \"INBOX\"") => format!(
+                        "* FLAGS (\\Seen \\Deleted)\r\n* 1 EXISTS\r\n* 0 RECENT\r\n{} OK [READ-WRITE] SELECT completed\r\n",
+                        tag
+                    ),
+                    command if command.starts_with("SELECT") => {
+                        format!("{} NO Mailbox doesn't exist\r\n", tag)
+                    }
+                    command if command.starts_with("UID SEARCH ALL") => {
+                        format!("* SEARCH 1\r\n{} OK SEARCH completed\r\n", tag)
+                    }
+                    command if command.starts_with("UID SEARCH") => {
+                        format!("* SEARCH\r\n{} OK SEARCH completed\r\n", tag)
+                    }
+                    command if command.starts_with("UID FETCH") => format!(
+                        "* 1 FETCH (UID 1 BODY[] {{{}}}\r\n{})\r\n{} OK FETCH completed\r\n",
+                        MESSAGE.len(),
+                        MESSAGE,
+                        tag
+                    ),
+                    command if command.starts_with("UID STORE") => format!(
+                        "* 1 FETCH (UID 1 FLAGS (\\Seen))\r\n{} OK STORE completed\r\n",
+                        tag
+                    ),
+                    command if command.starts_with("EXPUNGE") => {
+                        format!("* 1 EXPUNGE\r\n{} OK EXPUNGE completed\r\n", tag)
+                    }
+                    command if command.starts_with("LOGOUT") => {
+                        format!("* BYE\r\n{} OK LOGOUT completed\r\n", tag)
+                    }
+                    _ => format!("{} BAD Unknown command\r\n", tag),
+                };
+                sender.send(command).await.unwrap();
+                writer.write_all(response.as_bytes()).await.unwrap();
+            }
+        })
+        .detach();
+
+        (endpoint, receiver)
+    }
+
+    fn connector(endpoint: String) -> Imap {
+        let mut connector = Imap {
+            endpoint,
+            username: Some("user".to_string()),
+            password: Some("pass".to_string()),
+            attachment: "*.jsonl".to_string(),
+            timeout: 5,
+            ..Default::default()
+        };
+        connector.set_document(Box::new(Jsonl::default())).unwrap();
+        connector
+    }
+
+    #[apply(test!)]
+    async fn read_message() {
+        let connector = connector(String::default());
+        let dataset = connector.read_message(1, MESSAGE.as_bytes()).unwrap();
+
+        assert_eq!(2, dataset.len());
+        assert_eq!(
+            json!({
+                "column1": "value1",
+                "_email": {
+                    "uid": 1,
+                    "subject": "Daily export",
+                    "from": "partner@example.com",
+                    "date": "2024-02-05T10:00:00Z",
+                    "attachment": "export.jsonl"
+                }
+            }),
+            dataset[0].to_value()
+        );
+    }
+    #[apply(test!)]
+    async fn read_message_without_attachment_matching() {
+        let mut connector = connector(String::default());
+        connector.attachment = "*.csv".to_string();
+        let dataset = connector.read_message(1, MESSAGE.as_bytes()).unwrap();
+
+        assert_eq!(0, dataset.len());
+    }
+    #[apply(test!)]
+    async fn fetch() {
+        let (endpoint, receiver) = imap_server().await;
+        let mut connector = connector(endpoint);
+        connector.mark_as_seen = true;
+
+        let dataset: Vec<DataResult> = connector.fetch().await.unwrap().unwrap().collect().await;
+        assert_eq!(2, dataset.len());
+        assert_eq!(
+            json!("value2"),
+            dataset[1].to_value().get("column1").cloned().unwrap()
+        );
+
+        let commands: Vec<String> = std::iter::from_fn(|| receiver.try_recv().ok()).collect();
+        assert!(commands
+            .iter()
+            .any(|command| command.starts_with("UID FETCH 1 (UID BODY.PEEK[])")));
+        assert!(commands
+            .iter()
+            .any(|command| command.starts_with("UID STORE 1 +FLAGS (\\Seen)")));
+    }
+    #[apply(test!)]
+    async fn fetch_without_message() {
+        let (endpoint, _receiver) = imap_server().await;
+        let mut connector = connector(endpoint);
+        connector.search = "UNSEEN".to_string();
+
+        assert!(connector.fetch().await.unwrap().is_none());
+    }
+    #[apply(test!)]
+    async fn fetch_with_wrong_credentials() {
+        let (endpoint, _receiver) = imap_server().await;
+        let mut connector = connector(endpoint);
+        connector.username = Some("unknown".to_string());
+
+        let error = connector.fetch().await.err().unwrap();
+        assert_eq!(ErrorKind::PermissionDenied, error.kind());
+    }
+    #[apply(test!)]
+    async fn fetch_with_unknown_folder() {
+        let (endpoint, _receiver) = imap_server().await;
+        let mut connector = connector(endpoint);
+        connector.folder = "Unknown".to_string();
+
+        let error = connector.fetch().await.err().unwrap();
+        assert_eq!(ErrorKind::InvalidInput, error.kind());
+    }
+    #[apply(test!)]
+    async fn erase() {
+        let (endpoint, receiver) = imap_server().await;
+        let mut connector = connector(endpoint);
+
+        connector.erase().await.unwrap();
+
+        let commands: Vec<String> = std::iter::from_fn(|| receiver.try_recv().ok()).collect();
+        assert!(commands
+            .iter()
+            .any(|command| command.starts_with("UID STORE 1 +FLAGS (\\Deleted)")));
+        assert!(commands.iter().any(|command| command == "EXPUNGE"));
+    }
+}
@@ -1,38 +1,104 @@
 #[cfg(feature = "curl")]
 pub mod authenticator;
+#[cfg(feature = "azure_blob")]
+pub mod azure_blob;
 #[cfg(feature = "bucket")]
 pub mod bucket;
 #[cfg(feature = "bucket")]
 pub mod bucket_select;
+#[cfg(feature = "clickhouse")]
+pub mod clickhouse;
 pub mod cli;
 pub mod counter;
 #[cfg(feature = "curl")]
 pub mod curl;
+#[cfg(feature = "dynamodb")]
+pub mod dynamodb;
+#[cfg(feature = "elasticsearch")]
+pub mod elasticsearch;
+#[cfg(feature = "exec")]
+pub mod exec;
+#[cfg(feature = "ftp")]
+pub mod ftp;
+#[cfg(feature = "gcs")]
+pub mod gcs;
+#[cfg(feature = "http_server")]
+pub mod http_server;
+#[cfg(feature = "imap")]
+pub mod imap;
 pub mod in_memory;
 pub mod local;
 #[cfg(feature = "mongodb")]
 pub mod mongodb;
+#[cfg(feature = "mqtt")]
+pub mod mqtt;
+#[cfg(feature = "nats")]
+pub mod nats;
 pub mod paginator;
 #[cfg(feature = "psql")]
 pub mod psql;
+#[cfg(feature = "smtp")]
+pub mod smtp;
+#[cfg(feature = "sns")]
+pub mod sns;
+#[cfg(feature = "socket")]
+pub mod socket;
+#[cfg(feature = "sqs")]
+pub mod sqs;
+#[cfg(feature = "websocket")]
+pub mod websocket;
 
+#[cfg(feature = "azure_blob")]
+use self::azure_blob::AzureBlob;
 #[cfg(feature = "bucket")]
 use self::bucket::Bucket;
 #[cfg(feature = "bucket")]
 use self::bucket_select::BucketSelect;
+#[cfg(feature = "clickhouse")]
+use self::clickhouse::Clickhouse;
 use self::cli::Cli;
 #[cfg(feature = "curl")]
 use self::curl::Curl;
+#[cfg(feature = "dynamodb")]
+use self::dynamodb::Dynamodb;
+#[cfg(feature = "elasticsearch")]
+use self::elasticsearch::Elasticsearch;
+#[cfg(feature = "exec")]
+use self::exec::Exec;
+#[cfg(feature = "ftp")]
+use self::ftp::Ftp;
+#[cfg(feature = "gcs")]
+use self::gcs::Gcs;
+#[cfg(feature = "http_server")]
+use self::http_server::HttpServer;
+#[cfg(feature = "imap")]
+use self::imap::Imap;
 use self::in_memory::InMemory;
 use self::local::Local;
 #[cfg(feature = "mongodb")]
 use self::mongodb::Mongodb;
+#[cfg(feature = "mqtt")]
+use self::mqtt::Mqtt;
+#[cfg(feature = "nats")]
+use self::nats::Nats;
 #[cfg(feature = "psql")]
 use self::psql::Psql;
+#[cfg(feature = "smtp")]
+use self::smtp::Smtp;
+#[cfg(feature = "sns")]
+use self::sns::Sns;
+#[cfg(feature = "socket")]
+use self::socket::Socket;
+#[cfg(feature = "sqs")]
+use self::sqs::Sqs;
+#[cfg(feature = "websocket")]
+use self::websocket::Websocket;
 use crate::document::Document;
+use crate::DataResult;
 use crate::DataSet;
 use crate::DataStream;
 use crate::Metadata;
+use async_channel::Sender;
 use async_trait::async_trait;
 use futures::stream::Stream;
 use serde::{Deserialize, Serialize};
@@ -51,12 +117,30 @@ pub enum ConnectorType {
     Cli(Cli),
     #[serde(rename = "local")]
     Local(Local),
+    #[cfg(feature = "socket")]
+    #[serde(rename = "socket")]
+    #[serde(alias = "tcp")]
+    #[serde(alias = "udp")]
+    Socket(Socket),
+    #[cfg(feature = "exec")]
+    #[serde(rename = "exec")]
+    #[serde(alias = "command")]
+    #[serde(alias = "process")]
+    Exec(Exec),
     #[cfg(feature = "bucket")]
     #[serde(rename = "bucket")]
     Bucket(Bucket),
     #[cfg(feature = "bucket")]
     #[serde(rename = "bucket_select")]
     BucketSelect(BucketSelect),
+    #[cfg(feature = "azure_blob")]
+    #[serde(rename = "azure_blob")]
+    #[serde(alias = "azure")]
+    AzureBlob(AzureBlob),
+    #[cfg(feature = "gcs")]
+    #[serde(rename = "gcs")]
+    #[serde(alias = "google_cloud_storage")]
+    Gcs(Gcs),
     #[cfg(feature = "curl")]
     #[serde(rename = "curl")]
     Curl(Curl),
@@ -69,6 +153,51 @@ pub enum ConnectorType {
     #[serde(alias = "pgsql")]
     #[serde(alias = "pg")]
     Psql(Psql),
+    #[cfg(feature = "elasticsearch")]
+    #[serde(rename = "elasticsearch")]
+    #[serde(alias = "opensearch")]
+    #[serde(alias = "es")]
+    Elasticsearch(Elasticsearch),
+    #[cfg(feature = "ftp")]
+    #[serde(rename = "ftp")]
+    #[serde(alias = "sftp")]
+    Ftp(Ftp),
+    #[cfg(feature = "http_server")]
+    #[serde(rename = "http_server")]
+    #[serde(alias = "webhook")]
+    HttpServer(HttpServer),
+    #[cfg(feature = "mqtt")]
+    #[serde(rename = "mqtt")]
+    Mqtt(Mqtt),
+    #[cfg(feature = "nats")]
+    #[serde(rename = "nats")]
+    #[serde(alias = "jetstream")]
+    Nats(Nats),
+    #[cfg(feature = "websocket")]
+    #[serde(rename = "websocket")]
+    #[serde(alias = "ws")]
+    Websocket(Websocket),
+    #[cfg(feature = "smtp")]
+    #[serde(rename = "smtp")]
+    #[serde(alias = "email")]
+    Smtp(Smtp),
+    #[cfg(feature = "imap")]
+    #[serde(rename = "imap")]
+    Imap(Imap),
+    #[cfg(feature = "sqs")]
+    #[serde(rename = "sqs")]
+    Sqs(Sqs),
+    #[cfg(feature = "sns")]
+    #[serde(rename = "sns")]
+    Sns(Sns),
+    #[cfg(feature = "dynamodb")]
+    #[serde(rename = "dynamodb")]
+    #[serde(alias = "dynamo")]
+    Dynamodb(Dynamodb),
+    #[cfg(feature = "clickhouse")]
+    #[serde(rename = "clickhouse")]
+    #[serde(alias = "ch")]
+    Clickhouse(Clickhouse),
 }
 
 impl Default for ConnectorType {
@@ -83,16 +212,48 @@ impl ConnectorType {
             ConnectorType::InMemory(connector) => Box::new(connector),
             ConnectorType::Cli(connector) => Box::new(connector),
             ConnectorType::Local(connector) => Box::new(connector),
+            #[cfg(feature = "socket")]
+            ConnectorType::Socket(connector) => Box::new(connector),
+            #[cfg(feature = "exec")]
+            ConnectorType::Exec(connector) => Box::new(connector),
             #[cfg(feature = "curl")]
             ConnectorType::Curl(connector) => Box::new(connector),
             #[cfg(feature = "bucket")]
             ConnectorType::Bucket(connector) => Box::new(connector),
             #[cfg(feature = "bucket")]
             ConnectorType::BucketSelect(connector) => Box::new(connector),
+            #[cfg(feature = "azure_blob")]
+            ConnectorType::AzureBlob(connector) => Box::new(connector),
+            #[cfg(feature = "gcs")]
+            ConnectorType::Gcs(connector) => Box::new(connector),
             #[cfg(feature = "mongodb")]
             ConnectorType::Mongodb(connector) => Box::new(connector),
             #[cfg(feature = "psql")]
             ConnectorType::Psql(connector) => Box::new(connector),
+            #[cfg(feature = "elasticsearch")]
+            ConnectorType::Elasticsearch(connector) => Box::new(connector),
+            #[cfg(feature = "ftp")]
+            ConnectorType::Ftp(connector) => Box::new(connector),
+            #[cfg(feature = "http_server")]
+            ConnectorType::HttpServer(connector) => Box::new(connector),
+            #[cfg(feature = "mqtt")]
+            ConnectorType::Mqtt(connector) => Box::new(connector),
+            #[cfg(feature = "nats")]
+            ConnectorType::Nats(connector) => Box::new(connector),
+            #[cfg(feature = "websocket")]
+            ConnectorType::Websocket(connector) => Box::new(connector),
+            #[cfg(feature = "smtp")]
+            ConnectorType::Smtp(connector) => Box::new(connector),
+            #[cfg(feature = "imap")]
+            ConnectorType::Imap(connector) => Box::new(connector),
+            #[cfg(feature = "sqs")]
+            ConnectorType::Sqs(connector) => Box::new(connector),
+            #[cfg(feature = "sns")]
+            ConnectorType::Sns(connector) => Box::new(connector),
+            #[cfg(feature = "dynamodb")]
+            ConnectorType::Dynamodb(connector) => Box::new(connector),
+            #[cfg(feature = "clickhouse")]
+            ConnectorType::Clickhouse(connector) => Box::new(connector),
         }
     }
 }
@@ -103,16 +264,48 @@ impl ConnectorType {
             ConnectorType::InMemory(connector) => connector,
             ConnectorType::Cli(connector) => connector,
             ConnectorType::Local(connector) => connector,
+            #[cfg(feature = "socket")]
+            ConnectorType::Socket(connector) => connector,
+            #[cfg(feature = "exec")]
+            ConnectorType::Exec(connector) => connector,
             #[cfg(feature = "curl")]
             ConnectorType::Curl(connector) => connector,
             #[cfg(feature = "bucket")]
             ConnectorType::Bucket(connector) => connector,
             #[cfg(feature = "bucket")]
             ConnectorType::BucketSelect(connector) => connector,
+            #[cfg(feature = "azure_blob")]
+            ConnectorType::AzureBlob(connector) => connector,
+            #[cfg(feature = "gcs")]
+            ConnectorType::Gcs(connector) => connector,
             #[cfg(feature = "mongodb")]
             ConnectorType::Mongodb(connector) => connector,
             #[cfg(feature = "psql")]
             ConnectorType::Psql(connector) => connector,
+            #[cfg(feature = "elasticsearch")]
+            ConnectorType::Elasticsearch(connector) => connector,
+            #[cfg(feature = "ftp")]
+            ConnectorType::Ftp(connector) => connector,
+            #[cfg(feature = "http_server")]
+            ConnectorType::HttpServer(connector) => connector,
+            #[cfg(feature = "mqtt")]
+            ConnectorType::Mqtt(connector) => connector,
+            #[cfg(feature = "nats")]
+            ConnectorType::Nats(connector) => connector,
+            #[cfg(feature = "websocket")]
+            ConnectorType::Websocket(connector) => connector,
+            #[cfg(feature = "smtp")]
+            ConnectorType::Smtp(connector) => connector,
+            #[cfg(feature = "imap")]
+            ConnectorType::Imap(connector) => connector,
+            #[cfg(feature = "sqs")]
+            ConnectorType::Sqs(connector) => connector,
+            #[cfg(feature = "sns")]
+            ConnectorType::Sns(connector) => connector,
+            #[cfg(feature = "dynamodb")]
+            ConnectorType::Dynamodb(connector) => connector,
+            #[cfg(feature = "clickhouse")]
+            ConnectorType::Clickhouse(connector) => connector,
         }
     }
 }
@@ -154,6 +347,14 @@ pub trait Connector: Send + Sync + std::fmt::Debug + ConnectorClone + Unpin {
     async fn fetch(&mut self) -> std::io::Result<Option<DataStream>>;
     /// Send the data from the inner connector to the remote resource.
     async fn send(&mut self, dataset: &DataSet) -> std::io::Result<Option<DataStream>>;
+    /// Test if the stream returned by the send contains the result of each record sent, in the same order.
+    fn has_send_results(&self) -> bool {
+        false
+    }
+    /// Channel that waits the result of the writer for each record fetched.
+    fn acknowledgement(&self) -> Option<Sender<DataResult>> {
+        None
+    }
     /// Erase the content of the resource.
     async fn erase(&mut self) -> Result<()> {
         Err(Error::new(
@@ -0,0 +1,727 @@
+//! Subscribe and publish messages on an MQTT broker.
+//!
+//! When the connector reads, it subscribes to the `filters` and parses the payload of each message received with the [`crate::document`].
+//! The topic of the message is added in each record in the field `_topic`.
+//! The connector stops to read after `message_limit` messages or if no message is received before the `timeout`. Otherwise, it reads without end.
+//!
+//! When the connector writes, it publishes each record in its own message on the `topic`.
+//! The `topic` can be a template rendered with the parameters of the connector. The field `_topic` is removed from the records before to be published.
+//!
+//! ### Configuration
+//!
+//! | key           | alias       | Description                                                                    | Default Value           | Possible Values                      |
+//! | ------------- | ----------- | ------------------------------------------------------------------------------ | ----------------------- | ------------------------------------ |
+//! | type          | -           | Required in order to use this connector                                        | `mqtt`                  | `mqtt`                               |
+//! | metadata      | meta        | Override metadata information                                                  | `null`                  | [`crate::Metadata`]                  |
+//! | endpoint      | -           | The address of the broker                                                      | `mqtt://localhost:1883` | String                               |
+//! | topic         | path        | The topic used to publish the messages. Can use mustache variables             | `null`                  | String                               |
+//! | filters       | -           | The topic filters to subscribe. Use the `topic` if not set                     | `[]`                    | List of string                       |
+//! | qos           | -           | The quality of service used to publish and subscribe                           | `1`                     | `0` / `1` / `2`                      |
+//! | retain        | -           | Ask the broker to retain the published messages                                | `false`                 | `true` / `false`                     |
+//! | client_id     | id          | The identifier of the client. Generated if not set                             | `null`                  | String                               |
+//! | username      | user        | The username used to connect to the broker                                     | `null`                  | String                               |
+//! | password      | pass        | The password used to connect to the broker                                     | `null`                  | String                               |
+//! | keep_alive    | -           | Time in second between two pings                                               | `60`                    | Unsigned number                      |
+//! | clean_session | -           | Start a new session on the broker. Use `false` and a `client_id` to resume it  | `true`                  | `true` / `false`                     |
+//! | message_limit | limit       | Stop to read after N messages. Read without limit if not set                   | `null`                  | Unsigned number                      |
+//! | timeout       | -           | Time in second without message before to stop to read, or to wait the acks     | `null`                  | Unsigned number                      |
+//! | parameters    | params      | Variables used to render the `topic`                                           | `null`                  | Object                               |
+//!
+//! ### Examples
+//!
+//! ```json
+//! [
+//!     {
+//!         "type": "r",
+//!         "connector": {
+//!             "type": "mqtt",
+//!             "endpoint": "mqtt://localhost:1883",
+//!             "filters": ["sensors/+/temperature"],
+//!             "qos": 1,
+//!             "message_limit": 100
+//!         }
+//!     },
+//!     {
+//!         "type": "w",
+//!         "connector": {
+//!             "type": "mqtt",
+//!             "endpoint": "mqtt://localhost:1883",
+//!             "topic": "archive/{{ input._topic }}",
+//!             "retain": true
+//!         }
+//!     }
+//! ]
+//! ```
+use super::Connector;
+use crate::connector::paginator::once::Once;
+use crate::document::Document;
+use crate::helper::mustache::Mustache;
+use crate::helper::string::DisplayOnlyForDebugging;
+use crate::{DataResult, DataSet, DataStream, Metadata};
+use async_compat::Compat;
+use async_stream::stream;
+use async_trait::async_trait;
+use futures::Stream;
+use json_value_merge::Merge;
+use rumqttc::{
+    AsyncClient, ConnectReturnCode, Event, EventLoop, MqttOptions, Outgoing, Packet, QoS,
+    SubscribeFilter, SubscribeReasonCode,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use smol_timeout::TimeoutExt;
+use std::pin::Pin;
+use std::time::Duration;
+use std::{
+    fmt,
+    io::{Error, ErrorKind, Result},
+};
+
+const DEFAULT_ENDPOINT: &str = "mqtt://localhost:1883";
+const DEFAULT_PORT: u16 = 1883;
+const DEFAULT_KEEP_ALIVE: u64 = 60;
+const DEFAULT_TIMEOUT: u64 = 30;
+const MAX_PACKET_SIZE: usize = 256 * 1024 * 1024;
+pub const FIELD_TOPIC: &str = "_topic";
+
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct Mqtt {
+    #[serde(skip)]
+    document: Option<Box<dyn Document>>,
+    #[serde(rename = "metadata")]
+    #[serde(alias = "meta")]
+    pub metadata: Metadata,
+    pub endpoint: String,
+    #[serde(alias = "path")]
+    pub topic: String,
+    pub filters: Vec<String>,
+    pub qos: u8,
+    pub retain: bool,
+    #[serde(alias = "id")]
+    pub client_id: Option<String>,
+    #[serde(alias = "user")]
+    pub username: Option<String>,
+    #[serde(alias = "pass")]
+    pub password: Option<String>,
+    pub keep_alive: u64,
+    pub clean_session: bool,
+    #[serde(alias = "limit")]
+    pub message_limit: Option<usize>,
+    pub timeout: Option<u64>,
+    #[serde(alias = "params")]
+    pub parameters: Box<Value>,
+}
+
+impl fmt::Debug for Mqtt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Mqtt")
+            .field("document", &self.document.display_only_for_debugging())
+            .field("metadata", &self.metadata.display_only_for_debugging())
+            .field("endpoint", &self.endpoint)
+            .field("topic", &self.topic)
+            .field("filters", &self.filters)
+            .field("qos", &self.qos)
+            .field("retain", &self.retain)
+            .field("client_id", &self.client_id)
+            .field("username", &self.username)
+            .field("keep_alive", &self.keep_alive)
+            .field("clean_session", &self.clean_session)
+            .field("message_limit", &self.message_limit)
+            .field("timeout", &self.timeout)
+            .field("parameters", &self.parameters.display_only_for_debugging())
+            .finish()
+    }
+}
+
+impl Default for Mqtt {
+    fn default() -> Self {
+        Mqtt {
+            document: None,
+            metadata: Metadata::default(),
+            endpoint: DEFAULT_ENDPOINT.to_string(),
+            topic: String::default(),
+            filters: Vec::default(),
+            qos: 1,
+            retain: false,
+            client_id: None,
+            username: None,
+            password: None,
+            keep_alive: DEFAULT_KEEP_ALIVE,
+            clean_session: true,
+            message_limit: None,
+            timeout: None,
+            parameters: Box::<Value>::default(),
+        }
+    }
+}
+
+impl Mqtt {
+    /// Get the host and the port of the broker from the endpoint.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::mqtt::Mqtt;
+    ///
+    /// let mut connector = Mqtt::default();
+    /// assert_eq!(("localhost".to_string(), 1883), connector.address().unwrap());
+    /// connector.endpoint = "tcp://broker:1884".to_string();
+    /// assert_eq!(("broker".to_string(), 1884), connector.address().unwrap());
+    /// connector.endpoint = "broker".to_string();
+    /// assert_eq!(("broker".to_string(), 1883), connector.address().unwrap());
+    /// connector.endpoint = "mqtts://broker:8883".to_string();
+    /// assert!(connector.address().is_err());
+    /// ```
+    pub fn address(&self) -> Result<(String, u16)> {
+        let address = match self.endpoint.split_once("://") {
+            Some(("mqtt", address)) | Some(("tcp", address)) => address,
+            Some((scheme, _)) => {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!(
+                        "The scheme '{}' is not supported by the mqtt connector",
+                        scheme
+                    ),
+                ))
+            }
+            None => self.endpoint.as_str(),
+        };
+        let address = address.trim_end_matches('/');
+
+        match address.rsplit_once(':') {
+            Some((host, port)) => Ok((
+                host.to_string(),
+                port.parse()
+                    .map_err(|e| Error::new(ErrorKind::InvalidInput, e))?,
+            )),
+            None => Ok((address.to_string(), DEFAULT_PORT)),
+        }
+    }
+    /// Get the quality of service.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::mqtt::Mqtt;
+    /// use rumqttc::QoS;
+    ///
+    /// let mut connector = Mqtt::default();
+    /// assert_eq!(QoS::AtLeastOnce, connector.qos().unwrap());
+    /// connector.qos = 2;
+    /// assert_eq!(QoS::ExactlyOnce, connector.qos().unwrap());
+    /// connector.qos = 3;
+    /// assert!(connector.qos().is_err());
+    /// ```
+    pub fn qos(&self) -> Result<QoS> {
+        rumqttc::qos(self.qos).map_err(|e| Error::new(ErrorKind::InvalidInput, e.to_string()))
+    }
+    /// Get the topic filters to subscribe.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::mqtt::Mqtt;
+    ///
+    /// let mut connector = Mqtt::default();
+    /// connector.topic = "sensors/room1".to_string();
+    /// assert_eq!(vec!["sensors/room1".to_string()], connector.filters());
+    /// connector.filters = vec!["sensors/#".to_string()];
+    /// assert_eq!(vec!["sensors/#".to_string()], connector.filters());
+    /// ```
+    pub fn filters(&self) -> Vec<String> {
+        match self.filters.is_empty() {
+            true => vec![self.path()],
+            false => self.filters.clone(),
+        }
+    }
+    fn options(&self) -> Result<MqttOptions> {
+        let (host, port) = self.address()?;
+        let client_id = self
+            .client_id
+            .clone()
+            .unwrap_or_else(|| format!("chewdata-{}", uuid::Uuid::new_v4().simple()));
+
+        let mut options = MqttOptions::new(client_id, host, port);
+        options
+            .set_keep_alive(Duration::from_secs(self.keep_alive))
+            .set_clean_session(self.clean_session)
+            .set_max_packet_size(MAX_PACKET_SIZE, MAX_PACKET_SIZE);
+
+        if let Some(username) = &self.username {
+            options.set_credentials(username, self.password.clone().unwrap_or_default());
+        }
+
+        Ok(options)
+    }
+    /// Publish the messages and wait the acknowledgement of the broker for each of them.
+    async fn publish(&self, messages: Vec<Vec<u8>>) -> Result<()> {
+        let qos = self.qos()?;
+        let topic = self.path();
+        let expected = messages.len();
+
+        if topic.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The topic is required in order to publish messages",
+            ));
+        }
+
+        // The capacity allows to push all the requests without polling the event loop.
+        let (client, mut eventloop) = AsyncClient::new(self.options()?, expected + 1);
+        for payload in messages {
+            client
+                .publish(topic.as_str(), qos, self.retain, payload)
+                .await
+                .map_err(|e| Error::new(ErrorKind::Interrupted, e))?;
+        }
+
+        let timeout = Duration::from_secs(self.timeout.unwrap_or(DEFAULT_TIMEOUT));
+        let mut acknowledged = 0;
+        while acknowledged < expected {
+            match poll(&mut eventloop, timeout).await? {
+                Event::Incoming(Packet::PubAck(_)) if QoS::AtLeastOnce == qos => acknowledged += 1,
+                Event::Incoming(Packet::PubComp(_)) if QoS::ExactlyOnce == qos => acknowledged += 1,
+                Event::Outgoing(Outgoing::Publish(_)) if QoS::AtMostOnce == qos => {
+                    acknowledged += 1
+                }
+                _ => (),
+            }
+        }
+
+        client
+            .disconnect()
+            .await
+            .map_err(|e| Error::new(ErrorKind::Interrupted, e))?;
+        while !matches!(
+            poll(&mut eventloop, timeout).await?,
+            Event::Outgoing(Outgoing::Disconnect)
+        ) {}
+
+        info!(
+            topic,
+            messages = expected,
+            "Messages published with success"
+        );
+
+        Ok(())
+    }
+}
+
+/// Poll the next event of the connection and fail if the broker refuses it.
+async fn poll(eventloop: &mut EventLoop, timeout: Duration) -> Result<Event> {
+    let event = Compat::new(eventloop.poll())
+        .timeout(timeout)
+        .await
+        .ok_or_else(|| {
+            Error::new(
+                ErrorKind::TimedOut,
+                "The broker didn't respond before the timeout",
+            )
+        })?
+        .map_err(|e| Error::new(ErrorKind::Interrupted, e))?;
+
+    if let Event::Incoming(Packet::ConnAck(connack)) = &event {
+        if ConnectReturnCode::Success != connack.code {
+            return Err(Error::new(
+                ErrorKind::PermissionDenied,
+                format!("The broker refuses the connection: {:?}", connack.code),
+            ));
+        }
+    }
+
+    Ok(event)
+}
+
+/// Add the topic of the message in the record.
+fn with_topic(data: DataResult, topic: &str) -> DataResult {
+    let add_topic = |mut value: Value| {
+        if let Value::Object(map) = &mut value {
+            map.insert(FIELD_TOPIC.to_string(), Value::String(topic.to_string()));
+        }
+        value
+    };
+
+    match data {
+        DataResult::Ok(value) => DataResult::Ok(add_topic(value)),
+        DataResult::Err((value, e)) => DataResult::Err((add_topic(value), e)),
+    }
+}
+
+#[async_trait]
+impl Connector for Mqtt {
+    /// See [`Connector::set_document`] for more details.
+    fn set_document(&mut self, document: Box<dyn Document>) -> Result<()> {
+        self.document = Some(document.clone());
+
+        Ok(())
+    }
+    /// See [`Connector::document`] for more details.
+    fn document(&self) -> Result<&dyn Document> {
+        self.document.as_deref().ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                "The document has not been set in the connector",
+            )
+        })
+    }
+    /// See [`Connector::set_parameters`] for more details.
+    fn set_parameters(&mut self, parameters: Value) {
+        *self.parameters = parameters;
+    }
+    /// See [`Connector::is_variable`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::mqtt::Mqtt;
+    /// use chewdata::connector::Connector;
+    ///
+    /// let mut connector = Mqtt::default();
+    /// connector.topic = "sensors/room1".to_string();
+    /// assert_eq!(false, connector.is_variable());
+    /// connector.topic = "sensors/{{ room }}".to_string();
+    /// assert_eq!(true, connector.is_variable());
+    /// ```
+    fn is_variable(&self) -> bool {
+        self.topic.has_mustache()
+    }
+    /// See [`Connector::is_resource_will_change`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::mqtt::Mqtt;
+    /// use chewdata::connector::Connector;
+    /// use serde_json::Value;
+    ///
+    /// let mut connector = Mqtt::default();
+    /// let params = serde_json::from_str(r#"{"room":"room1"}"#).unwrap();
+    /// connector.topic = "sensors/room1".to_string();
+    /// assert_eq!(false, connector.is_resource_will_change(Value::Null).unwrap());
+    /// connector.topic = "sensors/{{ room }}".to_string();
+    /// assert_eq!(true, connector.is_resource_will_change(params).unwrap());
+    /// ```
+    fn is_resource_will_change(&self, new_parameters: Value) -> Result<bool> {
+        if !self.is_variable() {
+            trace!("Stay link to the same resource");
+            return Ok(false);
+        }
+
+        let mut metadata_kv = Map::default();
+        metadata_kv.insert("metadata".to_string(), self.metadata().into());
+        let metadata = Value::Object(metadata_kv);
+
+        let mut new_parameters = new_parameters;
+        new_parameters.merge(&metadata);
+        let mut old_parameters = *self.parameters.clone();
+        old_parameters.merge(&metadata);
+
+        let mut previous_topic = self.topic.clone();
+        previous_topic.replace_mustache(old_parameters);
+
+        let mut new_topic = self.topic.clone();
+        new_topic.replace_mustache(new_parameters);
+
+        if previous_topic == new_topic {
+            trace!(topic = previous_topic, "Topic didn't change");
+            return Ok(false);
+        }
+
+        info!(
+            previous_topic = previous_topic,
+            new_topic = new_topic,
+            "Topic will change"
+        );
+
+        Ok(true)
+    }
+    /// See [`Connector::path`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::mqtt::Mqtt;
+    /// use chewdata::connector::Connector;
+    ///
+    /// let mut connector = Mqtt::default();
+    /// connector.topic = "sensors/{{ room }}/temperature".to_string();
+    /// connector.set_parameters(serde_json::from_str(r#"{"room":"room1"}"#).unwrap());
+    /// assert_eq!("sensors/room1/temperature", connector.path());
+    /// ```
+    fn path(&self) -> String {
+        if !self.is_variable() {
+            return self.topic.clone();
+        }
+
+        let mut params = *self.parameters.clone();
+        params.merge(&serde_json::json!({
+            "metadata": self.metadata()
+        }));
+
+        let mut topic = self.topic.clone();
+        topic.replace_mustache(params);
+        topic
+    }
+    /// See [`Connector::metadata`] for more details.
+    fn metadata(&self) -> Metadata {
+        match &self.document {
+            Some(document) => self.metadata.clone().merge(&document.metadata()),
+            None => self.metadata.clone(),
+        }
+    }
+    /// See [`Connector::fetch`] for more details.
+    ///
+    /// Subscribe to the topic filters and yield the records of each message received.
+    #[instrument(name = "mqtt::fetch")]
+    async fn fetch(&mut self) -> Result<Option<DataStream>> {
+        let document = self.document()?.clone_box();
+        let qos = self.qos()?;
+        let filters = self.filters();
+        let message_limit = self.message_limit;
+        let timeout = self.timeout.map(Duration::from_secs);
+
+        let (client, mut eventloop) = AsyncClient::new(self.options()?, 10);
+        client
+            .subscribe_many(
+                filters
+                    .iter()
+                    .map(|filter| SubscribeFilter::new(filter.clone(), qos)),
+            )
+            .await
+            .map_err(|e| Error::new(ErrorKind::Interrupted, e))?;
+
+        info!(
+            filters = format!("{:?}", filters),
+            "Subscribe to the topics"
+        );
+
+        Ok(Some(Box::pin(stream! {
+            // Keep the client alive, the connection is closed if it's dropped.
+            let _client = client;
+            let mut messages = 0;
+
+            while message_limit.is_none_or(|limit| messages < limit) {
+                let event = match timeout {
+                    Some(timeout) => match poll(&mut eventloop, timeout).await {
+                        Err(e) if ErrorKind::TimedOut == e.kind() => {
+                            info!("No message received before the timeout");
+                            break;
+                        }
+                        event => event,
+                    },
+                    None => poll(&mut eventloop, Duration::MAX).await,
+                };
+
+                let publish = match event {
+                    Ok(Event::Incoming(Packet::Publish(publish))) => publish,
+                    Ok(Event::Incoming(Packet::SubAck(suback))) => {
+                        if suback.return_codes.contains(&SubscribeReasonCode::Failure) {
+                            warn!(return_codes = format!("{:?}", suback.return_codes), "The broker refuses some subscriptions");
+                        }
+                        continue;
+                    }
+                    Ok(_) => continue,
+                    Err(e) => {
+                        warn!(error = e.to_string().as_str(), "The connection with the broker is lost");
+                        break;
+                    }
+                };
+                messages += 1;
+
+                // An empty payload removes the retained message of the topic.
+                match document.has_data(&publish.payload) {
+                    Ok(false) => continue,
+                    Ok(true) => (),
+                    Err(e) => {
+                        yield DataResult::Err((Value::String(String::from_utf8_lossy(&publish.payload).to_string()), e));
+                        continue;
+                    }
+                }
+
+                match document.read(&publish.payload) {
+                    Ok(dataset) => {
+                        for data in dataset {
+                            yield with_topic(data, &publish.topic);
+                        }
+                    }
+                    Err(e) => {
+                        yield with_topic(DataResult::Err((Value::String(String::from_utf8_lossy(&publish.payload).to_string()), e)), &publish.topic);
+                    }
+                }
+            }
+
+            info!(messages, "Stop to read the messages");
+        })))
+    }
+    /// See [`Connector::send`] for more details.
+    ///
+    /// Publish each record in its own message.
+    #[instrument(name = "mqtt::send", skip(dataset))]
+    async fn send(&mut self, dataset: &DataSet) -> Result<Option<DataStream>> {
+        let document = self.document()?;
+
+        let mut messages = Vec::default();
+        for data in dataset {
+            let mut value = data.to_value();
+            if let Value::Object(map) = &mut value {
+                map.remove(FIELD_TOPIC);
+            }
+            messages.push(document.write(&vec![DataResult::Ok(value)])?);
+        }
+
+        if messages.is_empty() {
+            return Ok(None);
+        }
+
+        self.publish(messages).await?;
+
+        Ok(None)
+    }
+    /// See [`Connector::erase`] for more details.
+    ///
+    /// Remove the retained message of the topic.
+    #[instrument(name = "mqtt::erase")]
+    async fn erase(&mut self) -> Result<()> {
+        let mut connector = self.clone();
+        connector.retain = true;
+        connector.publish(vec![Vec::default()]).await
+    }
+    /// See [`Connector::paginate`] for more details.
+    async fn paginate(
+        &self,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<Box<dyn Connector>>> + Send>>> {
+        let paginator = Once {};
+        paginator.paginate(self).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::json::Json;
+    use macro_rules_attribute::apply;
+    use smol::stream::StreamExt;
+    use smol_macros::test;
+
+    fn connector(topic: &str) -> Mqtt {
+        let mut connector = Mqtt::default();
+        if let Ok(endpoint) = std::env::var("MQTT_ENDPOINT") {
+            connector.endpoint = endpoint;
+        }
+        connector.topic = topic.to_string();
+        connector.timeout = Some(5);
+        connector.set_document(Box::new(Json::default())).unwrap();
+        connector
+    }
+
+    #[test]
+    fn with_topic_in_object() {
+        let data = with_topic(
+            DataResult::Ok(serde_json::json!({"field":"value"})),
+            "sensors/room1",
+        );
+        assert_eq!(
+            serde_json::json!({"field":"value","_topic":"sensors/room1"}),
+            data.to_value()
+        );
+    }
+    #[apply(test!)]
+    async fn send_and_fetch() {
+        let topic = format!("chewdata/{}/send_and_fetch", uuid::Uuid::new_v4().simple());
+        let mut reader = connector(topic.as_str());
+        reader.message_limit = Some(2);
+        let datastream = reader.fetch().await.unwrap().unwrap();
+        let reading = smol::spawn(datastream.collect::<Vec<DataResult>>());
+
+        // Wait the subscription.
+        smol::Timer::after(Duration::from_millis(500)).await;
+
+        let mut writer = connector(topic.as_str());
+        writer
+            .send(&vec![
+                DataResult::Ok(serde_json::json!({"field":"value1"})),
+                DataResult::Ok(serde_json::json!({"field":"value2","_topic":"other"})),
+            ])
+            .await
+            .unwrap();
+
+        let dataset = reading.await;
+        assert_eq!(
+            vec![
+                serde_json::json!({"field":"value1","_topic":topic}),
+                serde_json::json!({"field":"value2","_topic":topic}),
+            ],
+            dataset
+                .iter()
+                .map(DataResult::to_value)
+                .collect::<Vec<Value>>()
+        );
+    }
+    #[apply(test!)]
+    async fn send_with_qos_and_filters() {
+        let prefix = format!("chewdata/{}", uuid::Uuid::new_v4().simple());
+
+        let mut reader = connector("");
+        reader.filters = vec![format!("{}/+/temperature", prefix)];
+        reader.qos = 2;
+        reader.message_limit = Some(2);
+        let datastream = reader.fetch().await.unwrap().unwrap();
+        let reading = smol::spawn(datastream.collect::<Vec<DataResult>>());
+
+        smol::Timer::after(Duration::from_millis(500)).await;
+
+        for (qos, room) in [(0, "room1"), (2, "room2")] {
+            let mut writer = connector(format!("{}/{{{{ room }}}}/temperature", prefix).as_str());
+            writer.qos = qos;
+            writer.set_parameters(serde_json::json!({ "room": room }));
+            writer
+                .send(&vec![DataResult::Ok(serde_json::json!({ "room": room }))])
+                .await
+                .unwrap();
+        }
+
+        let mut topics: Vec<Value> = reading
+            .await
+            .iter()
+            .map(|data| data.to_value()[FIELD_TOPIC].clone())
+            .collect();
+        topics.sort_by_key(|topic| topic.to_string());
+        assert_eq!(
+            vec![
+                Value::String(format!("{}/room1/temperature", prefix)),
+                Value::String(format!("{}/room2/temperature", prefix)),
+            ],
+            topics
+        );
+    }
+    #[apply(test!)]
+    async fn send_retained_and_erase() {
+        let topic = format!("chewdata/{}/retained", uuid::Uuid::new_v4().simple());
+        let mut writer = connector(topic.as_str());
+        writer.retain = true;
+        writer
+            .send(&vec![DataResult::Ok(serde_json::json!({"field":"value"}))])
+            .await
+            .unwrap();
+
+        let mut reader = connector(topic.as_str());
+        reader.message_limit = Some(1);
+        let dataset: Vec<DataResult> = reader.fetch().await.unwrap().unwrap().collect().await;
+        assert_eq!(1, dataset.len());
+
+        writer.erase().await.unwrap();
+
+        let mut reader = connector(topic.as_str());
+        reader.timeout = Some(1);
+        let dataset: Vec<DataResult> = reader.fetch().await.unwrap().unwrap().collect().await;
+        assert_eq!(0, dataset.len());
+    }
+    #[apply(test!)]
+    async fn paginate() {
+        let connector = connector("sensors/room1");
+        let mut paging = connector.paginate().await.unwrap();
+        assert!(paging.next().await.transpose().unwrap().is_some());
+        assert!(paging.next().await.transpose().unwrap().is_none());
+    }
+}
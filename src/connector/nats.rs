@@ -0,0 +1,942 @@
+//! Subscribe and publish messages on a NATS server, with or without JetStream, and read the entries of a key-value bucket.
+//!
+//! The connector works in three modes:
+//!
+//! * Core: without `stream` and `bucket`, the connector subscribes to the `subject` and publishes on it.
+//!   The records read contain the subject of their message in the field `_subject`.
+//!   It stops to read after `message_limit` messages or if no message is received before the `timeout`. Otherwise, it reads without end.
+//! * JetStream: with a `stream`, the connector reads the messages of the stream with the durable consumer `durable`.
+//!   Each message is acknowledged once all its records are written with success. If the writer fails, the message is redelivered later, with a delay increasing with the number of deliveries.
+//!   After `max_deliver` deliveries in failure, the message is terminated.
+//!   Records dropped by a step or skipped by the writers are considered as processed. A message that can't be parsed is terminated.
+//!   The connector publishes in the stream and waits the acknowledgement of the server for each message.
+//! * Key-value: with a `bucket`, the connector reads the last value of each key. The records contain their key in the field `_key`.
+//!
+//! When the connector writes, it publishes each record in its own message. The `subject` can be a template rendered with the parameters of the connector.
+//! The fields `_subject` and `_key` are removed from the records before to be published.
+//!
+//! ### Configuration
+//!
+//! | key           | alias          | Description                                                                     | Default Value           | Possible Values     |
+//! | ------------- | -------------- | ------------------------------------------------------------------------------- | ----------------------- | ------------------- |
+//! | type          | -              | Required in order to use this connector                                         | `nats`                  | `nats` / `jetstream`|
+//! | metadata      | meta           | Override metadata information                                                   | `null`                  | [`crate::Metadata`] |
+//! | endpoint      | -              | The address of the server                                                       | `nats://localhost:4222` | String              |
+//! | subject       | topic / path   | The subject to subscribe or to publish. Can use mustache variables to publish   | `null`                  | String              |
+//! | stream        | -              | The JetStream stream to read and to publish                                     | `null`                  | String              |
+//! | durable       | consumer       | The name of the JetStream durable consumer                                      | `chewdata`              | String              |
+//! | ack_wait      | -              | Time in second to wait the result of the writer before the message redelivery   | `30`                    | Unsigned number     |
+//! | max_deliver   | -              | Maximum number of deliveries of a message before to terminate it. `0` for no limit | `5`                  | Unsigned number     |
+//! | bucket        | kv             | The key-value bucket to read                                                    | `null`                  | String              |
+//! | username      | user           | The username used to connect to the server                                      | `null`                  | String              |
+//! | password      | pass           | The password used to connect to the server                                      | `null`                  | String              |
+//! | token         | -              | The token used to connect to the server                                         | `null`                  | String              |
+//! | message_limit | limit          | Stop to read after N messages. Read without limit if not set                    | `null`                  | Unsigned number     |
+//! | timeout       | -              | Time in second without message before to stop to read                           | `null`                  | Unsigned number     |
+//! | parameters    | params         | Variables used to render the `subject`                                          | `null`                  | Object              |
+//!
+//! ### Examples
+//!
+//! ```json
+//! [
+//!     {
+//!         "type": "r",
+//!         "connector": {
+//!             "type": "nats",
+//!             "endpoint": "nats://localhost:4222",
+//!             "stream": "ORDERS",
+//!             "subject": "orders.>",
+//!             "durable": "orders-archiver"
+//!         }
+//!     },
+//!     {
+//!         "type": "w",
+//!         "connector": {
+//!             "type": "nats",
+//!             "endpoint": "nats://localhost:4222",
+//!             "subject": "archive.{{ input._subject }}"
+//!         }
+//!     }
+//! ]
+//! ```
+use super::Connector;
+use crate::connector::paginator::once::Once;
+use crate::document::Document;
+use crate::helper::mustache::Mustache;
+use crate::helper::string::DisplayOnlyForDebugging;
+use crate::{ConnectorStream, DataResult, DataSet, DataStream, Metadata};
+use async_channel::{Receiver, Sender};
+use async_compat::Compat;
+use async_lock::OnceCell;
+use async_nats::jetstream::{self, consumer::pull, AckKind};
+use async_nats::{Client, ConnectOptions};
+use async_stream::stream;
+use async_trait::async_trait;
+use bytes::Bytes;
+use dashmap::DashMap;
+use futures::{Stream, StreamExt};
+use json_value_merge::Merge;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use smol::Timer;
+use smol_timeout::TimeoutExt;
+use std::pin::Pin;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+use std::{
+    fmt,
+    io::{Error, ErrorKind, Result},
+};
+
+const DEFAULT_ENDPOINT: &str = "nats://localhost:4222";
+const DEFAULT_DURABLE: &str = "chewdata";
+const DEFAULT_ACK_WAIT: u64 = 30;
+const DEFAULT_MAX_DELIVER: u64 = 5;
+const NAK_DELAY: Duration = Duration::from_secs(1);
+const NAK_DELAY_MAX: u32 = 30;
+const RECEIVE_RETRY_DELAY: Duration = Duration::from_secs(1);
+const RECEIVE_RETRY_MAX: u32 = 30;
+pub const FIELD_SUBJECT: &str = "_subject";
+pub const FIELD_KEY: &str = "_key";
+
+type SharedClients = DashMap<String, Arc<OnceCell<Client>>>;
+static CLIENTS: OnceLock<SharedClients> = OnceLock::new();
+
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct Nats {
+    #[serde(skip)]
+    document: Option<Box<dyn Document>>,
+    #[serde(rename = "metadata")]
+    #[serde(alias = "meta")]
+    pub metadata: Metadata,
+    pub endpoint: String,
+    #[serde(alias = "topic")]
+    #[serde(alias = "path")]
+    pub subject: String,
+    pub stream: Option<String>,
+    #[serde(alias = "consumer")]
+    pub durable: String,
+    pub ack_wait: u64,
+    pub max_deliver: u64,
+    #[serde(alias = "kv")]
+    pub bucket: Option<String>,
+    #[serde(alias = "user")]
+    pub username: Option<String>,
+    #[serde(alias = "pass")]
+    pub password: Option<String>,
+    pub token: Option<String>,
+    #[serde(alias = "limit")]
+    pub message_limit: Option<usize>,
+    pub timeout: Option<u64>,
+    #[serde(alias = "params")]
+    pub parameters: Box<Value>,
+    // Records of the JetStream message read.
+    #[serde(skip)]
+    dataset: Option<DataSet>,
+    #[serde(skip)]
+    acknowledgement: Option<Sender<DataResult>>,
+}
+
+impl fmt::Debug for Nats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Nats")
+            .field("document", &self.document.display_only_for_debugging())
+            .field("metadata", &self.metadata.display_only_for_debugging())
+            .field("endpoint", &self.endpoint)
+            .field("subject", &self.subject)
+            .field("stream", &self.stream)
+            .field("durable", &self.durable)
+            .field("ack_wait", &self.ack_wait)
+            .field("max_deliver", &self.max_deliver)
+            .field("bucket", &self.bucket)
+            .field("username", &self.username)
+            .field("message_limit", &self.message_limit)
+            .field("timeout", &self.timeout)
+            .field("parameters", &self.parameters.display_only_for_debugging())
+            .field("dataset", &self.dataset.display_only_for_debugging())
+            .finish()
+    }
+}
+
+impl Default for Nats {
+    fn default() -> Self {
+        Nats {
+            document: None,
+            metadata: Metadata::default(),
+            endpoint: DEFAULT_ENDPOINT.to_string(),
+            subject: String::default(),
+            stream: None,
+            durable: DEFAULT_DURABLE.to_string(),
+            ack_wait: DEFAULT_ACK_WAIT,
+            max_deliver: DEFAULT_MAX_DELIVER,
+            bucket: None,
+            username: None,
+            password: None,
+            token: None,
+            message_limit: None,
+            timeout: None,
+            parameters: Box::<Value>::default(),
+            dataset: None,
+            acknowledgement: None,
+        }
+    }
+}
+
+impl Nats {
+    /// Get the client shared by the connectors with the same endpoint and credentials.
+    #[instrument(name = "nats::client")]
+    pub async fn client(&self) -> Result<Client> {
+        let clients = CLIENTS.get_or_init(DashMap::new);
+        let key = format!(
+            "{}|{:?}|{:?}|{:?}",
+            self.endpoint, self.username, self.password, self.token
+        );
+
+        let cell = clients
+            .entry(key)
+            .or_insert_with(|| Arc::new(OnceCell::new()))
+            .clone();
+
+        let client = cell
+            .get_or_try_init(|| async {
+                trace!("storing client in shared container");
+
+                let mut options = ConnectOptions::new().name("chewdata");
+                if let Some(username) = &self.username {
+                    options = options.user_and_password(
+                        username.clone(),
+                        self.password.clone().unwrap_or_default(),
+                    );
+                }
+                if let Some(token) = &self.token {
+                    options = options.token(token.clone());
+                }
+
+                Compat::new(options.connect(self.endpoint.as_str()))
+                    .await
+                    .map_err(|e| Error::new(ErrorKind::Interrupted, e))
+            })
+            .await?;
+
+        Ok(client.clone())
+    }
+    /// Parse the payload of a message and add the field in each record.
+    fn read(&self, payload: &[u8], field: &str, name: &str) -> Result<DataSet> {
+        let document = self.document()?;
+
+        if !document.has_data(payload)? {
+            return Ok(DataSet::default());
+        }
+
+        Ok(document
+            .read(payload)?
+            .into_iter()
+            .map(|data| with_field(data, field, name))
+            .collect())
+    }
+    /// Subscribe to the subject and yield the records of each message received.
+    async fn subscribe(&self) -> Result<DataStream> {
+        let client = self.client().await?;
+        let subject = self.subject.clone();
+
+        let mut subscriber = Compat::new(client.subscribe(subject.clone()))
+            .await
+            .map_err(|e| Error::new(ErrorKind::Interrupted, e))?;
+
+        info!(subject, "Subscribe to the subject");
+
+        let connector = self.clone();
+        Ok(Box::pin(stream! {
+            let mut messages = 0;
+
+            while connector.message_limit.is_none_or(|limit| messages < limit) {
+                let message = match next(&mut subscriber, connector.timeout).await {
+                    Some(message) => message,
+                    None => break,
+                };
+                messages += 1;
+
+                match connector.read(&message.payload, FIELD_SUBJECT, message.subject.as_str()) {
+                    Ok(dataset) => {
+                        for data in dataset {
+                            yield data;
+                        }
+                    }
+                    Err(e) => {
+                        yield with_field(DataResult::Err((Value::String(String::from_utf8_lossy(&message.payload).to_string()), e)), FIELD_SUBJECT, message.subject.as_str());
+                    }
+                }
+            }
+
+            info!(messages, "Stop to read the messages");
+        }))
+    }
+    /// Read the last value of each key in the bucket.
+    async fn entries(&self, bucket: &str) -> Result<DataStream> {
+        let context = jetstream::new(self.client().await?);
+        let store = Compat::new(context.get_key_value(bucket))
+            .await
+            .map_err(|e| Error::new(ErrorKind::NotFound, e))?;
+        let mut keys = Compat::new(store.keys())
+            .await
+            .map_err(|e| Error::new(ErrorKind::Interrupted, e))?;
+
+        info!(bucket, "Read the entries of the bucket");
+
+        let connector = self.clone();
+        Ok(Box::pin(stream! {
+            while let Some(key) = Compat::new(keys.next()).await {
+                let key = match key {
+                    Ok(key) => key,
+                    Err(e) => {
+                        warn!(error = e.to_string().as_str(), "Can't list the keys of the bucket");
+                        break;
+                    }
+                };
+                let value = match Compat::new(store.get(key.as_str())).await {
+                    Ok(Some(value)) => value,
+                    Ok(None) => continue,
+                    Err(e) => {
+                        yield DataResult::Err((serde_json::json!({ FIELD_KEY: key }), Error::new(ErrorKind::Interrupted, e)));
+                        continue;
+                    }
+                };
+
+                match connector.read(&value, FIELD_KEY, key.as_str()) {
+                    Ok(dataset) => {
+                        for data in dataset {
+                            yield data;
+                        }
+                    }
+                    Err(e) => {
+                        yield with_field(DataResult::Err((Value::String(String::from_utf8_lossy(&value).to_string()), e)), FIELD_KEY, key.as_str());
+                    }
+                }
+            }
+        }))
+    }
+    /// Consume the messages of the stream and return a new connector for each message.
+    async fn consume(&self, stream_name: &str) -> Result<ConnectorStream> {
+        let context = jetstream::new(self.client().await?);
+        let ack_wait = Duration::from_secs(self.ack_wait);
+        let max_deliver = i64::try_from(self.max_deliver).unwrap_or(i64::MAX);
+
+        let mut messages = Compat::new(async {
+            let stream = context
+                .get_stream(stream_name)
+                .await
+                .map_err(|e| Error::new(ErrorKind::NotFound, e))?;
+            let consumer: jetstream::consumer::Consumer<pull::Config> = stream
+                .get_or_create_consumer(
+                    self.durable.as_str(),
+                    pull::Config {
+                        durable_name: Some(self.durable.clone()),
+                        filter_subject: self.subject.clone(),
+                        ack_wait,
+                        max_deliver,
+                        ..Default::default()
+                    },
+                )
+                .await
+                .map_err(|e| Error::new(ErrorKind::Interrupted, e))?;
+            consumer
+                .messages()
+                .await
+                .map_err(|e| Error::new(ErrorKind::Interrupted, e))
+        })
+        .await?;
+
+        info!(
+            stream = stream_name,
+            durable = self.durable,
+            "Consume the messages of the stream"
+        );
+
+        let connector = self.clone();
+        Ok(Box::pin(stream! {
+            let mut count = 0;
+            let mut errors: u32 = 0;
+
+            while connector.message_limit.is_none_or(|limit| count < limit) {
+                let message = match next(&mut messages, connector.timeout).await {
+                    Some(Ok(message)) => {
+                        errors = 0;
+                        message
+                    }
+                    Some(Err(e)) => {
+                        // Wait before to retry, the error can persist like with a lost connection.
+                        errors = errors.saturating_add(1);
+                        warn!(error = e.to_string().as_str(), retry = errors, "Can't receive the message, retry later");
+                        Timer::after(RECEIVE_RETRY_DELAY * errors.min(RECEIVE_RETRY_MAX)).await;
+                        continue;
+                    }
+                    None => break,
+                };
+                count += 1;
+
+                let dataset = match connector.read(&message.payload, FIELD_SUBJECT, message.subject.as_str()) {
+                    Ok(dataset) => dataset,
+                    Err(e) => {
+                        warn!(error = e.to_string().as_str(), subject = message.subject.as_str(), "Can't parse the message, it's terminated");
+                        if let Err(e) = Compat::new(message.ack_with(AckKind::Term)).await {
+                            warn!(error = e.to_string().as_str(), "Can't terminate the message");
+                        }
+                        continue;
+                    }
+                };
+
+                let (sender, receiver) = async_channel::unbounded();
+                smol::spawn(acknowledge(message, receiver, dataset.len(), ack_wait, max_deliver)).detach();
+
+                let mut connector = connector.clone();
+                connector.dataset = Some(dataset);
+                connector.acknowledgement = Some(sender);
+
+                trace!("The stream yields a new connector");
+                yield Ok(Box::new(connector) as Box<dyn Connector>);
+            }
+
+            info!(messages = count, "Stop to consume the messages");
+        }))
+    }
+}
+
+/// Wait the next item of a stream during the timeout if it's set.
+async fn next<S>(stream: &mut S, timeout: Option<u64>) -> Option<S::Item>
+where
+    S: Stream + Unpin,
+{
+    match timeout {
+        Some(timeout) => match Compat::new(stream.next())
+            .timeout(Duration::from_secs(timeout))
+            .await
+        {
+            Some(item) => item,
+            None => {
+                info!("No message received before the timeout");
+                None
+            }
+        },
+        None => Compat::new(stream.next()).await,
+    }
+}
+
+/// Wait the result of the writer for each record of the message and acknowledge it.
+async fn acknowledge(
+    message: jetstream::Message,
+    receiver: Receiver<DataResult>,
+    expected: usize,
+    ack_wait: Duration,
+    max_deliver: i64,
+) {
+    let delivered = message.info().map_or(1, |info| info.delivered);
+    let results = async {
+        let mut received = 0;
+        let mut errors = 0;
+        // Stop if all the contexts are dropped, the missing records never reach the writer.
+        while received < expected {
+            match receiver.recv().await {
+                Ok(result) => {
+                    received += 1;
+                    if result.is_type(DataResult::ERR) {
+                        errors += 1;
+                    }
+                }
+                Err(_) => break,
+            }
+        }
+        (received, errors)
+    }
+    .timeout(ack_wait)
+    .await;
+
+    let kind = match results {
+        Some((received, errors)) => {
+            if received < expected {
+                warn!(
+                    received,
+                    expected, "The pipeline stopped before writing all the records of the message"
+                );
+            }
+            let kind = ack_kind(received, expected, errors, delivered, max_deliver);
+            match kind {
+                AckKind::Term => warn!(
+                    delivered,
+                    "The message failed too many times, it's terminated"
+                ),
+                AckKind::Nak(_) => warn!(delivered, "The message failed, it will be redelivered"),
+                _ => (),
+            };
+            kind
+        }
+        None => {
+            warn!("The writer didn't return the results before the ack wait, the message will be redelivered");
+            return;
+        }
+    };
+
+    if let Err(e) = Compat::new(message.ack_with(kind)).await {
+        warn!(
+            error = e.to_string().as_str(),
+            "Can't acknowledge the message"
+        );
+    }
+}
+
+/// Acknowledge the message only if each of its records is written without error.
+/// Otherwise, redeliver it later or terminate it after the last delivery.
+fn ack_kind(
+    received: usize,
+    expected: usize,
+    errors: usize,
+    delivered: i64,
+    max_deliver: i64,
+) -> AckKind {
+    match (received == expected, errors) {
+        (true, 0) => AckKind::Ack,
+        _ if max_deliver > 0 && delivered >= max_deliver => AckKind::Term,
+        _ => {
+            let delay = u32::try_from(delivered.max(1))
+                .unwrap_or(NAK_DELAY_MAX)
+                .min(NAK_DELAY_MAX);
+            AckKind::Nak(Some(NAK_DELAY * delay))
+        }
+    }
+}
+
+/// Add a field in the record.
+fn with_field(data: DataResult, field: &str, name: &str) -> DataResult {
+    let add_field = |mut value: Value| {
+        if let Value::Object(map) = &mut value {
+            map.insert(field.to_string(), Value::String(name.to_string()));
+        }
+        value
+    };
+
+    match data {
+        DataResult::Ok(value) => DataResult::Ok(add_field(value)),
+        DataResult::Err((value, e)) => DataResult::Err((add_field(value), e)),
+    }
+}
+
+#[async_trait]
+impl Connector for Nats {
+    /// See [`Connector::set_document`] for more details.
+    fn set_document(&mut self, document: Box<dyn Document>) -> Result<()> {
+        self.document = Some(document.clone());
+
+        Ok(())
+    }
+    /// See [`Connector::document`] for more details.
+    fn document(&self) -> Result<&dyn Document> {
+        self.document.as_deref().ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                "The document has not been set in the connector",
+            )
+        })
+    }
+    /// See [`Connector::set_parameters`] for more details.
+    fn set_parameters(&mut self, parameters: Value) {
+        *self.parameters = parameters;
+    }
+    /// See [`Connector::is_variable`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::nats::Nats;
+    /// use chewdata::connector::Connector;
+    ///
+    /// let mut connector = Nats::default();
+    /// connector.subject = "orders.created".to_string();
+    /// assert_eq!(false, connector.is_variable());
+    /// connector.subject = "orders.{{ status }}".to_string();
+    /// assert_eq!(true, connector.is_variable());
+    /// ```
+    fn is_variable(&self) -> bool {
+        self.subject.has_mustache()
+    }
+    /// See [`Connector::is_resource_will_change`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::nats::Nats;
+    /// use chewdata::connector::Connector;
+    /// use serde_json::Value;
+    ///
+    /// let mut connector = Nats::default();
+    /// let params = serde_json::from_str(r#"{"status":"created"}"#).unwrap();
+    /// connector.subject = "orders.created".to_string();
+    /// assert_eq!(false, connector.is_resource_will_change(Value::Null).unwrap());
+    /// connector.subject = "orders.{{ status }}".to_string();
+    /// assert_eq!(true, connector.is_resource_will_change(params).unwrap());
+    /// ```
+    fn is_resource_will_change(&self, new_parameters: Value) -> Result<bool> {
+        if !self.is_variable() {
+            trace!("Stay link to the same resource");
+            return Ok(false);
+        }
+
+        let mut metadata_kv = Map::default();
+        metadata_kv.insert("metadata".to_string(), self.metadata().into());
+        let metadata = Value::Object(metadata_kv);
+
+        let mut new_parameters = new_parameters;
+        new_parameters.merge(&metadata);
+        let mut old_parameters = *self.parameters.clone();
+        old_parameters.merge(&metadata);
+
+        let mut previous_subject = self.subject.clone();
+        previous_subject.replace_mustache(old_parameters);
+
+        let mut new_subject = self.subject.clone();
+        new_subject.replace_mustache(new_parameters);
+
+        if previous_subject == new_subject {
+            trace!(subject = previous_subject, "Subject didn't change");
+            return Ok(false);
+        }
+
+        info!(
+            previous_subject = previous_subject,
+            new_subject = new_subject,
+            "Subject will change"
+        );
+
+        Ok(true)
+    }
+    /// See [`Connector::path`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::nats::Nats;
+    /// use chewdata::connector::Connector;
+    ///
+    /// let mut connector = Nats::default();
+    /// connector.subject = "orders.{{ status }}".to_string();
+    /// connector.set_parameters(serde_json::from_str(r#"{"status":"created"}"#).unwrap());
+    /// assert_eq!("orders.created", connector.path());
+    /// ```
+    fn path(&self) -> String {
+        if !self.is_variable() {
+            return self.subject.clone();
+        }
+
+        let mut params = *self.parameters.clone();
+        params.merge(&serde_json::json!({
+            "metadata": self.metadata()
+        }));
+
+        let mut subject = self.subject.clone();
+        subject.replace_mustache(params);
+        subject
+    }
+    /// See [`Connector::metadata`] for more details.
+    fn metadata(&self) -> Metadata {
+        match &self.document {
+            Some(document) => self.metadata.clone().merge(&document.metadata()),
+            None => self.metadata.clone(),
+        }
+    }
+    /// See [`Connector::len`] for more details.
+    async fn len(&self) -> Result<usize> {
+        Ok(self.dataset.as_ref().map_or(0, |dataset| dataset.len()))
+    }
+    /// See [`Connector::acknowledgement`] for more details.
+    fn acknowledgement(&self) -> Option<Sender<DataResult>> {
+        self.acknowledgement.clone()
+    }
+    /// See [`Connector::fetch`] for more details.
+    #[instrument(name = "nats::fetch")]
+    async fn fetch(&mut self) -> Result<Option<DataStream>> {
+        if let Some(dataset) = self.dataset.take() {
+            return Ok(Some(Box::pin(stream! {
+                for data in dataset {
+                    yield data;
+                }
+            })));
+        }
+
+        match (&self.bucket, &self.stream) {
+            (Some(bucket), _) => Ok(Some(self.entries(bucket).await?)),
+            // The messages of the stream are read by the paginator.
+            (None, Some(_)) => Ok(None),
+            (None, None) => Ok(Some(self.subscribe().await?)),
+        }
+    }
+    /// See [`Connector::send`] for more details.
+    ///
+    /// Publish each record in its own message.
+    #[instrument(name = "nats::send", skip(dataset))]
+    async fn send(&mut self, dataset: &DataSet) -> Result<Option<DataStream>> {
+        if self.bucket.is_some() {
+            return Err(Error::new(
+                ErrorKind::Unsupported,
+                "Can't write in a key-value bucket with the nats connector",
+            ));
+        }
+
+        let document = self.document()?;
+        let subject = self.path();
+
+        let mut messages = Vec::default();
+        for data in dataset {
+            let mut value = data.to_value();
+            if let Value::Object(map) = &mut value {
+                map.remove(FIELD_SUBJECT);
+                map.remove(FIELD_KEY);
+            }
+            messages.push(Bytes::from(document.write(&vec![DataResult::Ok(value)])?));
+        }
+
+        if messages.is_empty() {
+            return Ok(None);
+        }
+
+        let client = self.client().await?;
+        let count = messages.len();
+
+        Compat::new(async {
+            match &self.stream {
+                Some(_) => {
+                    let context = jetstream::new(client);
+                    let mut acks = Vec::default();
+                    for payload in messages {
+                        acks.push(
+                            context
+                                .publish(subject.clone(), payload)
+                                .await
+                                .map_err(|e| Error::new(ErrorKind::Interrupted, e))?,
+                        );
+                    }
+                    for ack in acks {
+                        ack.await
+                            .map_err(|e| Error::new(ErrorKind::Interrupted, e))?;
+                    }
+                }
+                None => {
+                    for payload in messages {
+                        client
+                            .publish(subject.clone(), payload)
+                            .await
+                            .map_err(|e| Error::new(ErrorKind::Interrupted, e))?;
+                    }
+                    client
+                        .flush()
+                        .await
+                        .map_err(|e| Error::new(ErrorKind::Interrupted, e))?;
+                }
+            };
+
+            Ok::<(), Error>(())
+        })
+        .await?;
+
+        info!(subject, messages = count, "Messages published with success");
+
+        Ok(None)
+    }
+    /// See [`Connector::paginate`] for more details.
+    ///
+    /// With a stream, return a new connector for each message consumed.
+    #[instrument(name = "nats::paginate")]
+    async fn paginate(
+        &self,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<Box<dyn Connector>>> + Send>>> {
+        match (&self.bucket, &self.stream) {
+            (None, Some(stream)) => self.consume(stream).await,
+            _ => {
+                let paginator = Once {};
+                paginator.paginate(self).await
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::json::Json;
+    use crate::step::StepType;
+    use macro_rules_attribute::apply;
+    use smol_macros::test;
+
+    fn connector(subject: &str) -> Nats {
+        let mut connector = Nats::default();
+        if let Ok(endpoint) = std::env::var("NATS_ENDPOINT") {
+            connector.endpoint = endpoint;
+        }
+        connector.subject = subject.to_string();
+        connector.timeout = Some(5);
+        connector.set_document(Box::new(Json::default())).unwrap();
+        connector
+    }
+
+    #[test]
+    fn ack_kind_with_missing_results() {
+        assert!(matches!(ack_kind(2, 2, 0, 1, 5), AckKind::Ack));
+        assert!(matches!(ack_kind(2, 2, 1, 1, 5), AckKind::Nak(Some(delay)) if delay == NAK_DELAY));
+        assert!(matches!(ack_kind(1, 2, 0, 1, 5), AckKind::Nak(Some(_))));
+        assert!(matches!(ack_kind(0, 2, 0, 1, 5), AckKind::Nak(Some(_))));
+    }
+    #[test]
+    fn ack_kind_after_deliveries() {
+        assert!(
+            matches!(ack_kind(2, 2, 1, 3, 5), AckKind::Nak(Some(delay)) if delay == NAK_DELAY * 3)
+        );
+        assert!(matches!(ack_kind(2, 2, 1, 5, 5), AckKind::Term));
+        assert!(matches!(ack_kind(2, 2, 0, 5, 5), AckKind::Ack));
+        assert!(
+            matches!(ack_kind(2, 2, 1, 100, -1), AckKind::Nak(Some(delay)) if delay == NAK_DELAY * NAK_DELAY_MAX)
+        );
+    }
+    #[test]
+    fn with_field_in_object() {
+        let data = with_field(
+            DataResult::Ok(serde_json::json!({"field":"value"})),
+            FIELD_SUBJECT,
+            "orders.created",
+        );
+        assert_eq!(
+            serde_json::json!({"field":"value","_subject":"orders.created"}),
+            data.to_value()
+        );
+    }
+    #[apply(test!)]
+    async fn send_and_fetch() {
+        let subject = format!("chewdata.{}.send_and_fetch", uuid::Uuid::new_v4().simple());
+        let mut reader = connector(subject.as_str());
+        reader.message_limit = Some(2);
+        let datastream = reader.fetch().await.unwrap().unwrap();
+        let reading = smol::spawn(datastream.collect::<Vec<DataResult>>());
+
+        let mut writer = connector(subject.as_str());
+        writer
+            .send(&vec![
+                DataResult::Ok(serde_json::json!({"field":"value1"})),
+                DataResult::Ok(serde_json::json!({"field":"value2","_subject":"other"})),
+            ])
+            .await
+            .unwrap();
+
+        let dataset = reading.await;
+        assert_eq!(
+            vec![
+                serde_json::json!({"field":"value1","_subject":subject}),
+                serde_json::json!({"field":"value2","_subject":subject}),
+            ],
+            dataset
+                .iter()
+                .map(DataResult::to_value)
+                .collect::<Vec<Value>>()
+        );
+    }
+    #[apply(test!)]
+    async fn exec_with_jetstream() {
+        let name = format!("chewdata_{}", uuid::Uuid::new_v4().simple());
+        let subject = format!("{}.orders", name);
+        let context = jetstream::new(connector("").client().await.unwrap());
+        Compat::new(context.get_or_create_stream(jetstream::stream::Config {
+            name: name.clone(),
+            subjects: vec![format!("{}.>", name)],
+            ..Default::default()
+        }))
+        .await
+        .unwrap();
+
+        let mut writer = connector(subject.as_str());
+        writer.stream = Some(name.clone());
+        writer
+            .send(&vec![
+                DataResult::Ok(serde_json::json!({"field":"value1"})),
+                DataResult::Ok(serde_json::json!({"field":"value2"})),
+            ])
+            .await
+            .unwrap();
+
+        let config = format!(
+            r#"[
+                {{
+                    "type": "r",
+                    "connector": {{
+                        "type": "nats",
+                        "endpoint": "{}",
+                        "stream": "{}",
+                        "subject": "{}",
+                        "message_limit": 2,
+                        "timeout": 5
+                    }}
+                }},
+                {{
+                    "type": "w",
+                    "connector": {{
+                        "type": "in_memory"
+                    }},
+                    "record_limit": 1
+                }}
+            ]"#,
+            connector("").endpoint,
+            name,
+            subject
+        );
+        let steps: Vec<StepType> = serde_json::from_str(config.as_str()).unwrap();
+        crate::exec(steps, None, None).await.unwrap();
+
+        // Wait the acknowledgements.
+        smol::Timer::after(Duration::from_millis(500)).await;
+
+        let info = Compat::new(async {
+            let stream = context.get_stream(name.as_str()).await.unwrap();
+            let consumer: jetstream::consumer::Consumer<pull::Config> =
+                stream.get_consumer(DEFAULT_DURABLE).await.unwrap();
+            consumer.get_info().await.unwrap()
+        })
+        .await;
+        assert_eq!(0, info.num_pending, "All the messages must be delivered");
+        assert_eq!(
+            0, info.num_ack_pending,
+            "All the messages must be acknowledged"
+        );
+    }
+    #[apply(test!)]
+    async fn fetch_bucket() {
+        let bucket = format!("chewdata_{}", uuid::Uuid::new_v4().simple());
+        let context = jetstream::new(connector("").client().await.unwrap());
+        Compat::new(async {
+            let store = context
+                .create_key_value(jetstream::kv::Config {
+                    bucket: bucket.clone(),
+                    ..Default::default()
+                })
+                .await
+                .unwrap();
+            store
+                .put("key1", Bytes::from(r#"{"field":"value1"}"#))
+                .await
+                .unwrap();
+        })
+        .await;
+
+        let mut reader = connector("");
+        reader.bucket = Some(bucket);
+        let dataset: Vec<DataResult> = reader.fetch().await.unwrap().unwrap().collect().await;
+        assert_eq!(
+            vec![serde_json::json!({"field":"value1","_key":"key1"})],
+            dataset
+                .iter()
+                .map(DataResult::to_value)
+                .collect::<Vec<Value>>()
+        );
+    }
+    #[apply(test!)]
+    async fn paginate() {
+        let connector = connector("orders.created");
+        let mut paging = connector.paginate().await.unwrap();
+        assert!(paging.next().await.transpose().unwrap().is_some());
+        assert!(paging.next().await.transpose().unwrap().is_none());
+    }
+}
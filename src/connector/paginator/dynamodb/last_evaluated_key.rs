@@ -0,0 +1,120 @@
+//! Paginate with the key of the last item evaluated by the previous call. The paginator cannot be parallelized.
+//!
+//! A page can be empty if the filter excludes all its items, the pagination stops when there is no more key to evaluate.
+//!
+//! ### Configuration
+//!
+//! | key                 | alias | Description                                                 | Default Value        | Possible Values                  |
+//! | ------------------- | ----- | ----------------------------------------------------------- | -------------------- | -------------------------------- |
+//! | type                | -     | Required in order to use this paginator                     | `last_evaluated_key` | `last_evaluated_key` / `cursor`  |
+//! | limit               | -     | Maximum number of items to evaluate for each call           | `100`                | Unsigned number                  |
+//! | exclusive_start_key | -     | Force to start the pagination after this key                | `null`               | Object                           |
+//!
+//! ### Examples
+//!
+//! ```json
+//! [
+//!     {
+//!         "type": "read",
+//!         "connector":{
+//!             "type": "dynamodb",
+//!             "table": "orders",
+//!             "paginator": {
+//!                 "type": "last_evaluated_key",
+//!                 "limit": 100,
+//!                 "exclusive_start_key": {"id": "order-100"}
+//!             }
+//!         }
+//!     }
+//! ]
+//! ```
+use crate::{
+    connector::{dynamodb::Dynamodb, Connector},
+    ConnectorStream,
+};
+use async_stream::stream;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::io::Result;
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct LastEvaluatedKey {
+    pub limit: Option<i32>,
+    pub exclusive_start_key: Option<Value>,
+}
+
+impl Default for LastEvaluatedKey {
+    fn default() -> Self {
+        LastEvaluatedKey {
+            limit: Some(100),
+            exclusive_start_key: None,
+        }
+    }
+}
+
+impl LastEvaluatedKey {
+    /// Paginate through the table.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use chewdata::connector::{dynamodb::Dynamodb, Connector};
+    /// use chewdata::connector::paginator::dynamodb::last_evaluated_key::LastEvaluatedKey;
+    /// use smol::prelude::*;
+    /// use std::io;
+    ///
+    /// use macro_rules_attribute::apply;
+    /// use smol_macros::main;
+    ///
+    /// #[apply(main!)]
+    /// async fn main() -> io::Result<()> {
+    ///     let mut connector = Dynamodb::default();
+    ///     connector.endpoint = Some("http://localhost:8000".into());
+    ///     connector.table = "orders".into();
+    ///
+    ///     let paginator = LastEvaluatedKey {
+    ///         limit: Some(1),
+    ///         ..Default::default()
+    ///     };
+    ///
+    ///     let mut paging = paginator.paginate(&connector).await?;
+    ///     assert!(paging.next().await.transpose()?.is_some(), "Can't get the first reader.");
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[instrument(name = "last_evaluated_key::paginate")]
+    pub async fn paginate(&self, connector: &Dynamodb) -> Result<ConnectorStream> {
+        let connector = connector.clone();
+        let limit = self.limit;
+        let mut exclusive_start_key = self.exclusive_start_key.clone();
+
+        Ok(Box::pin(stream! {
+            loop {
+                let (items, last_evaluated_key) = match connector.page(limit, exclusive_start_key.as_ref()).await {
+                    Ok(page) => page,
+                    Err(e) => {
+                        yield Err(e);
+                        break;
+                    }
+                };
+
+                exclusive_start_key = last_evaluated_key;
+
+                if !items.is_empty() {
+                    let mut new_connector = connector.clone();
+                    new_connector.items = Some(items);
+
+                    trace!(connector = format!("{:?}", new_connector).as_str(), "Yield a new connector");
+                    yield Ok(Box::new(new_connector) as Box<dyn Connector>);
+                }
+
+                if exclusive_start_key.is_none() {
+                    break;
+                }
+            }
+            trace!("Stop yielding new connector");
+        }))
+    }
+}
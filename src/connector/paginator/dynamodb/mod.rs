@@ -0,0 +1,34 @@
+pub mod last_evaluated_key;
+
+use futures::Stream;
+use last_evaluated_key::LastEvaluatedKey;
+use serde::{Deserialize, Serialize};
+use std::io::Result;
+use std::pin::Pin;
+
+use crate::connector::{dynamodb::Dynamodb, Connector};
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(tag = "type")]
+pub enum PaginatorType {
+    #[serde(rename = "last_evaluated_key")]
+    #[serde(alias = "cursor")]
+    LastEvaluatedKey(LastEvaluatedKey),
+}
+
+impl Default for PaginatorType {
+    fn default() -> Self {
+        PaginatorType::LastEvaluatedKey(LastEvaluatedKey::default())
+    }
+}
+
+impl PaginatorType {
+    pub async fn paginate(
+        &self,
+        connector: &Dynamodb,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<Box<dyn Connector>>> + Send>>> {
+        match self {
+            PaginatorType::LastEvaluatedKey(paginator) => paginator.paginate(connector).await,
+        }
+    }
+}
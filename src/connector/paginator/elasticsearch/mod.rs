@@ -0,0 +1,34 @@
+pub mod search_after;
+
+use futures::Stream;
+use search_after::SearchAfter;
+use serde::{Deserialize, Serialize};
+use std::io::Result;
+use std::pin::Pin;
+
+use crate::connector::{elasticsearch::Elasticsearch, Connector};
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(tag = "type")]
+pub enum PaginatorType {
+    #[serde(rename = "search_after")]
+    #[serde(alias = "pit")]
+    SearchAfter(SearchAfter),
+}
+
+impl Default for PaginatorType {
+    fn default() -> Self {
+        PaginatorType::SearchAfter(SearchAfter::default())
+    }
+}
+
+impl PaginatorType {
+    pub async fn paginate(
+        &self,
+        connector: &Elasticsearch,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<Box<dyn Connector>>> + Send>>> {
+        match self {
+            PaginatorType::SearchAfter(paginator) => paginator.paginate(connector).await,
+        }
+    }
+}
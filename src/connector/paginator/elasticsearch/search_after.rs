@@ -0,0 +1,218 @@
+//! Paginate with a point in time and the sort values of the last hit. The paginator cannot be parallelized.
+//!
+//! The point in time keeps the same view of the index during the pagination and is closed at the end.
+//!
+//! ### Configuration
+//!
+//! | key          | alias | Description                                                     | Default Value             | Possible Values |
+//! | ------------ | ----- | --------------------------------------------------------------- | ------------------------- | --------------- |
+//! | type         | -     | Required in order to use this paginator                         | `search_after`            | `search_after` / `pit` |
+//! | limit        | -     | Limit of records to retrieve for each call                      | `100`                     | Unsigned number |
+//! | keep_alive   | -     | Time to keep the point in time alive between two calls          | `1m`                      | String          |
+//! | sort         | -     | Sort of the hits. Must be unique in order to paginate           | `[{"_shard_doc":"asc"}]`  | Json array      |
+//! | search_after | -     | Force to start the pagination after these sort values           | `null`                    | Json array      |
+//!
+//! ### Examples
+//!
+//! ```json
+//! [
+//!     {
+//!         "type": "read",
+//!         "connector":{
+//!             "type": "elasticsearch",
+//!             "endpoint": "http://localhost:9200",
+//!             "index": "my_index",
+//!             "paginator": {
+//!                 "type": "search_after",
+//!                 "limit": 100,
+//!                 "keep_alive": "1m",
+//!                 "sort": [{"number": "asc"}, {"_shard_doc": "asc"}]
+//!             }
+//!         }
+//!     }
+//! ]
+//! ```
+use crate::{
+    connector::{elasticsearch::Elasticsearch, Connector},
+    ConnectorStream,
+};
+use async_stream::stream;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::io::Result;
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct SearchAfter {
+    pub limit: usize,
+    pub keep_alive: String,
+    pub sort: Value,
+    pub search_after: Option<Value>,
+}
+
+impl Default for SearchAfter {
+    fn default() -> Self {
+        SearchAfter {
+            limit: 100,
+            keep_alive: "1m".to_string(),
+            sort: serde_json::json!([{ "_shard_doc": "asc" }]),
+            search_after: None,
+        }
+    }
+}
+
+impl SearchAfter {
+    /// Paginate through the index.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::{elasticsearch::Elasticsearch, Connector};
+    /// use chewdata::connector::paginator::elasticsearch::search_after::SearchAfter;
+    /// use chewdata::DataResult;
+    /// use smol::prelude::*;
+    /// use std::io;
+    ///
+    /// use macro_rules_attribute::apply;
+    /// use smol_macros::main;
+    ///
+    /// #[apply(main!)]
+    /// async fn main() -> io::Result<()> {
+    ///     let mut connector = Elasticsearch::default();
+    ///     connector.endpoint = "http://localhost:9200".into();
+    ///     connector.index = "paginate".into();
+    ///     connector.refresh = Some("true".into());
+    ///     let dataset = vec![
+    ///         DataResult::Ok(serde_json::from_str(r#"{"number":1}"#)?),
+    ///         DataResult::Ok(serde_json::from_str(r#"{"number":2}"#)?),
+    ///     ];
+    ///     connector.send(&dataset).await?;
+    ///
+    ///     let paginator = SearchAfter {
+    ///         limit: 1,
+    ///         ..Default::default()
+    ///     };
+    ///
+    ///     let mut paging = paginator.paginate(&connector).await?;
+    ///     assert!(paging.next().await.transpose()?.is_some(), "Can't get the first reader.");
+    ///     assert!(paging.next().await.transpose()?.is_some(), "Can't get the second reader.");
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[instrument(name = "search_after::paginate")]
+    pub async fn paginate(&self, connector: &Elasticsearch) -> Result<ConnectorStream> {
+        let connector = connector.clone();
+        let limit = self.limit;
+        let keep_alive = self.keep_alive.clone();
+        let sort = self.sort.clone();
+        let mut search_after = self.search_after.clone();
+        let mut pit_id = connector.open_pit(&keep_alive).await?;
+
+        Ok(Box::pin(stream! {
+            loop {
+                let mut body = serde_json::json!({
+                    "query": connector.query(),
+                    "size": limit,
+                    "sort": sort.clone(),
+                    "pit": { "id": pit_id.clone(), "keep_alive": keep_alive.clone() },
+                });
+
+                if let (Some(search_after), Value::Object(map)) = (&search_after, &mut body) {
+                    map.insert("search_after".to_string(), search_after.clone());
+                }
+
+                let response = match connector.search(&body).await {
+                    Ok(response) => response,
+                    Err(e) => {
+                        yield Err(e);
+                        break;
+                    }
+                };
+
+                // The point in time id can change between two calls.
+                if let Some(Value::String(id)) = response.get("pit_id") {
+                    pit_id = id.clone();
+                }
+
+                let hits = match response.pointer("/hits/hits") {
+                    Some(Value::Array(hits)) => hits.clone(),
+                    _ => Vec::default(),
+                };
+
+                if hits.is_empty() {
+                    break;
+                }
+
+                search_after = hits.last().and_then(|hit| hit.get("sort")).cloned();
+                let has_next = limit <= hits.len() && search_after.is_some();
+
+                let mut new_connector = connector.clone();
+                new_connector.hits = Some(hits);
+
+                trace!(connector = format!("{:?}", new_connector).as_str(), "Yield a new connector");
+                yield Ok(Box::new(new_connector) as Box<dyn Connector>);
+
+                if !has_next {
+                    break;
+                }
+            }
+
+            if let Err(e) = connector.close_pit(&pit_id).await {
+                warn!(error = e.to_string(), "Can't close the point in time");
+            }
+            trace!("Stop yielding new connector");
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DataResult;
+    use macro_rules_attribute::apply;
+    use smol::stream::StreamExt;
+    use smol_macros::test;
+
+    #[apply(test!)]
+    async fn paginate() {
+        let mut connector = Elasticsearch::default();
+        connector.endpoint = "http://localhost:9200".into();
+        connector.index = "paginate".into();
+        connector.refresh = Some("true".into());
+        connector.erase().await.unwrap();
+        let dataset = vec![
+            DataResult::Ok(serde_json::from_str(r#"{"number":1}"#).unwrap()),
+            DataResult::Ok(serde_json::from_str(r#"{"number":2}"#).unwrap()),
+            DataResult::Ok(serde_json::from_str(r#"{"number":3}"#).unwrap()),
+        ];
+        connector.send(&dataset).await.unwrap();
+
+        let paginator = SearchAfter {
+            limit: 2,
+            sort: serde_json::json!([{ "number": "asc" }]),
+            ..Default::default()
+        };
+
+        let mut paging = paginator.paginate(&connector).await.unwrap();
+
+        let mut connector = paging.next().await.transpose().unwrap().unwrap();
+        let datastream = connector.fetch().await.unwrap().unwrap();
+        assert_eq!(2, datastream.count().await);
+
+        let mut connector = paging.next().await.transpose().unwrap().unwrap();
+        let mut datastream = connector.fetch().await.unwrap().unwrap();
+        assert_eq!(
+            Some(3),
+            datastream
+                .next()
+                .await
+                .unwrap()
+                .to_value()
+                .get("number")
+                .and_then(Value::as_u64)
+        );
+
+        assert!(paging.next().await.is_none());
+    }
+}
@@ -0,0 +1,150 @@
+use crate::connector::ftp::Ftp;
+use crate::connector::Connector;
+use crate::ConnectorStream;
+use async_stream::stream;
+use serde::{Deserialize, Serialize};
+use std::io::Result;
+use std::io::{Error, ErrorKind};
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct Wildcard {
+    pub paths: Vec<String>,
+}
+
+impl Wildcard {
+    /// Create a new Wildcard paginator and load in memory all remote file paths that match the connector's path.
+    /// The wildcard is only supported in the file name.
+    pub async fn new(connector: &Ftp) -> Result<Self> {
+        if connector.path().is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The field 'path' for a ftp connector can't be an empty string".to_string(),
+            ));
+        }
+
+        if !connector.path().contains('*') {
+            return Ok(Wildcard {
+                paths: vec![connector.path()],
+            });
+        }
+
+        let paths = connector.list().await?;
+
+        if paths.is_empty() {
+            return Err(Error::new(
+                ErrorKind::NotFound,
+                format!(
+                    "No files found with this path pattern '{}'",
+                    connector.path()
+                ),
+            ));
+        }
+
+        Ok(Wildcard { paths })
+    }
+    /// Paginate through the connector.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::ftp::Ftp;
+    /// use chewdata::connector::Connector;
+    /// use chewdata::document::json::Json;
+    /// use chewdata::DataResult;
+    /// use smol::prelude::*;
+    /// use std::io;
+    /// use chewdata::connector::paginator::ftp::wildcard::Wildcard;
+    ///
+    /// use macro_rules_attribute::apply;
+    /// use smol_macros::main;
+    ///
+    /// #[apply(main!)]
+    /// async fn main() -> io::Result<()> {
+    ///     let mut connector = Ftp::default();
+    ///     connector.endpoint = "sftp://localhost:2222".to_string();
+    ///     connector.username = Some("chewdata".to_string());
+    ///     connector.password = Some("chewdata".to_string());
+    ///     connector.set_document(Box::new(Json::default()))?;
+    ///     for path in ["/upload/paginate_1.json", "/upload/paginate_2.json"] {
+    ///         connector.path = path.to_string();
+    ///         connector.erase().await?;
+    ///         connector.send(&vec![DataResult::Ok(serde_json::from_str(r#"{"column1":"value1"}"#)?)]).await?;
+    ///     }
+    ///     connector.path = "/upload/paginate_*.json".to_string();
+    ///
+    ///     let paginator = Wildcard::new(&connector).await?;
+    ///
+    ///     let mut paging = paginator.paginate(&connector).await?;
+    ///     assert!(paging.next().await.transpose()?.is_some(), "Can't get the first reader.");
+    ///     assert!(paging.next().await.transpose()?.is_some(), "Can't get the second reader.");
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[instrument(name = "wildcard::paginate")]
+    pub async fn paginate(&self, connector: &Ftp) -> Result<ConnectorStream> {
+        let connector = connector.clone();
+        let mut paths = self.paths.clone().into_iter();
+
+        Ok(Box::pin(stream! {
+            for path in &mut paths {
+                let mut new_connector = connector.clone();
+                new_connector.path = path.clone();
+
+                trace!(connector = format!("{:?}", new_connector).as_str(), "Yield a new connector");
+                yield Ok(Box::new(new_connector) as Box<dyn Connector>);
+            }
+            trace!("Stop yielding new connector");
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use smol::stream::StreamExt;
+
+    use super::*;
+    use crate::document::json::Json;
+    use crate::DataResult;
+    use macro_rules_attribute::apply;
+    use smol_macros::test;
+
+    #[apply(test!)]
+    async fn paginate() {
+        let mut connector = Ftp::default();
+        connector.endpoint = "ftp://localhost:21".to_string();
+        connector.username = Some("chewdata".to_string());
+        connector.password = Some("chewdata".to_string());
+        connector.set_document(Box::new(Json::default())).unwrap();
+
+        let dataset = vec![DataResult::Ok(
+            serde_json::from_str(r#"{"column1":"value1"}"#).unwrap(),
+        )];
+        for path in ["test_paginate_1.json", "test_paginate_2.json"] {
+            connector.path = path.to_string();
+            connector.erase().await.unwrap();
+            connector.send(&dataset).await.unwrap();
+        }
+        connector.path = "test_paginate_*.json".to_string();
+
+        let paginator = Wildcard::new(&connector).await.unwrap();
+        let mut paging = paginator.paginate(&connector).await.unwrap();
+
+        let connector = paging.next().await.transpose().unwrap().unwrap();
+        assert!(0 < connector.len().await.unwrap());
+        let connector = paging.next().await.transpose().unwrap().unwrap();
+        assert!(0 < connector.len().await.unwrap());
+        assert!(paging.next().await.is_none());
+    }
+    #[apply(test!)]
+    async fn new_without_files() {
+        let mut connector = Ftp::default();
+        connector.endpoint = "sftp://localhost:2222".to_string();
+        connector.username = Some("chewdata".to_string());
+        connector.password = Some("chewdata".to_string());
+        connector.path = "/upload/not_found_*.json".to_string();
+
+        let error = Wildcard::new(&connector).await.err().unwrap();
+        assert_eq!(ErrorKind::NotFound, error.kind());
+    }
+}
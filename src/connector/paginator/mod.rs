@@ -1,5 +1,11 @@
 #[cfg(feature = "curl")]
 pub mod curl;
+#[cfg(feature = "dynamodb")]
+pub mod dynamodb;
+#[cfg(feature = "elasticsearch")]
+pub mod elasticsearch;
+#[cfg(feature = "ftp")]
+pub mod ftp;
 pub mod local;
 #[cfg(feature = "mongodb")]
 pub mod mongodb;
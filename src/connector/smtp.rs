@@ -0,0 +1,629 @@
+//! Send the data by email through an SMTP server.
+//!
+//! When the connector writes, it sends an email with the dataset written by the [`crate::document`] in attachment.
+//! The `from`, the recipients, the `subject`, the `body` and the `attachment` name are [Tera](https://keats.github.io/tera/docs/#templates) templates rendered with the parameters of the connector,
+//! the `metadata` of the document and the `dataset` sent.
+//! A new email is sent when the recipients or the subject rendered with the parameters change.
+//!
+//! The connector can't read data.
+//!
+//! ### Configuration
+//!
+//! | key        | alias  | Description                                                                  | Default Value                       | Possible Values                                                     |
+//! | ---------- | ------ | ---------------------------------------------------------------------------- | ----------------------------------- | ------------------------------------------------------------------- |
+//! | type       | -      | Required in order to use this connector                                      | `smtp`                              | `smtp`                                                              |
+//! | metadata   | meta   | Override metadata information                                                | `null`                              | [`crate::Metadata`]                                                 |
+//! | endpoint   | -      | The address of the server. Use `smtps://` or `?tls=required` to use TLS      | `smtp://localhost:25`               | String                                                              |
+//! | username   | user   | The username used to authenticate on the server                              | `null`                              | String                                                              |
+//! | password   | pass   | The password used to authenticate on the server                              | `null`                              | String                                                              |
+//! | from       | -      | The sender of the email                                                      | `null`                              | String                                                              |
+//! | to         | -      | The recipients of the email. Each value can contain a list separated by `,`  | `[]`                                | List of string                                                      |
+//! | cc         | -      | The recipients in copy of the email                                          | `[]`                                | List of string                                                      |
+//! | bcc        | -      | The recipients in blind copy of the email                                    | `[]`                                | List of string                                                      |
+//! | subject    | -      | The subject of the email                                                     | `null`                              | String                                                              |
+//! | body       | -      | The content of the email                                                     | `null`                              | String                                                              |
+//! | is_html    | html   | Send the body as html instead of plain text                                  | `false`                             | `true` / `false`                                                    |
+//! | attachment | -      | The file name of the document attached to the email                          | `data.{{ metadata.mime_subtype }}`  | String                                                              |
+//! | timeout    | -      | Time in second to wait the response of the server                            | `null`                              | Unsigned number                                                     |
+//! | parameters | params | Variables used to render the templates                                       | `null`                              | Object                                                              |
+//!
+//! ### Examples
+//!
+//! ```json
+//! [
+//!     {
+//!         "type": "w",
+//!         "document": {
+//!             "type": "csv"
+//!         },
+//!         "connector": {
+//!             "type": "smtp",
+//!             "endpoint": "smtps://smtp.example.com:465",
+//!             "username": "{{ SMTP_USERNAME }}",
+//!             "password": "{{ SMTP_PASSWORD }}",
+//!             "from": "Reports <reports@example.com>",
+//!             "to": ["{{ input.email }}"],
+//!             "subject": "Report of {{ now() | date(format='%Y-%m-%d') }}",
+//!             "body": "Find in attachment the {{ dataset | length }} lines of the report.",
+//!             "attachment": "report_{{ now() | date(format='%Y%m%d') }}.csv"
+//!         }
+//!     }
+//! ]
+//! ```
+use super::Connector;
+use crate::connector::paginator::once::Once;
+use crate::document::Document;
+use crate::helper::string::DisplayOnlyForDebugging;
+use crate::updater::tera::engine;
+use crate::{DataSet, DataStream, Metadata};
+use async_trait::async_trait;
+use futures::Stream;
+use json_value_merge::Merge;
+use lettre::message::header::ContentType;
+use lettre::message::{Attachment, Mailbox, MultiPart, SinglePart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncStd1Executor, AsyncTransport, Message};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::error::Error as StdError;
+use std::pin::Pin;
+use std::time::Duration;
+use std::{
+    fmt,
+    io::{Error, ErrorKind, Result},
+};
+
+const DEFAULT_ENDPOINT: &str = "smtp://localhost:25";
+const DEFAULT_ATTACHMENT: &str = "data.{{ metadata.mime_subtype }}";
+const DEFAULT_CONTENT_TYPE: &str = "application/octet-stream";
+
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct Smtp {
+    #[serde(skip)]
+    document: Option<Box<dyn Document>>,
+    #[serde(rename = "metadata")]
+    #[serde(alias = "meta")]
+    pub metadata: Metadata,
+    pub endpoint: String,
+    #[serde(alias = "user")]
+    pub username: Option<String>,
+    #[serde(alias = "pass")]
+    pub password: Option<String>,
+    pub from: String,
+    pub to: Vec<String>,
+    pub cc: Vec<String>,
+    pub bcc: Vec<String>,
+    pub subject: String,
+    pub body: String,
+    #[serde(alias = "html")]
+    pub is_html: bool,
+    pub attachment: String,
+    pub timeout: Option<u64>,
+    #[serde(alias = "params")]
+    pub parameters: Box<Value>,
+}
+
+impl fmt::Debug for Smtp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Smtp")
+            .field("document", &self.document.display_only_for_debugging())
+            .field("metadata", &self.metadata.display_only_for_debugging())
+            .field("endpoint", &self.endpoint)
+            .field("username", &self.username)
+            .field("from", &self.from)
+            .field("to", &self.to)
+            .field("cc", &self.cc)
+            .field("bcc", &self.bcc)
+            .field("subject", &self.subject)
+            .field("is_html", &self.is_html)
+            .field("attachment", &self.attachment)
+            .field("timeout", &self.timeout)
+            .field("parameters", &self.parameters.display_only_for_debugging())
+            .finish()
+    }
+}
+
+impl Default for Smtp {
+    fn default() -> Self {
+        Smtp {
+            document: None,
+            metadata: Metadata::default(),
+            endpoint: DEFAULT_ENDPOINT.to_string(),
+            username: None,
+            password: None,
+            from: String::default(),
+            to: Vec::default(),
+            cc: Vec::default(),
+            bcc: Vec::default(),
+            subject: String::default(),
+            body: String::default(),
+            is_html: false,
+            attachment: DEFAULT_ATTACHMENT.to_string(),
+            timeout: None,
+            parameters: Box::<Value>::default(),
+        }
+    }
+}
+
+impl Smtp {
+    /// Build the email with the dataset written by the document in attachment.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::smtp::Smtp;
+    /// use chewdata::connector::Connector;
+    /// use chewdata::document::json::Json;
+    /// use chewdata::DataResult;
+    /// use serde_json::json;
+    ///
+    /// let mut connector = Smtp::default();
+    /// connector.set_document(Box::new(Json::default())).unwrap();
+    /// connector.from = "reports@example.com".to_string();
+    /// connector.to = vec!["{{ input.email }}".to_string()];
+    /// connector.subject = "{{ dataset | length }} line(s)".to_string();
+    /// connector.set_parameters(json!({"input":{"email":"john@example.com"}}));
+    ///
+    /// let dataset = vec![DataResult::Ok(json!({"field":"value"}))];
+    /// let email = String::from_utf8(connector.message(&dataset).unwrap().formatted()).unwrap();
+    /// assert!(email.contains("To: john@example.com"));
+    /// assert!(email.contains("Subject: 1 line(s)"));
+    /// assert!(email.contains("filename=\"data.json\""));
+    /// ```
+    pub fn message(&self, dataset: &DataSet) -> Result<Message> {
+        let document = self.document()?;
+        let mut content = document.header(dataset)?;
+        content.append(&mut document.write(dataset)?);
+        content.append(&mut document.footer(dataset)?);
+
+        let context = self.context(dataset);
+        let mut builder = Message::builder()
+            .from(mailbox(&render(&self.from, &context)?)?)
+            .subject(render(&self.subject, &context)?);
+
+        let to = recipients(&self.to, &context)?;
+        if to.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "At least one recipient is required in order to send an email",
+            ));
+        }
+        for mailbox in to {
+            builder = builder.to(mailbox);
+        }
+        for mailbox in recipients(&self.cc, &context)? {
+            builder = builder.cc(mailbox);
+        }
+        for mailbox in recipients(&self.bcc, &context)? {
+            builder = builder.bcc(mailbox);
+        }
+
+        let body = render(&self.body, &context)?;
+        let body = match self.is_html {
+            true => SinglePart::html(body),
+            false => SinglePart::plain(body),
+        };
+
+        let content_type = match self.metadata().content_type() {
+            content_type if content_type.is_empty() => DEFAULT_CONTENT_TYPE.to_string(),
+            content_type => content_type,
+        };
+        let attachment = Attachment::new(render(&self.attachment, &context)?).body(
+            content,
+            ContentType::parse(&content_type)
+                .map_err(|e| Error::new(ErrorKind::InvalidInput, e))?,
+        );
+
+        builder
+            .multipart(MultiPart::mixed().singlepart(body).singlepart(attachment))
+            .map_err(|e| Error::new(ErrorKind::InvalidInput, e))
+    }
+    fn transport(&self) -> Result<AsyncSmtpTransport<AsyncStd1Executor>> {
+        let mut builder = AsyncSmtpTransport::<AsyncStd1Executor>::from_url(&self.endpoint)
+            .map_err(|e| Error::new(ErrorKind::InvalidInput, e))?
+            .timeout(self.timeout.map(Duration::from_secs));
+
+        if let Some(username) = &self.username {
+            builder = builder.credentials(Credentials::new(
+                username.clone(),
+                self.password.clone().unwrap_or_default(),
+            ));
+        }
+
+        Ok(builder.build())
+    }
+    /// The values available in the templates.
+    fn context(&self, dataset: &DataSet) -> Value {
+        let mut context = match *self.parameters.clone() {
+            Value::Object(map) => Value::Object(map),
+            _ => Value::Object(Default::default()),
+        };
+        context.merge(&serde_json::json!({
+            "metadata": self.metadata(),
+            "dataset": dataset.iter().map(|data| data.to_value()).collect::<Vec<Value>>()
+        }));
+        context
+    }
+    /// Render the headers that identify an email.
+    fn headers(&self, parameters: Value) -> Result<Vec<String>> {
+        let mut context = match parameters {
+            Value::Object(map) => Value::Object(map),
+            _ => Value::Object(Default::default()),
+        };
+        context.merge(&serde_json::json!({
+            "metadata": self.metadata(),
+            "dataset": []
+        }));
+
+        let mut headers = vec![render(&self.subject, &context)?];
+        for template in self.to.iter().chain(&self.cc).chain(&self.bcc) {
+            headers.push(render(template, &context)?);
+        }
+
+        Ok(headers)
+    }
+}
+
+/// Test if the text is a template.
+fn is_template(text: &str) -> bool {
+    text.contains("{{") || text.contains("{%")
+}
+
+/// Render a template with the context.
+fn render(template: &str, context: &Value) -> Result<String> {
+    if !is_template(template) {
+        return Ok(template.to_string());
+    }
+
+    let context = tera::Context::from_value(context.clone())
+        .map_err(|e| Error::new(ErrorKind::InvalidInput, e))?;
+    let engine = engine();
+    let mut engine = engine.lock().map_err(|e| Error::other(e.to_string()))?;
+
+    engine.render_str(template, &context).map_err(|e| {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "Failed to render the template '{}'. {}",
+                template,
+                e.source()
+                    .map_or(e.to_string(), |source| source.to_string())
+                    .replace(" '__tera_one_off'", "")
+            ),
+        )
+    })
+}
+
+fn mailbox(address: &str) -> Result<Mailbox> {
+    address.trim().parse().map_err(|e| {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!("The address '{}' is not valid. {}", address, e),
+        )
+    })
+}
+
+/// Render the templates and split them into a list of mailboxes.
+fn recipients(templates: &[String], context: &Value) -> Result<Vec<Mailbox>> {
+    let mut mailboxes = Vec::default();
+    for template in templates {
+        for address in render(template, context)?.split(',') {
+            if !address.trim().is_empty() {
+                mailboxes.push(mailbox(address)?);
+            }
+        }
+    }
+
+    Ok(mailboxes)
+}
+
+#[async_trait]
+impl Connector for Smtp {
+    /// See [`Connector::set_document`] for more details.
+    fn set_document(&mut self, document: Box<dyn Document>) -> Result<()> {
+        self.document = Some(document.clone());
+
+        Ok(())
+    }
+    /// See [`Connector::document`] for more details.
+    fn document(&self) -> Result<&dyn Document> {
+        self.document.as_deref().ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                "The document has not been set in the connector",
+            )
+        })
+    }
+    /// See [`Connector::set_parameters`] for more details.
+    fn set_parameters(&mut self, parameters: Value) {
+        *self.parameters = parameters;
+    }
+    /// See [`Connector::is_variable`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::smtp::Smtp;
+    /// use chewdata::connector::Connector;
+    ///
+    /// let mut connector = Smtp::default();
+    /// connector.to = vec!["john@example.com".to_string()];
+    /// assert_eq!(false, connector.is_variable());
+    /// connector.to = vec!["{{ input.email }}".to_string()];
+    /// assert_eq!(true, connector.is_variable());
+    /// ```
+    fn is_variable(&self) -> bool {
+        is_template(&self.subject)
+            || self
+                .to
+                .iter()
+                .chain(&self.cc)
+                .chain(&self.bcc)
+                .any(|template| is_template(template))
+    }
+    /// See [`Connector::is_resource_will_change`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::smtp::Smtp;
+    /// use chewdata::connector::Connector;
+    /// use serde_json::json;
+    ///
+    /// let mut connector = Smtp::default();
+    /// connector.to = vec!["{{ input.email }}".to_string()];
+    /// connector.set_parameters(json!({"input":{"email":"john@example.com"}}));
+    /// assert_eq!(false, connector.is_resource_will_change(json!({"input":{"email":"john@example.com"}})).unwrap());
+    /// assert_eq!(true, connector.is_resource_will_change(json!({"input":{"email":"jane@example.com"}})).unwrap());
+    /// ```
+    fn is_resource_will_change(&self, new_parameters: Value) -> Result<bool> {
+        if !self.is_variable() {
+            trace!("Stay link to the same resource");
+            return Ok(false);
+        }
+
+        let previous_headers = self.headers(*self.parameters.clone())?;
+        let new_headers = self.headers(new_parameters)?;
+
+        if previous_headers == new_headers {
+            trace!("Recipients and subject didn't change");
+            return Ok(false);
+        }
+
+        info!(
+            previous_headers = format!("{:?}", previous_headers),
+            new_headers = format!("{:?}", new_headers),
+            "Recipients or subject will change"
+        );
+
+        Ok(true)
+    }
+    /// See [`Connector::path`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::smtp::Smtp;
+    /// use chewdata::connector::Connector;
+    /// use serde_json::json;
+    ///
+    /// let mut connector = Smtp::default();
+    /// connector.to = vec!["{{ input.email }}".to_string(), "jane@example.com".to_string()];
+    /// connector.set_parameters(json!({"input":{"email":"john@example.com"}}));
+    /// assert_eq!("john@example.com,jane@example.com", connector.path());
+    /// ```
+    fn path(&self) -> String {
+        let context = self.context(&DataSet::default());
+
+        self.to
+            .iter()
+            .map(|template| render(template, &context).unwrap_or_else(|_| template.clone()))
+            .collect::<Vec<String>>()
+            .join(",")
+    }
+    /// See [`Connector::metadata`] for more details.
+    fn metadata(&self) -> Metadata {
+        match &self.document {
+            Some(document) => self.metadata.clone().merge(&document.metadata()),
+            None => self.metadata.clone(),
+        }
+    }
+    /// See [`Connector::fetch`] for more details.
+    async fn fetch(&mut self) -> Result<Option<DataStream>> {
+        Err(Error::new(
+            ErrorKind::Unsupported,
+            "Can't fetch data with the smtp connector. Use the imap connector to read emails",
+        ))
+    }
+    /// See [`Connector::send`] for more details.
+    ///
+    /// Send one email with the dataset in attachment.
+    #[instrument(name = "smtp::send", skip(dataset))]
+    async fn send(&mut self, dataset: &DataSet) -> Result<Option<DataStream>> {
+        let message = self.message(dataset)?;
+        let transport = self.transport()?;
+
+        let response = transport
+            .send(message)
+            .await
+            .map_err(|e| match e.is_permanent() {
+                true => Error::new(ErrorKind::PermissionDenied, e),
+                false => Error::new(ErrorKind::Interrupted, e),
+            })?;
+
+        info!(
+            recipients = self.path(),
+            code = response.code().to_string(),
+            "Email sent with success"
+        );
+
+        Ok(None)
+    }
+    /// See [`Connector::paginate`] for more details.
+    async fn paginate(
+        &self,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<Box<dyn Connector>>> + Send>>> {
+        let paginator = Once {};
+        paginator.paginate(self).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+        use crate::document::json::Json;
+    use crate::document::jsonl::Jsonl;
+    use crate::DataResult;
+    use macro_rules_attribute::apply;
+    use serde_json::json;
+    use smol::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use smol::net::TcpListener;
+    use smol_macros::test;
+
+    /// Start an SMTP server that accepts one email and returns its content.
+    async fn smtp_server() -> (String, async_channel::Receiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("smtp://{}", listener.local_addr().unwrap());
+        let (sender, receiver) = async_channel::bounded(1);
+
+        smol::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut reader = BufReader::new(stream.clone());
+            let mut writer = stream;
+            let mut email = String::default();
+            let mut is_data = false;
+
+            writer.write_all(b"220 localhost ESMTP\r\n").await.unwrap();
+            loop {
+                let mut line = String::default();
+                if 0 == reader.read_line(&mut line).await.unwrap() {
+                    break;
+                }
+                let response = match (is_data, line.to_uppercase()) {
+                    (true, _) if ".\r\n" == line => {
+                        is_data = false;
+                        sender.send(email.clone()).await.unwrap();
+                        "250 Queued\r\n"
+                    }
+                    (true, _) => {
+                        email.push_str(&line);
+                        continue;
+                    }
+                    (false, command) if command.starts_with("DATA") => {
+                        is_data = true;
+                        "354 Start mail input\r\n"
+                    }
+                    (false, command) if command.starts_with("QUIT") => {
+                        writer.write_all(b"221 Bye\r\n").await.unwrap();
+                        break;
+                    }
+                    (false, command) if command.starts_with("RCPT TO:<UNKNOWN") => {
+                        "550 Mailbox unavailable\r\n"
+                    }
+                    _ => "250 OK\r\n",
+                };
+                writer.write_all(response.as_bytes()).await.unwrap();
+            }
+        })
+        .detach();
+
+        (endpoint, receiver)
+    }
+
+    #[apply(test!)]
+    async fn message_with_cc_and_html() {
+        let mut connector = Smtp {
+            from: "Reports <reports@example.com>".to_string(),
+            to: vec!["{{ input.to }}".to_string()],
+            cc: vec!["jane@example.com, paul@example.com".to_string()],
+            subject: "Report".to_string(),
+            body: "<p>{{ dataset | length }} lines</p>".to_string(),
+            is_html: true,
+            attachment: "report.jsonl".to_string(),
+            ..Default::default()
+        };
+        connector.set_document(Box::new(Jsonl::default())).unwrap();
+        connector.set_parameters(json!({"input":{"to":"john@example.com"}}));
+
+        let dataset = vec![
+            DataResult::Ok(json!({"column1":"value1"})),
+            DataResult::Ok(json!({"column1":"value2"})),
+        ];
+        let email = String::from_utf8(connector.message(&dataset).unwrap().formatted()).unwrap();
+
+        assert!(email.contains("From: Reports <reports@example.com>"));
+        assert!(email.contains("To: john@example.com"));
+        assert!(email.contains("Cc: jane@example.com, paul@example.com"));
+        assert!(email.contains("Content-Type: text/html"));
+        assert!(email.contains("<p>2 lines</p>"));
+        assert!(email.contains("Content-Disposition: attachment; filename=\"report.jsonl\""));
+        assert!(email.contains("Content-Type: application/x-ndjson"));
+    }
+    #[apply(test!)]
+    async fn message_without_recipient() {
+        let mut connector = Smtp {
+            from: "reports@example.com".to_string(),
+            ..Default::default()
+        };
+        connector.set_document(Box::new(Json::default())).unwrap();
+
+        let error = connector.message(&DataSet::default()).unwrap_err();
+        assert_eq!(ErrorKind::InvalidInput, error.kind());
+    }
+    #[apply(test!)]
+    async fn message_with_wrong_template() {
+        let mut connector = Smtp {
+            from: "reports@example.com".to_string(),
+            to: vec!["john@example.com".to_string()],
+            subject: "{{ input.not_found }}".to_string(),
+            ..Default::default()
+        };
+        connector.set_document(Box::new(Json::default())).unwrap();
+
+        let error = connector.message(&DataSet::default()).unwrap_err();
+        assert_eq!(ErrorKind::InvalidInput, error.kind());
+    }
+    #[apply(test!)]
+    async fn send() {
+        let (endpoint, receiver) = smtp_server().await;
+        let mut connector = Smtp {
+            endpoint,
+            from: "reports@example.com".to_string(),
+            to: vec!["john@example.com".to_string()],
+            subject: "Report".to_string(),
+            body: "Find the report in attachment".to_string(),
+            timeout: Some(5),
+            ..Default::default()
+        };
+        connector.set_document(Box::new(Json::default())).unwrap();
+
+        let dataset = vec![DataResult::Ok(json!({"column1":"value1"}))];
+        connector.send(&dataset).await.unwrap();
+
+        let email = receiver.recv().await.unwrap();
+        assert!(email.contains("Subject: Report"));
+        assert!(email.contains("filename=\"data.json\""));
+    }
+    #[apply(test!)]
+    async fn send_to_unknown_recipient() {
+        let (endpoint, _receiver) = smtp_server().await;
+        let mut connector = Smtp {
+            endpoint,
+            from: "reports@example.com".to_string(),
+            to: vec!["unknown@example.com".to_string()],
+            timeout: Some(5),
+            ..Default::default()
+        };
+        connector.set_document(Box::new(Json::default())).unwrap();
+
+        let error = connector.send(&DataSet::default()).await.err().unwrap();
+        assert_eq!(ErrorKind::PermissionDenied, error.kind());
+    }
+    #[apply(test!)]
+    async fn fetch() {
+        let mut connector = Smtp::default();
+        let error = connector.fetch().await.err().unwrap();
+        assert_eq!(ErrorKind::Unsupported, error.kind());
+    }
+}
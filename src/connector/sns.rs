@@ -0,0 +1,613 @@
+//! Publish messages in an Amazon SNS topic.
+//!
+//! The connector publishes the records by batch of ten, each record in its own message formatted with the [`crate::document`].
+//! The `topic`, the `subject`, the message `attributes`, the `group_id` and the `deduplication_id` can be templates rendered with the parameters of the connector and the record in `input`.
+//! The records rejected by the topic are returned in error.
+//!
+//! The credentials are read from the `SNS_ACCESS_KEY_ID` and `SNS_SECRET_ACCESS_KEY` variables if set, then from the environment, the profile or the role of the instance like the other AWS clients.
+//!
+//! ### Configuration
+//!
+//! | key              | alias            | Description                                                                      | Default Value | Possible Values      |
+//! | ---------------- | ---------------- | -------------------------------------------------------------------------------- | ------------- | -------------------- |
+//! | type             | -                | Required in order to use this connector                                          | `sns`         | `sns`                |
+//! | metadata         | meta             | Override metadata information                                                    | `null`        | [`crate::Metadata`]  |
+//! | endpoint         | -                | Endpoint of the service. Use `SNS_ENDPOINT` or `AWS_ENDPOINT_URL_SNS` if not set | `null`        | String               |
+//! | region           | -                | The region of the topic. Use `AWS_REGION` or `AWS_DEFAULT_REGION` if not set     | `us-east-1`   | String               |
+//! | topic            | topic_arn / path | The ARN of the topic. Can use mustache variables                                 | `null`        | String               |
+//! | subject          | -                | The subject of the messages sent by email. Can use mustache variables            | `null`        | String               |
+//! | attributes       | -                | The message attributes to send. Can use mustache variables                       | `{}`          | List of Key/Value    |
+//! | group_id         | -                | The message group of a FIFO topic. Can use mustache variables                    | `null`        | String               |
+//! | deduplication_id | -                | The deduplication identifier of a FIFO topic. Can use mustache variables         | `null`        | String               |
+//! | parameters       | params           | Variables used to render the templates                                           | `null`        | Object               |
+//!
+//! ### Examples
+//!
+//! ```json
+//! [
+//!     {
+//!         "type": "w",
+//!         "connector": {
+//!             "type": "sns",
+//!             "topic": "arn:aws:sns:eu-west-1:000000000000:orders",
+//!             "subject": "Order {{ input.id }}",
+//!             "attributes": {
+//!                 "status": "{{ input.status }}"
+//!             }
+//!         }
+//!     }
+//! ]
+//! ```
+use super::Connector;
+use crate::connector::paginator::once::Once;
+use crate::document::Document;
+use crate::helper::aws::shared_client;
+use crate::helper::mustache::Mustache;
+use crate::helper::string::DisplayOnlyForDebugging;
+use crate::{DataResult, DataSet, DataStream, Metadata};
+use async_compat::Compat;
+use async_stream::stream;
+use async_trait::async_trait;
+use aws_sdk_sns::error::DisplayErrorContext;
+use aws_sdk_sns::types::{MessageAttributeValue, PublishBatchRequestEntry};
+use aws_sdk_sns::Client;
+use futures::Stream;
+use json_value_merge::Merge;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::env;
+use std::pin::Pin;
+use std::{
+    fmt,
+    io::{Error, ErrorKind, Result},
+};
+
+const DEFAULT_REGION: &str = "us-east-1";
+const MAX_BATCH_SIZE: usize = 10;
+
+#[derive(Deserialize, Serialize, Clone, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct Sns {
+    #[serde(skip)]
+    document: Option<Box<dyn Document>>,
+    #[serde(rename = "metadata")]
+    #[serde(alias = "meta")]
+    pub metadata: Metadata,
+    pub endpoint: Option<String>,
+    pub region: Option<String>,
+    #[serde(alias = "topic_arn")]
+    #[serde(alias = "path")]
+    pub topic: String,
+    pub subject: Option<String>,
+    pub attributes: HashMap<String, String>,
+    pub group_id: Option<String>,
+    pub deduplication_id: Option<String>,
+    #[serde(alias = "params")]
+    pub parameters: Box<Value>,
+}
+
+impl fmt::Debug for Sns {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sns")
+            .field("document", &self.document.display_only_for_debugging())
+            .field("metadata", &self.metadata.display_only_for_debugging())
+            .field("endpoint", &self.endpoint)
+            .field("region", &self.region)
+            .field("topic", &self.topic)
+            .field("subject", &self.subject)
+            .field("attributes", &self.attributes)
+            .field("group_id", &self.group_id)
+            .field("deduplication_id", &self.deduplication_id)
+            .field("parameters", &self.parameters.display_only_for_debugging())
+            .finish()
+    }
+}
+
+impl Sns {
+    fn region(&self) -> String {
+        match (
+            self.region.clone(),
+            env::var("AWS_REGION"),
+            env::var("AWS_DEFAULT_REGION"),
+        ) {
+            (Some(region), _, _) => region,
+            (None, Ok(region), _) => region,
+            (None, Err(_), Ok(region)) => region,
+            (None, Err(_), Err(_)) => DEFAULT_REGION.to_string(),
+        }
+    }
+    fn endpoint(&self) -> Option<String> {
+        self.endpoint
+            .clone()
+            .or_else(|| env::var("SNS_ENDPOINT").ok())
+            .or_else(|| env::var("AWS_ENDPOINT_URL_SNS").ok())
+    }
+    /// Get the client shared by the connectors with the same endpoint and region.
+    #[instrument(name = "sns::client")]
+    async fn client(&self) -> Result<Client> {
+        Ok(shared_client(
+            "SNS",
+            self.endpoint(),
+            self.region(),
+            |provider, region, endpoint| {
+                let mut builder = aws_sdk_sns::Config::builder()
+                    .region(region)
+                    .credentials_provider(provider);
+                if let Some(endpoint) = endpoint {
+                    builder = builder.endpoint_url(endpoint);
+                }
+
+                Client::from_conf(builder.build())
+            },
+        )
+        .await)
+    }
+    /// Render a template with the parameters of the connector and the record in `input`.
+    fn render(&self, template: &str, value: &Value) -> String {
+        let mut text = template.to_string();
+        if !text.has_mustache() {
+            return text;
+        }
+
+        let mut params = *self.parameters.clone();
+        params.merge(&serde_json::json!({
+            "input": value,
+            "metadata": self.metadata()
+        }));
+
+        text.replace_mustache(params);
+        text
+    }
+    /// Build the message of a record.
+    fn entry(&self, id: usize, value: &Value) -> Result<PublishBatchRequestEntry> {
+        let document = self.document()?;
+        let message = String::from_utf8(document.write(&vec![DataResult::Ok(value.clone())])?)
+            .map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+
+        let mut entry = PublishBatchRequestEntry::builder()
+            .id(id.to_string())
+            .message(message);
+
+        for (name, template) in &self.attributes {
+            entry = entry.message_attributes(
+                name,
+                MessageAttributeValue::builder()
+                    .data_type("String")
+                    .string_value(self.render(template, value))
+                    .build()
+                    .map_err(|e| Error::new(ErrorKind::InvalidInput, e))?,
+            );
+        }
+        if let Some(subject) = &self.subject {
+            entry = entry.subject(self.render(subject, value));
+        }
+        if let Some(group_id) = &self.group_id {
+            entry = entry.message_group_id(self.render(group_id, value));
+        }
+        if let Some(deduplication_id) = &self.deduplication_id {
+            entry = entry.message_deduplication_id(self.render(deduplication_id, value));
+        }
+
+        entry
+            .build()
+            .map_err(|e| Error::new(ErrorKind::InvalidInput, e))
+    }
+}
+
+#[async_trait]
+impl Connector for Sns {
+    /// See [`Connector::set_document`] for more details.
+    fn set_document(&mut self, document: Box<dyn Document>) -> Result<()> {
+        self.document = Some(document.clone());
+
+        Ok(())
+    }
+    /// See [`Connector::document`] for more details.
+    fn document(&self) -> Result<&dyn Document> {
+        self.document.as_deref().ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                "The document has not been set in the connector",
+            )
+        })
+    }
+    /// See [`Connector::set_parameters`] for more details.
+    fn set_parameters(&mut self, parameters: Value) {
+        *self.parameters = parameters;
+    }
+    /// See [`Connector::is_variable`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::sns::Sns;
+    /// use chewdata::connector::Connector;
+    ///
+    /// let mut connector = Sns::default();
+    /// connector.topic = "arn:aws:sns:us-east-1:000000000000:orders".to_string();
+    /// assert_eq!(false, connector.is_variable());
+    /// connector.topic = "arn:aws:sns:us-east-1:000000000000:orders-{{ status }}".to_string();
+    /// assert_eq!(true, connector.is_variable());
+    /// ```
+    fn is_variable(&self) -> bool {
+        self.topic.has_mustache()
+    }
+    /// See [`Connector::is_resource_will_change`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::sns::Sns;
+    /// use chewdata::connector::Connector;
+    /// use serde_json::Value;
+    ///
+    /// let mut connector = Sns::default();
+    /// let params = serde_json::from_str(r#"{"status":"created"}"#).unwrap();
+    /// connector.topic = "arn:aws:sns:us-east-1:000000000000:orders".to_string();
+    /// assert_eq!(false, connector.is_resource_will_change(Value::Null).unwrap());
+    /// connector.topic = "arn:aws:sns:us-east-1:000000000000:orders-{{ status }}".to_string();
+    /// assert_eq!(true, connector.is_resource_will_change(params).unwrap());
+    /// ```
+    fn is_resource_will_change(&self, new_parameters: Value) -> Result<bool> {
+        if !self.is_variable() {
+            trace!("Stay link to the same resource");
+            return Ok(false);
+        }
+
+        let mut metadata_kv = Map::default();
+        metadata_kv.insert("metadata".to_string(), self.metadata().into());
+        let metadata = Value::Object(metadata_kv);
+
+        let mut new_parameters = new_parameters;
+        new_parameters.merge(&metadata);
+        let mut old_parameters = *self.parameters.clone();
+        old_parameters.merge(&metadata);
+
+        let mut previous_topic = self.topic.clone();
+        previous_topic.replace_mustache(old_parameters);
+
+        let mut new_topic = self.topic.clone();
+        new_topic.replace_mustache(new_parameters);
+
+        if previous_topic == new_topic {
+            trace!(topic = previous_topic, "Topic didn't change");
+            return Ok(false);
+        }
+
+        info!(
+            previous_topic = previous_topic,
+            new_topic = new_topic,
+            "Topic will change"
+        );
+
+        Ok(true)
+    }
+    /// See [`Connector::path`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::sns::Sns;
+    /// use chewdata::connector::Connector;
+    ///
+    /// let mut connector = Sns::default();
+    /// connector.topic = "arn:aws:sns:us-east-1:000000000000:orders-{{ status }}".to_string();
+    /// connector.set_parameters(serde_json::from_str(r#"{"status":"created"}"#).unwrap());
+    /// assert_eq!("arn:aws:sns:us-east-1:000000000000:orders-created", connector.path());
+    /// ```
+    fn path(&self) -> String {
+        if !self.is_variable() {
+            return self.topic.clone();
+        }
+
+        let mut params = *self.parameters.clone();
+        params.merge(&serde_json::json!({
+            "metadata": self.metadata()
+        }));
+
+        let mut topic = self.topic.clone();
+        topic.replace_mustache(params);
+        topic
+    }
+    /// See [`Connector::metadata`] for more details.
+    fn metadata(&self) -> Metadata {
+        match &self.document {
+            Some(document) => self.metadata.clone().merge(&document.metadata()),
+            None => self.metadata.clone(),
+        }
+    }
+    /// See [`Connector::fetch`] for more details.
+    async fn fetch(&mut self) -> Result<Option<DataStream>> {
+        Err(Error::new(
+            ErrorKind::Unsupported,
+            "Can't fetch data with the sns connector. Subscribe a queue to the topic and use the sqs connector to read the messages",
+        ))
+    }
+    /// See [`Connector::send`] for more details.
+    ///
+    /// Publish each record in its own message by batch of ten messages.
+    #[instrument(name = "sns::send", skip(dataset))]
+    async fn send(&mut self, dataset: &DataSet) -> Result<Option<DataStream>> {
+        if dataset.is_empty() {
+            return Ok(Some(Box::pin(futures::stream::empty())));
+        }
+
+        let topic = self.path();
+        if topic.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The topic is required by the sns connector",
+            ));
+        }
+
+        let client = self.client().await?;
+
+        let mut results = Vec::default();
+        for records in dataset.chunks(MAX_BATCH_SIZE) {
+            let entries = records
+                .iter()
+                .enumerate()
+                .map(|(index, data)| self.entry(index, &data.to_value()))
+                .collect::<Result<Vec<PublishBatchRequestEntry>>>()?;
+
+            let output = Compat::new(
+                client
+                    .publish_batch()
+                    .topic_arn(topic.as_str())
+                    .set_publish_batch_request_entries(Some(entries))
+                    .send(),
+            )
+            .await
+            .map_err(|e| Error::new(ErrorKind::Interrupted, DisplayErrorContext(e).to_string()))?;
+
+            let mut failures: HashMap<String, Error> = output
+                .failed()
+                .iter()
+                .map(|failure| {
+                    let kind = match failure.sender_fault() {
+                        true => ErrorKind::InvalidInput,
+                        false => ErrorKind::Interrupted,
+                    };
+                    (
+                        failure.id().to_string(),
+                        Error::new(
+                            kind,
+                            format!(
+                                "The topic rejects the message with the code '{}'. {}",
+                                failure.code(),
+                                failure.message().unwrap_or_default()
+                            ),
+                        ),
+                    )
+                })
+                .collect();
+
+            for (index, data) in records.iter().enumerate() {
+                results.push(match failures.remove(&index.to_string()) {
+                    Some(e) => DataResult::Err((data.to_value(), e)),
+                    None => data.clone(),
+                });
+            }
+        }
+
+        info!(
+            topic = topic,
+            messages = results
+                .iter()
+                .filter(|data| data.is_type(DataResult::OK))
+                .count(),
+            errors = results
+                .iter()
+                .filter(|data| data.is_type(DataResult::ERR))
+                .count(),
+            "Messages published"
+        );
+
+        Ok(Some(Box::pin(stream! {
+            for data in results {
+                yield data;
+            }
+        })))
+    }
+    /// See [`Connector::has_send_results`] for more details.
+    fn has_send_results(&self) -> bool {
+        true
+    }
+    /// See [`Connector::paginate`] for more details.
+    async fn paginate(
+        &self,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<Box<dyn Connector>>> + Send>>> {
+        let paginator = Once {};
+        paginator.paginate(self).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::json::Json;
+    use async_channel::Receiver;
+    use futures::StreamExt;
+    use macro_rules_attribute::apply;
+    use smol::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+    use smol::net::TcpListener;
+    use smol_macros::test;
+
+    /// Start an SNS server that answers each request with the response and returns the form parameters received.
+    async fn sns_server(response: &'static str) -> (String, Receiver<HashMap<String, String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        let (sender, receiver) = async_channel::unbounded();
+
+        smol::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let sender = sender.clone();
+                smol::spawn(async move {
+                    let mut reader = BufReader::new(stream.clone());
+                    let mut writer = stream;
+                    loop {
+                        let mut length = 0;
+                        loop {
+                            let mut line = String::default();
+                            if 0 == reader.read_line(&mut line).await.unwrap_or(0) {
+                                return;
+                            }
+                            match line.trim_end().split_once(':') {
+                                Some((name, value)) if name.eq_ignore_ascii_case("content-length") => {
+                                    length = value.trim().parse().unwrap()
+                                }
+                                Some(_) => (),
+                                None if line.trim_end().is_empty() => break,
+                                None => (),
+                            }
+                        }
+                        let mut body = vec![0; length];
+                        reader.read_exact(&mut body).await.unwrap();
+                        let form = parse_form(&String::from_utf8(body).unwrap());
+                        sender.send(form).await.unwrap();
+                        writer
+                            .write_all(
+                                format!(
+                                    "HTTP/1.1 200 OK\r\nContent-Type: text/xml\r\nContent-Length: {}\r\n\r\n{}",
+                                    response.len(),
+                                    response
+                                )
+                                .as_bytes(),
+                            )
+                            .await
+                            .unwrap();
+                    }
+                })
+                .detach();
+            }
+        })
+        .detach();
+
+        (endpoint, receiver)
+    }
+
+    /// Decode the parameters of the request sent with the query protocol.
+    fn parse_form(body: &str) -> HashMap<String, String> {
+        let decode = |text: &str| {
+            let bytes = text.replace('+', " ").into_bytes();
+            let mut decoded = Vec::default();
+            let mut index = 0;
+            while index < bytes.len() {
+                match bytes[index] {
+                    b'%' => {
+                        let hex = std::str::from_utf8(&bytes[index + 1..index + 3]).unwrap();
+                        decoded.push(u8::from_str_radix(hex, 16).unwrap());
+                        index += 3;
+                    }
+                    byte => {
+                        decoded.push(byte);
+                        index += 1;
+                    }
+                }
+            }
+            String::from_utf8(decoded).unwrap()
+        };
+
+        body.split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .map(|(name, value)| (decode(name), decode(value)))
+            .collect()
+    }
+
+    fn connector(endpoint: String) -> Sns {
+        std::env::set_var("AWS_ACCESS_KEY_ID", "test");
+        std::env::set_var("AWS_SECRET_ACCESS_KEY", "test");
+
+        let mut connector = Sns {
+            endpoint: Some(endpoint),
+            region: Some("us-east-1".to_string()),
+            topic: "arn:aws:sns:us-east-1:000000000000:orders".to_string(),
+            ..Default::default()
+        };
+        connector.set_document(Box::new(Json::default())).unwrap();
+        connector
+    }
+
+    #[apply(test!)]
+    async fn send() {
+        let (endpoint, receiver) = sns_server(
+            r#"<PublishBatchResponse xmlns="http://sns.amazonaws.com/doc/2010-03-31/">
+                <PublishBatchResult>
+                    <Successful><member><Id>0</Id><MessageId>id-1</MessageId></member></Successful>
+                    <Failed><member><Id>1</Id><SenderFault>true</SenderFault><Code>InvalidParameter</Code><Message>Too big</Message></member></Failed>
+                </PublishBatchResult>
+                <ResponseMetadata><RequestId>request-1</RequestId></ResponseMetadata>
+            </PublishBatchResponse>"#,
+        )
+        .await;
+        let mut connector = connector(endpoint);
+        connector.subject = Some("Order {{ input.id }}".to_string());
+        connector.attributes =
+            HashMap::from([("status".to_string(), "{{ input.status }}".to_string())]);
+
+        let results: Vec<DataResult> = connector
+            .send(&vec![
+                DataResult::Ok(serde_json::json!({"id":1,"status":"created"})),
+                DataResult::Ok(serde_json::json!({"id":2,"status":"refund"})),
+            ])
+            .await
+            .unwrap()
+            .unwrap()
+            .collect()
+            .await;
+
+        assert!(results[0].is_type(DataResult::OK));
+        match &results[1] {
+            DataResult::Err((value, e)) => {
+                assert_eq!(serde_json::json!({"id":2,"status":"refund"}), *value);
+                assert_eq!(ErrorKind::InvalidInput, e.kind());
+            }
+            _ => panic!("The second message must be rejected"),
+        };
+
+        let form = receiver.try_recv().unwrap();
+        assert_eq!("PublishBatch", form["Action"]);
+        assert_eq!(
+            "arn:aws:sns:us-east-1:000000000000:orders",
+            form["TopicArn"]
+        );
+        let entry = "PublishBatchRequestEntries.member.1";
+        assert_eq!("0", form[&format!("{}.Id", entry)]);
+        assert_eq!(
+            r#"{"id":1,"status":"created"}"#,
+            form[&format!("{}.Message", entry)]
+        );
+        assert_eq!("Order 1", form[&format!("{}.Subject", entry)]);
+        assert_eq!(
+            "status",
+            form[&format!("{}.MessageAttributes.entry.1.Name", entry)]
+        );
+        assert_eq!(
+            "created",
+            form[&format!("{}.MessageAttributes.entry.1.Value.StringValue", entry)]
+        );
+        assert_eq!(
+            "Order 2",
+            form["PublishBatchRequestEntries.member.2.Subject"]
+        );
+    }
+    #[apply(test!)]
+    async fn send_without_topic() {
+        let mut connector = Sns::default();
+        connector.set_document(Box::new(Json::default())).unwrap();
+
+        let error = connector
+            .send(&vec![DataResult::Ok(serde_json::json!({"id":1}))])
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(ErrorKind::InvalidInput, error.kind());
+    }
+    #[apply(test!)]
+    async fn fetch() {
+        let mut connector = Sns::default();
+        let error = connector.fetch().await.err().unwrap();
+        assert_eq!(ErrorKind::Unsupported, error.kind());
+    }
+}
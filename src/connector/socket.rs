@@ -0,0 +1,794 @@
+//! Read and write a stream of bytes through a TCP, UDP or Unix domain socket.
+//!
+//! When the connector reads, it listens or connects to the `endpoint` and splits the stream of bytes with the terminator of the [`crate::document`].
+//! Each frame is parsed with the [`crate::document`]. With UDP, each datagram is split too.
+//! The connector stops to read after `message_limit` frames or if no frame is received before the `timeout`. Otherwise, it reads without end.
+//! A connection that sends a frame bigger than `max_frame_size` is closed.
+//!
+//! When the connector writes, it connects to the `endpoint` and sends each record followed by the terminator. With UDP, each record is sent in its own datagram.
+//! If the connection is lost, the connector reconnects and sends the records again up to `retry` times.
+//!
+//! ### Configuration
+//!
+//! | key           | alias | Description                                                                  | Default Value          | Possible Values                                                    |
+//! | ------------- | ----- | ---------------------------------------------------------------------------- | ---------------------- | ------------------------------------------------------------------ |
+//! | type          | -     | Required in order to use this connector                                      | `socket`               | `socket` / `tcp` / `udp`                                           |
+//! | metadata      | meta  | Override metadata information                                                | `null`                 | [`crate::Metadata`]                                                |
+//! | endpoint      | -     | The address of the socket                                                    | `tcp://127.0.0.1:5140` | `tcp://host:port` / `udp://host:port` / `unix:///path/to/socket`   |
+//! | mode          | -     | Listen the endpoint or connect to it in order to read                        | `listen`               | `listen` / `connect`                                               |
+//! | message_limit | limit | Stop to read after N frames. Read without limit if not set                   | `null`                 | Unsigned number                                                    |
+//! | timeout       | -     | Time in second without frame before to stop to read                          | `null`                 | Unsigned number                                                    |
+//! | retry         | -     | Number of reconnections before to fail to write                              | `3`                    | Unsigned number                                                    |
+//! | max_frame_size | -    | Maximum size in bytes of a frame received                                    | `1048576`              | Unsigned number                                                    |
+//!
+//! ### Examples
+//!
+//! ```json
+//! [
+//!     {
+//!         "type": "r",
+//!         "connector": {
+//!             "type": "socket",
+//!             "endpoint": "udp://0.0.0.0:5140",
+//!             "mode": "listen"
+//!         },
+//!         "document": {
+//!             "type": "text"
+//!         }
+//!     },
+//!     {
+//!         "type": "w",
+//!         "connector": {
+//!             "type": "socket",
+//!             "endpoint": "tcp://collector:5170"
+//!         },
+//!         "document": {
+//!             "type": "jsonl"
+//!         }
+//!     }
+//! ]
+//! ```
+use super::Connector;
+use crate::connector::paginator::once::Once;
+use crate::document::Document;
+use crate::helper::string::DisplayOnlyForDebugging;
+use crate::{DataResult, DataSet, DataStream, Metadata};
+use async_channel::Sender;
+use async_stream::stream;
+use async_trait::async_trait;
+use futures::{AsyncRead, AsyncReadExt, AsyncWriteExt, Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+#[cfg(unix)]
+use smol::net::unix::{UnixListener, UnixStream};
+use smol::net::{TcpListener, TcpStream, UdpSocket};
+use smol::Task;
+use smol_timeout::TimeoutExt;
+use std::pin::Pin;
+use std::time::Duration;
+use std::{
+    fmt,
+    io::{Error, ErrorKind, Result},
+};
+
+const DEFAULT_ENDPOINT: &str = "tcp://127.0.0.1:5140";
+const DEFAULT_TERMINATOR: &str = "\n";
+const DEFAULT_RETRY: usize = 3;
+const RETRY_DELAY: Duration = Duration::from_secs(1);
+const BUFFER_SIZE: usize = 64 * 1024;
+const DEFAULT_MAX_FRAME_SIZE: usize = 1024 * 1024;
+// Maximum number of frames received and not read yet.
+const FRAME_QUEUE_SIZE: usize = 1000;
+const RECEIVE_RETRY_DELAY: Duration = Duration::from_millis(100);
+const RECEIVE_RETRY_MAX: u32 = 50;
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+pub enum Mode {
+    #[default]
+    #[serde(rename = "listen")]
+    #[serde(alias = "server")]
+    Listen,
+    #[serde(rename = "connect")]
+    #[serde(alias = "client")]
+    Connect,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Protocol {
+    Tcp(String),
+    Udp(String),
+    Unix(String),
+}
+
+#[derive(Clone)]
+enum Connection {
+    Tcp(TcpStream),
+    Udp(UdpSocket),
+    #[cfg(unix)]
+    Unix(UnixStream),
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct Socket {
+    #[serde(skip)]
+    document: Option<Box<dyn Document>>,
+    #[serde(rename = "metadata")]
+    #[serde(alias = "meta")]
+    pub metadata: Metadata,
+    pub endpoint: String,
+    pub mode: Mode,
+    #[serde(alias = "limit")]
+    pub message_limit: Option<usize>,
+    pub timeout: Option<u64>,
+    pub retry: usize,
+    pub max_frame_size: usize,
+    // Connection kept between two sends.
+    #[serde(skip)]
+    connection: Option<Connection>,
+}
+
+impl fmt::Debug for Socket {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Socket")
+            .field("document", &self.document.display_only_for_debugging())
+            .field("metadata", &self.metadata.display_only_for_debugging())
+            .field("endpoint", &self.endpoint)
+            .field("mode", &self.mode)
+            .field("message_limit", &self.message_limit)
+            .field("timeout", &self.timeout)
+            .field("retry", &self.retry)
+            .field("max_frame_size", &self.max_frame_size)
+            .finish()
+    }
+}
+
+impl Default for Socket {
+    fn default() -> Self {
+        Socket {
+            document: None,
+            metadata: Metadata::default(),
+            endpoint: DEFAULT_ENDPOINT.to_string(),
+            mode: Mode::default(),
+            message_limit: None,
+            timeout: None,
+            retry: DEFAULT_RETRY,
+            max_frame_size: DEFAULT_MAX_FRAME_SIZE,
+            connection: None,
+        }
+    }
+}
+
+impl Socket {
+    /// Get the protocol and the address of the endpoint.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::socket::{Protocol, Socket};
+    ///
+    /// let mut connector = Socket::default();
+    /// assert_eq!(Protocol::Tcp("127.0.0.1:5140".to_string()), connector.protocol().unwrap());
+    /// connector.endpoint = "udp://0.0.0.0:514".to_string();
+    /// assert_eq!(Protocol::Udp("0.0.0.0:514".to_string()), connector.protocol().unwrap());
+    /// connector.endpoint = "unix:///tmp/chewdata.sock".to_string();
+    /// assert_eq!(Protocol::Unix("/tmp/chewdata.sock".to_string()), connector.protocol().unwrap());
+    /// connector.endpoint = "http://localhost".to_string();
+    /// assert!(connector.protocol().is_err());
+    /// ```
+    pub fn protocol(&self) -> Result<Protocol> {
+        match self.endpoint.split_once("://") {
+            Some(("tcp", address)) => Ok(Protocol::Tcp(address.to_string())),
+            Some(("udp", address)) => Ok(Protocol::Udp(address.to_string())),
+            Some(("unix", path)) => Ok(Protocol::Unix(path.to_string())),
+            _ => Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "The endpoint '{}' must start with 'tcp://', 'udp://' or 'unix://'",
+                    self.endpoint
+                ),
+            )),
+        }
+    }
+    /// Get the terminator used to split the frames.
+    fn terminator(&self) -> Result<Vec<u8>> {
+        let terminator = self.document()?.terminator()?;
+        if !terminator.is_empty() {
+            return Ok(terminator);
+        }
+
+        Ok(self
+            .metadata()
+            .terminator
+            .filter(|terminator| !terminator.is_empty())
+            .unwrap_or_else(|| DEFAULT_TERMINATOR.to_string())
+            .into_bytes())
+    }
+    /// Listen or connect to the endpoint and push the frames received.
+    async fn receive(&self, terminator: Vec<u8>, sender: Sender<Vec<u8>>) -> Result<Task<()>> {
+        let max_frame_size = self.max_frame_size;
+
+        Ok(match (self.protocol()?, &self.mode) {
+            (Protocol::Tcp(address), Mode::Listen) => {
+                let listener = TcpListener::bind(address).await?;
+                smol::spawn(async move {
+                    serve(listener.incoming(), terminator, max_frame_size, sender).await
+                })
+            }
+            (Protocol::Tcp(address), Mode::Connect) => {
+                let stream = TcpStream::connect(address).await?;
+                smol::spawn(read_frames(stream, terminator, max_frame_size, sender))
+            }
+            (Protocol::Udp(address), Mode::Listen) => {
+                let socket = UdpSocket::bind(address).await?;
+                smol::spawn(receive_datagrams(socket, terminator, sender))
+            }
+            (Protocol::Udp(_), Mode::Connect) => {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "Can't connect to an UDP socket in order to read. Use the listen mode",
+                ))
+            }
+            #[cfg(unix)]
+            (Protocol::Unix(path), Mode::Listen) => {
+                let listener = UnixListener::bind(path)?;
+                smol::spawn(async move {
+                    serve(listener.incoming(), terminator, max_frame_size, sender).await
+                })
+            }
+            #[cfg(unix)]
+            (Protocol::Unix(path), Mode::Connect) => {
+                let stream = UnixStream::connect(path).await?;
+                smol::spawn(read_frames(stream, terminator, max_frame_size, sender))
+            }
+            #[cfg(not(unix))]
+            (Protocol::Unix(_), _) => {
+                return Err(Error::new(
+                    ErrorKind::Unsupported,
+                    "Unix domain sockets are not supported on this platform",
+                ))
+            }
+        })
+    }
+    async fn connect(&self) -> Result<Connection> {
+        Ok(match self.protocol()? {
+            Protocol::Tcp(address) => Connection::Tcp(TcpStream::connect(address).await?),
+            Protocol::Udp(address) => {
+                let socket = UdpSocket::bind(match address.starts_with('[') {
+                    true => "[::]:0",
+                    false => "0.0.0.0:0",
+                })
+                .await?;
+                socket.connect(address).await?;
+                Connection::Udp(socket)
+            }
+            #[cfg(unix)]
+            Protocol::Unix(path) => Connection::Unix(UnixStream::connect(path).await?),
+            #[cfg(not(unix))]
+            Protocol::Unix(_) => {
+                return Err(Error::new(
+                    ErrorKind::Unsupported,
+                    "Unix domain sockets are not supported on this platform",
+                ))
+            }
+        })
+    }
+    /// Write the frames in the connection and reconnect if the connection is lost.
+    async fn write(&mut self, frames: &[Vec<u8>], terminator: &[u8]) -> Result<()> {
+        let mut attempt = 0;
+
+        loop {
+            let result = async {
+                let connection = match self.connection.take() {
+                    Some(connection) => connection,
+                    None => self.connect().await?,
+                };
+                write_frames(&connection, frames, terminator).await?;
+                self.connection = Some(connection);
+                Ok::<(), Error>(())
+            }
+            .await;
+
+            match result {
+                Ok(()) => return Ok(()),
+                Err(e) if attempt < self.retry => {
+                    attempt += 1;
+                    warn!(
+                        error = e.to_string().as_str(),
+                        attempt, "Can't write in the socket, reconnect"
+                    );
+                    smol::Timer::after(RETRY_DELAY).await;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+/// Drain the complete frames of the buffer. The bytes after the last terminator stay in the buffer.
+fn split_frames(buffer: &mut Vec<u8>, terminator: &[u8]) -> Vec<Vec<u8>> {
+    let mut frames = Vec::default();
+    let mut start = 0;
+    let mut position = 0;
+
+    while position + terminator.len() <= buffer.len() {
+        if buffer[position..].starts_with(terminator) {
+            frames.push(buffer[start..position].to_vec());
+            position += terminator.len();
+            start = position;
+        } else {
+            position += 1;
+        }
+    }
+    buffer.drain(..start);
+
+    frames
+}
+
+/// Accept the connections and read the frames of each of them.
+async fn serve<S, R>(
+    mut incoming: S,
+    terminator: Vec<u8>,
+    max_frame_size: usize,
+    sender: Sender<Vec<u8>>,
+) where
+    S: Stream<Item = Result<R>> + Unpin,
+    R: AsyncRead + Unpin + Send + 'static,
+{
+    // The connections are closed when the server stops.
+    let mut connections: Vec<Task<()>> = Vec::default();
+    let mut errors: u32 = 0;
+
+    while let Some(stream) = incoming.next().await {
+        match stream {
+            Ok(stream) => {
+                errors = 0;
+                trace!("Accept a new connection");
+                // Release the connections already closed.
+                connections.retain(|connection| !connection.is_finished());
+                connections.push(smol::spawn(read_frames(
+                    stream,
+                    terminator.clone(),
+                    max_frame_size,
+                    sender.clone(),
+                )));
+            }
+            Err(e) => {
+                // Wait before to retry, the error can persist like with too many open files.
+                errors = errors.saturating_add(1);
+                warn!(
+                    error = e.to_string().as_str(),
+                    retry = errors,
+                    "Can't accept the connection"
+                );
+                smol::Timer::after(RECEIVE_RETRY_DELAY * errors.min(RECEIVE_RETRY_MAX)).await;
+            }
+        }
+    }
+}
+
+/// Read the stream until the end and push each frame. Close the stream if a frame exceeds the maximum size.
+async fn read_frames<R>(
+    mut reader: R,
+    terminator: Vec<u8>,
+    max_frame_size: usize,
+    sender: Sender<Vec<u8>>,
+) where
+    R: AsyncRead + Unpin,
+{
+    let mut buffer = Vec::default();
+    let mut chunk = vec![0; BUFFER_SIZE];
+
+    loop {
+        let size = match reader.read(&mut chunk).await {
+            Ok(size) => size,
+            Err(e) => {
+                warn!(error = e.to_string().as_str(), "Can't read the stream");
+                break;
+            }
+        };
+
+        if 0 == size {
+            if !buffer.is_empty() {
+                let _ = sender.send(buffer).await;
+            }
+            trace!("The stream is closed");
+            break;
+        }
+
+        buffer.extend_from_slice(&chunk[..size]);
+        for frame in split_frames(&mut buffer, &terminator) {
+            if sender.send(frame).await.is_err() {
+                return;
+            }
+        }
+
+        if buffer.len() > max_frame_size {
+            warn!(
+                size = buffer.len(),
+                max_frame_size, "The frame exceeds the maximum size, the stream is closed"
+            );
+            break;
+        }
+    }
+}
+
+/// Receive the datagrams and push each frame.
+async fn receive_datagrams(socket: UdpSocket, terminator: Vec<u8>, sender: Sender<Vec<u8>>) {
+    let mut datagram = vec![0; BUFFER_SIZE];
+    let mut errors: u32 = 0;
+
+    loop {
+        let size = match socket.recv_from(&mut datagram).await {
+            Ok((size, _)) => {
+                errors = 0;
+                size
+            }
+            Err(e) => {
+                // Wait before to retry, the error can persist.
+                errors = errors.saturating_add(1);
+                warn!(
+                    error = e.to_string().as_str(),
+                    retry = errors,
+                    "Can't receive the datagram"
+                );
+                smol::Timer::after(RECEIVE_RETRY_DELAY * errors.min(RECEIVE_RETRY_MAX)).await;
+                continue;
+            }
+        };
+
+        let mut buffer = datagram[..size].to_vec();
+        let mut frames = split_frames(&mut buffer, &terminator);
+        if !buffer.is_empty() {
+            frames.push(buffer);
+        }
+
+        for frame in frames {
+            if sender.send(frame).await.is_err() {
+                return;
+            }
+        }
+    }
+}
+
+async fn write_frames(
+    connection: &Connection,
+    frames: &[Vec<u8>],
+    terminator: &[u8],
+) -> Result<()> {
+    match connection {
+        Connection::Udp(socket) => {
+            for frame in frames {
+                socket.send(frame).await?;
+            }
+        }
+        Connection::Tcp(stream) => {
+            let mut stream = stream.clone();
+            stream.write_all(&frames.join(terminator)).await?;
+            stream.write_all(terminator).await?;
+            stream.flush().await?;
+        }
+        #[cfg(unix)]
+        Connection::Unix(stream) => {
+            let mut stream = stream.clone();
+            stream.write_all(&frames.join(terminator)).await?;
+            stream.write_all(terminator).await?;
+            stream.flush().await?;
+        }
+    }
+
+    Ok(())
+}
+
+#[async_trait]
+impl Connector for Socket {
+    /// See [`Connector::set_document`] for more details.
+    fn set_document(&mut self, document: Box<dyn Document>) -> Result<()> {
+        self.document = Some(document.clone());
+
+        Ok(())
+    }
+    /// See [`Connector::document`] for more details.
+    fn document(&self) -> Result<&dyn Document> {
+        self.document.as_deref().ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                "The document has not been set in the connector",
+            )
+        })
+    }
+    /// See [`Connector::set_parameters`] for more details.
+    fn set_parameters(&mut self, _parameters: Value) {}
+    /// See [`Connector::is_variable`] for more details.
+    fn is_variable(&self) -> bool {
+        false
+    }
+    /// See [`Connector::is_resource_will_change`] for more details.
+    fn is_resource_will_change(&self, _new_parameters: Value) -> Result<bool> {
+        Ok(false)
+    }
+    /// See [`Connector::path`] for more details.
+    fn path(&self) -> String {
+        self.endpoint.clone()
+    }
+    /// See [`Connector::metadata`] for more details.
+    fn metadata(&self) -> Metadata {
+        match &self.document {
+            Some(document) => self.metadata.clone().merge(&document.metadata()),
+            None => self.metadata.clone(),
+        }
+    }
+    /// See [`Connector::fetch`] for more details.
+    ///
+    /// Listen or connect to the endpoint and yield the records of each frame received.
+    #[instrument(name = "socket::fetch")]
+    async fn fetch(&mut self) -> Result<Option<DataStream>> {
+        let document = self.document()?.clone_box();
+        let message_limit = self.message_limit;
+        let timeout = self.timeout.map(Duration::from_secs);
+
+        let (sender, receiver) = async_channel::bounded(FRAME_QUEUE_SIZE);
+        let task = self.receive(self.terminator()?, sender).await?;
+
+        info!(
+            endpoint = self.endpoint,
+            mode = format!("{:?}", self.mode),
+            "Start to read the socket"
+        );
+
+        Ok(Some(Box::pin(stream! {
+            // The socket is closed when the stream is dropped.
+            let _task = task;
+            let mut frames = 0;
+
+            while message_limit.is_none_or(|limit| frames < limit) {
+                let frame = match timeout {
+                    Some(timeout) => match receiver.recv().timeout(timeout).await {
+                        Some(frame) => frame,
+                        None => {
+                            info!("No frame received before the timeout");
+                            break;
+                        }
+                    },
+                    None => receiver.recv().await,
+                };
+                let frame = match frame {
+                    Ok(frame) => frame,
+                    Err(_) => break,
+                };
+
+                match document.has_data(&frame) {
+                    Ok(false) => continue,
+                    Ok(true) => (),
+                    Err(e) => {
+                        yield DataResult::Err((Value::String(String::from_utf8_lossy(&frame).to_string()), e));
+                        continue;
+                    }
+                }
+                frames += 1;
+
+                match document.read(&frame) {
+                    Ok(dataset) => {
+                        for data in dataset {
+                            yield data;
+                        }
+                    }
+                    Err(e) => {
+                        yield DataResult::Err((Value::String(String::from_utf8_lossy(&frame).to_string()), e));
+                    }
+                }
+            }
+
+            info!(frames, "Stop to read the socket");
+        })))
+    }
+    /// See [`Connector::send`] for more details.
+    ///
+    /// Send each record followed by the terminator.
+    #[instrument(name = "socket::send", skip(dataset))]
+    async fn send(&mut self, dataset: &DataSet) -> Result<Option<DataStream>> {
+        let document = self.document()?;
+        let terminator = self.terminator()?;
+
+        let mut frames = Vec::default();
+        for data in dataset {
+            frames.push(document.write(&vec![data.clone()])?);
+        }
+
+        if frames.is_empty() {
+            return Ok(None);
+        }
+
+        self.write(&frames, &terminator).await?;
+
+        info!(
+            endpoint = self.endpoint,
+            frames = frames.len(),
+            "Frames sent with success"
+        );
+
+        Ok(None)
+    }
+    /// See [`Connector::paginate`] for more details.
+    async fn paginate(
+        &self,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<Box<dyn Connector>>> + Send>>> {
+        let paginator = Once {};
+        paginator.paginate(self).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::jsonl::Jsonl;
+    use crate::document::text::Text;
+    use macro_rules_attribute::apply;
+    use smol_macros::test;
+
+    fn connector(endpoint: &str) -> Socket {
+        let mut connector = Socket {
+            endpoint: endpoint.to_string(),
+            timeout: Some(5),
+            ..Default::default()
+        };
+        connector.set_document(Box::new(Jsonl::default())).unwrap();
+        connector
+    }
+    async fn send_and_fetch(endpoint: &str) -> Vec<Value> {
+        let mut reader = connector(endpoint);
+        reader.message_limit = Some(2);
+        let datastream = reader.fetch().await.unwrap().unwrap();
+
+        let mut writer = connector(endpoint);
+        writer
+            .send(&vec![
+                DataResult::Ok(serde_json::json!({"field":"value1"})),
+                DataResult::Ok(serde_json::json!({"field":"value2"})),
+            ])
+            .await
+            .unwrap();
+
+        datastream.map(|data| data.to_value()).collect().await
+    }
+
+    #[test]
+    fn split_frames() {
+        let mut buffer = b"line1\r\nline2\r\nline".to_vec();
+        let frames = super::split_frames(&mut buffer, b"\r\n");
+        assert_eq!(vec![b"line1".to_vec(), b"line2".to_vec()], frames);
+        assert_eq!(b"line".to_vec(), buffer);
+    }
+    #[test]
+    fn terminator() {
+        let mut connector = Socket::default();
+        connector.set_document(Box::new(Text::default())).unwrap();
+        assert_eq!(b"\n".to_vec(), connector.terminator().unwrap());
+    }
+    #[apply(test!)]
+    async fn send_and_fetch_with_tcp() {
+        let expected = vec![
+            serde_json::json!({"field":"value1"}),
+            serde_json::json!({"field":"value2"}),
+        ];
+        assert_eq!(expected, send_and_fetch("tcp://127.0.0.1:5141").await);
+    }
+    #[apply(test!)]
+    async fn send_and_fetch_with_udp() {
+        let expected = vec![
+            serde_json::json!({"field":"value1"}),
+            serde_json::json!({"field":"value2"}),
+        ];
+        assert_eq!(expected, send_and_fetch("udp://127.0.0.1:5142").await);
+    }
+    #[cfg(unix)]
+    #[apply(test!)]
+    async fn send_and_fetch_with_unix() {
+        let path = std::env::temp_dir().join(format!("chewdata-{}.sock", uuid::Uuid::new_v4()));
+        let expected = vec![
+            serde_json::json!({"field":"value1"}),
+            serde_json::json!({"field":"value2"}),
+        ];
+        assert_eq!(
+            expected,
+            send_and_fetch(format!("unix://{}", path.display()).as_str()).await
+        );
+        std::fs::remove_file(path).unwrap();
+    }
+    #[apply(test!)]
+    async fn fetch_in_connect_mode() {
+        let listener = TcpListener::bind("127.0.0.1:5143").await.unwrap();
+        let server = smol::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            stream
+                .write_all(b"{\"field\":\"value1\"}\n\n{\"field\":\"value2\"}")
+                .await
+                .unwrap();
+        });
+
+        let mut reader = connector("tcp://127.0.0.1:5143");
+        reader.mode = Mode::Connect;
+        let datastream = reader.fetch().await.unwrap().unwrap();
+        server.await;
+
+        let dataset: Vec<Value> = datastream.map(|data| data.to_value()).collect().await;
+        assert_eq!(
+            vec![
+                serde_json::json!({"field":"value1"}),
+                serde_json::json!({"field":"value2"}),
+            ],
+            dataset
+        );
+    }
+    #[apply(test!)]
+    async fn fetch_with_frame_too_large() {
+        let listener = TcpListener::bind("127.0.0.1:5146").await.unwrap();
+        let server = smol::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            stream
+                .write_all(b"{\"field\":\"value1\"}\n{\"field\":\"value_without_terminator\"}")
+                .await
+                .unwrap();
+            // Keep the connection open.
+            stream
+        });
+
+        let mut reader = connector("tcp://127.0.0.1:5146");
+        reader.mode = Mode::Connect;
+        reader.max_frame_size = 20;
+        let datastream = reader.fetch().await.unwrap().unwrap();
+        let _stream = server.await;
+
+        let dataset: Vec<Value> = datastream.map(|data| data.to_value()).collect().await;
+        assert_eq!(vec![serde_json::json!({"field":"value1"})], dataset);
+    }
+    #[apply(test!)]
+    async fn send_with_reconnect() {
+        let listener = TcpListener::bind("127.0.0.1:5144").await.unwrap();
+        let (sender, receiver) = async_channel::unbounded();
+
+        // The server closes the first connection after the first line.
+        let _server = smol::spawn(async move {
+            let mut connections = Vec::default();
+            for index in 0.. {
+                let (stream, _) = listener.accept().await.unwrap();
+                let sender = sender.clone();
+                connections.push(smol::spawn(async move {
+                    let mut lines =
+                        futures::AsyncBufReadExt::lines(futures::io::BufReader::new(stream));
+                    while let Some(Ok(line)) = lines.next().await {
+                        sender.send(line).await.unwrap();
+                        if 0 == index {
+                            break;
+                        }
+                    }
+                }));
+            }
+        });
+
+        let mut writer = connector("tcp://127.0.0.1:5144");
+        writer
+            .send(&vec![DataResult::Ok(serde_json::json!({"field":"value1"}))])
+            .await
+            .unwrap();
+        assert_eq!(r#"{"field":"value1"}"#, receiver.recv().await.unwrap());
+
+        // Wait the server to close the first connection.
+        smol::Timer::after(Duration::from_millis(100)).await;
+        // The first write in a closed connection can succeed, the next one reconnects.
+        for _ in 0..2 {
+            writer
+                .send(&vec![DataResult::Ok(serde_json::json!({"field":"value2"}))])
+                .await
+                .unwrap();
+        }
+        assert_eq!(r#"{"field":"value2"}"#, receiver.recv().await.unwrap());
+    }
+    #[apply(test!)]
+    async fn paginate() {
+        let connector = connector("tcp://127.0.0.1:5145");
+        let mut paging = connector.paginate().await.unwrap();
+        assert!(paging.next().await.transpose().unwrap().is_some());
+        assert!(paging.next().await.transpose().unwrap().is_none());
+    }
+}
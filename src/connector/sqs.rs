@@ -0,0 +1,1192 @@
+//! Receive and send messages with an Amazon SQS queue.
+//!
+//! When the connector reads, it receives the messages of the `queue` with long polling and parses the body of each message with the [`crate::document`].
+//! The information of the message is added in each record in the field `_sqs`.
+//! Each message is deleted once all its records are written with success. If the writer fails, the message becomes visible again and is received
+//! until the `maxReceiveCount` of the redrive policy moves it in the dead-letter queue. A message that can't be parsed is left in the queue for the same reason.
+//! The connector stops to read after `message_limit` messages or if no message is received before the `timeout`. Otherwise, it reads without end.
+//! If the messages can't be received, the connector waits longer after each failure before to retry.
+//!
+//! When the connector writes, it sends the records by batch of ten, each record in its own message.
+//! The `queue`, the message `attributes`, the `group_id` and the `deduplication_id` can be templates rendered with the parameters of the connector and the record in `input`.
+//! The field `_sqs` is removed from the records before to be sent. The records rejected by the queue are returned in error.
+//!
+//! The credentials are read from the `SQS_ACCESS_KEY_ID` and `SQS_SECRET_ACCESS_KEY` variables if set, then from the environment, the profile or the role of the instance like the other AWS clients.
+//!
+//! ### Configuration
+//!
+//! | key                | alias              | Description                                                                        | Default Value | Possible Values      |
+//! | ------------------ | ------------------ | ---------------------------------------------------------------------------------- | ------------- | -------------------- |
+//! | type               | -                  | Required in order to use this connector                                            | `sqs`         | `sqs`                |
+//! | metadata           | meta               | Override metadata information                                                      | `null`        | [`crate::Metadata`]  |
+//! | endpoint           | -                  | Endpoint of the service. Use `SQS_ENDPOINT` or `AWS_ENDPOINT_URL_SQS` if not set   | `null`        | String               |
+//! | region             | -                  | The region of the queue. Use `AWS_REGION` or `AWS_DEFAULT_REGION` if not set       | `us-east-1`   | String               |
+//! | queue              | queue_url / path   | The url or the name of the queue. Can use mustache variables to write              | `null`        | String               |
+//! | wait_time          | long_polling       | Time in second to wait a message in each request. Between `0` and `20`             | `20`          | Unsigned number      |
+//! | visibility_timeout | ack_wait           | Time in second to wait the writer before the message is visible again             | `null`        | Unsigned number      |
+//! | batch_size         | max_messages       | Number of messages to receive in each request. Between `1` and `10`                | `10`          | Unsigned number      |
+//! | attributes         | -                  | The message attributes to send. Can use mustache variables                         | `{}`          | List of Key/Value    |
+//! | group_id           | -                  | The message group of a FIFO queue. Can use mustache variables                      | `null`        | String               |
+//! | deduplication_id   | -                  | The deduplication identifier of a FIFO queue. Can use mustache variables           | `null`        | String               |
+//! | message_limit      | limit              | Stop to read after N messages. Read without limit if not set                       | `null`        | Unsigned number      |
+//! | timeout            | -                  | Time in second without message before to stop to read                              | `null`        | Unsigned number      |
+//! | parameters         | params             | Variables used to render the templates                                             | `null`        | Object               |
+//!
+//! ### Examples
+//!
+//! ```json
+//! [
+//!     {
+//!         "type": "r",
+//!         "connector": {
+//!             "type": "sqs",
+//!             "queue": "https://sqs.eu-west-1.amazonaws.com/000000000000/orders",
+//!             "visibility_timeout": 60
+//!         }
+//!     },
+//!     {
+//!         "type": "w",
+//!         "connector": {
+//!             "type": "sqs",
+//!             "queue": "orders-{{ input.status }}",
+//!             "attributes": {
+//!                 "source": "{{ input._sqs.message_id }}"
+//!             }
+//!         }
+//!     }
+//! ]
+//! ```
+use super::Connector;
+use crate::document::Document;
+use crate::helper::aws::shared_client;
+use crate::helper::mustache::Mustache;
+use crate::helper::string::DisplayOnlyForDebugging;
+use crate::{ConnectorStream, DataResult, DataSet, DataStream, Metadata};
+use async_channel::{Receiver, Sender};
+use async_compat::Compat;
+use async_stream::stream;
+use async_trait::async_trait;
+use aws_sdk_sqs::error::DisplayErrorContext;
+use aws_sdk_sqs::types::{
+    MessageAttributeValue, MessageSystemAttributeName, QueueAttributeName,
+    SendMessageBatchRequestEntry,
+};
+use aws_sdk_sqs::Client;
+use futures::Stream;
+use json_value_merge::Merge;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use smol::Timer;
+use smol_timeout::TimeoutExt;
+use std::collections::HashMap;
+use std::env;
+use std::pin::Pin;
+use std::time::{Duration, Instant};
+use std::{
+    fmt,
+    io::{Error, ErrorKind, Result},
+};
+
+const DEFAULT_REGION: &str = "us-east-1";
+const DEFAULT_WAIT_TIME: i32 = 20;
+const DEFAULT_VISIBILITY_TIMEOUT: u64 = 30;
+const MAX_BATCH_SIZE: usize = 10;
+const RECEIVE_RETRY_DELAY: Duration = Duration::from_secs(1);
+const RECEIVE_RETRY_MAX: u32 = 30;
+pub const FIELD_SQS: &str = "_sqs";
+
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct Sqs {
+    #[serde(skip)]
+    document: Option<Box<dyn Document>>,
+    #[serde(rename = "metadata")]
+    #[serde(alias = "meta")]
+    pub metadata: Metadata,
+    pub endpoint: Option<String>,
+    pub region: Option<String>,
+    #[serde(alias = "queue_url")]
+    #[serde(alias = "path")]
+    pub queue: String,
+    #[serde(alias = "long_polling")]
+    pub wait_time: i32,
+    #[serde(alias = "ack_wait")]
+    pub visibility_timeout: Option<i32>,
+    #[serde(alias = "max_messages")]
+    pub batch_size: i32,
+    pub attributes: HashMap<String, String>,
+    pub group_id: Option<String>,
+    pub deduplication_id: Option<String>,
+    #[serde(alias = "limit")]
+    pub message_limit: Option<usize>,
+    pub timeout: Option<u64>,
+    #[serde(alias = "params")]
+    pub parameters: Box<Value>,
+    // Records of the message received.
+    #[serde(skip)]
+    dataset: Option<DataSet>,
+    #[serde(skip)]
+    acknowledgement: Option<Sender<DataResult>>,
+}
+
+impl fmt::Debug for Sqs {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sqs")
+            .field("document", &self.document.display_only_for_debugging())
+            .field("metadata", &self.metadata.display_only_for_debugging())
+            .field("endpoint", &self.endpoint)
+            .field("region", &self.region)
+            .field("queue", &self.queue)
+            .field("wait_time", &self.wait_time)
+            .field("visibility_timeout", &self.visibility_timeout)
+            .field("batch_size", &self.batch_size)
+            .field("attributes", &self.attributes)
+            .field("group_id", &self.group_id)
+            .field("deduplication_id", &self.deduplication_id)
+            .field("message_limit", &self.message_limit)
+            .field("timeout", &self.timeout)
+            .field("parameters", &self.parameters.display_only_for_debugging())
+            .field("dataset", &self.dataset.display_only_for_debugging())
+            .finish()
+    }
+}
+
+impl Default for Sqs {
+    fn default() -> Self {
+        Sqs {
+            document: None,
+            metadata: Metadata::default(),
+            endpoint: None,
+            region: None,
+            queue: String::default(),
+            wait_time: DEFAULT_WAIT_TIME,
+            visibility_timeout: None,
+            batch_size: MAX_BATCH_SIZE as i32,
+            attributes: HashMap::default(),
+            group_id: None,
+            deduplication_id: None,
+            message_limit: None,
+            timeout: None,
+            parameters: Box::<Value>::default(),
+            dataset: None,
+            acknowledgement: None,
+        }
+    }
+}
+
+/// The redrive policy of a queue.
+#[derive(Debug, Clone, Default)]
+struct RedrivePolicy {
+    dead_letter_queue: Option<String>,
+    max_receive_count: Option<u64>,
+}
+
+impl Sqs {
+    fn region(&self) -> String {
+        match (
+            self.region.clone(),
+            env::var("AWS_REGION"),
+            env::var("AWS_DEFAULT_REGION"),
+        ) {
+            (Some(region), _, _) => region,
+            (None, Ok(region), _) => region,
+            (None, Err(_), Ok(region)) => region,
+            (None, Err(_), Err(_)) => DEFAULT_REGION.to_string(),
+        }
+    }
+    fn endpoint(&self) -> Option<String> {
+        self.endpoint
+            .clone()
+            .or_else(|| env::var("SQS_ENDPOINT").ok())
+            .or_else(|| env::var("AWS_ENDPOINT_URL_SQS").ok())
+    }
+    /// Get the client shared by the connectors with the same endpoint and region.
+    #[instrument(name = "sqs::client")]
+    async fn client(&self) -> Result<Client> {
+        Ok(shared_client(
+            "SQS",
+            self.endpoint(),
+            self.region(),
+            |provider, region, endpoint| {
+                let mut builder = aws_sdk_sqs::Config::builder()
+                    .region(region)
+                    .credentials_provider(provider);
+                if let Some(endpoint) = endpoint {
+                    builder = builder.endpoint_url(endpoint);
+                }
+
+                Client::from_conf(builder.build())
+            },
+        )
+        .await)
+    }
+    /// Get the url of the queue. Resolve it if the queue is a name.
+    async fn queue_url(&self, client: &Client) -> Result<String> {
+        let queue = self.path();
+
+        if queue.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The queue is required by the sqs connector",
+            ));
+        }
+        if queue.starts_with("http://") || queue.starts_with("https://") {
+            return Ok(queue);
+        }
+
+        Compat::new(client.get_queue_url().queue_name(queue.as_str()).send())
+            .await
+            .map_err(|e| Error::new(ErrorKind::NotFound, DisplayErrorContext(e).to_string()))?
+            .queue_url()
+            .map(|url| url.to_string())
+            .ok_or_else(|| {
+                Error::new(
+                    ErrorKind::NotFound,
+                    format!("The url of the queue '{}' is not found", queue),
+                )
+            })
+    }
+    /// Get the redrive policy of the queue in order to know when a message goes to the dead-letter queue.
+    async fn redrive_policy(&self, client: &Client, queue_url: &str) -> RedrivePolicy {
+        let attributes = match Compat::new(
+            client
+                .get_queue_attributes()
+                .queue_url(queue_url)
+                .attribute_names(QueueAttributeName::RedrivePolicy)
+                .send(),
+        )
+        .await
+        {
+            Ok(output) => output,
+            Err(e) => {
+                warn!(
+                    error = DisplayErrorContext(e).to_string().as_str(),
+                    "Can't get the redrive policy of the queue"
+                );
+                return RedrivePolicy::default();
+            }
+        };
+
+        let policy: Value = attributes
+            .attributes()
+            .and_then(|attributes| attributes.get(&QueueAttributeName::RedrivePolicy))
+            .and_then(|policy| serde_json::from_str(policy).ok())
+            .unwrap_or_default();
+
+        RedrivePolicy {
+            dead_letter_queue: policy
+                .get("deadLetterTargetArn")
+                .and_then(Value::as_str)
+                .map(|arn| arn.to_string()),
+            max_receive_count: policy.get("maxReceiveCount").and_then(|count| {
+                count
+                    .as_u64()
+                    .or_else(|| count.as_str().and_then(|count| count.parse().ok()))
+            }),
+        }
+    }
+    /// Parse the body of a message and add the information of the message in each record.
+    fn read(&self, body: &str, information: &Value) -> Result<DataSet> {
+        let document = self.document()?;
+
+        if !document.has_data(body.as_bytes())? {
+            return Ok(DataSet::default());
+        }
+
+        Ok(document
+            .read(body.as_bytes())?
+            .into_iter()
+            .map(|data| with_information(data, information))
+            .collect())
+    }
+    /// Receive the messages of the queue and return a new connector for each message.
+    async fn consume(&self) -> Result<ConnectorStream> {
+        let client = self.client().await?;
+        let queue_url = self.queue_url(&client).await?;
+        let redrive_policy = self.redrive_policy(&client, &queue_url).await;
+        let ack_wait = Duration::from_secs(
+            self.visibility_timeout
+                .map_or(DEFAULT_VISIBILITY_TIMEOUT, |timeout| timeout as u64),
+        );
+
+        match &redrive_policy.dead_letter_queue {
+            Some(dead_letter_queue) => info!(
+                queue = queue_url,
+                dead_letter_queue,
+                max_receive_count = redrive_policy.max_receive_count,
+                "Receive the messages of the queue"
+            ),
+            None => info!(
+                queue = queue_url,
+                "Receive the messages of the queue without dead-letter queue"
+            ),
+        };
+
+        let connector = self.clone();
+        Ok(Box::pin(stream! {
+            let mut count = 0;
+            let mut errors: u32 = 0;
+            let mut last_message = Instant::now();
+
+            while connector.message_limit.is_none_or(|limit| count < limit) {
+                let max_messages = connector.message_limit.map_or(connector.batch_size, |limit| {
+                    connector.batch_size.min((limit - count) as i32)
+                });
+                let mut request = client
+                    .receive_message()
+                    .queue_url(queue_url.as_str())
+                    .max_number_of_messages(max_messages.clamp(1, MAX_BATCH_SIZE as i32))
+                    .wait_time_seconds(connector.wait_time)
+                    .message_system_attribute_names(MessageSystemAttributeName::All)
+                    .message_attribute_names("All");
+                if let Some(visibility_timeout) = connector.visibility_timeout {
+                    request = request.visibility_timeout(visibility_timeout);
+                }
+
+                let messages = match Compat::new(request.send()).await {
+                    Ok(output) => {
+                        errors = 0;
+                        output.messages.unwrap_or_default()
+                    }
+                    Err(e) => {
+                        errors += 1;
+                        warn!(error = DisplayErrorContext(e).to_string().as_str(), retry = errors, "Can't receive the messages, retry later");
+                        Timer::after(RECEIVE_RETRY_DELAY * errors.min(RECEIVE_RETRY_MAX)).await;
+                        Vec::default()
+                    }
+                };
+
+                if messages.is_empty() {
+                    if connector.timeout.is_some_and(|timeout| last_message.elapsed() >= Duration::from_secs(timeout)) {
+                        info!("No message received before the timeout");
+                        break;
+                    }
+                    continue;
+                }
+                last_message = Instant::now();
+
+                for message in messages {
+                    count += 1;
+
+                    let receipt_handle = message.receipt_handle().unwrap_or_default().to_string();
+                    let information = information(&message);
+                    let receive_count = information.get("receive_count").and_then(Value::as_u64).unwrap_or(1);
+
+                    let dataset = match connector.read(message.body().unwrap_or_default(), &information) {
+                        Ok(dataset) => dataset,
+                        Err(e) => {
+                            warn!(error = e.to_string().as_str(), message_id = message.message_id(), receive_count, "Can't parse the message, it stays in the queue");
+                            continue;
+                        }
+                    };
+
+                    let (sender, receiver) = async_channel::unbounded();
+                    smol::spawn(acknowledge(
+                        client.clone(),
+                        queue_url.clone(),
+                        receipt_handle,
+                        receive_count,
+                        redrive_policy.clone(),
+                        receiver,
+                        dataset.len(),
+                        ack_wait,
+                    ))
+                    .detach();
+
+                    let mut connector = connector.clone();
+                    connector.dataset = Some(dataset);
+                    connector.acknowledgement = Some(sender);
+
+                    trace!("The stream yields a new connector");
+                    yield Ok(Box::new(connector) as Box<dyn Connector>);
+                }
+            }
+
+            info!(messages = count, "Stop to receive the messages");
+        }))
+    }
+    /// Render a template with the parameters of the connector and the record in `input`.
+    fn render(&self, template: &str, value: &Value) -> String {
+        let mut text = template.to_string();
+        if !text.has_mustache() {
+            return text;
+        }
+
+        let mut params = *self.parameters.clone();
+        params.merge(&serde_json::json!({
+            "input": value,
+            "metadata": self.metadata()
+        }));
+
+        text.replace_mustache(params);
+        text
+    }
+    /// Build the message of a record.
+    fn entry(&self, id: usize, value: &Value) -> Result<SendMessageBatchRequestEntry> {
+        let document = self.document()?;
+        let body = String::from_utf8(document.write(&vec![DataResult::Ok(value.clone())])?)
+            .map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+
+        let mut entry = SendMessageBatchRequestEntry::builder()
+            .id(id.to_string())
+            .message_body(body);
+
+        for (name, template) in &self.attributes {
+            entry = entry.message_attributes(
+                name,
+                MessageAttributeValue::builder()
+                    .data_type("String")
+                    .string_value(self.render(template, value))
+                    .build()
+                    .map_err(|e| Error::new(ErrorKind::InvalidInput, e))?,
+            );
+        }
+        if let Some(group_id) = &self.group_id {
+            entry = entry.message_group_id(self.render(group_id, value));
+        }
+        if let Some(deduplication_id) = &self.deduplication_id {
+            entry = entry.message_deduplication_id(self.render(deduplication_id, value));
+        }
+
+        entry
+            .build()
+            .map_err(|e| Error::new(ErrorKind::InvalidInput, e))
+    }
+}
+
+/// Get the information of the message added in the records.
+fn information(message: &aws_sdk_sqs::types::Message) -> Value {
+    let mut information = Map::default();
+    information.insert("message_id".to_string(), message.message_id().into());
+
+    let system_attributes = message.attributes().cloned().unwrap_or_default();
+    information.insert(
+        "receive_count".to_string(),
+        system_attributes
+            .get(&MessageSystemAttributeName::ApproximateReceiveCount)
+            .and_then(|count| count.parse::<u64>().ok())
+            .into(),
+    );
+    if let Some(group_id) = system_attributes.get(&MessageSystemAttributeName::MessageGroupId) {
+        information.insert("group_id".to_string(), Value::String(group_id.clone()));
+    }
+
+    let attributes: Map<String, Value> = message
+        .message_attributes()
+        .map(|attributes| {
+            attributes
+                .iter()
+                .map(|(name, attribute)| (name.clone(), attribute.string_value().into()))
+                .collect()
+        })
+        .unwrap_or_default();
+    information.insert("attributes".to_string(), Value::Object(attributes));
+
+    Value::Object(information)
+}
+
+/// Add the information of the message in the record.
+fn with_information(data: DataResult, information: &Value) -> DataResult {
+    let add_information = |mut value: Value| {
+        if let Value::Object(map) = &mut value {
+            map.insert(FIELD_SQS.to_string(), information.clone());
+        }
+        value
+    };
+
+    match data {
+        DataResult::Ok(value) => DataResult::Ok(add_information(value)),
+        DataResult::Err((value, e)) => DataResult::Err((add_information(value), e)),
+    }
+}
+
+/// Wait the result of the writer for each record of the message. Delete the message if all its records are written with success, otherwise make it visible again.
+#[allow(clippy::too_many_arguments)]
+async fn acknowledge(
+    client: Client,
+    queue_url: String,
+    receipt_handle: String,
+    receive_count: u64,
+    redrive_policy: RedrivePolicy,
+    receiver: Receiver<DataResult>,
+    expected: usize,
+    ack_wait: Duration,
+) {
+    let results = async {
+        let mut received = 0;
+        let mut errors = 0;
+        // Stop if all the contexts are dropped, the missing records never reach the writer.
+        while received < expected {
+            match receiver.recv().await {
+                Ok(result) => {
+                    received += 1;
+                    if result.is_type(DataResult::ERR) {
+                        errors += 1;
+                    }
+                }
+                Err(_) => break,
+            }
+        }
+        (received, errors)
+    }
+    .timeout(ack_wait)
+    .await;
+
+    let result = match results {
+        Some((received, 0)) if received == expected => Compat::new(
+            client
+                .delete_message()
+                .queue_url(queue_url)
+                .receipt_handle(receipt_handle)
+                .send(),
+        )
+        .await
+        .map(|_| ())
+        .map_err(|e| DisplayErrorContext(e).to_string()),
+        Some((received, _)) => {
+            if received < expected {
+                warn!(
+                    received,
+                    expected, "The pipeline stopped before writing all the records of the message"
+                );
+            }
+            match (
+                &redrive_policy.dead_letter_queue,
+                redrive_policy.max_receive_count,
+            ) {
+                (Some(dead_letter_queue), Some(max)) if receive_count >= max => warn!(
+                    dead_letter_queue,
+                    receive_count,
+                    "The message failed too many times, it goes to the dead-letter queue"
+                ),
+                _ => warn!(
+                    receive_count,
+                    "The message failed, it will be received again"
+                ),
+            };
+            Compat::new(
+                client
+                    .change_message_visibility()
+                    .queue_url(queue_url)
+                    .receipt_handle(receipt_handle)
+                    .visibility_timeout(0)
+                    .send(),
+            )
+            .await
+            .map(|_| ())
+            .map_err(|e| DisplayErrorContext(e).to_string())
+        }
+        None => {
+            warn!("The writer didn't return the results before the visibility timeout, the message will be received again");
+            return;
+        }
+    };
+
+    if let Err(e) = result {
+        warn!(error = e.as_str(), "Can't acknowledge the message");
+    }
+}
+
+#[async_trait]
+impl Connector for Sqs {
+    /// See [`Connector::set_document`] for more details.
+    fn set_document(&mut self, document: Box<dyn Document>) -> Result<()> {
+        self.document = Some(document.clone());
+
+        Ok(())
+    }
+    /// See [`Connector::document`] for more details.
+    fn document(&self) -> Result<&dyn Document> {
+        self.document.as_deref().ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                "The document has not been set in the connector",
+            )
+        })
+    }
+    /// See [`Connector::set_parameters`] for more details.
+    fn set_parameters(&mut self, parameters: Value) {
+        *self.parameters = parameters;
+    }
+    /// See [`Connector::is_variable`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::sqs::Sqs;
+    /// use chewdata::connector::Connector;
+    ///
+    /// let mut connector = Sqs::default();
+    /// connector.queue = "orders".to_string();
+    /// assert_eq!(false, connector.is_variable());
+    /// connector.queue = "orders-{{ status }}".to_string();
+    /// assert_eq!(true, connector.is_variable());
+    /// ```
+    fn is_variable(&self) -> bool {
+        self.queue.has_mustache()
+    }
+    /// See [`Connector::is_resource_will_change`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::sqs::Sqs;
+    /// use chewdata::connector::Connector;
+    /// use serde_json::Value;
+    ///
+    /// let mut connector = Sqs::default();
+    /// let params = serde_json::from_str(r#"{"status":"created"}"#).unwrap();
+    /// connector.queue = "orders".to_string();
+    /// assert_eq!(false, connector.is_resource_will_change(Value::Null).unwrap());
+    /// connector.queue = "orders-{{ status }}".to_string();
+    /// assert_eq!(true, connector.is_resource_will_change(params).unwrap());
+    /// ```
+    fn is_resource_will_change(&self, new_parameters: Value) -> Result<bool> {
+        if !self.is_variable() {
+            trace!("Stay link to the same resource");
+            return Ok(false);
+        }
+
+        let mut metadata_kv = Map::default();
+        metadata_kv.insert("metadata".to_string(), self.metadata().into());
+        let metadata = Value::Object(metadata_kv);
+
+        let mut new_parameters = new_parameters;
+        new_parameters.merge(&metadata);
+        let mut old_parameters = *self.parameters.clone();
+        old_parameters.merge(&metadata);
+
+        let mut previous_queue = self.queue.clone();
+        previous_queue.replace_mustache(old_parameters);
+
+        let mut new_queue = self.queue.clone();
+        new_queue.replace_mustache(new_parameters);
+
+        if previous_queue == new_queue {
+            trace!(queue = previous_queue, "Queue didn't change");
+            return Ok(false);
+        }
+
+        info!(
+            previous_queue = previous_queue,
+            new_queue = new_queue,
+            "Queue will change"
+        );
+
+        Ok(true)
+    }
+    /// See [`Connector::path`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::sqs::Sqs;
+    /// use chewdata::connector::Connector;
+    ///
+    /// let mut connector = Sqs::default();
+    /// connector.queue = "orders-{{ status }}".to_string();
+    /// connector.set_parameters(serde_json::from_str(r#"{"status":"created"}"#).unwrap());
+    /// assert_eq!("orders-created", connector.path());
+    /// ```
+    fn path(&self) -> String {
+        if !self.is_variable() {
+            return self.queue.clone();
+        }
+
+        let mut params = *self.parameters.clone();
+        params.merge(&serde_json::json!({
+            "metadata": self.metadata()
+        }));
+
+        let mut queue = self.queue.clone();
+        queue.replace_mustache(params);
+        queue
+    }
+    /// See [`Connector::metadata`] for more details.
+    fn metadata(&self) -> Metadata {
+        match &self.document {
+            Some(document) => self.metadata.clone().merge(&document.metadata()),
+            None => self.metadata.clone(),
+        }
+    }
+    /// See [`Connector::len`] for more details.
+    async fn len(&self) -> Result<usize> {
+        Ok(self.dataset.as_ref().map_or(0, |dataset| dataset.len()))
+    }
+    /// See [`Connector::acknowledgement`] for more details.
+    fn acknowledgement(&self) -> Option<Sender<DataResult>> {
+        self.acknowledgement.clone()
+    }
+    /// See [`Connector::fetch`] for more details.
+    ///
+    /// The messages are received by the paginator, the connector yields the records of its message.
+    #[instrument(name = "sqs::fetch")]
+    async fn fetch(&mut self) -> Result<Option<DataStream>> {
+        let dataset = match self.dataset.take() {
+            Some(dataset) => dataset,
+            None => return Ok(None),
+        };
+
+        Ok(Some(Box::pin(stream! {
+            for data in dataset {
+                yield data;
+            }
+        })))
+    }
+    /// See [`Connector::send`] for more details.
+    ///
+    /// Send each record in its own message by batch of ten messages.
+    #[instrument(name = "sqs::send", skip(dataset))]
+    async fn send(&mut self, dataset: &DataSet) -> Result<Option<DataStream>> {
+        if dataset.is_empty() {
+            return Ok(Some(Box::pin(futures::stream::empty())));
+        }
+
+        let client = self.client().await?;
+        let queue_url = self.queue_url(&client).await?;
+
+        let mut results = Vec::default();
+        for records in dataset.chunks(MAX_BATCH_SIZE) {
+            let entries = records
+                .iter()
+                .enumerate()
+                .map(|(index, data)| {
+                    let mut value = data.to_value();
+                    if let Value::Object(map) = &mut value {
+                        map.remove(FIELD_SQS);
+                    }
+                    self.entry(index, &value)
+                })
+                .collect::<Result<Vec<SendMessageBatchRequestEntry>>>()?;
+
+            let output = Compat::new(
+                client
+                    .send_message_batch()
+                    .queue_url(queue_url.as_str())
+                    .set_entries(Some(entries))
+                    .send(),
+            )
+            .await
+            .map_err(|e| Error::new(ErrorKind::Interrupted, DisplayErrorContext(e).to_string()))?;
+
+            let mut failures: HashMap<String, Error> = output
+                .failed()
+                .iter()
+                .map(|failure| {
+                    let kind = match failure.sender_fault() {
+                        true => ErrorKind::InvalidInput,
+                        false => ErrorKind::Interrupted,
+                    };
+                    (
+                        failure.id().to_string(),
+                        Error::new(
+                            kind,
+                            format!(
+                                "The queue rejects the message with the code '{}'. {}",
+                                failure.code(),
+                                failure.message().unwrap_or_default()
+                            ),
+                        ),
+                    )
+                })
+                .collect();
+
+            for (index, data) in records.iter().enumerate() {
+                results.push(match failures.remove(&index.to_string()) {
+                    Some(e) => DataResult::Err((data.to_value(), e)),
+                    None => data.clone(),
+                });
+            }
+        }
+
+        info!(
+            queue = queue_url,
+            messages = results
+                .iter()
+                .filter(|data| data.is_type(DataResult::OK))
+                .count(),
+            errors = results
+                .iter()
+                .filter(|data| data.is_type(DataResult::ERR))
+                .count(),
+            "Messages sent"
+        );
+
+        Ok(Some(Box::pin(stream! {
+            for data in results {
+                yield data;
+            }
+        })))
+    }
+    /// See [`Connector::has_send_results`] for more details.
+    fn has_send_results(&self) -> bool {
+        true
+    }
+    /// See [`Connector::erase`] for more details.
+    ///
+    /// Purge the messages of the queue.
+    #[instrument(name = "sqs::erase")]
+    async fn erase(&mut self) -> Result<()> {
+        let client = self.client().await?;
+        let queue_url = self.queue_url(&client).await?;
+
+        Compat::new(client.purge_queue().queue_url(queue_url.as_str()).send())
+            .await
+            .map_err(|e| Error::new(ErrorKind::Interrupted, DisplayErrorContext(e).to_string()))?;
+
+        info!(queue = queue_url, "Queue purged with success");
+
+        Ok(())
+    }
+    /// See [`Connector::paginate`] for more details.
+    ///
+    /// Return a new connector for each message received.
+    #[instrument(name = "sqs::paginate")]
+    async fn paginate(
+        &self,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<Box<dyn Connector>>> + Send>>> {
+        self.consume().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::json::Json;
+    use crate::step::StepType;
+    use futures::StreamExt;
+    use macro_rules_attribute::apply;
+    use smol::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+    use smol::net::TcpListener;
+    use smol_macros::test;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+
+    type Handler = Arc<dyn Fn(&str, &Value) -> Value + Send + Sync>;
+
+    /// Start an SQS server that answers each action with the handler and returns the actions received.
+    async fn sqs_server(handler: Handler) -> (String, Receiver<(String, Value)>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        let (sender, receiver) = async_channel::unbounded();
+
+        smol::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let (sender, handler) = (sender.clone(), handler.clone());
+                smol::spawn(async move {
+                    let mut reader = BufReader::new(stream.clone());
+                    let mut writer = stream;
+                    loop {
+                        let (mut action, mut length) = (String::default(), 0);
+                        loop {
+                            let mut line = String::default();
+                            if 0 == reader.read_line(&mut line).await.unwrap_or(0) {
+                                return;
+                            }
+                            match line.trim_end().split_once(':') {
+                                Some((name, value)) if name.eq_ignore_ascii_case("x-amz-target") => {
+                                    action = value.trim().trim_start_matches("AmazonSQS.").to_string()
+                                }
+                                Some((name, value)) if name.eq_ignore_ascii_case("content-length") => {
+                                    length = value.trim().parse().unwrap()
+                                }
+                                Some(_) => (),
+                                None if line.trim_end().is_empty() => break,
+                                None => (),
+                            }
+                        }
+                        let mut body = vec![0; length];
+                        reader.read_exact(&mut body).await.unwrap();
+                        let request: Value = serde_json::from_slice(&body).unwrap_or_default();
+                        let response = handler(&action, &request).to_string();
+                        sender.send((action, request)).await.unwrap();
+                        writer
+                            .write_all(
+                                format!(
+                                    "HTTP/1.1 200 OK\r\nContent-Type: application/x-amz-json-1.0\r\nContent-Length: {}\r\n\r\n{}",
+                                    response.len(),
+                                    response
+                                )
+                                .as_bytes(),
+                            )
+                            .await
+                            .unwrap();
+                    }
+                })
+                .detach();
+            }
+        })
+        .detach();
+
+        (endpoint, receiver)
+    }
+
+    /// Answer with two messages to the first receive and without message after.
+    fn queue_with_messages() -> Handler {
+        let is_received = AtomicBool::new(false);
+        Arc::new(move |action, _| match action {
+            "GetQueueAttributes" => serde_json::json!({"Attributes": {
+                "RedrivePolicy": "{\"deadLetterTargetArn\":\"arn:aws:sqs:us-east-1:000000000000:orders-dlq\",\"maxReceiveCount\":\"3\"}"
+            }}),
+            "ReceiveMessage" if !is_received.swap(true, Ordering::SeqCst) => {
+                serde_json::json!({"Messages": [
+                    {
+                        "MessageId": "id-1",
+                        "ReceiptHandle": "handle-1",
+                        "Body": "{\"field\":\"value1\"}",
+                        "Attributes": {"ApproximateReceiveCount": "1"},
+                        "MessageAttributes": {"type": {"DataType": "String", "StringValue": "order"}}
+                    },
+                    {
+                        "MessageId": "id-2",
+                        "ReceiptHandle": "handle-2",
+                        "Body": "{\"field\":\"value2\"}",
+                        "Attributes": {"ApproximateReceiveCount": "3"}
+                    }
+                ]})
+            }
+            _ => serde_json::json!({}),
+        })
+    }
+
+    fn connector(endpoint: String) -> Sqs {
+        std::env::set_var("AWS_ACCESS_KEY_ID", "test");
+        std::env::set_var("AWS_SECRET_ACCESS_KEY", "test");
+
+        let mut connector = Sqs {
+            endpoint: Some(endpoint.clone()),
+            region: Some("us-east-1".to_string()),
+            queue: format!("{}/000000000000/orders", endpoint),
+            wait_time: 0,
+            timeout: Some(1),
+            ..Default::default()
+        };
+        connector.set_document(Box::new(Json::default())).unwrap();
+        connector
+    }
+
+    fn actions(receiver: &Receiver<(String, Value)>, action: &str) -> Vec<Value> {
+        std::iter::from_fn(|| receiver.try_recv().ok())
+            .filter(|(name, _)| action == name)
+            .map(|(_, request)| request)
+            .collect()
+    }
+
+    #[apply(test!)]
+    async fn send() {
+        let (endpoint, receiver) = sqs_server(Arc::new(|action, _| match action {
+            "SendMessageBatch" => serde_json::json!({
+                "Successful": [{"Id": "0", "MessageId": "id-1", "MD5OfMessageBody": "md5"}],
+                "Failed": [{"Id": "1", "SenderFault": true, "Code": "InvalidParameterValue", "Message": "Too big"}]
+            }),
+            _ => serde_json::json!({}),
+        }))
+        .await;
+        let mut connector = connector(endpoint);
+        connector.attributes =
+            HashMap::from([("type".to_string(), "{{ input.type }}".to_string())]);
+
+        let results: Vec<DataResult> = connector
+            .send(&vec![
+                DataResult::Ok(serde_json::json!({"type":"order","_sqs":{"message_id":"id-0"}})),
+                DataResult::Ok(serde_json::json!({"type":"refund"})),
+            ])
+            .await
+            .unwrap()
+            .unwrap()
+            .collect()
+            .await;
+
+        assert!(results[0].is_type(DataResult::OK));
+        match &results[1] {
+            DataResult::Err((value, e)) => {
+                assert_eq!(serde_json::json!({"type":"refund"}), *value);
+                assert_eq!(ErrorKind::InvalidInput, e.kind());
+            }
+            _ => panic!("The second message must be rejected"),
+        };
+
+        let requests = actions(&receiver, "SendMessageBatch");
+        assert_eq!(
+            serde_json::json!([
+                {
+                    "Id": "0",
+                    "MessageBody": "{\"type\":\"order\"}",
+                    "MessageAttributes": {"type": {"DataType": "String", "StringValue": "order"}}
+                },
+                {
+                    "Id": "1",
+                    "MessageBody": "{\"type\":\"refund\"}",
+                    "MessageAttributes": {"type": {"DataType": "String", "StringValue": "refund"}}
+                }
+            ]),
+            requests[0]["Entries"]
+        );
+    }
+    #[apply(test!)]
+    async fn send_with_queue_name() {
+        let (endpoint, receiver) = sqs_server(Arc::new(|action, _| match action {
+            "GetQueueUrl" => {
+                serde_json::json!({"QueueUrl": "http://localhost/000000000000/orders-created"})
+            }
+            "SendMessageBatch" => serde_json::json!({"Successful": [], "Failed": []}),
+            _ => serde_json::json!({}),
+        }))
+        .await;
+        let mut connector = connector(endpoint);
+        connector.queue = "orders-{{ input.status }}".to_string();
+        connector.set_parameters(serde_json::json!({"input":{"status":"created"}}));
+
+        connector
+            .send(&vec![DataResult::Ok(
+                serde_json::json!({"status":"created"}),
+            )])
+            .await
+            .unwrap();
+
+        assert_eq!(
+            serde_json::json!("orders-created"),
+            actions(&receiver, "GetQueueUrl")[0]["QueueName"]
+        );
+    }
+    #[apply(test!)]
+    async fn paginate_and_acknowledge() {
+        let (endpoint, receiver) = sqs_server(queue_with_messages()).await;
+        let connector = connector(endpoint);
+
+        let mut paging = connector.paginate().await.unwrap();
+
+        let mut first = paging.next().await.unwrap().unwrap();
+        let dataset: Vec<DataResult> = first.fetch().await.unwrap().unwrap().collect().await;
+        assert_eq!(
+            serde_json::json!({"field":"value1","_sqs":{
+                "message_id":"id-1",
+                "receive_count":1,
+                "attributes":{"type":"order"}
+            }}),
+            dataset[0].to_value()
+        );
+        first
+            .acknowledgement()
+            .unwrap()
+            .send(dataset[0].clone())
+            .await
+            .unwrap();
+
+        let mut second = paging.next().await.unwrap().unwrap();
+        let dataset: Vec<DataResult> = second.fetch().await.unwrap().unwrap().collect().await;
+        second
+            .acknowledgement()
+            .unwrap()
+            .send(DataResult::Err((
+                dataset[0].to_value(),
+                Error::other("Can't write the record"),
+            )))
+            .await
+            .unwrap();
+
+        assert!(paging.next().await.is_none(), "The queue must be empty");
+
+        smol::Timer::after(Duration::from_millis(200)).await;
+        let requests: Vec<(String, Value)> =
+            std::iter::from_fn(|| receiver.try_recv().ok()).collect();
+        assert!(requests.iter().any(|(action, request)| {
+            "DeleteMessage" == action && "handle-1" == request["ReceiptHandle"]
+        }));
+        assert!(requests.iter().any(|(action, request)| {
+            "ChangeMessageVisibility" == action
+                && "handle-2" == request["ReceiptHandle"]
+                && 0 == request["VisibilityTimeout"]
+        }));
+    }
+    #[apply(test!)]
+    async fn paginate_and_acknowledge_with_missing_results() {
+        let is_received = AtomicBool::new(false);
+        let (endpoint, receiver) = sqs_server(Arc::new(move |action, _| match action {
+            "ReceiveMessage" if !is_received.swap(true, Ordering::SeqCst) => {
+                serde_json::json!({"Messages": [{
+                    "MessageId": "id-1",
+                    "ReceiptHandle": "handle-1",
+                    "Body": "[{\"field\":\"value1\"},{\"field\":\"value2\"}]"
+                }]})
+            }
+            _ => serde_json::json!({}),
+        }))
+        .await;
+        let connector = connector(endpoint);
+
+        let mut paging = connector.paginate().await.unwrap();
+
+        let mut first = paging.next().await.unwrap().unwrap();
+        let dataset: Vec<DataResult> = first.fetch().await.unwrap().unwrap().collect().await;
+        assert_eq!(2, dataset.len());
+        // The pipeline stops after the first record.
+        first
+            .acknowledgement()
+            .unwrap()
+            .send(dataset[0].clone())
+            .await
+            .unwrap();
+        drop(first);
+
+        assert!(paging.next().await.is_none(), "The queue must be empty");
+
+        smol::Timer::after(Duration::from_millis(200)).await;
+        let requests: Vec<(String, Value)> =
+            std::iter::from_fn(|| receiver.try_recv().ok()).collect();
+        assert!(!requests.iter().any(|(action, _)| "DeleteMessage" == action));
+        assert!(requests.iter().any(|(action, request)| {
+            "ChangeMessageVisibility" == action && "handle-1" == request["ReceiptHandle"]
+        }));
+    }
+    #[apply(test!)]
+    async fn exec() {
+        let (endpoint, receiver) = sqs_server(queue_with_messages()).await;
+        std::env::set_var("AWS_ACCESS_KEY_ID", "test");
+        std::env::set_var("AWS_SECRET_ACCESS_KEY", "test");
+
+        let config = format!(
+            r#"[
+                {{
+                    "type": "r",
+                    "connector": {{
+                        "type": "sqs",
+                        "endpoint": "{0}",
+                        "region": "us-east-1",
+                        "queue": "{0}/000000000000/orders",
+                        "wait_time": 0,
+                        "message_limit": 2
+                    }}
+                }},
+                {{
+                    "type": "w",
+                    "connector": {{
+                        "type": "in_memory"
+                    }},
+                    "record_limit": 1
+                }}
+            ]"#,
+            endpoint
+        );
+        let steps: Vec<StepType> = serde_json::from_str(config.as_str()).unwrap();
+        crate::exec(steps, None, None).await.unwrap();
+
+        // Wait the acknowledgements.
+        smol::Timer::after(Duration::from_millis(500)).await;
+
+        assert_eq!(
+            2,
+            actions(&receiver, "DeleteMessage").len(),
+            "All the messages must be deleted"
+        );
+    }
+    #[apply(test!)]
+    async fn erase() {
+        let (endpoint, receiver) = sqs_server(Arc::new(|_, _| serde_json::json!({}))).await;
+        let mut connector = connector(endpoint);
+
+        connector.erase().await.unwrap();
+
+        assert_eq!(1, actions(&receiver, "PurgeQueue").len());
+    }
+}
@@ -0,0 +1,794 @@
+//! Read and write messages through a WebSocket connection.
+//!
+//! The connection reuses the TCP/TLS stack of the [`crate::connector::curl`] connector and the same authenticators.
+//!
+//! When the connector reads, it connects to the `endpoint`, sends the `subscription` message if it is set and parses each text or binary frame received with the [`crate::document`].
+//! If the connection is lost, the connector reconnects with an exponential backoff and sends the `subscription` message again.
+//! A ping is sent every `ping_interval` seconds and the connection is considered as lost if the server doesn't answer with a pong before the next ping.
+//! The connector stops to read after `message_limit` messages, if no message is received before the `timeout` or if it can't reconnect after `retry` attempts. Otherwise, it reads without end.
+//!
+//! When the connector writes, it sends each record in its own frame. The frame is a text frame if the document produces UTF-8 content, otherwise a binary frame.
+//!
+//! ### Configuration
+//!
+//! | key           | alias   | Description                                                                              | Default Value | Possible Values                                                                                                                                          |
+//! | ------------- | ------- | ---------------------------------------------------------------------------------------- | ------------- | -------------------------------------------------------------------------------------------------------------------------------------------------------- |
+//! | type          | -       | Required in order to use this connector                                                  | `websocket`   | `websocket` / `ws`                                                                                                                                       |
+//! | metadata      | meta    | Override metadata information                                                            | `null`        | [`crate::Metadata`]                                                                                                                                      |
+//! | authenticator | auth    | Define the authentification that secure the connection                                   | `null`        | [`crate::connector::authenticator::basic::Basic`] / [`crate::connector::authenticator::bearer::Bearer`] / [`crate::connector::authenticator::jwt::Jwt`] |
+//! | endpoint      | -       | The endpoint of the server like <ws://my_site.com:80>                                    | `null`        | String                                                                                                                                                   |
+//! | path          | uri     | The path of the resource. Can use mustache variables                                     | `null`        | String                                                                                                                                                   |
+//! | headers       | -       | The http headers added to the handshake request                                          | `null`        | List of key/value                                                                                                                                        |
+//! | subscription  | message | The message sent after each connection. Can use mustache variables                       | `null`        | String                                                                                                                                                   |
+//! | ping_interval | ping    | Time in second between two pings. Disable the keepalive if `null`                        | `30`          | Unsigned number                                                                                                                                          |
+//! | message_limit | limit   | Stop to read after N messages. Read without limit if not set                             | `null`        | Unsigned number                                                                                                                                          |
+//! | timeout       | -       | Time in second without message before to stop to read. Also used to abort the connection | `null`        | Unsigned number                                                                                                                                          |
+//! | retry         | -       | Number of reconnection attempts before to stop                                           | `3`           | Unsigned number                                                                                                                                          |
+//! | certificate   | crt     | Path to a local certificate file used to trust the WSS connection                        | `null`        | Local path of a .crt file                                                                                                                                |
+//! | parameters    | params  | Variables used to render the `path` and the `subscription`                               | `null`        | Object                                                                                                                                                   |
+//!
+//! ### Examples
+//!
+//! ```json
+//! [
+//!     {
+//!         "type": "r",
+//!         "connector": {
+//!             "type": "websocket",
+//!             "endpoint": "wss://stream.my_site.com",
+//!             "path": "/ws",
+//!             "authenticator": {
+//!                 "type": "bearer",
+//!                 "token": "abcd1234"
+//!             },
+//!             "subscription": "{\"method\":\"subscribe\",\"channel\":\"{{ channel }}\"}",
+//!             "parameters": {
+//!                 "channel": "trades"
+//!             },
+//!             "message_limit": 100
+//!         }
+//!     }
+//! ]
+//! ```
+use super::authenticator::AuthenticatorType;
+use super::curl::{backoff, connect, SmolStream};
+use super::Connector;
+use crate::connector::paginator::once::Once;
+use crate::document::Document;
+use crate::helper::mustache::Mustache;
+use crate::helper::string::{DisplayOnlyForDebugging, Obfuscate};
+use crate::{DataResult, DataSet, DataStream, Metadata};
+use async_stream::stream;
+use async_trait::async_trait;
+use async_tungstenite::tungstenite::client::IntoClientRequest;
+use async_tungstenite::tungstenite::Message;
+use async_tungstenite::{client_async, WebSocketStream};
+use futures::{Stream, StreamExt};
+use http::{HeaderMap, HeaderName, HeaderValue};
+use json_value_merge::Merge;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use smol::future::FutureExt;
+use smol::Timer;
+use smol_timeout::TimeoutExt;
+use std::pin::Pin;
+use std::time::{Duration, Instant};
+use std::{
+    fmt,
+    io::{Error, ErrorKind, Result},
+};
+
+const DEFAULT_TIMEOUT: u64 = 5;
+const DEFAULT_PING_INTERVAL: u64 = 30;
+const DEFAULT_RETRY: usize = 3;
+const RETRY_DELAY: Duration = Duration::from_millis(500);
+
+type WebSocket = WebSocketStream<SmolStream>;
+
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct Websocket {
+    #[serde(skip)]
+    document: Option<Box<dyn Document>>,
+    #[serde(rename = "metadata")]
+    #[serde(alias = "meta")]
+    pub metadata: Metadata,
+    #[serde(alias = "auth")]
+    #[serde(rename = "authenticator")]
+    pub authenticator_type: Option<Box<AuthenticatorType>>,
+    pub endpoint: String,
+    #[serde(alias = "uri")]
+    pub path: String,
+    #[serde(with = "http_serde::header_map")]
+    pub headers: HeaderMap,
+    #[serde(alias = "message")]
+    pub subscription: Option<String>,
+    #[serde(alias = "ping")]
+    pub ping_interval: Option<u64>,
+    #[serde(alias = "limit")]
+    pub message_limit: Option<usize>,
+    pub timeout: Option<u64>,
+    pub retry: usize,
+    #[serde(alias = "crt")]
+    pub certificate: Option<String>,
+    #[serde(alias = "params")]
+    pub parameters: Box<Value>,
+}
+
+impl fmt::Debug for Websocket {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Websocket")
+            .field("document", &self.document.display_only_for_debugging())
+            .field("metadata", &self.metadata.display_only_for_debugging())
+            .field(
+                "authenticator_type",
+                &self.authenticator_type.display_only_for_debugging(),
+            )
+            .field("endpoint", &self.endpoint.to_obfuscate())
+            .field("path", &self.path)
+            // Can contain sensitive data
+            .field("headers", &self.headers.display_only_for_debugging())
+            .field(
+                "subscription",
+                &self.subscription.display_only_for_debugging(),
+            )
+            .field("ping_interval", &self.ping_interval)
+            .field("message_limit", &self.message_limit)
+            .field("timeout", &self.timeout)
+            .field("retry", &self.retry)
+            .field("certificate", &self.certificate)
+            .field("parameters", &self.parameters.display_only_for_debugging())
+            .finish()
+    }
+}
+
+impl Default for Websocket {
+    fn default() -> Self {
+        Websocket {
+            document: None,
+            metadata: Metadata::default(),
+            authenticator_type: None,
+            endpoint: String::default(),
+            path: String::default(),
+            headers: HeaderMap::default(),
+            subscription: None,
+            ping_interval: Some(DEFAULT_PING_INTERVAL),
+            message_limit: None,
+            timeout: None,
+            retry: DEFAULT_RETRY,
+            certificate: None,
+            parameters: Box::<Value>::default(),
+        }
+    }
+}
+
+/// Event that wakes up the reading loop.
+enum Event {
+    Frame(Option<std::result::Result<Message, async_tungstenite::tungstenite::Error>>),
+    Ping,
+    Idle,
+}
+
+impl Websocket {
+    /// Get the parameters used to render the templates.
+    fn parameters(&self) -> Value {
+        let mut params = *self.parameters.clone();
+        params.merge(&serde_json::json!({
+            "metadata": self.metadata()
+        }));
+        params
+    }
+    /// Get the subscription message rendered with the parameters.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::websocket::Websocket;
+    /// use chewdata::connector::Connector;
+    ///
+    /// let mut connector = Websocket::default();
+    /// assert_eq!(None, connector.subscription());
+    /// connector.subscription = Some(r#"{"channel":"{{ channel }}"}"#.to_string());
+    /// connector.set_parameters(serde_json::from_str(r#"{"channel":"trades"}"#).unwrap());
+    /// assert_eq!(Some(r#"{"channel":"trades"}"#.to_string()), connector.subscription());
+    /// ```
+    pub fn subscription(&self) -> Option<String> {
+        let mut subscription = self.subscription.clone()?;
+        if subscription.has_mustache() {
+            subscription.replace_mustache(self.parameters());
+        }
+        Some(subscription)
+    }
+    /// Open the connection and do the handshake.
+    async fn connect(&self) -> Result<WebSocket> {
+        let url = format!("{}{}", self.endpoint, self.path());
+        let timeout = self.timeout.unwrap_or(DEFAULT_TIMEOUT);
+
+        let mut request = url
+            .as_str()
+            .into_client_request()
+            .map_err(|e| Error::new(ErrorKind::InvalidInput, e))?;
+
+        for (header_name, header_value) in self.headers.iter() {
+            request
+                .headers_mut()
+                .insert(header_name.clone(), header_value.clone());
+        }
+
+        if let Some(authenticator_type) = &self.authenticator_type {
+            let (auth_name, auth_value) = authenticator_type.authenticator().authenticate().await?;
+            request.headers_mut().insert(
+                HeaderName::from_bytes(&auth_name)
+                    .map_err(|e| Error::new(ErrorKind::InvalidData, e))?,
+                HeaderValue::from_bytes(&auth_value)
+                    .map_err(|e| Error::new(ErrorKind::InvalidData, e))?,
+            );
+        }
+
+        let stream = connect(&url, timeout, self.certificate.clone()).await?;
+
+        let (websocket, response) = client_async(request, stream)
+            .timeout(Duration::from_secs(timeout))
+            .await
+            .ok_or_else(|| Error::new(ErrorKind::TimedOut, "handshake timeout"))?
+            .map_err(|e| Error::new(ErrorKind::ConnectionRefused, e))?;
+
+        info!(
+            url = url.to_obfuscate(),
+            status = response.status().as_u16(),
+            "Connected to the websocket"
+        );
+
+        Ok(websocket)
+    }
+    /// Open the connection with an exponential backoff between each attempt and send the subscription.
+    async fn connect_with_retry(&self) -> Result<WebSocket> {
+        let mut attempt = 0;
+
+        loop {
+            let result = match self.connect().await {
+                Ok(mut websocket) => match self.subscription() {
+                    Some(subscription) => websocket
+                        .send(Message::Text(subscription.into()))
+                        .await
+                        .map(|_| websocket)
+                        .map_err(|e| Error::new(ErrorKind::BrokenPipe, e)),
+                    None => Ok(websocket),
+                },
+                Err(e) => Err(e),
+            };
+
+            match result {
+                Ok(websocket) => return Ok(websocket),
+                Err(e) if attempt < self.retry => {
+                    warn!(
+                        error = e.to_string().as_str(),
+                        attempt, "Can't connect to the websocket, retry"
+                    );
+                    backoff(attempt, RETRY_DELAY).await;
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+/// Wait the next frame, the next ping or the end of the idle time.
+async fn next_event(
+    websocket: &mut WebSocket,
+    next_ping: Option<Instant>,
+    idle_deadline: Option<Instant>,
+) -> Event {
+    async { Event::Frame(websocket.next().await) }
+        .or(async {
+            match next_ping {
+                Some(deadline) => {
+                    Timer::at(deadline).await;
+                    Event::Ping
+                }
+                None => futures::future::pending().await,
+            }
+        })
+        .or(async {
+            match idle_deadline {
+                Some(deadline) => {
+                    Timer::at(deadline).await;
+                    Event::Idle
+                }
+                None => futures::future::pending().await,
+            }
+        })
+        .await
+}
+
+#[async_trait]
+impl Connector for Websocket {
+    /// See [`Connector::set_document`] for more details.
+    fn set_document(&mut self, document: Box<dyn Document>) -> Result<()> {
+        self.document = Some(document.clone());
+
+        Ok(())
+    }
+    /// See [`Connector::document`] for more details.
+    fn document(&self) -> Result<&dyn Document> {
+        self.document.as_deref().ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                "The document has not been set in the connector",
+            )
+        })
+    }
+    /// See [`Connector::set_parameters`] for more details.
+    fn set_parameters(&mut self, parameters: Value) {
+        *self.parameters = parameters;
+    }
+    /// See [`Connector::is_variable`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::websocket::Websocket;
+    /// use chewdata::connector::Connector;
+    ///
+    /// let mut connector = Websocket::default();
+    /// connector.path = "/ws".to_string();
+    /// assert_eq!(false, connector.is_variable());
+    /// connector.path = "/ws/{{ channel }}".to_string();
+    /// assert_eq!(true, connector.is_variable());
+    /// ```
+    fn is_variable(&self) -> bool {
+        self.path.has_mustache()
+    }
+    /// See [`Connector::is_resource_will_change`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::websocket::Websocket;
+    /// use chewdata::connector::Connector;
+    /// use serde_json::Value;
+    ///
+    /// let mut connector = Websocket::default();
+    /// let params = serde_json::from_str(r#"{"channel":"trades"}"#).unwrap();
+    /// connector.path = "/ws".to_string();
+    /// assert_eq!(false, connector.is_resource_will_change(Value::Null).unwrap());
+    /// connector.path = "/ws/{{ channel }}".to_string();
+    /// assert_eq!(true, connector.is_resource_will_change(params).unwrap());
+    /// ```
+    fn is_resource_will_change(&self, new_parameters: Value) -> Result<bool> {
+        if !self.is_variable() {
+            trace!("Stay link to the same resource");
+            return Ok(false);
+        }
+
+        let mut metadata_kv = Map::default();
+        metadata_kv.insert("metadata".to_string(), self.metadata().into());
+        let metadata = Value::Object(metadata_kv);
+
+        let mut new_parameters = new_parameters;
+        new_parameters.merge(&metadata);
+        let mut old_parameters = *self.parameters.clone();
+        old_parameters.merge(&metadata);
+
+        let mut previous_path = self.path.clone();
+        previous_path.replace_mustache(old_parameters);
+
+        let mut new_path = self.path.clone();
+        new_path.replace_mustache(new_parameters);
+
+        if previous_path == new_path {
+            trace!(path = previous_path, "Path didn't change");
+            return Ok(false);
+        }
+
+        info!(
+            previous_path = previous_path,
+            new_path = new_path,
+            "Path will change"
+        );
+
+        Ok(true)
+    }
+    /// See [`Connector::path`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::connector::websocket::Websocket;
+    /// use chewdata::connector::Connector;
+    ///
+    /// let mut connector = Websocket::default();
+    /// connector.path = "/ws/{{ channel }}".to_string();
+    /// connector.set_parameters(serde_json::from_str(r#"{"channel":"trades"}"#).unwrap());
+    /// assert_eq!("/ws/trades", connector.path());
+    /// ```
+    fn path(&self) -> String {
+        if !self.is_variable() {
+            return self.path.clone();
+        }
+
+        let mut path = self.path.clone();
+        path.replace_mustache(self.parameters());
+        path
+    }
+    /// See [`Connector::metadata`] for more details.
+    fn metadata(&self) -> Metadata {
+        match &self.document {
+            Some(document) => self.metadata.clone().merge(&document.metadata()),
+            None => self.metadata.clone(),
+        }
+    }
+    /// See [`Connector::fetch`] for more details.
+    ///
+    /// Connect to the server and yield the records of each frame received.
+    #[instrument(name = "websocket::fetch")]
+    async fn fetch(&mut self) -> Result<Option<DataStream>> {
+        let document = self.document()?.clone_box();
+        let connector = self.clone();
+        let message_limit = self.message_limit;
+        let timeout = self.timeout.map(Duration::from_secs);
+        let ping_interval = self.ping_interval.map(Duration::from_secs);
+
+        let mut websocket = self.connect_with_retry().await?;
+
+        Ok(Some(Box::pin(stream! {
+            let mut messages = 0;
+            let mut last_message = Instant::now();
+
+            'connection: loop {
+                let mut next_ping = ping_interval.map(|interval| Instant::now() + interval);
+                let mut is_waiting_pong = false;
+
+                loop {
+                    if message_limit.is_some_and(|limit| messages >= limit) {
+                        let _ = websocket.close(None).await;
+                        break 'connection;
+                    }
+
+                    let idle_deadline = timeout.map(|timeout| last_message + timeout);
+                    let frame = match next_event(&mut websocket, next_ping, idle_deadline).await {
+                        Event::Frame(Some(Ok(frame))) => frame,
+                        Event::Frame(Some(Err(e))) => {
+                            warn!(error = e.to_string().as_str(), "The connection with the server is lost");
+                            break;
+                        }
+                        Event::Frame(None) => {
+                            warn!("The connection is closed by the server");
+                            break;
+                        }
+                        Event::Ping => {
+                            if is_waiting_pong {
+                                warn!("The server didn't answer to the last ping");
+                                break;
+                            }
+                            if let Err(e) = websocket.send(Message::Ping(Default::default())).await {
+                                warn!(error = e.to_string().as_str(), "Can't send the ping");
+                                break;
+                            }
+                            is_waiting_pong = true;
+                            next_ping = ping_interval.map(|interval| Instant::now() + interval);
+                            continue;
+                        }
+                        Event::Idle => {
+                            info!("No message received before the timeout");
+                            let _ = websocket.close(None).await;
+                            break 'connection;
+                        }
+                    };
+
+                    let payload = match frame {
+                        Message::Text(text) => text.as_bytes().to_vec(),
+                        Message::Binary(binary) => binary.to_vec(),
+                        Message::Pong(_) => {
+                            is_waiting_pong = false;
+                            continue;
+                        }
+                        Message::Close(frame) => {
+                            info!(frame = format!("{:?}", frame), "The server closes the connection");
+                            break;
+                        }
+                        Message::Ping(_) | Message::Frame(_) => continue,
+                    };
+                    messages += 1;
+                    last_message = Instant::now();
+
+                    match document.has_data(&payload) {
+                        Ok(false) => continue,
+                        Ok(true) => (),
+                        Err(e) => {
+                            yield DataResult::Err((Value::String(String::from_utf8_lossy(&payload).to_string()), e));
+                            continue;
+                        }
+                    }
+
+                    match document.read(&payload) {
+                        Ok(dataset) => {
+                            for data in dataset {
+                                yield data;
+                            }
+                        }
+                        Err(e) => {
+                            yield DataResult::Err((Value::String(String::from_utf8_lossy(&payload).to_string()), e));
+                        }
+                    }
+                }
+
+                websocket = match connector.connect_with_retry().await {
+                    Ok(websocket) => websocket,
+                    Err(e) => {
+                        warn!(error = e.to_string().as_str(), "Can't reconnect to the websocket");
+                        break;
+                    }
+                };
+            }
+
+            info!(messages, "Stop to read the messages");
+        })))
+    }
+    /// See [`Connector::send`] for more details.
+    ///
+    /// Send each record in its own frame.
+    #[instrument(name = "websocket::send", skip(dataset))]
+    async fn send(&mut self, dataset: &DataSet) -> Result<Option<DataStream>> {
+        let document = self.document()?;
+
+        let mut frames = Vec::default();
+        for data in dataset {
+            let payload = document.write(&vec![data.clone()])?;
+            frames.push(match String::from_utf8(payload) {
+                Ok(text) => Message::Text(text.into()),
+                Err(e) => Message::Binary(e.into_bytes().into()),
+            });
+        }
+
+        if frames.is_empty() {
+            return Ok(None);
+        }
+
+        let mut websocket = self.connect().await?;
+        let expected = frames.len();
+        for frame in frames {
+            websocket
+                .send(frame)
+                .await
+                .map_err(|e| Error::new(ErrorKind::BrokenPipe, e))?;
+        }
+        websocket
+            .close(None)
+            .await
+            .map_err(|e| Error::new(ErrorKind::BrokenPipe, e))?;
+
+        info!(frames = expected, "Frames sent with success");
+
+        Ok(None)
+    }
+    /// See [`Connector::paginate`] for more details.
+    async fn paginate(
+        &self,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<Box<dyn Connector>>> + Send>>> {
+        let paginator = Once {};
+        paginator.paginate(self).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::connector::authenticator::bearer::Bearer;
+    use crate::document::json::Json;
+    use async_tungstenite::accept_hdr_async;
+    use async_tungstenite::tungstenite::handshake::server::{Request, Response};
+    use macro_rules_attribute::apply;
+    use smol::net::{TcpListener, TcpStream};
+    use smol_macros::test;
+
+    async fn listen() -> (TcpListener, Websocket) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let mut connector = Websocket {
+            endpoint: format!("ws://{}", listener.local_addr().unwrap()),
+            path: "/ws".to_string(),
+            timeout: Some(5),
+            ..Default::default()
+        };
+        connector.set_document(Box::new(Json::default())).unwrap();
+        (listener, connector)
+    }
+
+    async fn accept(listener: &TcpListener) -> WebSocketStream<TcpStream> {
+        let (stream, _) = listener.accept().await.unwrap();
+        async_tungstenite::accept_async(stream).await.unwrap()
+    }
+
+    #[apply(test!)]
+    async fn fetch_with_subscription_and_authenticator() {
+        let (listener, mut connector) = listen().await;
+        connector.authenticator_type =
+            Some(Box::new(AuthenticatorType::Bearer(Bearer::new("abcd1234"))));
+        connector.subscription = Some(r#"{"channel":"{{ channel }}"}"#.to_string());
+        connector.set_parameters(serde_json::json!({"channel":"trades"}));
+        connector.message_limit = Some(2);
+
+        let server = smol::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let (sender, receiver) = async_channel::bounded(1);
+            // The error type of the callback is imposed by tungstenite.
+            #[allow(clippy::result_large_err)]
+            let callback = |request: &Request, response: Response| {
+                let authorization = request
+                    .headers()
+                    .get(http::header::AUTHORIZATION)
+                    .map(|value| value.to_str().unwrap().to_string());
+                sender
+                    .try_send((request.uri().path().to_string(), authorization))
+                    .unwrap();
+                Ok(response)
+            };
+            let mut websocket = accept_hdr_async(stream, callback).await.unwrap();
+
+            let subscription = websocket.next().await.unwrap().unwrap();
+            for number in [1, 2] {
+                websocket
+                    .send(Message::Text(format!(r#"{{"number":{}}}"#, number).into()))
+                    .await
+                    .unwrap();
+            }
+            let _ = websocket.next().await;
+
+            (receiver.recv().await.unwrap(), subscription)
+        });
+
+        let dataset: Vec<Value> = connector
+            .fetch()
+            .await
+            .unwrap()
+            .unwrap()
+            .map(|data| data.to_value())
+            .collect()
+            .await;
+        assert_eq!(
+            vec![
+                serde_json::json!({"number":1}),
+                serde_json::json!({"number":2})
+            ],
+            dataset
+        );
+
+        let ((path, authorization), subscription) = server.await;
+        assert_eq!("/ws", path);
+        assert_eq!(Some("Bearer abcd1234".to_string()), authorization);
+        assert_eq!(
+            Message::Text(r#"{"channel":"trades"}"#.into()),
+            subscription
+        );
+    }
+    #[apply(test!)]
+    async fn fetch_and_reconnect() {
+        let (listener, mut connector) = listen().await;
+        connector.message_limit = Some(2);
+
+        let server = smol::spawn(async move {
+            let mut websocket = accept(&listener).await;
+            websocket
+                .send(Message::Text(r#"{"number":1}"#.into()))
+                .await
+                .unwrap();
+            websocket.close(None).await.unwrap();
+
+            let mut websocket = accept(&listener).await;
+            websocket
+                .send(Message::Text(r#"{"number":2}"#.into()))
+                .await
+                .unwrap();
+            let _ = websocket.next().await;
+        });
+
+        let dataset: Vec<Value> = connector
+            .fetch()
+            .await
+            .unwrap()
+            .unwrap()
+            .map(|data| data.to_value())
+            .collect()
+            .await;
+        assert_eq!(
+            vec![
+                serde_json::json!({"number":1}),
+                serde_json::json!({"number":2})
+            ],
+            dataset
+        );
+
+        server.await;
+    }
+    #[apply(test!)]
+    async fn fetch_with_ping() {
+        let (listener, mut connector) = listen().await;
+        connector.ping_interval = Some(1);
+        connector.message_limit = Some(1);
+
+        let server = smol::spawn(async move {
+            let mut websocket = accept(&listener).await;
+            // The pong is sent by the server with the next frame.
+            while !matches!(websocket.next().await, Some(Ok(Message::Ping(_)))) {}
+            websocket
+                .send(Message::Text(r#"{"ping":true}"#.into()))
+                .await
+                .unwrap();
+            let _ = websocket.next().await;
+        });
+
+        let dataset: Vec<Value> = connector
+            .fetch()
+            .await
+            .unwrap()
+            .unwrap()
+            .map(|data| data.to_value())
+            .collect()
+            .await;
+        assert_eq!(vec![serde_json::json!({"ping":true})], dataset);
+
+        server.await;
+    }
+    #[apply(test!)]
+    async fn fetch_stop_after_timeout() {
+        let (listener, mut connector) = listen().await;
+        connector.timeout = Some(1);
+
+        let server = smol::spawn(async move {
+            let mut websocket = accept(&listener).await;
+            let _ = websocket.next().await;
+        });
+
+        let dataset: Vec<DataResult> = connector.fetch().await.unwrap().unwrap().collect().await;
+        assert_eq!(0, dataset.len());
+
+        server.await;
+    }
+    #[apply(test!)]
+    async fn fetch_without_server() {
+        let (listener, mut connector) = listen().await;
+        connector.retry = 1;
+        drop(listener);
+
+        assert!(connector.fetch().await.is_err());
+    }
+    #[apply(test!)]
+    async fn send() {
+        let (listener, mut connector) = listen().await;
+
+        let server = smol::spawn(async move {
+            let mut websocket = accept(&listener).await;
+            let mut frames = Vec::default();
+            while let Some(Ok(frame)) = websocket.next().await {
+                if let Message::Text(text) = frame {
+                    frames.push(serde_json::from_str::<Value>(text.as_str()).unwrap());
+                }
+            }
+            frames
+        });
+
+        connector
+            .send(&vec![
+                DataResult::Ok(serde_json::json!({"number":1})),
+                DataResult::Ok(serde_json::json!({"number":2})),
+            ])
+            .await
+            .unwrap();
+
+        assert_eq!(
+            vec![
+                serde_json::json!({"number":1}),
+                serde_json::json!({"number":2})
+            ],
+            server.await
+        );
+    }
+    #[apply(test!)]
+    async fn paginate() {
+        let (_listener, connector) = listen().await;
+        let mut paging = connector.paginate().await.unwrap();
+        assert!(paging.next().await.transpose().unwrap().is_some());
+        assert!(paging.next().await.transpose().unwrap().is_none());
+    }
+}
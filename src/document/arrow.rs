@@ -0,0 +1,308 @@
+//! Read and write data in **Arrow IPC** format.
+//!
+//! The records are written in the Arrow IPC `file` format, also known as Feather v2, or in the IPC `stream` format.
+//! The records are converted into record batches like the [`crate::document::parquet`] document, with the same `schema` override.
+//! If the schema is not set, it is inferred from the first record.
+//!
+//! The file can be read without copy by Arrow, Polars or Pandas with `pyarrow.feather.read_table`.
+//!
+//! ### Configuration
+//!
+//! | key         | alias | Description                                             | Default Value | Possible Values                                                                                   |
+//! | ----------- | ----- | ------------------------------------------------------- | ------------- | ------------------------------------------------------------------------------------------------- |
+//! | type        | -     | Required in order to use this document.                 | `arrow`       | `arrow` / `feather`                                                                               |
+//! | metadata    | meta  | Metadata describe the resource.                         | `null`        | [`crate::Metadata`]                                                                               |
+//! | format      | -     | The IPC format.                                         | `file`        | `file` / `stream`                                                                                 |
+//! | schema      | -     | Overrides the schema inferred from the first record.    | `null`        | JSON schema definition (https://github.com/apache/arrow-rs/blob/main/arrow-schema/src/schema.rs) |
+//! | batch_size  | -     | Number of records per record batch.                     | `1000`        | Unsigned integer                                                                                  |
+//! | compression | -     | Compression of the record batches.                      | `null`        | `null` / `lz4` / `zstd`                                                                           |
+//!
+//! ### Examples
+//!
+//! ```json
+//! [
+//!     {
+//!         "type": "read",
+//!         "connector":{
+//!             "type": "local",
+//!             "path": "./data/multi_lines.json"
+//!         }
+//!     },
+//!     {
+//!         "type": "write",
+//!         "document": {
+//!             "type": "arrow",
+//!             "compression": "zstd"
+//!         },
+//!         "connector":{
+//!             "type": "local",
+//!             "path": "./data/out/multi_lines.arrow"
+//!         }
+//!     }
+//! ]
+//! ```
+use crate::document::Document;
+use crate::helper::arrow::{record_batches, records, schema};
+use crate::helper::string::DisplayOnlyForDebugging;
+use crate::DataResult;
+use crate::{DataSet, Metadata};
+use arrow_array::RecordBatch;
+use arrow_ipc::reader::{FileReader, StreamReader};
+use arrow_ipc::writer::{FileWriter, IpcWriteOptions, StreamWriter};
+use arrow_ipc::CompressionType;
+use arrow_schema::ArrowError;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::io::{self, Cursor};
+
+const DEFAULT_FILE_SUBTYPE: &str = "vnd.apache.arrow.file";
+const DEFAULT_STREAM_SUBTYPE: &str = "vnd.apache.arrow.stream";
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Format {
+    #[default]
+    File,
+    Stream,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Compression {
+    #[default]
+    Null,
+    Lz4,
+    Zstd,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Arrow {
+    #[serde(rename = "metadata")]
+    #[serde(alias = "meta")]
+    pub metadata: Metadata,
+    pub format: Format,
+    pub schema: Option<Value>,
+    pub batch_size: usize,
+    pub compression: Compression,
+}
+
+impl Default for Arrow {
+    fn default() -> Self {
+        let metadata = Metadata {
+            mime_type: Some(mime::APPLICATION.to_string()),
+            mime_subtype: Some(DEFAULT_FILE_SUBTYPE.to_string()),
+            ..Default::default()
+        };
+        Arrow {
+            metadata,
+            format: Format::default(),
+            schema: None,
+            batch_size: 1000,
+            compression: Compression::default(),
+        }
+    }
+}
+
+impl Arrow {
+    fn write_options(&self) -> io::Result<IpcWriteOptions> {
+        let compression = match self.compression {
+            Compression::Null => None,
+            Compression::Lz4 => Some(CompressionType::LZ4_FRAME),
+            Compression::Zstd => Some(CompressionType::ZSTD),
+        };
+
+        IpcWriteOptions::default()
+            .try_with_compression(compression)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))
+    }
+}
+
+fn invalid_data(error: ArrowError) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+impl Document for Arrow {
+    /// See [`Document::set_metadata`] for more details.
+    fn set_metadata(&mut self, metadata: Metadata) {
+        self.metadata = metadata.clone();
+    }
+    /// See [`Document::metadata`] for more details.
+    fn metadata(&self) -> Metadata {
+        let mut metadata = Arrow::default().metadata.merge(&self.metadata);
+        if Format::Stream == self.format
+            && Some(DEFAULT_FILE_SUBTYPE) == metadata.mime_subtype.as_deref()
+        {
+            metadata.mime_subtype = Some(DEFAULT_STREAM_SUBTYPE.to_string());
+        }
+
+        metadata
+    }
+    /// See [`Document::can_append`] for more details.
+    fn can_append(&self) -> bool {
+        false
+    }
+    /// See [`Document::read`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::document::arrow::Arrow;
+    /// use chewdata::document::Document;
+    /// use chewdata::DataResult;
+    /// use serde_json::json;
+    ///
+    /// let document = Arrow::default();
+    /// let dataset = vec![DataResult::Ok(json!({"number": 10, "string": "value"}))];
+    /// let buffer = document.write(&dataset).unwrap();
+    ///
+    /// let mut dataset = document.read(&buffer).unwrap().into_iter();
+    /// assert_eq!(json!({"number": 10, "string": "value"}), dataset.next().unwrap().to_value());
+    /// ```
+    #[instrument(skip(buffer), name = "arrow::read")]
+    fn read(&self, buffer: &[u8]) -> io::Result<DataSet> {
+        let batches: Vec<RecordBatch> = match self.format {
+            Format::File => FileReader::try_new(Cursor::new(buffer), None)
+                .map_err(invalid_data)?
+                .collect::<Result<_, _>>(),
+            Format::Stream => StreamReader::try_new(buffer, None)
+                .map_err(invalid_data)?
+                .collect::<Result<_, _>>(),
+        }
+        .map_err(invalid_data)?;
+
+        let mut dataset = Vec::default();
+        for batch in batches {
+            for record in records(&batch)? {
+                trace!(
+                    record = record.display_only_for_debugging(),
+                    "Record deserialized"
+                );
+                dataset.push(DataResult::Ok(record));
+            }
+        }
+
+        Ok(dataset)
+    }
+    /// See [`Document::write`] for more details.
+    #[instrument(skip(dataset), name = "arrow::write")]
+    fn write(&self, dataset: &DataSet) -> io::Result<Vec<u8>> {
+        let Some(first) = dataset.first() else {
+            return Ok(vec![]);
+        };
+
+        let schema = schema(self.schema.as_ref(), &first.to_value())?;
+        let values: Vec<Value> = dataset.iter().map(|data| data.to_value()).collect();
+        let batches = record_batches(&schema, &values, self.batch_size)?;
+        let options = self.write_options()?;
+
+        match self.format {
+            Format::File => {
+                let mut writer = FileWriter::try_new_with_options(Vec::default(), &schema, options)
+                    .map_err(invalid_data)?;
+                for batch in &batches {
+                    writer.write(batch).map_err(invalid_data)?;
+                }
+                writer.finish().map_err(invalid_data)?;
+                writer.into_inner().map_err(invalid_data)
+            }
+            Format::Stream => {
+                let mut writer =
+                    StreamWriter::try_new_with_options(Vec::default(), &schema, options)
+                        .map_err(invalid_data)?;
+                for batch in &batches {
+                    writer.write(batch).map_err(invalid_data)?;
+                }
+                writer.finish().map_err(invalid_data)?;
+                writer.into_inner().map_err(invalid_data)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn dataset() -> DataSet {
+        (0..5)
+            .map(|number| {
+                DataResult::Ok(json!({
+                    "number": number,
+                    "string": format!("value {}", number),
+                    "list": [number],
+                    "object": {"boolean": 0 == number % 2}
+                }))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn write_and_read_file() {
+        let document = Arrow {
+            batch_size: 2,
+            ..Default::default()
+        };
+        let buffer = document.write(&dataset()).unwrap();
+        assert_eq!(b"ARROW1", &buffer[..6]);
+
+        let values: Vec<Value> = document
+            .read(&buffer)
+            .unwrap()
+            .iter()
+            .map(DataResult::to_value)
+            .collect();
+        let expected: Vec<Value> = dataset().iter().map(DataResult::to_value).collect();
+        assert_eq!(expected, values);
+    }
+    #[test]
+    fn write_and_read_stream_with_compression() {
+        let document = Arrow {
+            format: Format::Stream,
+            compression: Compression::Zstd,
+            ..Default::default()
+        };
+        let buffer = document.write(&dataset()).unwrap();
+
+        let dataset = document.read(&buffer).unwrap();
+        assert_eq!(5, dataset.len());
+        assert_eq!(
+            json!({"number": 4, "string": "value 4", "list": [4], "object": {"boolean": true}}),
+            dataset[4].to_value()
+        );
+        assert_eq!(
+            Some(DEFAULT_STREAM_SUBTYPE.to_string()),
+            document.metadata().mime_subtype
+        );
+    }
+    #[test]
+    fn write_with_schema() {
+        let document = Arrow {
+            schema: Some(json!({
+                "fields": [
+                    {"name": "number", "nullable": false, "type": {"name": "int", "bitWidth": 8, "isSigned": false}},
+                    {"name": "comment", "nullable": true, "type": {"name": "utf8"}}
+                ]
+            })),
+            ..Default::default()
+        };
+        let buffer = document.write(&dataset()).unwrap();
+
+        let dataset = document.read(&buffer).unwrap();
+        assert_eq!(json!({"number": 1, "comment": null}), dataset[1].to_value());
+    }
+    #[test]
+    fn read_with_wrong_format() {
+        let document = Arrow::default();
+        let buffer = Arrow {
+            format: Format::Stream,
+            ..Default::default()
+        }
+        .write(&dataset())
+        .unwrap();
+
+        let error = document.read(&buffer).unwrap_err();
+        assert_eq!(io::ErrorKind::InvalidData, error.kind());
+    }
+}
@@ -0,0 +1,877 @@
+//! Read and write data in **Avro** format.
+//!
+//! The records can be encoded in three ways:
+//!
+//! * `container`: an Avro object container file. The writer schema is stored in the header of the file.
+//! * `single_object`: the Avro single-object encoding. Each record starts with a marker and the fingerprint of the schema.
+//! * `confluent`: the framing of the Confluent schema registry. Each record starts with a magic byte and the id of the schema in the registry.
+//!
+//! The `single_object` and `confluent` records can be concatenated, so several records can be read from the same buffer.
+//!
+//! The writer schema is the `schema` or the schema in the `schema_path` file. If not set, the schema is inferred from the first record:
+//! each field is nullable and the keys that are not valid Avro names are renamed with `_`.
+//! When reading, the `schema` is used as reader schema in order to resolve the writer schema of the data.
+//!
+//! ### Configuration
+//!
+//! | key         | alias | Description                                                         | Default Value | Possible Values                                       |
+//! | ----------- | ----- | ------------------------------------------------------------------- | ------------- | ----------------------------------------------------- |
+//! | type        | -     | Required in order to use this document.                             | `avro`        | `avro`                                                |
+//! | metadata    | meta  | Metadata describe the resource.                                     | `null`        | [`crate::Metadata`]                                   |
+//! | encoding    | -     | The way to encode the records.                                      | `container`   | `container` / `single_object` / `confluent`           |
+//! | schema      | -     | The Avro schema of the records.                                     | `null`        | Avro schema in json                                   |
+//! | schema_path | -     | Path of a local `.avsc` file that contains the Avro schema.          | `null`        | String                                                |
+//! | codec       | -     | The compression of the blocks of a container file.                  | `null`        | `null` / `deflate`                                    |
+//! | registry    | -     | The schema registry used with the `confluent` encoding.             | `null`        | [`crate::document::avro::SchemaRegistry`]             |
+//!
+//! ### Schema registry
+//!
+//! | key      | alias | Description                                                        | Default Value | Possible Values |
+//! | -------- | ----- | ------------------------------------------------------------------ | ------------- | --------------- |
+//! | endpoint | url   | Endpoint of the schema registry.                                   | ``            | String          |
+//! | subject  | -     | The subject used to register the writer schema.                    | `null`        | String          |
+//! | username | user  | The username of the basic authentication.                          | `null`        | String          |
+//! | password | pass  | The password of the basic authentication.                          | `null`        | String          |
+//! | timeout  | -     | Time in secound before to abort the call.                          | `5`           | Unsigned number |
+//!
+//! ### Examples
+//!
+//! ```json
+//! [
+//!     {
+//!         "type": "read",
+//!         "document": {
+//!             "type": "avro",
+//!             "encoding": "confluent",
+//!             "registry": {
+//!                 "endpoint": "{{ SCHEMA_REGISTRY_ENDPOINT }}"
+//!             }
+//!         }
+//!     },
+//!     {
+//!         "type": "write",
+//!         "document": {
+//!             "type": "avro",
+//!             "schema_path": "./data/multi_lines.avsc",
+//!             "codec": "deflate"
+//!         }
+//!     }
+//! ]
+//! ```
+use crate::connector::authenticator::{basic::Basic, AuthenticatorType};
+use crate::connector::curl::Curl;
+use crate::document::Document;
+use crate::helper::string::{percent_encode, DisplayOnlyForDebugging, Obfuscate};
+use crate::DataResult;
+use crate::{DataSet, Metadata};
+use apache_avro::rabin::Rabin;
+use apache_avro::types::Value as AvroValue;
+use apache_avro::{from_avro_datum, to_avro_datum, Codec, DeflateSettings, Reader, Schema, Writer};
+use bytes::Bytes;
+use dashmap::DashMap;
+use http::Method;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::io::{self, Cursor, Read};
+use std::sync::OnceLock;
+use std::{fmt, fs};
+
+const DEFAULT_SUBTYPE: &str = "avro";
+const SINGLE_OBJECT_MARKER: [u8; 2] = [0xC3, 0x01];
+const CONFLUENT_MAGIC_BYTE: u8 = 0;
+
+// Schemas and ids already resolved with the schema registries.
+static REGISTRY_SCHEMAS: OnceLock<DashMap<String, Schema>> = OnceLock::new();
+static REGISTRY_IDS: OnceLock<DashMap<String, u32>> = OnceLock::new();
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Encoding {
+    #[default]
+    Container,
+    SingleObject,
+    Confluent,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum CodecType {
+    #[default]
+    Null,
+    Deflate,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Avro {
+    #[serde(rename = "metadata")]
+    #[serde(alias = "meta")]
+    pub metadata: Metadata,
+    pub encoding: Encoding,
+    pub schema: Option<Value>,
+    pub schema_path: Option<String>,
+    pub codec: CodecType,
+    pub registry: Option<SchemaRegistry>,
+}
+
+impl Default for Avro {
+    fn default() -> Self {
+        let metadata = Metadata {
+            mime_type: Some(mime::APPLICATION.to_string()),
+            mime_subtype: Some(DEFAULT_SUBTYPE.to_string()),
+            ..Default::default()
+        };
+        Avro {
+            metadata,
+            encoding: Encoding::default(),
+            schema: None,
+            schema_path: None,
+            codec: CodecType::default(),
+            registry: None,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Default, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct SchemaRegistry {
+    #[serde(alias = "url")]
+    pub endpoint: String,
+    pub subject: Option<String>,
+    #[serde(alias = "user")]
+    pub username: Option<String>,
+    #[serde(alias = "pass")]
+    pub password: Option<String>,
+    pub timeout: Option<u64>,
+}
+
+impl fmt::Debug for SchemaRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SchemaRegistry")
+            .field("endpoint", &self.endpoint.to_obfuscate())
+            .field("subject", &self.subject)
+            .field("username", &self.username)
+            // Can contain sensitive data
+            .field("password", &self.password.display_only_for_debugging())
+            .field("timeout", &self.timeout)
+            .finish()
+    }
+}
+
+impl SchemaRegistry {
+    fn curl(&self) -> Curl {
+        let mut curl = Curl::default();
+        curl.endpoint = self.endpoint.trim_end_matches('/').to_string();
+        curl.metadata = Metadata {
+            mime_type: Some(mime::APPLICATION.to_string()),
+            mime_subtype: Some("vnd.schemaregistry.v1+json".to_string()),
+            ..Default::default()
+        };
+        if self.timeout.is_some() {
+            curl.timeout = self.timeout;
+        }
+        if let Some(username) = &self.username {
+            curl.authenticator_type = Some(Box::new(AuthenticatorType::Basic(Basic {
+                username: username.clone(),
+                password: self.password.clone().unwrap_or_default(),
+            })));
+        }
+        curl
+    }
+    /// Call the registry and return the response body.
+    ///
+    /// The documents are synchronous, so the call blocks the current thread without waiting the executor.
+    fn call(&self, method: Method, path: &str, body: Option<Value>) -> io::Result<Value> {
+        let body = match body {
+            Some(body) => Bytes::from(serde_json::to_vec(&body)?),
+            None => Bytes::new(),
+        };
+
+        let (status, data) = self.curl().call_blocking(&method, path, body)?;
+
+        if status >= 400 {
+            return Err(io::Error::new(
+                io::ErrorKind::Interrupted,
+                format!(
+                    "The call '{} {}' to the schema registry failed with the status '{}': {}",
+                    method,
+                    path,
+                    status,
+                    String::from_utf8_lossy(&data)
+                ),
+            ));
+        }
+
+        serde_json::from_slice(&data).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+    /// Get the schema registered with an id.
+    pub fn schema(&self, id: u32) -> io::Result<Schema> {
+        let key = format!("{}/schemas/ids/{}", self.endpoint, id);
+        let schemas = REGISTRY_SCHEMAS.get_or_init(DashMap::default);
+
+        if let Some(schema) = schemas.get(&key) {
+            return Ok(schema.clone());
+        }
+
+        let response = self.call(Method::GET, &format!("/schemas/ids/{}", id), None)?;
+        let schema = match response.get("schema") {
+            Some(Value::String(schema)) => parse_schema(schema)?,
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!(
+                        "The schema registry returns an invalid schema: {}",
+                        response
+                    ),
+                ))
+            }
+        };
+
+        schemas.insert(key, schema.clone());
+
+        Ok(schema)
+    }
+    /// Register a schema in the subject and return its id. If the schema already exists, the registry returns the existing id.
+    pub fn register(&self, schema: &Schema) -> io::Result<u32> {
+        let subject = self.subject.clone().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "The subject of the schema registry is required to write the records",
+            )
+        })?;
+        let canonical_form = schema.canonical_form();
+        let key = format!("{}/subjects/{}/{}", self.endpoint, subject, canonical_form);
+        let ids = REGISTRY_IDS.get_or_init(DashMap::default);
+
+        if let Some(id) = ids.get(&key) {
+            return Ok(*id);
+        }
+
+        let response = self.call(
+            Method::POST,
+            &format!("/subjects/{}/versions", percent_encode(&subject, false)),
+            Some(serde_json::json!({ "schema": canonical_form })),
+        )?;
+        let id = response
+            .get("id")
+            .and_then(Value::as_u64)
+            .and_then(|id| u32::try_from(id).ok())
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("The schema registry returns an invalid id: {}", response),
+                )
+            })?;
+
+        ids.insert(key, id);
+
+        Ok(id)
+    }
+}
+
+fn parse_schema(schema: &str) -> io::Result<Schema> {
+    Schema::parse_str(schema).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))
+}
+
+fn invalid_data<E: fmt::Display>(error: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error.to_string())
+}
+
+/// Rename a key that is not a valid Avro name.
+fn avro_name(key: &str) -> String {
+    let mut name: String = key
+        .chars()
+        .map(|c| match c.is_ascii_alphanumeric() {
+            true => c,
+            false => '_',
+        })
+        .collect();
+
+    if !name.starts_with(|c: char| c.is_ascii_alphabetic() || '_' == c) {
+        name.insert(0, '_');
+    }
+
+    name
+}
+
+/// Rename the keys of the objects that are not valid Avro names.
+fn with_avro_names(value: Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .map(|(key, value)| (avro_name(&key), with_avro_names(value)))
+                .collect(),
+        ),
+        Value::Array(values) => Value::Array(values.into_iter().map(with_avro_names).collect()),
+        value => value,
+    }
+}
+
+/// Infer the Avro type of a value. The fields of the records are nullable.
+fn infer_type(value: &Value, name: &str) -> Value {
+    match value {
+        Value::Null => Value::String("string".to_string()),
+        Value::Bool(_) => Value::String("boolean".to_string()),
+        Value::Number(number) if number.is_f64() => Value::String("double".to_string()),
+        Value::Number(_) => Value::String("long".to_string()),
+        Value::String(_) => Value::String("string".to_string()),
+        Value::Array(values) => {
+            let item = values.iter().find(|value| !value.is_null());
+            serde_json::json!({
+                "type": "array",
+                "items": infer_type(item.unwrap_or(&Value::Null), name)
+            })
+        }
+        Value::Object(map) => {
+            let fields: Vec<Value> = map
+                .iter()
+                .map(|(key, value)| {
+                    let key = avro_name(key);
+                    serde_json::json!({
+                        "name": key,
+                        "type": ["null", infer_type(value, &format!("{}_{}", name, key))],
+                        "default": null
+                    })
+                })
+                .collect();
+            serde_json::json!({
+                "type": "record",
+                "name": name,
+                "fields": fields
+            })
+        }
+    }
+}
+
+/// Infer an Avro schema from a record.
+///
+/// # Examples
+///
+/// ```
+/// use chewdata::document::avro::infer_schema;
+/// use serde_json::json;
+///
+/// let schema = infer_schema(&json!({"long-string": "value", "number": 10})).unwrap();
+/// assert_eq!(
+///     r#"{"name":"record","type":"record","fields":[{"name":"long_string","type":["null","string"]},{"name":"number","type":["null","long"]}]}"#,
+///     schema.canonical_form()
+/// );
+/// ```
+pub fn infer_schema(value: &Value) -> io::Result<Schema> {
+    let schema = match value {
+        Value::Object(_) => infer_type(value, "record"),
+        _ => infer_type(&serde_json::json!({ "value": value }), "record"),
+    };
+
+    parse_schema(&schema.to_string())
+}
+
+impl Avro {
+    /// Get the schema from the configuration or from the schema file.
+    pub fn schema(&self) -> io::Result<Option<Schema>> {
+        if let Some(schema) = &self.schema {
+            return Ok(Some(match schema {
+                Value::String(schema) => parse_schema(schema)?,
+                schema => parse_schema(&schema.to_string())?,
+            }));
+        }
+
+        if let Some(path) = &self.schema_path {
+            return Ok(Some(parse_schema(&fs::read_to_string(path)?)?));
+        }
+
+        Ok(None)
+    }
+    fn registry(&self) -> io::Result<&SchemaRegistry> {
+        self.registry.as_ref().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "The schema registry is required with the confluent encoding",
+            )
+        })
+    }
+    fn codec(&self) -> Codec {
+        match self.codec {
+            CodecType::Null => Codec::Null,
+            CodecType::Deflate => Codec::Deflate(DeflateSettings::default()),
+        }
+    }
+}
+
+/// Read the header of a single object or a confluent record and return the writer schema.
+fn read_header(
+    document: &Avro,
+    cursor: &mut Cursor<&[u8]>,
+    schema: Option<&Schema>,
+) -> io::Result<Schema> {
+    match document.encoding {
+        Encoding::SingleObject => {
+            let schema = schema.ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "The schema is required to read the single object encoding",
+                )
+            })?;
+            let mut header = [0; 10];
+            cursor.read_exact(&mut header)?;
+
+            let fingerprint = schema.fingerprint::<Rabin>().bytes;
+            if header[..2] != SINGLE_OBJECT_MARKER || header[2..] != fingerprint[..] {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "The single object header doesn't match the fingerprint of the schema",
+                ));
+            }
+
+            Ok(schema.clone())
+        }
+        _ => {
+            let mut header = [0; 5];
+            cursor.read_exact(&mut header)?;
+
+            if CONFLUENT_MAGIC_BYTE != header[0] {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("The magic byte '{}' is not supported", header[0]),
+                ));
+            }
+
+            let id = u32::from_be_bytes([header[1], header[2], header[3], header[4]]);
+            document.registry()?.schema(id)
+        }
+    }
+}
+
+impl Document for Avro {
+    /// See [`Document::set_metadata`] for more details.
+    fn set_metadata(&mut self, metadata: Metadata) {
+        self.metadata = metadata.clone();
+    }
+    /// See [`Document::metadata`] for more details.
+    fn metadata(&self) -> Metadata {
+        Avro::default().metadata.merge(&self.metadata)
+    }
+    /// See [`Document::can_append`] for more details.
+    ///
+    /// A container file has only one header, so it can't be appended.
+    fn can_append(&self) -> bool {
+        Encoding::Container != self.encoding
+    }
+    /// See [`Document::read`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::document::avro::Avro;
+    /// use chewdata::document::Document;
+    /// use chewdata::DataResult;
+    /// use serde_json::json;
+    ///
+    /// let document = Avro::default();
+    /// let dataset = vec![DataResult::Ok(json!({"number": 10, "string": "value"}))];
+    /// let buffer = document.write(&dataset).unwrap();
+    ///
+    /// let mut dataset = document.read(&buffer).unwrap().into_iter();
+    /// assert_eq!(json!({"number": 10, "string": "value"}), dataset.next().unwrap().to_value());
+    /// ```
+    #[instrument(skip(buffer), name = "avro::read")]
+    fn read(&self, buffer: &[u8]) -> io::Result<DataSet> {
+        let schema = self.schema()?;
+        let mut values = Vec::default();
+
+        match self.encoding {
+            Encoding::Container => {
+                let reader = match &schema {
+                    Some(schema) => Reader::with_schema(schema, buffer),
+                    None => Reader::new(buffer),
+                }
+                .map_err(invalid_data)?;
+
+                for value in reader {
+                    values.push(value.map_err(invalid_data)?);
+                }
+            }
+            Encoding::SingleObject | Encoding::Confluent => {
+                let mut cursor = Cursor::new(buffer);
+
+                while (cursor.position() as usize) < buffer.len() {
+                    let writer_schema = read_header(self, &mut cursor, schema.as_ref())?;
+                    let reader_schema = match self.encoding {
+                        Encoding::Confluent => schema.as_ref(),
+                        _ => None,
+                    };
+
+                    values.push(
+                        from_avro_datum(&writer_schema, &mut cursor, reader_schema)
+                            .map_err(invalid_data)?,
+                    );
+                }
+            }
+        }
+
+        let mut dataset = Vec::default();
+        for value in values {
+            let record = Value::try_from(value).map_err(invalid_data)?;
+            trace!(
+                record = record.display_only_for_debugging(),
+                "Record deserialized"
+            );
+            dataset.push(DataResult::Ok(record));
+        }
+
+        Ok(dataset)
+    }
+    /// See [`Document::write`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::document::avro::{Avro, Encoding};
+    /// use chewdata::document::Document;
+    /// use chewdata::DataResult;
+    /// use serde_json::json;
+    ///
+    /// let mut document = Avro::default();
+    /// document.encoding = Encoding::SingleObject;
+    /// document.schema = Some(json!({"type": "record", "name": "order", "fields": [{"name": "number", "type": "int"}]}));
+    /// let dataset = vec![DataResult::Ok(json!({"number": 10}))];
+    /// let buffer = document.write(&dataset).unwrap();
+    /// assert_eq!(vec![0xC3, 0x01], buffer[..2]);
+    /// assert_eq!(20, buffer[10]);
+    /// ```
+    #[instrument(skip(dataset), name = "avro::write")]
+    fn write(&self, dataset: &DataSet) -> io::Result<Vec<u8>> {
+        let Some(first) = dataset.first() else {
+            return Ok(vec![]);
+        };
+
+        let (schema, is_inferred) = match self.schema()? {
+            Some(schema) => (schema, false),
+            None => (infer_schema(&first.to_value())?, true),
+        };
+
+        let mut values = Vec::default();
+        for data in dataset {
+            let value = match (data.to_value(), is_inferred) {
+                (Value::Object(map), true) => with_avro_names(Value::Object(map)),
+                (value, true) => {
+                    let mut map = Map::default();
+                    map.insert("value".to_string(), with_avro_names(value));
+                    Value::Object(map)
+                }
+                (value, false) => value,
+            };
+
+            values.push(
+                AvroValue::from(value)
+                    .resolve(&schema)
+                    .map_err(invalid_data)?,
+            );
+        }
+
+        let header = match self.encoding {
+            Encoding::Container => {
+                let mut writer = Writer::with_codec(&schema, Vec::default(), self.codec());
+                for value in values {
+                    writer.append(value).map_err(invalid_data)?;
+                }
+                return writer.into_inner().map_err(invalid_data);
+            }
+            Encoding::SingleObject => {
+                let mut header = SINGLE_OBJECT_MARKER.to_vec();
+                header.extend_from_slice(&schema.fingerprint::<Rabin>().bytes);
+                header
+            }
+            Encoding::Confluent => {
+                let mut header = vec![CONFLUENT_MAGIC_BYTE];
+                header.extend_from_slice(&self.registry()?.register(&schema)?.to_be_bytes());
+                header
+            }
+        };
+
+        let mut buffer = Vec::default();
+        for value in values {
+            buffer.extend_from_slice(&header);
+            buffer.extend_from_slice(&to_avro_datum(&schema, value).map_err(invalid_data)?);
+        }
+
+        Ok(buffer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::step::StepType;
+    use macro_rules_attribute::apply;
+    use serde_json::json;
+    use smol_macros::test;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+
+    fn dataset() -> DataSet {
+        vec![
+            DataResult::Ok(
+                json!({"number": 10, "long-string": "value 1", "round": 10.5, "tags": ["a"], "nested": {"field": true}}),
+            ),
+            DataResult::Ok(
+                json!({"number": 20, "long-string": null, "round": 2, "tags": [], "nested": null}),
+            ),
+        ]
+    }
+
+    /// Start a schema registry that knows the schema with the id 7.
+    fn schema_registry() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+
+        std::thread::spawn(move || {
+            let mut schema = String::default();
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                loop {
+                    let mut request_line = String::default();
+                    if 0 == reader.read_line(&mut request_line).unwrap_or(0) {
+                        break;
+                    }
+                    let mut length = 0;
+                    loop {
+                        let mut line = String::default();
+                        reader.read_line(&mut line).unwrap();
+                        match line.trim_end().split_once(':') {
+                            Some((name, value)) if name.eq_ignore_ascii_case("content-length") => {
+                                length = value.trim().parse().unwrap()
+                            }
+                            Some(_) => (),
+                            None => break,
+                        }
+                    }
+                    let mut body = vec![0; length];
+                    reader.read_exact(&mut body).unwrap();
+
+                    let response = match request_line.contains("/subjects/orders-value/versions") {
+                        true => {
+                            let request: Value = serde_json::from_slice(&body).unwrap();
+                            schema = request["schema"].as_str().unwrap().to_string();
+                            json!({"id": 7})
+                        }
+                        false if request_line.contains("/schemas/ids/7") => {
+                            json!({"schema": schema})
+                        }
+                        false => json!({"error_code": 40403, "message": "Schema not found"}),
+                    }
+                    .to_string();
+                    let status = match response.contains("error_code") {
+                        true => "404 Not Found",
+                        false => "200 OK",
+                    };
+
+                    stream
+                        .write_all(
+                            format!(
+                                "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+                                status,
+                                response.len(),
+                                response
+                            )
+                            .as_bytes(),
+                        )
+                        .unwrap();
+                }
+            }
+        });
+
+        endpoint
+    }
+
+    #[test]
+    fn write_and_read_container_with_inferred_schema() {
+        let document = Avro::default();
+        let buffer = document.write(&dataset()).unwrap();
+
+        let dataset = document.read(&buffer).unwrap();
+        assert_eq!(
+            json!({"number": 10, "long_string": "value 1", "round": 10.5, "tags": ["a"], "nested": {"field": true}}),
+            dataset[0].to_value()
+        );
+        assert_eq!(
+            json!({"number": 20, "long_string": null, "round": 2.0, "tags": [], "nested": null}),
+            dataset[1].to_value()
+        );
+    }
+    #[test]
+    fn write_and_read_container_with_schema_and_codec() {
+        let mut document = Avro {
+            schema: Some(json!({
+                "type": "record",
+                "name": "order",
+                "fields": [
+                    {"name": "number", "type": "int"},
+                    {"name": "status", "type": {"type": "enum", "name": "status", "symbols": ["NEW", "DONE"]}, "default": "NEW"}
+                ]
+            })),
+            codec: CodecType::Deflate,
+            ..Default::default()
+        };
+        let dataset = vec![
+            DataResult::Ok(json!({"number": 1, "status": "DONE"})),
+            DataResult::Ok(json!({"number": 2})),
+        ];
+        let buffer = document.write(&dataset).unwrap();
+        assert!(!document.can_append());
+
+        // The reader schema adds a field with a default value.
+        document.schema = Some(json!({
+            "type": "record",
+            "name": "order",
+            "fields": [
+                {"name": "number", "type": "long"},
+                {"name": "status", "type": {"type": "enum", "name": "status", "symbols": ["NEW", "DONE"]}},
+                {"name": "label", "type": "string", "default": "none"}
+            ]
+        }));
+        let values: Vec<Value> = document
+            .read(&buffer)
+            .unwrap()
+            .iter()
+            .map(DataResult::to_value)
+            .collect();
+        assert_eq!(
+            vec![
+                json!({"number": 1, "status": "DONE", "label": "none"}),
+                json!({"number": 2, "status": "NEW", "label": "none"})
+            ],
+            values
+        );
+    }
+    #[test]
+    fn write_with_invalid_record() {
+        let document = Avro {
+            schema: Some(
+                json!({"type": "record", "name": "order", "fields": [{"name": "number", "type": "int"}]}),
+            ),
+            ..Default::default()
+        };
+        let dataset = vec![DataResult::Ok(json!({"number": "ten"}))];
+        assert!(document.write(&dataset).is_err());
+    }
+    #[test]
+    fn write_and_read_single_object() {
+        let mut document = Avro {
+            encoding: Encoding::SingleObject,
+            schema: Some(
+                json!({"type": "record", "name": "order", "fields": [{"name": "number", "type": "long"}]}),
+            ),
+            ..Default::default()
+        };
+        let dataset = vec![DataResult::Ok(json!({"number": 10}))];
+        assert!(document.can_append());
+        let mut buffer = document.write(&dataset).unwrap();
+        buffer.extend(document.write(&dataset).unwrap());
+
+        let dataset = document.read(&buffer).unwrap();
+        assert_eq!(2, dataset.len());
+        assert_eq!(json!({"number": 10}), dataset[1].to_value());
+
+        document.schema = Some(
+            json!({"type": "record", "name": "order", "fields": [{"name": "number", "type": "int"}]}),
+        );
+        let error = document.read(&buffer).unwrap_err();
+        assert_eq!(io::ErrorKind::InvalidData, error.kind());
+
+        document.schema = None;
+        let error = document.read(&buffer).unwrap_err();
+        assert_eq!(io::ErrorKind::InvalidInput, error.kind());
+    }
+    #[test]
+    fn write_and_read_confluent() {
+        let mut document = Avro {
+            encoding: Encoding::Confluent,
+            registry: Some(SchemaRegistry {
+                endpoint: schema_registry(),
+                subject: Some("orders-value".to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let buffer = document.write(&dataset()).unwrap();
+        assert_eq!(vec![0, 0, 0, 0, 7], buffer[..5]);
+
+        // Read with the schema of the registry.
+        document.registry.as_mut().unwrap().subject = None;
+        let dataset = document.read(&buffer).unwrap();
+        assert_eq!(2, dataset.len());
+        assert_eq!(
+            json!({"number": 20, "long_string": null, "round": 2.0, "tags": [], "nested": null}),
+            dataset[1].to_value()
+        );
+
+        let error = document.read(&[0, 0, 0, 0, 8, 2]).unwrap_err();
+        assert_eq!(io::ErrorKind::Interrupted, error.kind());
+        let error = document.read(&[1, 0, 0, 0, 7, 2]).unwrap_err();
+        assert_eq!(io::ErrorKind::InvalidData, error.kind());
+    }
+    #[apply(test!)]
+    async fn exec_with_confluent() {
+        let document = json!({
+            "type": "avro",
+            "encoding": "confluent",
+            "registry": {
+                "endpoint": schema_registry(),
+                "subject": "orders-value"
+            }
+        });
+        let path = std::env::temp_dir().join(format!("chewdata-{}.avro", uuid::Uuid::new_v4()));
+
+        // The document calls the registry inside the steps spawned on the executor.
+        let steps: Vec<StepType> = serde_json::from_value(json!([
+            {
+                "type": "r",
+                "connector": {
+                    "type": "in_memory",
+                    "data": r#"[{"number":10,"label":"first"},{"number":20,"label":"second"}]"#
+                }
+            },
+            {
+                "type": "w",
+                "connector": {"type": "local", "path": path.display().to_string()},
+                "document": document
+            }
+        ]))
+        .unwrap();
+        crate::exec(steps, None, None).await.unwrap();
+
+        let steps: Vec<StepType> = serde_json::from_value(json!([
+            {
+                "type": "r",
+                "connector": {"type": "local", "path": path.display().to_string()},
+                "document": document
+            }
+        ]))
+        .unwrap();
+        let (sender, receiver) = async_channel::unbounded();
+        crate::exec(steps, None, Some(sender)).await.unwrap();
+        std::fs::remove_file(path).unwrap();
+
+        let mut values = Vec::default();
+        while let Ok(context) = receiver.try_recv() {
+            values.push(context.input().to_value());
+        }
+        assert_eq!(
+            vec![
+                json!({"number": 10, "label": "first"}),
+                json!({"number": 20, "label": "second"})
+            ],
+            values
+        );
+    }
+    #[test]
+    fn infer_schema_with_scalar() {
+        let schema = infer_schema(&json!([1, 2])).unwrap();
+        assert_eq!(
+            r#"{"name":"record","type":"record","fields":[{"name":"value","type":["null",{"type":"array","items":"long"}]}]}"#,
+            schema.canonical_form()
+        );
+    }
+}
@@ -0,0 +1,312 @@
+//! Read and write data in **BSON** format.
+//!
+//! The document contains concatenated BSON documents, like the `.bson` files of `mongodump`. Each BSON document is a record.
+//!
+//! The BSON types without JSON equivalent are converted in [MongoDB Extended JSON v2](https://www.mongodb.com/docs/manual/reference/mongodb-extended-json/),
+//! like `{"$oid": "..."}` for an ObjectId, `{"$date": "..."}` for a date or `{"$numberDecimal": "..."}` for a decimal.
+//! The relaxed mode keeps the numbers and the recent dates readable, the canonical mode keeps the exact type of each value.
+//! The records written are parsed as Extended JSON, so the records read in canonical mode are written back without loss.
+//!
+//! ### Configuration
+//!
+//! | key          | alias     | Description                                         | Default Value | Possible Values     |
+//! | ------------ | --------- | --------------------------------------------------- | ------------- | ------------------- |
+//! | type         | -         | Required in order to use this document.             | `bson`        | `bson`              |
+//! | metadata     | meta      | Metadata describe the resource.                     | `null`        | [`crate::Metadata`] |
+//! | is_canonical | canonical | Read the values in canonical Extended JSON.         | `false`       | `true` / `false`    |
+//!
+//! ### Examples
+//!
+//! ```json
+//! [
+//!     {
+//!         "type": "read",
+//!         "document": {
+//!             "type": "bson"
+//!         },
+//!         "connector":{
+//!             "type": "local",
+//!             "path": "./dump/shop/orders.bson"
+//!         }
+//!     },
+//!     {
+//!         "type": "write",
+//!         "document": {
+//!             "type": "bson"
+//!         },
+//!         "connector":{
+//!             "type": "bucket",
+//!             "bucket": "backups",
+//!             "path": "dump/shop/orders.bson"
+//!         }
+//!     }
+//! ]
+//! ```
+//!
+//! output:
+//!
+//! ```json
+//! [
+//!     {"_id": {"$oid": "5f1a2b3c4d5e6f7a8b9c0d1e"}, "amount": {"$numberDecimal": "10.50"}, "created_at": {"$date": "2020-12-31T10:00:00Z"}},
+//!     ...
+//! ]
+//! ```
+use crate::document::Document;
+use crate::helper::string::DisplayOnlyForDebugging;
+use crate::DataResult;
+use crate::{DataSet, Metadata};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::io::{self, Cursor};
+
+const DEFAULT_SUBTYPE: &str = "bson";
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Bson {
+    #[serde(rename = "metadata")]
+    #[serde(alias = "meta")]
+    pub metadata: Metadata,
+    #[serde(alias = "canonical")]
+    pub is_canonical: bool,
+}
+
+impl Default for Bson {
+    fn default() -> Self {
+        let metadata = Metadata {
+            mime_type: Some(mime::APPLICATION.to_string()),
+            mime_subtype: Some(DEFAULT_SUBTYPE.to_string()),
+            ..Default::default()
+        };
+        Bson {
+            metadata,
+            is_canonical: false,
+        }
+    }
+}
+
+impl Document for Bson {
+    /// See [`Document::set_metadata`] for more details.
+    fn set_metadata(&mut self, metadata: Metadata) {
+        self.metadata = metadata.clone();
+    }
+    /// See [`Document::metadata`] for more details.
+    fn metadata(&self) -> Metadata {
+        Bson::default().metadata.merge(&self.metadata)
+    }
+    /// See [`Document::read`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::document::bson::Bson;
+    /// use chewdata::document::Document;
+    /// use serde_json::json;
+    ///
+    /// let document = Bson::default();
+    /// // {"number": 10}
+    /// let buffer = vec![0x11, 0x00, 0x00, 0x00, 0x10, b'n', b'u', b'm', b'b', b'e', b'r', 0x00, 0x0a, 0x00, 0x00, 0x00, 0x00];
+    ///
+    /// let mut dataset = document.read(&buffer).unwrap().into_iter();
+    /// assert_eq!(json!({"number": 10}), dataset.next().unwrap().to_value());
+    /// ```
+    #[instrument(skip(buffer), name = "bson::read")]
+    fn read(&self, buffer: &[u8]) -> io::Result<DataSet> {
+        let mut cursor = Cursor::new(buffer);
+        let mut dataset = Vec::default();
+
+        while (cursor.position() as usize) < buffer.len() {
+            match ::bson::Document::from_reader(&mut cursor) {
+                Ok(document) => {
+                    let record = match self.is_canonical {
+                        true => ::bson::Bson::Document(document).into_canonical_extjson(),
+                        false => ::bson::Bson::Document(document).into_relaxed_extjson(),
+                    };
+                    trace!(
+                        record = record.display_only_for_debugging(),
+                        "Record deserialized"
+                    );
+                    dataset.push(DataResult::Ok(record));
+                }
+                Err(e) => {
+                    warn!(
+                        error = format!("{:?}", e).as_str(),
+                        "Can't deserialize the record"
+                    );
+                    dataset.push(DataResult::Err((
+                        Value::Null,
+                        io::Error::new(io::ErrorKind::InvalidInput, e.to_string()),
+                    )));
+                    break;
+                }
+            }
+        }
+
+        Ok(dataset)
+    }
+    /// See [`Document::write`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::document::bson::Bson;
+    /// use chewdata::document::Document;
+    /// use chewdata::DataResult;
+    /// use serde_json::json;
+    ///
+    /// let document = Bson::default();
+    /// let dataset = vec![DataResult::Ok(json!({"number": 10}))];
+    ///
+    /// let buffer = document.write(&dataset).unwrap();
+    /// assert_eq!(vec![0x11, 0x00, 0x00, 0x00, 0x10, b'n', b'u', b'm', b'b', b'e', b'r', 0x00, 0x0a, 0x00, 0x00, 0x00, 0x00], buffer);
+    /// ```
+    #[instrument(skip(dataset), name = "bson::write")]
+    fn write(&self, dataset: &DataSet) -> io::Result<Vec<u8>> {
+        let mut buffer = Vec::default();
+
+        for data in dataset {
+            let record = match data.to_value() {
+                Value::Object(record) => record,
+                record => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        format!(
+                            "The record '{}' is not an object",
+                            record.display_only_for_debugging()
+                        ),
+                    ))
+                }
+            };
+
+            let document = ::bson::Document::try_from(record.clone())
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+            document
+                .to_writer(&mut buffer)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+
+            let record = Value::Object(record);
+            trace!(
+                record = record.display_only_for_debugging(),
+                "Record serialized"
+            );
+        }
+
+        Ok(buffer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::bson::{doc, oid::ObjectId, DateTime, Decimal128};
+    use serde_json::json;
+    use std::str::FromStr;
+
+    fn buffer() -> Vec<u8> {
+        let mut buffer = Vec::default();
+        doc! {
+            "_id": ObjectId::parse_str("5f1a2b3c4d5e6f7a8b9c0d1e").unwrap(),
+            "amount": Decimal128::from_str("10.50").unwrap(),
+            "created_at": DateTime::parse_rfc3339_str("2020-12-31T10:00:00Z").unwrap(),
+            "quantity": 3_i64,
+            "tags": ["new", "promo"],
+        }
+        .to_writer(&mut buffer)
+        .unwrap();
+        doc! {"_id": 2, "comment": null}
+            .to_writer(&mut buffer)
+            .unwrap();
+
+        buffer
+    }
+
+    #[test]
+    fn read_relaxed() {
+        let document = Bson::default();
+
+        let values: Vec<Value> = document
+            .read(&buffer())
+            .unwrap()
+            .iter()
+            .map(DataResult::to_value)
+            .collect();
+        assert_eq!(
+            vec![
+                json!({
+                    "_id": {"$oid": "5f1a2b3c4d5e6f7a8b9c0d1e"},
+                    "amount": {"$numberDecimal": "10.50"},
+                    "created_at": {"$date": "2020-12-31T10:00:00Z"},
+                    "quantity": 3,
+                    "tags": ["new", "promo"]
+                }),
+                json!({"_id": 2, "comment": null})
+            ],
+            values
+        );
+    }
+    #[test]
+    fn read_canonical() {
+        let document = Bson {
+            is_canonical: true,
+            ..Default::default()
+        };
+
+        let dataset = document.read(&buffer()).unwrap();
+        assert_eq!(
+            json!({"$numberLong": "3"}),
+            dataset[0].to_value()["quantity"]
+        );
+        assert_eq!(
+            json!({"$date": {"$numberLong": "1609408800000"}}),
+            dataset[0].to_value()["created_at"]
+        );
+        assert_eq!(json!({"$numberInt": "2"}), dataset[1].to_value()["_id"]);
+    }
+    #[test]
+    fn read_truncated_document() {
+        let document = Bson::default();
+        let mut buffer = buffer();
+        buffer.truncate(buffer.len() - 2);
+
+        let dataset = document.read(&buffer).unwrap();
+        assert_eq!(2, dataset.len());
+        assert!(matches!(dataset[0], DataResult::Ok(_)));
+        assert!(matches!(dataset[1], DataResult::Err(_)));
+    }
+    #[test]
+    fn write_canonical_without_loss() {
+        let document = Bson {
+            is_canonical: true,
+            ..Default::default()
+        };
+
+        let dataset = document.read(&buffer()).unwrap();
+        assert_eq!(buffer(), document.write(&dataset).unwrap());
+    }
+    #[test]
+    fn write_relaxed() {
+        let dataset = Bson::default().read(&buffer()).unwrap();
+        let buffer = Bson::default().write(&dataset).unwrap();
+
+        let document = Bson {
+            is_canonical: true,
+            ..Default::default()
+        };
+        let record = document.read(&buffer).unwrap()[0].to_value();
+        assert_eq!(json!({"$oid": "5f1a2b3c4d5e6f7a8b9c0d1e"}), record["_id"]);
+        assert_eq!(json!({"$numberDecimal": "10.50"}), record["amount"]);
+        assert_eq!(
+            json!({"$date": {"$numberLong": "1609408800000"}}),
+            record["created_at"]
+        );
+        assert_eq!(json!({"$numberInt": "3"}), record["quantity"]);
+    }
+    #[test]
+    fn write_not_an_object() {
+        let document = Bson::default();
+        let error = document
+            .write(&vec![DataResult::Ok(json!([1, 2]))])
+            .unwrap_err();
+        assert_eq!(io::ErrorKind::InvalidInput, error.kind());
+    }
+}
@@ -0,0 +1,362 @@
+//! Read and write data in **CBOR** format ([RFC 8949](https://datatracker.ietf.org/doc/html/rfc8949)).
+//!
+//! The document can contain a single value, an array of values or a sequence of concatenated values ([RFC 8742](https://datatracker.ietf.org/doc/html/rfc8742)).
+//! Each object is a record and each item of an array is a record.
+//! The byte strings are converted into base64 strings and the tags are removed to keep only the tagged values.
+//!
+//! By default, the records are written as a sequence of concatenated values that can be appended to the document.
+//! With `is_array`, the records are written in one array and the document is replaced.
+//!
+//! ### Configuration
+//!
+//! | key        | alias | Description                                            | Default Value | Possible Values                                                                 |
+//! | ---------- | ----- | ------------------------------------------------------ | ------------- | ------------------------------------------------------------------------------- |
+//! | type       | -     | Required in order to use this document.                | `cbor`        | `cbor`                                                                          |
+//! | metadata   | meta  | Metadata describe the resource.                        | `null`        | [`crate::Metadata`]                                                             |
+//! | entry_path | -     | Use this field to read the records from a sub path.    | `null`        | JSON Pointer string ([RFC 6901](https://datatracker.ietf.org/doc/html/rfc6901)) |
+//! | is_array   | -     | Write the records in one array.                        | `false`       | `true` / `false`                                                                |
+//!
+//! ### Examples
+//!
+//! ```json
+//! [
+//!     {
+//!         "type": "read",
+//!         "connector":{
+//!             "type": "local",
+//!             "path": "./data/multi_lines.json"
+//!         }
+//!     },
+//!     {
+//!         "type": "write",
+//!         "document": {
+//!             "type": "cbor"
+//!         },
+//!         "connector":{
+//!             "type": "local",
+//!             "path": "./data/out/multi_lines.cbor"
+//!         }
+//!     }
+//! ]
+//! ```
+use crate::document::Document;
+use crate::helper::string::DisplayOnlyForDebugging;
+use crate::DataResult;
+use crate::{DataSet, Metadata};
+use base64::Engine;
+use json_value_search::Search;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Number, Value};
+use std::io::{self, Cursor};
+
+const DEFAULT_SUBTYPE: &str = "cbor";
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Cbor {
+    #[serde(rename = "metadata")]
+    #[serde(alias = "meta")]
+    pub metadata: Metadata,
+    pub entry_path: Option<String>,
+    pub is_array: bool,
+}
+
+impl Default for Cbor {
+    fn default() -> Self {
+        let metadata = Metadata {
+            mime_type: Some(mime::APPLICATION.to_string()),
+            mime_subtype: Some(DEFAULT_SUBTYPE.to_string()),
+            ..Default::default()
+        };
+        Cbor {
+            metadata,
+            entry_path: None,
+            is_array: false,
+        }
+    }
+}
+
+/// Transform a CBOR value into a json value.
+///
+/// # Examples
+///
+/// ```
+/// use chewdata::document::cbor::to_json;
+/// use serde_json::json;
+///
+/// let value = ciborium::Value::Map(vec![
+///     (ciborium::Value::from(1), ciborium::Value::Bytes(b"bytes".to_vec())),
+///     (ciborium::Value::from("date"), ciborium::Value::Tag(0, Box::new(ciborium::Value::from("2020-12-31T00:00:00Z")))),
+/// ]);
+/// assert_eq!(json!({"1": "Ynl0ZXM=", "date": "2020-12-31T00:00:00Z"}), to_json(value));
+/// ```
+pub fn to_json(value: ciborium::Value) -> Value {
+    match value {
+        ciborium::Value::Null => Value::Null,
+        ciborium::Value::Bool(boolean) => Value::Bool(boolean),
+        ciborium::Value::Integer(integer) => {
+            let integer = i128::from(integer);
+            match (i64::try_from(integer), u64::try_from(integer)) {
+                (Ok(integer), _) => Value::from(integer),
+                (_, Ok(integer)) => Value::from(integer),
+                _ => Value::String(integer.to_string()),
+            }
+        }
+        ciborium::Value::Float(float) => Number::from_f64(float).map_or(Value::Null, Value::Number),
+        ciborium::Value::Text(string) => Value::String(string),
+        ciborium::Value::Bytes(data) => {
+            Value::String(base64::engine::general_purpose::STANDARD.encode(data))
+        }
+        ciborium::Value::Tag(_, value) => to_json(*value),
+        ciborium::Value::Array(array) => Value::Array(array.into_iter().map(to_json).collect()),
+        ciborium::Value::Map(map) => Value::Object(
+            map.into_iter()
+                .map(|(key, value)| {
+                    let key = match to_json(key) {
+                        Value::String(key) => key,
+                        key => key.to_string(),
+                    };
+                    (key, to_json(value))
+                })
+                .collect::<Map<String, Value>>(),
+        ),
+        _ => Value::Null,
+    }
+}
+
+impl Cbor {
+    fn push_records(&self, record: Value, dataset: &mut DataSet) -> io::Result<()> {
+        let records = match &self.entry_path {
+            Some(entry_path) => match record.clone().search(entry_path)? {
+                Some(records) => records,
+                None => {
+                    warn!(
+                        entry_path = format!("{:?}", entry_path).as_str(),
+                        record = record.display_only_for_debugging(),
+                        "Entry path not found"
+                    );
+                    dataset.push(DataResult::Err((
+                        record,
+                        io::Error::new(
+                            io::ErrorKind::InvalidInput,
+                            format!("Entry path '{}' not found", entry_path),
+                        ),
+                    )));
+                    return Ok(());
+                }
+            },
+            None => record,
+        };
+
+        let records = match records {
+            Value::Array(records) => records,
+            record => vec![record],
+        };
+
+        for record in records {
+            trace!(
+                record = record.display_only_for_debugging(),
+                "Record deserialized"
+            );
+            dataset.push(DataResult::Ok(record));
+        }
+
+        Ok(())
+    }
+}
+
+impl Document for Cbor {
+    /// See [`Document::set_metadata`] for more details.
+    fn set_metadata(&mut self, metadata: Metadata) {
+        self.metadata = metadata.clone();
+    }
+    /// See [`Document::metadata`] for more details.
+    fn metadata(&self) -> Metadata {
+        Cbor::default().metadata.merge(&self.metadata)
+    }
+    /// See [`Document::set_entry_path`] for more details.
+    fn set_entry_path(&mut self, entry_path: String) {
+        if entry_path.is_empty() {
+            self.entry_path = None;
+            return;
+        }
+
+        self.entry_path = Some(entry_path);
+    }
+    /// See [`Document::can_append`] for more details.
+    fn can_append(&self) -> bool {
+        !self.is_array
+    }
+    /// See [`Document::read`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::document::cbor::Cbor;
+    /// use chewdata::document::Document;
+    /// use serde_json::json;
+    ///
+    /// let document = Cbor::default();
+    /// // [{"number": 10}, {"number": 20}]
+    /// let buffer = vec![0x82, 0xa1, 0x66, b'n', b'u', b'm', b'b', b'e', b'r', 0x0a, 0xa1, 0x66, b'n', b'u', b'm', b'b', b'e', b'r', 0x14];
+    ///
+    /// let mut dataset = document.read(&buffer).unwrap().into_iter();
+    /// assert_eq!(json!({"number": 10}), dataset.next().unwrap().to_value());
+    /// assert_eq!(json!({"number": 20}), dataset.next().unwrap().to_value());
+    /// ```
+    #[instrument(skip(buffer), name = "cbor::read")]
+    fn read(&self, buffer: &[u8]) -> io::Result<DataSet> {
+        let mut cursor = Cursor::new(buffer);
+        let mut dataset = Vec::default();
+
+        while (cursor.position() as usize) < buffer.len() {
+            match ciborium::de::from_reader::<ciborium::Value, _>(&mut cursor) {
+                Ok(value) => self.push_records(to_json(value), &mut dataset)?,
+                Err(e) => {
+                    warn!(
+                        error = format!("{:?}", e).as_str(),
+                        "Can't deserialize the record"
+                    );
+                    dataset.push(DataResult::Err((
+                        Value::Null,
+                        io::Error::new(io::ErrorKind::InvalidInput, e.to_string()),
+                    )));
+                    break;
+                }
+            }
+        }
+
+        Ok(dataset)
+    }
+    /// See [`Document::write`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::document::cbor::Cbor;
+    /// use chewdata::document::Document;
+    /// use chewdata::DataResult;
+    /// use serde_json::json;
+    ///
+    /// let document = Cbor::default();
+    /// let dataset = vec![DataResult::Ok(json!({"number": 10}))];
+    ///
+    /// let buffer = document.write(&dataset).unwrap();
+    /// assert_eq!(vec![0xa1, 0x66, b'n', b'u', b'm', b'b', b'e', b'r', 0x0a], buffer);
+    /// ```
+    #[instrument(skip(dataset), name = "cbor::write")]
+    fn write(&self, dataset: &DataSet) -> io::Result<Vec<u8>> {
+        let mut buffer = Vec::default();
+        let records: Vec<Value> = dataset
+            .iter()
+            .map(|data| {
+                let record = data.to_value();
+                trace!(
+                    record = record.display_only_for_debugging(),
+                    "Record serialized"
+                );
+                record
+            })
+            .collect();
+
+        if self.is_array {
+            ciborium::ser::into_writer(&records, &mut buffer)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e.to_string()))?;
+            return Ok(buffer);
+        }
+
+        for record in records {
+            ciborium::ser::into_writer(&record, &mut buffer)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e.to_string()))?;
+        }
+
+        Ok(buffer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn read_sequence_of_values() {
+        let document = Cbor::default();
+        let mut buffer = Vec::default();
+        ciborium::ser::into_writer(&json!({"number": 10}), &mut buffer).unwrap();
+        ciborium::ser::into_writer(&json!([{"number": 20}, {"number": 30}]), &mut buffer).unwrap();
+
+        let values: Vec<Value> = document
+            .read(&buffer)
+            .unwrap()
+            .iter()
+            .map(DataResult::to_value)
+            .collect();
+        assert_eq!(
+            vec![
+                json!({"number": 10}),
+                json!({"number": 20}),
+                json!({"number": 30})
+            ],
+            values
+        );
+    }
+    #[test]
+    fn read_with_entry_path() {
+        let document = Cbor {
+            entry_path: Some("/items".to_string()),
+            ..Default::default()
+        };
+        let buffer = Cbor::default()
+            .write(&vec![
+                DataResult::Ok(json!({"items": [{"number": 10}, {"number": 20}]})),
+                DataResult::Ok(json!({"other": true})),
+            ])
+            .unwrap();
+
+        let dataset = document.read(&buffer).unwrap();
+        assert_eq!(3, dataset.len());
+        assert_eq!(json!({"number": 20}), dataset[1].to_value());
+        assert!(matches!(dataset[2], DataResult::Err(_)));
+    }
+    #[test]
+    fn read_truncated_value() {
+        let document = Cbor::default();
+        let mut buffer = document
+            .write(&vec![
+                DataResult::Ok(json!({"number": 10})),
+                DataResult::Ok(json!({"string": "value"})),
+            ])
+            .unwrap();
+        buffer.truncate(buffer.len() - 2);
+
+        let dataset = document.read(&buffer).unwrap();
+        assert_eq!(2, dataset.len());
+        assert_eq!(json!({"number": 10}), dataset[0].to_value());
+        assert!(matches!(dataset[1], DataResult::Err(_)));
+    }
+    #[test]
+    fn write_array() {
+        let document = Cbor {
+            is_array: true,
+            ..Default::default()
+        };
+        let dataset = vec![
+            DataResult::Ok(json!({"number": 10, "float": 1.5, "list": [true, null]})),
+            DataResult::Ok(json!({"number": -20, "string": "é"})),
+        ];
+
+        let buffer = document.write(&dataset).unwrap();
+        assert_eq!(0x82, buffer[0]);
+        assert!(!document.can_append());
+
+        let values: Vec<Value> = document
+            .read(&buffer)
+            .unwrap()
+            .iter()
+            .map(DataResult::to_value)
+            .collect();
+        let expected: Vec<Value> = dataset.iter().map(DataResult::to_value).collect();
+        assert_eq!(expected, values);
+    }
+}
@@ -0,0 +1,720 @@
+//! Read and write data in **fixed-width** (positional) format.
+//!
+//! Each line is a record and each field is read at a fixed position of the line.
+//! The positions are counted in characters and start at `1`, like in the specifications of the mainframe exports.
+//!
+//! A file with several kinds of lines uses `layouts`. The layout of a line is found with the text of the `discriminator` field:
+//! the layout with the same `value` is used and a layout without `value` is used for the other lines.
+//! When the records are written, the layout is found with the value of the `discriminator` field of the record.
+//! The fields of a layout must start at the position `1` or more and must not overlap to be written.
+//!
+//! The string fields are trimmed of their padding character when `trim` is enabled. The other fields are always trimmed.
+//! The numbers padded with `0` and aligned on the right keep their sign in the first character, like `-0042`.
+//! The booleans are written with `1` and `0` and read from `1` / `0`, `true` / `false`, `t` / `f`, `yes` / `no` or `y` / `n`.
+//! An empty field is read as `null`.
+//!
+//! ### Configuration
+//!
+//! | key           | alias | Description                                                 | Default Value | Possible Values                                        |
+//! | ------------- | ----- | ----------------------------------------------------------- | ------------- | ------------------------------------------------------ |
+//! | type          | -     | Required in order to use this document.                     | `fixed_width` | `fixed_width` / `fwf` / `positional`                   |
+//! | metadata      | meta  | Metadata describe the resource.                             | `null`        | [`crate::Metadata`]                                    |
+//! | fields        | -     | Fields of the lines when the file has only one layout.      | `[]`          | List of [`crate::document::fixed_width::Field`]        |
+//! | layouts       | -     | Layouts of the lines when the file has several record types.| `[]`          | List of [`crate::document::fixed_width::Layout`]       |
+//! | discriminator | -     | Name of the field that gives the layout of the line.        | `null`        | String                                                 |
+//!
+//! ### Layout
+//!
+//! | key    | alias | Description                                                        | Default Value | Possible Values                                 |
+//! | ------ | ----- | ------------------------------------------------------------------ | ------------- | ----------------------------------------------- |
+//! | value  | -     | Value of the discriminator for this layout. `null` for the others. | `null`        | String                                          |
+//! | fields | -     | Fields of the lines.                                               | `[]`          | List of [`crate::document::fixed_width::Field`] |
+//!
+//! ### Field
+//!
+//! | key       | alias | Description                                            | Default Value | Possible Values                               |
+//! | --------- | ----- | ------------------------------------------------------ | ------------- | --------------------------------------------- |
+//! | name      | -     | Name of the field in the record.                       | Required      | String                                        |
+//! | start     | -     | Position of the first character, starting at `1`.      | Required      | Unsigned integer                              |
+//! | length    | -     | Number of characters.                                  | Required      | Unsigned integer                              |
+//! | type      | -     | Type of the value.                                     | `string`      | `string` / `integer` / `float` / `boolean`    |
+//! | alignment | align | Side of the value, the padding fills the other side.   | `left`        | `left` / `right`                              |
+//! | padding   | -     | Character used to fill the field.                      | ` `           | Character                                     |
+//! | trim      | -     | Remove the padding of the string values.               | `true`        | `true` / `false`                              |
+//!
+//! ### Examples
+//!
+//! ```json
+//! [
+//!     {
+//!         "type": "read",
+//!         "document": {
+//!             "type": "fixed_width",
+//!             "discriminator": "record_type",
+//!             "layouts": [
+//!                 {
+//!                     "value": "01",
+//!                     "fields": [
+//!                         {"name": "record_type", "start": 1, "length": 2},
+//!                         {"name": "bank", "start": 3, "length": 10}
+//!                     ]
+//!                 },
+//!                 {
+//!                     "value": "02",
+//!                     "fields": [
+//!                         {"name": "record_type", "start": 1, "length": 2},
+//!                         {"name": "account", "start": 3, "length": 11, "padding": "0", "align": "right"},
+//!                         {"name": "amount", "start": 14, "length": 12, "type": "float", "padding": "0", "align": "right"}
+//!                     ]
+//!                 }
+//!             ]
+//!         },
+//!         "connector":{
+//!             "type": "local",
+//!             "path": "./data/statements.txt"
+//!         }
+//!     }
+//! ]
+//! ```
+//!
+//! input:
+//!
+//! ```text
+//! 01BANK
+//! 02000123456780000000125.5
+//! ```
+//!
+//! output:
+//!
+//! ```json
+//! [
+//!     {"record_type": "01", "bank": "BANK"},
+//!     {"record_type": "02", "account": "12345678", "amount": 125.5}
+//! ]
+//! ```
+use crate::document::Document;
+use crate::helper::string::DisplayOnlyForDebugging;
+use crate::DataResult;
+use crate::{DataSet, Metadata};
+use serde::{Deserialize, Serialize};
+use serde_json::{Number, Value};
+use std::io;
+
+const DEFAULT_SUBTYPE: &str = "plain";
+const DEFAULT_TERMINATOR: &str = "\n";
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum FieldType {
+    #[default]
+    String,
+    #[serde(alias = "int")]
+    Integer,
+    #[serde(alias = "number")]
+    Float,
+    #[serde(alias = "bool")]
+    Boolean,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Alignment {
+    #[default]
+    Left,
+    Right,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct Field {
+    pub name: String,
+    pub start: usize,
+    pub length: usize,
+    #[serde(rename = "type")]
+    #[serde(default)]
+    pub field_type: FieldType,
+    #[serde(alias = "align")]
+    #[serde(default)]
+    pub alignment: Alignment,
+    #[serde(default = "default_padding")]
+    pub padding: char,
+    #[serde(default = "default_trim")]
+    pub trim: bool,
+}
+
+fn default_padding() -> char {
+    ' '
+}
+
+fn default_trim() -> bool {
+    true
+}
+
+impl Field {
+    pub fn new(name: &str, start: usize, length: usize) -> Self {
+        Field {
+            name: name.to_string(),
+            start,
+            length,
+            field_type: FieldType::default(),
+            alignment: Alignment::default(),
+            padding: default_padding(),
+            trim: default_trim(),
+        }
+    }
+    /// Position of the character following the field, starting at 0.
+    fn end(&self) -> usize {
+        self.start.saturating_sub(1) + self.length
+    }
+    /// Get the text of the field in the line. The missing characters at the end of a short line are ignored.
+    fn text(&self, line: &[char]) -> String {
+        let start = self.start.saturating_sub(1).min(line.len());
+        let end = self.end().min(line.len());
+
+        line[start..end].iter().collect()
+    }
+    /// Remove the padding of the text.
+    fn trim<'a>(&self, text: &'a str) -> &'a str {
+        match self.alignment {
+            Alignment::Left => text.trim_end_matches(self.padding),
+            Alignment::Right => text.trim_start_matches(self.padding),
+        }
+    }
+    /// Transform the text of the field into a json value.
+    fn value(&self, text: &str) -> io::Result<Value> {
+        if let FieldType::String = self.field_type {
+            return Ok(match self.trim {
+                true => Value::String(self.trim(text).to_string()),
+                false => Value::String(text.to_string()),
+            });
+        }
+
+        let trimmed = self.trim(text).trim();
+        let trimmed = match (trimmed.is_empty(), self.padding.is_ascii_digit()) {
+            (true, true) if !text.trim().is_empty() => "0",
+            (true, _) => return Ok(Value::Null),
+            _ => trimmed,
+        };
+        let invalid_value = || {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "The value '{}' of the field '{}' is not a valid {:?}",
+                    text, self.name, self.field_type
+                ),
+            )
+        };
+
+        match self.field_type {
+            FieldType::Integer => trimmed
+                .parse::<i64>()
+                .map(Value::from)
+                .map_err(|_| invalid_value()),
+            FieldType::Float => trimmed
+                .parse::<f64>()
+                .ok()
+                .and_then(Number::from_f64)
+                .map(Value::Number)
+                .ok_or_else(invalid_value),
+            FieldType::Boolean => match trimmed.to_lowercase().as_str() {
+                "1" | "true" | "t" | "yes" | "y" => Ok(Value::Bool(true)),
+                "0" | "false" | "f" | "no" | "n" => Ok(Value::Bool(false)),
+                _ => Err(invalid_value()),
+            },
+            FieldType::String => unreachable!(),
+        }
+    }
+    /// Transform a json value into the text of the field, padded to its length.
+    fn format(&self, value: Option<&Value>) -> io::Result<Vec<char>> {
+        let invalid_value = |value: &Value| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "The value '{}' of the field '{}' is not a valid {:?}",
+                    value, self.name, self.field_type
+                ),
+            )
+        };
+
+        let text = match (&self.field_type, value) {
+            (_, None) | (_, Some(Value::Null)) => String::default(),
+            (FieldType::String, Some(Value::String(string))) => string.clone(),
+            (FieldType::String, Some(value)) => value.to_string(),
+            (FieldType::Integer, Some(value)) => match value {
+                Value::Number(number) if number.is_i64() || number.is_u64() => number.to_string(),
+                Value::String(string) if string.trim().parse::<i64>().is_ok() => {
+                    string.trim().to_string()
+                }
+                value => return Err(invalid_value(value)),
+            },
+            (FieldType::Float, Some(value)) => match value {
+                Value::Number(number) => number.to_string(),
+                Value::String(string) if string.trim().parse::<f64>().is_ok() => {
+                    string.trim().to_string()
+                }
+                value => return Err(invalid_value(value)),
+            },
+            (FieldType::Boolean, Some(value)) => match value {
+                Value::Bool(true) => "1".to_string(),
+                Value::Bool(false) => "0".to_string(),
+                value => return Err(invalid_value(value)),
+            },
+        };
+
+        let chars: Vec<char> = text.chars().collect();
+        if chars.len() > self.length {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "The value '{}' of the field '{}' exceeds its length of {} characters",
+                    text, self.name, self.length
+                ),
+            ));
+        }
+
+        let padding = vec![self.padding; self.length - chars.len()];
+        Ok(match (&self.alignment, chars.first()) {
+            (Alignment::Left, _) => [chars, padding].concat(),
+            (Alignment::Right, Some('-' | '+')) if self.padding.is_ascii_digit() => {
+                [&chars[..1], &padding, &chars[1..]].concat()
+            }
+            (Alignment::Right, _) => [padding, chars].concat(),
+        })
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct Layout {
+    pub value: Option<String>,
+    pub fields: Vec<Field>,
+}
+
+impl Layout {
+    /// Get the text of the discriminator in the line.
+    fn discriminator(&self, discriminator: &str, line: &[char]) -> Option<String> {
+        self.fields
+            .iter()
+            .find(|field| field.name == discriminator)
+            .map(|field| field.trim(&field.text(line)).trim().to_string())
+    }
+    fn width(&self) -> usize {
+        self.fields.iter().map(Field::end).max().unwrap_or_default()
+    }
+    /// Check that the fields are in the line and don't overlap, in order to write them.
+    fn validate(&self) -> io::Result<()> {
+        let mut fields: Vec<&Field> = self.fields.iter().collect();
+        fields.sort_by_key(|field| field.start);
+
+        if let Some(field) = fields
+            .iter()
+            .find(|field| 0 == field.start || 0 == field.length)
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "The field '{}' must start at the position 1 or more and have a length",
+                    field.name
+                ),
+            ));
+        }
+
+        if let Some(pair) = fields
+            .windows(2)
+            .find(|pair| pair[1].start.saturating_sub(1) < pair[0].end())
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "The fields '{}' and '{}' overlap",
+                    pair[0].name, pair[1].name
+                ),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct FixedWidth {
+    #[serde(rename = "metadata")]
+    #[serde(alias = "meta")]
+    pub metadata: Metadata,
+    pub fields: Vec<Field>,
+    pub layouts: Vec<Layout>,
+    pub discriminator: Option<String>,
+}
+
+impl Default for FixedWidth {
+    fn default() -> Self {
+        let metadata = Metadata {
+            terminator: Some(DEFAULT_TERMINATOR.to_string()),
+            mime_type: Some(mime::TEXT.to_string()),
+            mime_subtype: Some(DEFAULT_SUBTYPE.to_string()),
+            charset: Some(mime::UTF_8.to_string()),
+            ..Default::default()
+        };
+        FixedWidth {
+            metadata,
+            fields: Vec::default(),
+            layouts: Vec::default(),
+            discriminator: None,
+        }
+    }
+}
+
+impl FixedWidth {
+    /// Get the layouts of the document. The `fields` are a layout without discriminator value.
+    fn layouts(&self) -> Vec<Layout> {
+        let mut layouts = self.layouts.clone();
+        if !self.fields.is_empty() {
+            layouts.push(Layout {
+                value: None,
+                fields: self.fields.clone(),
+            });
+        }
+
+        layouts
+    }
+    /// Find the layout of a line with the text of its discriminator.
+    fn layout_of_line<'a>(&self, layouts: &'a [Layout], line: &[char]) -> Option<&'a Layout> {
+        let Some(discriminator) = &self.discriminator else {
+            return layouts.first();
+        };
+
+        layouts
+            .iter()
+            .find(|layout| match &layout.value {
+                Some(value) => layout.discriminator(discriminator, line).as_ref() == Some(value),
+                None => false,
+            })
+            .or_else(|| layouts.iter().find(|layout| layout.value.is_none()))
+    }
+    /// Find the layout of a record with the value of its discriminator.
+    fn layout_of_record<'a>(&self, layouts: &'a [Layout], record: &Value) -> Option<&'a Layout> {
+        let Some(discriminator) = &self.discriminator else {
+            return layouts.first();
+        };
+
+        let record_value = match record.get(discriminator) {
+            Some(Value::String(value)) => Some(value.trim().to_string()),
+            Some(Value::Null) | None => None,
+            Some(value) => Some(value.to_string()),
+        };
+
+        layouts
+            .iter()
+            .find(|layout| layout.value.is_some() && layout.value == record_value)
+            .or_else(|| layouts.iter().find(|layout| layout.value.is_none()))
+    }
+    fn terminator_str(&self) -> String {
+        self.metadata
+            .terminator
+            .clone()
+            .unwrap_or_else(|| DEFAULT_TERMINATOR.to_string())
+    }
+}
+
+impl Document for FixedWidth {
+    /// See [`Document::set_metadata`] for more details.
+    fn set_metadata(&mut self, metadata: Metadata) {
+        self.metadata = metadata.clone();
+    }
+    /// See [`Document::metadata`] for more details.
+    fn metadata(&self) -> Metadata {
+        FixedWidth::default().metadata.merge(&self.metadata)
+    }
+    /// See [`Document::terminator`] for more details.
+    fn terminator(&self) -> io::Result<Vec<u8>> {
+        Ok(self.terminator_str().as_bytes().to_vec())
+    }
+    /// See [`Document::read`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::document::fixed_width::{Alignment, Field, FieldType, FixedWidth};
+    /// use chewdata::document::Document;
+    /// use serde_json::json;
+    ///
+    /// let document = FixedWidth {
+    ///     fields: vec![
+    ///         Field::new("name", 1, 10),
+    ///         Field {
+    ///             field_type: FieldType::Integer,
+    ///             alignment: Alignment::Right,
+    ///             padding: '0',
+    ///             ..Field::new("number", 11, 5)
+    ///         },
+    ///     ],
+    ///     ..Default::default()
+    /// };
+    /// let buffer = "john      00010\njane      -0020\n".as_bytes();
+    ///
+    /// let mut dataset = document.read(buffer).unwrap().into_iter();
+    /// assert_eq!(json!({"name": "john", "number": 10}), dataset.next().unwrap().to_value());
+    /// assert_eq!(json!({"name": "jane", "number": -20}), dataset.next().unwrap().to_value());
+    /// ```
+    #[instrument(skip(buffer), name = "fixed_width::read")]
+    fn read(&self, buffer: &[u8]) -> io::Result<DataSet> {
+        let text = std::str::from_utf8(buffer)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let terminator = self.terminator_str();
+        let layouts = self.layouts();
+        let mut dataset = Vec::default();
+
+        for line in text.split(terminator.as_str()) {
+            let line = line.strip_suffix('\r').unwrap_or(line);
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let chars: Vec<char> = line.chars().collect();
+            let Some(layout) = self.layout_of_line(&layouts, &chars) else {
+                warn!(line, "No layout found for the line");
+                dataset.push(DataResult::Err((
+                    Value::String(line.to_string()),
+                    io::Error::new(io::ErrorKind::InvalidData, "No layout found for the line"),
+                )));
+                continue;
+            };
+
+            let record: io::Result<serde_json::Map<String, Value>> = layout
+                .fields
+                .iter()
+                .map(|field| Ok((field.name.clone(), field.value(&field.text(&chars))?)))
+                .collect();
+
+            match record {
+                Ok(record) => {
+                    let record = Value::Object(record);
+                    trace!(
+                        record = record.display_only_for_debugging(),
+                        "Record deserialized"
+                    );
+                    dataset.push(DataResult::Ok(record));
+                }
+                Err(e) => {
+                    warn!(
+                        error = format!("{:?}", e).as_str(),
+                        "Can't deserialize the record"
+                    );
+                    dataset.push(DataResult::Err((Value::String(line.to_string()), e)));
+                }
+            }
+        }
+
+        Ok(dataset)
+    }
+    /// See [`Document::write`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::document::fixed_width::{Alignment, Field, FieldType, FixedWidth};
+    /// use chewdata::document::Document;
+    /// use chewdata::DataResult;
+    /// use serde_json::json;
+    ///
+    /// let document = FixedWidth {
+    ///     fields: vec![
+    ///         Field::new("name", 1, 10),
+    ///         Field {
+    ///             field_type: FieldType::Integer,
+    ///             alignment: Alignment::Right,
+    ///             padding: '0',
+    ///             ..Field::new("number", 11, 5)
+    ///         },
+    ///     ],
+    ///     ..Default::default()
+    /// };
+    /// let dataset = vec![
+    ///     DataResult::Ok(json!({"name": "john", "number": 10})),
+    ///     DataResult::Ok(json!({"name": "jane", "number": -20})),
+    /// ];
+    ///
+    /// let buffer = document.write(&dataset).unwrap();
+    /// assert_eq!("john      00010\njane      -0020", String::from_utf8(buffer).unwrap());
+    /// ```
+    #[instrument(skip(dataset), name = "fixed_width::write")]
+    fn write(&self, dataset: &DataSet) -> io::Result<Vec<u8>> {
+        let terminator = self.terminator_str();
+        let layouts = self.layouts();
+        for layout in &layouts {
+            layout.validate()?;
+        }
+        let mut lines = Vec::default();
+
+        for data in dataset {
+            let record = data.to_value();
+            let layout = self.layout_of_record(&layouts, &record).ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!(
+                        "No layout found for the record '{}'",
+                        record.display_only_for_debugging()
+                    ),
+                )
+            })?;
+
+            let mut line = vec![' '; layout.width()];
+            for field in &layout.fields {
+                let text = field.format(record.get(&field.name))?;
+                let start = field.start.saturating_sub(1);
+                line[start..start + text.len()].copy_from_slice(&text);
+            }
+
+            trace!(
+                record = record.display_only_for_debugging(),
+                "Record serialized"
+            );
+            lines.push(line.into_iter().collect::<String>());
+        }
+
+        Ok(lines.join(terminator.as_str()).into_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn document() -> FixedWidth {
+        serde_json::from_value(json!({
+            "discriminator": "record_type",
+            "layouts": [
+                {
+                    "value": "01",
+                    "fields": [
+                        {"name": "record_type", "start": 1, "length": 2},
+                        {"name": "bank", "start": 3, "length": 10},
+                        {"name": "date", "start": 13, "length": 10}
+                    ]
+                },
+                {
+                    "value": "02",
+                    "fields": [
+                        {"name": "record_type", "start": 1, "length": 2},
+                        {"name": "account", "start": 3, "length": 11, "padding": "0", "align": "right"},
+                        {"name": "amount", "start": 14, "length": 12, "type": "float", "padding": "0", "align": "right"},
+                        {"name": "is_credit", "start": 26, "length": 1, "type": "boolean"}
+                    ]
+                },
+                {
+                    "fields": [
+                        {"name": "record_type", "start": 1, "length": 2},
+                        {"name": "comment", "start": 3, "length": 20, "trim": false}
+                    ]
+                }
+            ]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn read_with_layouts() {
+        let buffer = "01BANK      2020-12-31\r\n0200012345678-000000125.51\n\n99note\n";
+
+        let values: Vec<Value> = document()
+            .read(buffer.as_bytes())
+            .unwrap()
+            .iter()
+            .map(DataResult::to_value)
+            .collect();
+        assert_eq!(
+            vec![
+                json!({"record_type": "01", "bank": "BANK", "date": "2020-12-31"}),
+                json!({"record_type": "02", "account": "12345678", "amount": -125.5, "is_credit": true}),
+                json!({"record_type": "99", "comment": "note"}),
+            ],
+            values
+        );
+    }
+    #[test]
+    fn read_invalid_value() {
+        let buffer = "0200012345678000000abc.001\n01BANK";
+
+        let dataset = document().read(buffer.as_bytes()).unwrap();
+        assert_eq!(2, dataset.len());
+        assert!(matches!(dataset[0], DataResult::Err(_)));
+        assert_eq!(json!("BANK"), dataset[1].to_value()["bank"]);
+    }
+    #[test]
+    fn read_without_layout() {
+        let document = FixedWidth {
+            layouts: vec![Layout {
+                value: Some("01".to_string()),
+                fields: vec![Field::new("record_type", 1, 2)],
+            }],
+            discriminator: Some("record_type".to_string()),
+            ..Default::default()
+        };
+
+        let dataset = document.read("02line".as_bytes()).unwrap();
+        assert!(matches!(dataset[0], DataResult::Err(_)));
+    }
+    #[test]
+    fn write_with_layouts() {
+        let dataset = vec![
+            DataResult::Ok(json!({"record_type": "01", "bank": "BANK", "date": "2020-12-31"})),
+            DataResult::Ok(
+                json!({"record_type": "02", "account": "12345678", "amount": -125.5, "is_credit": false}),
+            ),
+            DataResult::Ok(json!({"record_type": "99", "comment": null})),
+        ];
+
+        let buffer = document().write(&dataset).unwrap();
+        assert_eq!(
+            "01BANK      2020-12-31\n0200012345678-000000125.50\n99                    ",
+            String::from_utf8(buffer.clone()).unwrap()
+        );
+
+        let values: Vec<Value> = document()
+            .read(&buffer)
+            .unwrap()
+            .iter()
+            .map(DataResult::to_value)
+            .collect();
+        assert_eq!(json!(-125.5), values[1]["amount"]);
+        assert_eq!(json!(" ".repeat(20)), values[2]["comment"]);
+    }
+    #[test]
+    fn write_value_too_long() {
+        let document = FixedWidth {
+            fields: vec![Field::new("name", 1, 3)],
+            ..Default::default()
+        };
+
+        let error = document
+            .write(&vec![DataResult::Ok(json!({"name": "john"}))])
+            .unwrap_err();
+        assert_eq!(io::ErrorKind::InvalidInput, error.kind());
+    }
+    #[test]
+    fn write_with_overlapping_fields() {
+        let document = FixedWidth {
+            fields: vec![Field::new("name", 1, 10), Field::new("number", 10, 5)],
+            ..Default::default()
+        };
+
+        let error = document
+            .write(&vec![DataResult::Ok(json!({"name": "john", "number": 10}))])
+            .unwrap_err();
+        assert_eq!(io::ErrorKind::InvalidInput, error.kind());
+    }
+    #[test]
+    fn write_with_field_out_of_range() {
+        let document = FixedWidth {
+            fields: vec![Field::new("name", 0, 10)],
+            ..Default::default()
+        };
+
+        let error = document
+            .write(&vec![DataResult::Ok(json!({"name": "john"}))])
+            .unwrap_err();
+        assert_eq!(io::ErrorKind::InvalidInput, error.kind());
+    }
+}
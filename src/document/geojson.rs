@@ -0,0 +1,503 @@
+//! Read and write geographic data in **GeoJSON** format ([RFC 7946](https://datatracker.ietf.org/doc/html/rfc7946)).
+//!
+//! Each feature of a `FeatureCollection` is a record: its properties with its geometry in the `geometry_field` and its id in the `id_field`.
+//! A single `Feature` or a bare geometry gives one record.
+//!
+//! The records are written in a `FeatureCollection`. The geometry of a record can be a GeoJSON object or a WKT string
+//! and the other fields of the record are the properties of the feature.
+//!
+//! ### Configuration
+//!
+//! | key             | alias  | Description                                                     | Default Value | Possible Values     |
+//! | --------------- | ------ | --------------------------------------------------------------- | ------------- | ------------------- |
+//! | type            | -      | Required in order to use this document.                         | `geojson`     | `geojson`           |
+//! | metadata        | meta   | Metadata describe the resource.                                 | `null`        | [`crate::Metadata`] |
+//! | geometry_field  | -      | Field of the record with the geometry.                          | `geometry`    | String              |
+//! | id_field        | -      | Field of the record with the id of the feature.                 | `id`          | String              |
+//! | geometry_format | format | Format of the geometry in the records read.                     | `geojson`     | `geojson` / `wkt`   |
+//!
+//! ### Examples
+//!
+//! ```json
+//! [
+//!     {
+//!         "type": "read",
+//!         "document": {
+//!             "type": "geojson",
+//!             "geometry_format": "wkt"
+//!         },
+//!         "connector":{
+//!             "type": "local",
+//!             "path": "./data/multi_lines.geojson"
+//!         }
+//!     },
+//!     {
+//!         "type": "write",
+//!         "document": {
+//!             "type": "geojson"
+//!         }
+//!     }
+//! ]
+//! ```
+//!
+//! input:
+//!
+//! ```json
+//! {
+//!     "type": "FeatureCollection",
+//!     "features": [
+//!         {"type": "Feature", "id": 1, "geometry": {"type": "Point", "coordinates": [2.35, 48.85]}, "properties": {"name": "Paris"}},
+//!         ...
+//!     ]
+//! }
+//! ```
+//!
+//! record:
+//!
+//! ```json
+//! {"id": 1, "name": "Paris", "geometry": "POINT (2.35 48.85)"}
+//! ```
+use crate::document::Document;
+use crate::helper::geometry::Geometry;
+use crate::helper::string::DisplayOnlyForDebugging;
+use crate::DataResult;
+use crate::{DataSet, Metadata};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::io;
+
+const DEFAULT_SUBTYPE: &str = "geo+json";
+const DEFAULT_TERMINATOR: &str = ",";
+const HEADER: &str = r#"{"type":"FeatureCollection","features":["#;
+const FOOTER: &str = "]}";
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct GeoJson {
+    #[serde(rename = "metadata")]
+    #[serde(alias = "meta")]
+    pub metadata: Metadata,
+    pub geometry_field: String,
+    pub id_field: String,
+    #[serde(alias = "format")]
+    pub geometry_format: GeometryFormat,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum GeometryFormat {
+    #[default]
+    GeoJson,
+    Wkt,
+}
+
+impl Default for GeoJson {
+    fn default() -> Self {
+        let metadata = Metadata {
+            terminator: Some(DEFAULT_TERMINATOR.to_string()),
+            mime_type: Some(mime::APPLICATION.to_string()),
+            mime_subtype: Some(DEFAULT_SUBTYPE.to_string()),
+            charset: Some(mime::UTF_8.to_string()),
+            ..Default::default()
+        };
+        GeoJson {
+            metadata,
+            geometry_field: "geometry".to_string(),
+            id_field: "id".to_string(),
+            geometry_format: GeometryFormat::default(),
+        }
+    }
+}
+
+impl GeoJson {
+    /// Transform a feature into a record.
+    fn feature_to_record(&self, feature: &Value) -> io::Result<Value> {
+        let mut record = match feature.get("properties") {
+            Some(Value::Object(properties)) => properties.clone(),
+            Some(Value::Null) | None => Map::default(),
+            Some(properties) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("The properties '{}' are not an object", properties),
+                ))
+            }
+        };
+
+        if let Some(id) = feature.get("id") {
+            record.insert(self.id_field.clone(), id.clone());
+        }
+        record.insert(
+            self.geometry_field.clone(),
+            match feature.get("geometry") {
+                Some(Value::Null) | None => Value::Null,
+                Some(geometry) => self.geometry_to_value(geometry)?,
+            },
+        );
+
+        Ok(Value::Object(record))
+    }
+    fn geometry_to_value(&self, geometry: &Value) -> io::Result<Value> {
+        let parsed_geometry = Geometry::from_json(geometry)?;
+
+        Ok(match self.geometry_format {
+            GeometryFormat::GeoJson => geometry.clone(),
+            GeometryFormat::Wkt => Value::String(parsed_geometry.to_wkt()),
+        })
+    }
+    /// Transform a record into a feature.
+    fn record_to_feature(&self, record: Value) -> io::Result<Value> {
+        let mut properties = match record {
+            Value::Object(properties) => properties,
+            record => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!(
+                        "The record '{}' is not an object",
+                        record.display_only_for_debugging()
+                    ),
+                ))
+            }
+        };
+
+        let geometry = match properties.remove(&self.geometry_field) {
+            Some(Value::Null) | None => Value::Null,
+            Some(Value::String(wkt)) => Geometry::from_wkt(&wkt)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?
+                .to_json(),
+            Some(geometry) => {
+                Geometry::from_json(&geometry)
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+                geometry
+            }
+        };
+
+        let mut feature = Map::default();
+        feature.insert("type".to_string(), Value::from("Feature"));
+        if let Some(id) = properties.remove(&self.id_field) {
+            feature.insert("id".to_string(), id);
+        }
+        feature.insert("geometry".to_string(), geometry);
+        feature.insert("properties".to_string(), Value::Object(properties));
+
+        Ok(Value::Object(feature))
+    }
+}
+
+impl Document for GeoJson {
+    /// See [`Document::set_metadata`] for more details.
+    fn set_metadata(&mut self, metadata: Metadata) {
+        self.metadata = metadata.clone();
+    }
+    /// See [`Document::metadata`] for more details.
+    fn metadata(&self) -> Metadata {
+        GeoJson::default().metadata.merge(&self.metadata)
+    }
+    /// See [`Document::has_data`] for more details.
+    fn has_data(&self, buf: &[u8]) -> io::Result<bool> {
+        if buf == format!("{}{}", HEADER, FOOTER).as_bytes() {
+            return Ok(false);
+        }
+        Ok(!buf.is_empty())
+    }
+    /// See [`Document::read`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::document::geojson::GeoJson;
+    /// use chewdata::document::Document;
+    /// use serde_json::json;
+    ///
+    /// let document = GeoJson::default();
+    /// let buffer = r#"{"type":"FeatureCollection","features":[{"type":"Feature","id":1,"geometry":{"type":"Point","coordinates":[2.35,48.85]},"properties":{"name":"Paris"}}]}"#;
+    ///
+    /// let mut dataset = document.read(buffer.as_bytes()).unwrap().into_iter();
+    /// assert_eq!(
+    ///     json!({"id": 1, "name": "Paris", "geometry": {"type": "Point", "coordinates": [2.35, 48.85]}}),
+    ///     dataset.next().unwrap().to_value()
+    /// );
+    /// ```
+    #[instrument(skip(buffer), name = "geojson::read")]
+    fn read(&self, buffer: &[u8]) -> io::Result<DataSet> {
+        let value: Value = match serde_json::from_slice(buffer) {
+            Ok(value) => value,
+            Err(e) => {
+                warn!(
+                    error = format!("{:?}", e).as_str(),
+                    "Can't deserialize the record"
+                );
+                return Ok(vec![DataResult::Err((
+                    Value::Null,
+                    io::Error::new(io::ErrorKind::InvalidInput, e.to_string()),
+                ))]);
+            }
+        };
+
+        let features = match value.get("type").and_then(Value::as_str) {
+            Some("FeatureCollection") => match value.get("features") {
+                Some(Value::Array(features)) => features.clone(),
+                _ => Vec::default(),
+            },
+            Some("Feature") => vec![value],
+            Some(_) => {
+                let mut feature = Map::default();
+                feature.insert("geometry".to_string(), value);
+                vec![Value::Object(feature)]
+            }
+            None => {
+                return Ok(vec![DataResult::Err((
+                    value,
+                    io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "The GeoJSON object has no type",
+                    ),
+                ))])
+            }
+        };
+
+        let mut dataset = Vec::default();
+        for feature in features {
+            match self.feature_to_record(&feature) {
+                Ok(record) => {
+                    trace!(
+                        record = record.display_only_for_debugging(),
+                        "Record deserialized"
+                    );
+                    dataset.push(DataResult::Ok(record));
+                }
+                Err(e) => {
+                    warn!(
+                        error = format!("{:?}", e).as_str(),
+                        feature = feature.display_only_for_debugging(),
+                        "Can't deserialize the record"
+                    );
+                    dataset.push(DataResult::Err((feature, e)));
+                }
+            }
+        }
+
+        Ok(dataset)
+    }
+    /// See [`Document::write`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::document::geojson::GeoJson;
+    /// use chewdata::document::Document;
+    /// use chewdata::DataResult;
+    /// use serde_json::{json, Value};
+    ///
+    /// let document = GeoJson::default();
+    /// let dataset = vec![DataResult::Ok(json!({"id": 1, "name": "Paris", "geometry": "POINT (2.35 48.85)"}))];
+    ///
+    /// let buffer = document.write(&dataset).unwrap();
+    /// assert_eq!(
+    ///     json!({"type": "Feature", "id": 1, "geometry": {"type": "Point", "coordinates": [2.35, 48.85]}, "properties": {"name": "Paris"}}),
+    ///     serde_json::from_slice::<Value>(&buffer).unwrap()
+    /// );
+    /// ```
+    #[instrument(skip(dataset), name = "geojson::write")]
+    fn write(&self, dataset: &DataSet) -> io::Result<Vec<u8>> {
+        let mut buffer = Vec::default();
+
+        for data in dataset {
+            let feature = self.record_to_feature(data.to_value())?;
+
+            if !buffer.is_empty() {
+                buffer.append(&mut self.terminator()?);
+            }
+            serde_json::to_writer(&mut buffer, &feature)?;
+
+            trace!(
+                record = feature.display_only_for_debugging(),
+                "Record serialized"
+            );
+        }
+
+        Ok(buffer)
+    }
+    /// See [`Document::header`] for more details.
+    fn header(&self, _dataset: &DataSet) -> io::Result<Vec<u8>> {
+        Ok(HEADER.as_bytes().to_vec())
+    }
+    /// See [`Document::footer`] for more details.
+    fn footer(&self, _dataset: &DataSet) -> io::Result<Vec<u8>> {
+        Ok(FOOTER.as_bytes().to_vec())
+    }
+    /// See [`Document::terminator`] for more details.
+    fn terminator(&self) -> io::Result<Vec<u8>> {
+        Ok(self
+            .metadata()
+            .terminator
+            .unwrap_or_else(|| DEFAULT_TERMINATOR.to_string())
+            .as_bytes()
+            .to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn buffer() -> Vec<u8> {
+        json!({
+            "type": "FeatureCollection",
+            "features": [
+                {
+                    "type": "Feature",
+                    "id": "paris",
+                    "geometry": {"type": "Point", "coordinates": [2.35, 48.85]},
+                    "properties": {"name": "Paris", "population": 2102650}
+                },
+                {
+                    "type": "Feature",
+                    "geometry": {"type": "LineString", "coordinates": [[0, 0], [1, 1]]},
+                    "properties": null
+                },
+                {
+                    "type": "Feature",
+                    "geometry": null,
+                    "properties": {"name": "Nowhere"}
+                }
+            ]
+        })
+        .to_string()
+        .into_bytes()
+    }
+
+    #[test]
+    fn read_feature_collection() {
+        let document = GeoJson::default();
+
+        let values: Vec<Value> = document
+            .read(&buffer())
+            .unwrap()
+            .iter()
+            .map(DataResult::to_value)
+            .collect();
+        assert_eq!(
+            vec![
+                json!({"id": "paris", "name": "Paris", "population": 2102650, "geometry": {"type": "Point", "coordinates": [2.35, 48.85]}}),
+                json!({"geometry": {"type": "LineString", "coordinates": [[0, 0], [1, 1]]}}),
+                json!({"name": "Nowhere", "geometry": null}),
+            ],
+            values
+        );
+    }
+    #[test]
+    fn read_in_wkt_with_custom_fields() {
+        let document = GeoJson {
+            geometry_field: "shape".to_string(),
+            id_field: "code".to_string(),
+            geometry_format: GeometryFormat::Wkt,
+            ..Default::default()
+        };
+
+        let dataset = document.read(&buffer()).unwrap();
+        assert_eq!(
+            json!({"code": "paris", "name": "Paris", "population": 2102650, "shape": "POINT (2.35 48.85)"}),
+            dataset[0].to_value()
+        );
+        assert_eq!(
+            json!({"shape": "LINESTRING (0 0, 1 1)"}),
+            dataset[1].to_value()
+        );
+    }
+    #[test]
+    fn read_feature_and_geometry() {
+        let document = GeoJson::default();
+
+        let dataset = document
+            .read(br#"{"type":"Feature","geometry":{"type":"Point","coordinates":[1,2]},"properties":{"name":"a"}}"#)
+            .unwrap();
+        assert_eq!(
+            json!({"name": "a", "geometry": {"type": "Point", "coordinates": [1, 2]}}),
+            dataset[0].to_value()
+        );
+
+        let dataset = document
+            .read(br#"{"type":"Point","coordinates":[1,2]}"#)
+            .unwrap();
+        assert_eq!(
+            json!({"geometry": {"type": "Point", "coordinates": [1, 2]}}),
+            dataset[0].to_value()
+        );
+    }
+    #[test]
+    fn read_invalid_geometry() {
+        let document = GeoJson::default();
+
+        let dataset = document
+            .read(br#"{"type":"FeatureCollection","features":[{"type":"Feature","geometry":{"type":"Point","coordinates":[1]},"properties":{}}]}"#)
+            .unwrap();
+        assert!(matches!(dataset[0], DataResult::Err(_)));
+
+        let dataset = document.read(br#"{"features":[]}"#).unwrap();
+        assert!(matches!(dataset[0], DataResult::Err(_)));
+
+        let dataset = document.read(br#"{"type":"#).unwrap();
+        assert!(matches!(dataset[0], DataResult::Err(_)));
+    }
+    #[test]
+    fn write_and_read_back() {
+        let document = GeoJson::default();
+        let dataset = document.read(&buffer()).unwrap();
+
+        let mut buffer = document.header(&dataset).unwrap();
+        buffer.append(&mut document.write(&dataset).unwrap());
+        buffer.append(&mut document.footer(&dataset).unwrap());
+
+        let values: Vec<Value> = document
+            .read(&buffer)
+            .unwrap()
+            .iter()
+            .map(DataResult::to_value)
+            .collect();
+        let expected_values: Vec<Value> = dataset.iter().map(DataResult::to_value).collect();
+        assert_eq!(expected_values, values);
+    }
+    #[test]
+    fn write_wkt_geometry() {
+        let document = GeoJson::default();
+        let dataset = vec![
+            DataResult::Ok(json!({"name": "a", "geometry": "POLYGON ((0 0, 1 0, 1 1, 0 0))"})),
+            DataResult::Ok(json!({"name": "b"})),
+        ];
+
+        let buffer = document.write(&dataset).unwrap();
+        let features: Value =
+            serde_json::from_slice(&[b"[", buffer.as_slice(), b"]"].concat()).unwrap();
+        assert_eq!(
+            json!([
+                {"type": "Feature", "geometry": {"type": "Polygon", "coordinates": [[[0, 0], [1, 0], [1, 1], [0, 0]]]}, "properties": {"name": "a"}},
+                {"type": "Feature", "geometry": null, "properties": {"name": "b"}}
+            ]),
+            features
+        );
+    }
+    #[test]
+    fn write_invalid_record() {
+        let document = GeoJson::default();
+
+        let error = document
+            .write(&vec![DataResult::Ok(json!({"geometry": "CIRCLE (1 2)"}))])
+            .unwrap_err();
+        assert_eq!(io::ErrorKind::InvalidInput, error.kind());
+
+        let error = document
+            .write(&vec![DataResult::Ok(json!([1, 2]))])
+            .unwrap_err();
+        assert_eq!(io::ErrorKind::InvalidInput, error.kind());
+    }
+    #[test]
+    fn has_data() {
+        let document = GeoJson::default();
+        assert!(!document
+            .has_data(br#"{"type":"FeatureCollection","features":[]}"#)
+            .unwrap());
+        assert!(!document.has_data(b"").unwrap());
+        assert!(document.has_data(&buffer()).unwrap());
+    }
+}
@@ -0,0 +1,872 @@
+//! Read and write log lines.
+//!
+//! Each line is parsed into a record with one of the formats:
+//!
+//! * `logfmt`: `key=value` pairs, like `level=info msg="user created" id=42`. A key without value is `true`.
+//! * `common`: Common Log Format of Apache and Nginx, like `127.0.0.1 - frank [10/Oct/2000:13:55:36 -0700] "GET /index.html HTTP/1.0" 200 2326`.
+//! * `combined`: Combined Log Format, the common format followed by the `"referer"` and the `"user agent"`.
+//! * `syslog`: syslog messages in [RFC 5424](https://datatracker.ietf.org/doc/html/rfc5424) or [RFC 3164](https://datatracker.ietf.org/doc/html/rfc3164) format.
+//! * `regex`: a custom `pattern` where each named capture group is a field of the record.
+//!
+//! The `-` values of the access logs and syslog messages are read as `null`. The time of the access logs is converted in RFC 3339.
+//! A line that doesn't match the format is returned in error.
+//!
+//! The records are written in the same format, the syslog messages in RFC 5424. The `regex` format can't be written.
+//!
+//! ### Configuration
+//!
+//! | key      | alias | Description                                              | Default Value | Possible Values                                                |
+//! | -------- | ----- | -------------------------------------------------------- | ------------- | -------------------------------------------------------------- |
+//! | type     | -     | Required in order to use this document.                  | `log`         | `log`                                                          |
+//! | metadata | meta  | Metadata describe the resource.                          | `null`        | [`crate::Metadata`]                                            |
+//! | format   | -     | Format of the lines.                                     | `logfmt`      | `logfmt` / `common` / `combined` / `syslog` / `regex`          |
+//! | pattern  | -     | Regular expression with named capture groups for `regex`.| `null`        | [Regex syntax](https://docs.rs/regex/latest/regex/#syntax)     |
+//!
+//! ### Fields
+//!
+//! | format             | fields                                                                                                                  |
+//! | ------------------ | ----------------------------------------------------------------------------------------------------------------------- |
+//! | common             | `remote_host`, `ident`, `user`, `time`, `request`, `method`, `path`, `protocol`, `status`, `size`                       |
+//! | combined           | fields of `common`, `referer`, `user_agent`                                                                             |
+//! | syslog (RFC 5424)  | `facility`, `severity`, `version`, `timestamp`, `hostname`, `app_name`, `proc_id`, `msg_id`, `structured_data`, `message` |
+//! | syslog (RFC 3164)  | `facility`, `severity`, `timestamp`, `hostname`, `app_name`, `proc_id`, `message`                                       |
+//!
+//! ### Examples
+//!
+//! ```json
+//! [
+//!     {
+//!         "type": "read",
+//!         "document": {
+//!             "type": "log",
+//!             "format": "regex",
+//!             "pattern": "^(?P<level>[A-Z]+) \\[(?P<thread>[^\\]]+)\\] (?P<message>.*)$"
+//!         },
+//!         "connector":{
+//!             "type": "local",
+//!             "path": "./data/app.log"
+//!         }
+//!     }
+//! ]
+//! ```
+//!
+//! input:
+//!
+//! ```text
+//! INFO [main] Application started
+//! ```
+//!
+//! output:
+//!
+//! ```json
+//! [
+//!     {"level": "INFO", "thread": "main", "message": "Application started"}
+//! ]
+//! ```
+use crate::document::Document;
+use crate::helper::string::DisplayOnlyForDebugging;
+use crate::DataResult;
+use crate::{DataSet, Metadata};
+use chrono::DateTime;
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::io;
+use std::sync::OnceLock;
+
+const DEFAULT_TERMINATOR: &str = "\n";
+const ACCESS_LOG_TIME_FORMAT: &str = "%d/%b/%Y:%H:%M:%S %z";
+const DEFAULT_FACILITY: u64 = 1;
+const DEFAULT_SEVERITY: u64 = 6;
+
+static ACCESS_LOG_REGEX: OnceLock<Regex> = OnceLock::new();
+static RFC5424_REGEX: OnceLock<Regex> = OnceLock::new();
+static RFC3164_REGEX: OnceLock<Regex> = OnceLock::new();
+static STRUCTURED_DATA_REGEX: OnceLock<Regex> = OnceLock::new();
+static PARAM_REGEX: OnceLock<Regex> = OnceLock::new();
+
+fn regex(cell: &'static OnceLock<Regex>, pattern: &str) -> &'static Regex {
+    cell.get_or_init(|| Regex::new(pattern).expect("The pattern is not a valid regex"))
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    #[default]
+    Logfmt,
+    #[serde(alias = "clf")]
+    Common,
+    Combined,
+    #[serde(alias = "rfc5424")]
+    #[serde(alias = "rfc3164")]
+    Syslog,
+    Regex,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Log {
+    #[serde(rename = "metadata")]
+    #[serde(alias = "meta")]
+    pub metadata: Metadata,
+    pub format: LogFormat,
+    pub pattern: Option<String>,
+}
+
+impl Default for Log {
+    fn default() -> Self {
+        let metadata = Metadata {
+            terminator: Some(DEFAULT_TERMINATOR.to_string()),
+            mime_type: Some(mime::TEXT.to_string()),
+            mime_subtype: Some(mime::PLAIN.to_string()),
+            charset: Some(mime::UTF_8.to_string()),
+            ..Default::default()
+        };
+        Log {
+            metadata,
+            format: LogFormat::default(),
+            pattern: None,
+        }
+    }
+}
+
+/// Remove the escape characters of a quoted string.
+fn unescape(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(char) = chars.next() {
+        match (char, char == '\\') {
+            (_, true) => match chars.next() {
+                Some('n') => unescaped.push('\n'),
+                Some('t') => unescaped.push('\t'),
+                Some(char) => unescaped.push(char),
+                None => unescaped.push('\\'),
+            },
+            (char, false) => unescaped.push(char),
+        }
+    }
+
+    unescaped
+}
+
+/// Get a captured value. The `-` value is `null`.
+fn nil_value(captures: &Captures, name: &str) -> Value {
+    match captures.name(name).map(|capture| capture.as_str()) {
+        None | Some("-") => Value::Null,
+        Some(text) => Value::String(text.to_string()),
+    }
+}
+
+/// Parse a line in logfmt.
+///
+/// # Examples
+///
+/// ```
+/// use chewdata::document::log::parse_logfmt;
+/// use serde_json::json;
+///
+/// assert_eq!(
+///     json!({"level": "info", "msg": "user \"john\" created", "id": "42", "dry_run": true}),
+///     parse_logfmt(r#"level=info msg="user \"john\" created" id=42 dry_run"#)
+/// );
+/// ```
+pub fn parse_logfmt(line: &str) -> Value {
+    let mut record = Map::default();
+    let mut chars = line.chars().peekable();
+
+    loop {
+        while chars.next_if(|char| char.is_whitespace()).is_some() {}
+        if chars.peek().is_none() {
+            break;
+        }
+
+        let mut key = String::default();
+        while let Some(char) = chars.next_if(|char| !char.is_whitespace() && '=' != *char) {
+            key.push(char);
+        }
+        if chars.next_if_eq(&'=').is_none() {
+            if !key.is_empty() {
+                record.insert(key, Value::Bool(true));
+            }
+            continue;
+        }
+
+        let mut value = String::default();
+        match chars.next_if_eq(&'"') {
+            Some(_) => {
+                let mut quoted = String::default();
+                while let Some(char) = chars.next() {
+                    match char {
+                        '"' => break,
+                        '\\' => {
+                            quoted.push(char);
+                            if let Some(char) = chars.next() {
+                                quoted.push(char);
+                            }
+                        }
+                        char => quoted.push(char),
+                    }
+                }
+                value.push_str(&unescape(&quoted));
+            }
+            None => {
+                while let Some(char) = chars.next_if(|char| !char.is_whitespace()) {
+                    value.push(char);
+                }
+            }
+        }
+
+        if !key.is_empty() {
+            record.insert(key, Value::String(value));
+        }
+    }
+
+    Value::Object(record)
+}
+
+/// Format a record in logfmt.
+fn format_logfmt(record: &Map<String, Value>) -> String {
+    record
+        .iter()
+        .map(|(key, value)| {
+            let text = match value {
+                Value::Null => return format!("{}=", key),
+                Value::String(string) => string.clone(),
+                Value::Bool(_) | Value::Number(_) => value.to_string(),
+                Value::Array(_) | Value::Object(_) => value.to_string(),
+            };
+            let must_quote = text.is_empty()
+                || text
+                    .chars()
+                    .any(|char| char.is_whitespace() || matches!(char, '"' | '=' | '\\'));
+
+            match must_quote {
+                true => format!("{}={}", key, quote(&text)),
+                false => format!("{}={}", key, text),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// Quote a string and escape its special characters.
+fn quote(text: &str) -> String {
+    let escaped = text
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+        .replace('\t', "\\t");
+
+    format!("\"{}\"", escaped)
+}
+
+/// Parse a line of an access log in Common or Combined Log Format.
+fn parse_access_log(line: &str, is_combined: bool) -> Option<Value> {
+    let captures = regex(
+        &ACCESS_LOG_REGEX,
+        r#"^(?P<remote_host>\S+) (?P<ident>\S+) (?P<user>\S+) \[(?P<time>[^\]]+)\] "(?P<request>(?:[^"\\]|\\.)*)" (?P<status>\d{3}|-) (?P<size>\d+|-)(?: "(?P<referer>(?:[^"\\]|\\.)*)" "(?P<user_agent>(?:[^"\\]|\\.)*)")?"#,
+    )
+    .captures(line)?;
+
+    let mut record = Map::default();
+    for name in ["remote_host", "ident", "user"] {
+        record.insert(name.to_string(), nil_value(&captures, name));
+    }
+
+    let time = &captures["time"];
+    let time = DateTime::parse_from_str(time, ACCESS_LOG_TIME_FORMAT)
+        .map(|datetime| datetime.to_rfc3339())
+        .unwrap_or_else(|_| time.to_string());
+    record.insert("time".to_string(), Value::String(time));
+
+    let request = unescape(&captures["request"]);
+    let parts: Vec<&str> = request.split(' ').collect();
+    let (method, path, protocol) = match parts.as_slice() {
+        [method, path, protocol] => (
+            Value::from(*method),
+            Value::from(*path),
+            Value::from(*protocol),
+        ),
+        _ => (Value::Null, Value::Null, Value::Null),
+    };
+    record.insert("request".to_string(), Value::String(request));
+    record.insert("method".to_string(), method);
+    record.insert("path".to_string(), path);
+    record.insert("protocol".to_string(), protocol);
+
+    for name in ["status", "size"] {
+        let value = captures[name]
+            .parse::<u64>()
+            .map_or(Value::Null, Value::from);
+        record.insert(name.to_string(), value);
+    }
+
+    if is_combined {
+        for name in ["referer", "user_agent"] {
+            let value = match captures.name(name).map(|capture| capture.as_str()) {
+                None | Some("-") => Value::Null,
+                Some(text) => Value::String(unescape(text)),
+            };
+            record.insert(name.to_string(), value);
+        }
+    }
+
+    Some(Value::Object(record))
+}
+
+/// Format a record in Common or Combined Log Format.
+fn format_access_log(record: &Map<String, Value>, is_combined: bool) -> String {
+    let text = |name: &str| match record.get(name) {
+        None | Some(Value::Null) => "-".to_string(),
+        Some(Value::String(string)) if string.is_empty() => "-".to_string(),
+        Some(Value::String(string)) => string.clone(),
+        Some(value) => value.to_string(),
+    };
+    let quoted = |name: &str| {
+        let text = text(name);
+        quote(&text)
+    };
+
+    let time = text("time");
+    let time = DateTime::parse_from_rfc3339(&time)
+        .map(|datetime| datetime.format(ACCESS_LOG_TIME_FORMAT).to_string())
+        .unwrap_or(time);
+    let request = match record.get("request") {
+        Some(Value::String(request)) => quote(request),
+        _ => quote(&format!(
+            "{} {} {}",
+            text("method"),
+            text("path"),
+            text("protocol")
+        )),
+    };
+
+    let mut line = format!(
+        "{} {} {} [{}] {} {} {}",
+        text("remote_host"),
+        text("ident"),
+        text("user"),
+        time,
+        request,
+        text("status"),
+        text("size")
+    );
+    if is_combined {
+        line = format!("{} {} {}", line, quoted("referer"), quoted("user_agent"));
+    }
+
+    line
+}
+
+/// Insert the facility and the severity of a syslog priority.
+fn insert_priority(record: &mut Map<String, Value>, priority: Option<&str>) {
+    let priority = priority.and_then(|priority| priority.parse::<u64>().ok());
+    record.insert(
+        "facility".to_string(),
+        priority.map_or(Value::Null, |priority| Value::from(priority / 8)),
+    );
+    record.insert(
+        "severity".to_string(),
+        priority.map_or(Value::Null, |priority| Value::from(priority % 8)),
+    );
+}
+
+/// Parse a syslog message in RFC 5424 format then in RFC 3164 format.
+fn parse_syslog(line: &str) -> Option<Value> {
+    let rfc5424 = regex(
+        &RFC5424_REGEX,
+        r#"^<(?P<priority>\d{1,3})>(?P<version>\d{1,2}) (?P<timestamp>\S+) (?P<hostname>\S+) (?P<app_name>\S+) (?P<proc_id>\S+) (?P<msg_id>\S+) (?P<structured_data>-|(?:\[(?:[^\]"\\]|\\.|"(?:[^"\\]|\\.)*")*\])+)(?: (?P<message>.*))?$"#,
+    );
+    if let Some(captures) = rfc5424.captures(line) {
+        let mut record = Map::default();
+        insert_priority(&mut record, Some(&captures["priority"]));
+        record.insert(
+            "version".to_string(),
+            captures["version"]
+                .parse::<u64>()
+                .map_or(Value::Null, Value::from),
+        );
+        for name in ["timestamp", "hostname", "app_name", "proc_id", "msg_id"] {
+            record.insert(name.to_string(), nil_value(&captures, name));
+        }
+        record.insert(
+            "structured_data".to_string(),
+            parse_structured_data(&captures["structured_data"]),
+        );
+        record.insert(
+            "message".to_string(),
+            captures.name("message").map_or(Value::Null, |message| {
+                Value::from(message.as_str().trim_start_matches('\u{feff}'))
+            }),
+        );
+
+        return Some(Value::Object(record));
+    }
+
+    let captures = regex(
+        &RFC3164_REGEX,
+        r#"^(?:<(?P<priority>\d{1,3})>)?(?P<timestamp>[A-Z][a-z]{2} [ \d]\d \d{2}:\d{2}:\d{2}) (?P<hostname>\S+) (?:(?P<app_name>[^:\[\s]+)(?:\[(?P<proc_id>[^\]]+)\])?: )?(?P<message>.*)$"#,
+    )
+    .captures(line)?;
+
+    let mut record = Map::default();
+    insert_priority(
+        &mut record,
+        captures.name("priority").map(|priority| priority.as_str()),
+    );
+    for name in ["timestamp", "hostname", "app_name", "proc_id", "message"] {
+        record.insert(
+            name.to_string(),
+            captures
+                .name(name)
+                .map_or(Value::Null, |capture| Value::from(capture.as_str())),
+        );
+    }
+
+    Some(Value::Object(record))
+}
+
+/// Parse the structured data of a syslog message into `{"id": {"param": "value"}}`.
+fn parse_structured_data(text: &str) -> Value {
+    if "-" == text {
+        return Value::Null;
+    }
+
+    let elements = regex(
+        &STRUCTURED_DATA_REGEX,
+        r#"\[(?P<id>[^\s\]]+)(?P<params>(?:\s+[^=\s\]]+="(?:[^"\\]|\\.)*")*)\]"#,
+    );
+    let params = regex(
+        &PARAM_REGEX,
+        r#"(?P<name>[^=\s\]]+)="(?P<value>(?:[^"\\]|\\.)*)""#,
+    );
+
+    Value::Object(
+        elements
+            .captures_iter(text)
+            .map(|element| {
+                let values = params
+                    .captures_iter(&element["params"])
+                    .map(|param| {
+                        (
+                            param["name"].to_string(),
+                            Value::String(unescape(&param["value"])),
+                        )
+                    })
+                    .collect::<Map<String, Value>>();
+                (element["id"].to_string(), Value::Object(values))
+            })
+            .collect(),
+    )
+}
+
+/// Format a record in a syslog message of RFC 5424 format.
+fn format_syslog(record: &Map<String, Value>) -> String {
+    let text = |name: &str| match record.get(name) {
+        None | Some(Value::Null) => "-".to_string(),
+        Some(Value::String(string)) if string.is_empty() => "-".to_string(),
+        Some(Value::String(string)) => string.replace(' ', "_"),
+        Some(value) => value.to_string(),
+    };
+    let number =
+        |name: &str, default: u64| record.get(name).and_then(Value::as_u64).unwrap_or(default);
+
+    let priority = number("facility", DEFAULT_FACILITY) * 8 + number("severity", DEFAULT_SEVERITY);
+    let structured_data = match record.get("structured_data") {
+        Some(Value::Object(elements)) if !elements.is_empty() => elements
+            .iter()
+            .map(|(id, params)| {
+                let params = params
+                    .as_object()
+                    .map(|params| {
+                        params
+                            .iter()
+                            .map(|(name, value)| {
+                                let value = match value {
+                                    Value::String(string) => string.clone(),
+                                    value => value.to_string(),
+                                };
+                                format!(
+                                    " {}=\"{}\"",
+                                    name,
+                                    value
+                                        .replace('\\', "\\\\")
+                                        .replace('"', "\\\"")
+                                        .replace(']', "\\]")
+                                )
+                            })
+                            .collect::<String>()
+                    })
+                    .unwrap_or_default();
+                format!("[{}{}]", id, params)
+            })
+            .collect::<String>(),
+        _ => "-".to_string(),
+    };
+
+    let line = format!(
+        "<{}>{} {} {} {} {} {} {}",
+        priority,
+        number("version", 1),
+        text("timestamp"),
+        text("hostname"),
+        text("app_name"),
+        text("proc_id"),
+        text("msg_id"),
+        structured_data
+    );
+
+    match record.get("message") {
+        Some(Value::String(message)) => format!("{} {}", line, message),
+        Some(Value::Null) | None => line,
+        Some(message) => format!("{} {}", line, message),
+    }
+}
+
+impl Log {
+    fn parse(&self, line: &str, pattern: Option<&Regex>) -> Option<Value> {
+        match (&self.format, pattern) {
+            (LogFormat::Logfmt, _) => Some(parse_logfmt(line)),
+            (LogFormat::Common, _) => parse_access_log(line, false),
+            (LogFormat::Combined, _) => parse_access_log(line, true),
+            (LogFormat::Syslog, _) => parse_syslog(line),
+            (LogFormat::Regex, Some(pattern)) => {
+                let captures = pattern.captures(line)?;
+                Some(Value::Object(
+                    pattern
+                        .capture_names()
+                        .flatten()
+                        .map(|name| {
+                            let value = captures
+                                .name(name)
+                                .map_or(Value::Null, |capture| Value::from(capture.as_str()));
+                            (name.to_string(), value)
+                        })
+                        .collect(),
+                ))
+            }
+            (LogFormat::Regex, None) => None,
+        }
+    }
+    fn pattern(&self) -> io::Result<Option<Regex>> {
+        match (&self.format, &self.pattern) {
+            (LogFormat::Regex, Some(pattern)) => Regex::new(pattern)
+                .map(Some)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e)),
+            (LogFormat::Regex, None) => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "The pattern is required with the regex format",
+            )),
+            _ => Ok(None),
+        }
+    }
+    fn terminator_str(&self) -> String {
+        self.metadata
+            .terminator
+            .clone()
+            .unwrap_or_else(|| DEFAULT_TERMINATOR.to_string())
+    }
+}
+
+impl Document for Log {
+    /// See [`Document::set_metadata`] for more details.
+    fn set_metadata(&mut self, metadata: Metadata) {
+        self.metadata = metadata.clone();
+    }
+    /// See [`Document::metadata`] for more details.
+    fn metadata(&self) -> Metadata {
+        Log::default().metadata.merge(&self.metadata)
+    }
+    /// See [`Document::terminator`] for more details.
+    fn terminator(&self) -> io::Result<Vec<u8>> {
+        Ok(self.terminator_str().as_bytes().to_vec())
+    }
+    /// See [`Document::read`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::document::log::{Log, LogFormat};
+    /// use chewdata::document::Document;
+    /// use serde_json::json;
+    ///
+    /// let document = Log {
+    ///     format: LogFormat::Common,
+    ///     ..Default::default()
+    /// };
+    /// let buffer = r#"127.0.0.1 - frank [10/Oct/2000:13:55:36 -0700] "GET /index.html HTTP/1.0" 200 2326"#;
+    ///
+    /// let mut dataset = document.read(buffer.as_bytes()).unwrap().into_iter();
+    /// assert_eq!(
+    ///     json!({
+    ///         "remote_host": "127.0.0.1",
+    ///         "ident": null,
+    ///         "user": "frank",
+    ///         "time": "2000-10-10T13:55:36-07:00",
+    ///         "request": "GET /index.html HTTP/1.0",
+    ///         "method": "GET",
+    ///         "path": "/index.html",
+    ///         "protocol": "HTTP/1.0",
+    ///         "status": 200,
+    ///         "size": 2326
+    ///     }),
+    ///     dataset.next().unwrap().to_value()
+    /// );
+    /// ```
+    #[instrument(skip(buffer), name = "log::read")]
+    fn read(&self, buffer: &[u8]) -> io::Result<DataSet> {
+        let text = String::from_utf8_lossy(buffer);
+        let terminator = self.terminator_str();
+        let pattern = self.pattern()?;
+        let mut dataset = Vec::default();
+
+        for line in text.split(terminator.as_str()) {
+            let line = line.strip_suffix('\r').unwrap_or(line);
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            match self.parse(line, pattern.as_ref()) {
+                Some(record) => {
+                    trace!(
+                        record = record.display_only_for_debugging(),
+                        "Record deserialized"
+                    );
+                    dataset.push(DataResult::Ok(record));
+                }
+                None => {
+                    warn!(line, "The line doesn't match the format");
+                    dataset.push(DataResult::Err((
+                        Value::String(line.to_string()),
+                        io::Error::new(
+                            io::ErrorKind::InvalidData,
+                            format!("The line doesn't match the format {:?}", self.format),
+                        ),
+                    )));
+                }
+            }
+        }
+
+        Ok(dataset)
+    }
+    /// See [`Document::write`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::document::log::Log;
+    /// use chewdata::document::Document;
+    /// use chewdata::DataResult;
+    /// use serde_json::json;
+    ///
+    /// let document = Log::default();
+    /// let dataset = vec![DataResult::Ok(json!({"level": "info", "msg": "user created", "id": 42}))];
+    ///
+    /// let buffer = document.write(&dataset).unwrap();
+    /// assert_eq!(r#"level=info msg="user created" id=42"#, String::from_utf8(buffer).unwrap());
+    /// ```
+    #[instrument(skip(dataset), name = "log::write")]
+    fn write(&self, dataset: &DataSet) -> io::Result<Vec<u8>> {
+        let mut lines = Vec::default();
+
+        for data in dataset {
+            let record = match data.to_value() {
+                Value::Object(record) => record,
+                value => Map::from_iter([("message".to_string(), value)]),
+            };
+
+            let line = match self.format {
+                LogFormat::Logfmt => format_logfmt(&record),
+                LogFormat::Common => format_access_log(&record, false),
+                LogFormat::Combined => format_access_log(&record, true),
+                LogFormat::Syslog => format_syslog(&record),
+                LogFormat::Regex => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::Unsupported,
+                        "The records can't be written with the regex format",
+                    ))
+                }
+            };
+
+            let record = Value::Object(record);
+            trace!(
+                record = record.display_only_for_debugging(),
+                "Record serialized"
+            );
+            lines.push(line);
+        }
+
+        Ok(lines.join(self.terminator_str().as_str()).into_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn read(document: &Log, buffer: &str) -> Vec<Value> {
+        document
+            .read(buffer.as_bytes())
+            .unwrap()
+            .iter()
+            .map(DataResult::to_value)
+            .collect()
+    }
+
+    #[test]
+    fn read_and_write_logfmt() {
+        let document = Log::default();
+        let buffer =
+            "time=2020-12-31T10:00:00Z level=warn msg=\"disk \\\"/var\\\" full\" empty= debug\n\n";
+
+        let values = read(&document, buffer);
+        assert_eq!(
+            vec![json!({
+                "time": "2020-12-31T10:00:00Z",
+                "level": "warn",
+                "msg": "disk \"/var\" full",
+                "empty": "",
+                "debug": true
+            })],
+            values
+        );
+
+        let dataset: DataSet = values.into_iter().map(DataResult::Ok).collect();
+        let buffer = document.write(&dataset).unwrap();
+        assert_eq!(
+            "time=2020-12-31T10:00:00Z level=warn msg=\"disk \\\"/var\\\" full\" empty=\"\" debug=true",
+            String::from_utf8(buffer).unwrap()
+        );
+    }
+    #[test]
+    fn read_and_write_combined() {
+        let document = Log {
+            format: LogFormat::Combined,
+            ..Default::default()
+        };
+        let buffer = r#"192.168.1.10 - - [31/Dec/2020:23:59:59 +0000] "POST /api/items?id=1 HTTP/1.1" 201 - "https://example.com/" "Mozilla/5.0 (X11; Linux x86_64)"
+invalid line"#;
+
+        let dataset = document.read(buffer.as_bytes()).unwrap();
+        assert_eq!(2, dataset.len());
+        assert_eq!(
+            json!({
+                "remote_host": "192.168.1.10",
+                "ident": null,
+                "user": null,
+                "time": "2020-12-31T23:59:59+00:00",
+                "request": "POST /api/items?id=1 HTTP/1.1",
+                "method": "POST",
+                "path": "/api/items?id=1",
+                "protocol": "HTTP/1.1",
+                "status": 201,
+                "size": null,
+                "referer": "https://example.com/",
+                "user_agent": "Mozilla/5.0 (X11; Linux x86_64)"
+            }),
+            dataset[0].to_value()
+        );
+        assert!(matches!(dataset[1], DataResult::Err(_)));
+
+        let written = document.write(&vec![dataset[0].clone()]).unwrap();
+        assert_eq!(
+            buffer.lines().next().unwrap(),
+            String::from_utf8(written).unwrap()
+        );
+    }
+    #[test]
+    fn read_syslog_rfc5424() {
+        let document = Log {
+            format: LogFormat::Syslog,
+            ..Default::default()
+        };
+        let buffer = r#"<165>1 2003-10-11T22:14:15.003Z mymachine.example.com evntslog - ID47 [exampleSDID@32473 iut="3" eventSource="Application"][examplePriority@32473 class="high"] An application event"#;
+
+        let values = read(&document, buffer);
+        assert_eq!(
+            json!({
+                "facility": 20,
+                "severity": 5,
+                "version": 1,
+                "timestamp": "2003-10-11T22:14:15.003Z",
+                "hostname": "mymachine.example.com",
+                "app_name": "evntslog",
+                "proc_id": null,
+                "msg_id": "ID47",
+                "structured_data": {
+                    "exampleSDID@32473": {"iut": "3", "eventSource": "Application"},
+                    "examplePriority@32473": {"class": "high"}
+                },
+                "message": "An application event"
+            }),
+            values[0]
+        );
+
+        let dataset: DataSet = values.into_iter().map(DataResult::Ok).collect();
+        let buffer = document.write(&dataset).unwrap();
+        assert_eq!(
+            r#"<165>1 2003-10-11T22:14:15.003Z mymachine.example.com evntslog - ID47 [exampleSDID@32473 iut="3" eventSource="Application"][examplePriority@32473 class="high"] An application event"#,
+            String::from_utf8(buffer).unwrap()
+        );
+    }
+    #[test]
+    fn read_syslog_rfc3164() {
+        let document = Log {
+            format: LogFormat::Syslog,
+            ..Default::default()
+        };
+        let buffer = "<34>Oct 11 22:14:15 mymachine su[230]: 'su root' failed for lonvick on /dev/pts/8\nJan  5 08:00:01 host cron started";
+
+        let values = read(&document, buffer);
+        assert_eq!(
+            json!({
+                "facility": 4,
+                "severity": 2,
+                "timestamp": "Oct 11 22:14:15",
+                "hostname": "mymachine",
+                "app_name": "su",
+                "proc_id": "230",
+                "message": "'su root' failed for lonvick on /dev/pts/8"
+            }),
+            values[0]
+        );
+        assert_eq!(json!(null), values[1]["facility"]);
+        assert_eq!(json!(null), values[1]["app_name"]);
+        assert_eq!(json!("cron started"), values[1]["message"]);
+    }
+    #[test]
+    fn read_regex() {
+        let document = Log {
+            format: LogFormat::Regex,
+            pattern: Some(
+                r"^(?P<level>[A-Z]+) (?:\[(?P<thread>[^\]]+)\] )?(?P<message>.*)$".to_string(),
+            ),
+            ..Default::default()
+        };
+
+        let dataset = document
+            .read("INFO [main] started\r\nERROR failed\nlowercase".as_bytes())
+            .unwrap();
+        assert!(matches!(dataset[2], DataResult::Err(_)));
+
+        let values: Vec<Value> = dataset.iter().map(DataResult::to_value).collect();
+        assert_eq!(
+            json!({"level": "INFO", "thread": "main", "message": "started"}),
+            values[0]
+        );
+        assert_eq!(
+            json!({"level": "ERROR", "thread": null, "message": "failed"}),
+            values[1]
+        );
+        assert!(document
+            .write(&vec![DataResult::Ok(values[0].clone())])
+            .is_err());
+
+        let document = Log {
+            format: LogFormat::Regex,
+            ..Default::default()
+        };
+        assert!(document.read(b"line").is_err());
+    }
+}
@@ -1,25 +1,69 @@
+#[cfg(feature = "arrow")]
+pub mod arrow;
+#[cfg(feature = "avro")]
+pub mod avro;
+#[cfg(feature = "bson")]
+pub mod bson;
 pub mod byte;
+#[cfg(feature = "cbor")]
+pub mod cbor;
 #[cfg(feature = "csv")]
 pub mod csv;
+#[cfg(feature = "fixed_width")]
+pub mod fixed_width;
+#[cfg(feature = "geojson")]
+pub mod geojson;
 pub mod json;
 pub mod jsonl;
+#[cfg(feature = "log")]
+pub mod log;
+#[cfg(feature = "msgpack")]
+pub mod msgpack;
+#[cfg(feature = "orc")]
+pub mod orc;
 #[cfg(feature = "parquet")]
 pub mod parquet;
+#[cfg(feature = "protobuf")]
+pub mod protobuf;
 pub mod text;
 #[cfg(feature = "toml")]
 pub mod toml;
+#[cfg(feature = "xlsx")]
+pub mod xlsx;
 #[cfg(feature = "xml")]
 pub mod xml;
 pub mod yaml;
 
+#[cfg(feature = "arrow")]
+use self::arrow::Arrow;
+#[cfg(feature = "avro")]
+use self::avro::Avro;
+#[cfg(feature = "bson")]
+use self::bson::Bson;
+#[cfg(feature = "cbor")]
+use self::cbor::Cbor;
 #[cfg(feature = "csv")]
 use self::csv::Csv;
+#[cfg(feature = "fixed_width")]
+use self::fixed_width::FixedWidth;
+#[cfg(feature = "geojson")]
+use self::geojson::GeoJson;
 use self::jsonl::Jsonl;
+#[cfg(feature = "log")]
+use self::log::Log;
+#[cfg(feature = "msgpack")]
+use self::msgpack::Msgpack;
+#[cfg(feature = "orc")]
+use self::orc::Orc;
 #[cfg(feature = "parquet")]
 use self::parquet::Parquet;
+#[cfg(feature = "protobuf")]
+use self::protobuf::Protobuf;
 use self::text::Text;
 #[cfg(feature = "toml")]
 use self::toml::Toml;
+#[cfg(feature = "xlsx")]
+use self::xlsx::Xlsx;
 #[cfg(feature = "xml")]
 use self::xml::Xml;
 use self::yaml::Yaml;
@@ -51,11 +95,52 @@ pub enum DocumentType {
     #[serde(rename = "text")]
     #[serde(alias = "txt")]
     Text(Text),
+    #[cfg(feature = "fixed_width")]
+    #[serde(rename = "fixed_width")]
+    #[serde(alias = "fwf")]
+    #[serde(alias = "positional")]
+    FixedWidth(FixedWidth),
+    #[cfg(feature = "log")]
+    #[serde(rename = "log")]
+    Log(Log),
+    #[cfg(feature = "geojson")]
+    #[serde(rename = "geojson")]
+    GeoJson(GeoJson),
     #[serde(rename = "byte")]
     Byte(Byte),
     #[cfg(feature = "parquet")]
     #[serde(rename = "parquet")]
     Parquet(Parquet),
+    #[cfg(feature = "avro")]
+    #[serde(rename = "avro")]
+    Avro(Avro),
+    #[cfg(feature = "arrow")]
+    #[serde(rename = "arrow")]
+    #[serde(alias = "feather")]
+    Arrow(Arrow),
+    #[cfg(feature = "orc")]
+    #[serde(rename = "orc")]
+    Orc(Orc),
+    #[cfg(feature = "msgpack")]
+    #[serde(rename = "msgpack")]
+    #[serde(alias = "messagepack")]
+    Msgpack(Msgpack),
+    #[cfg(feature = "cbor")]
+    #[serde(rename = "cbor")]
+    Cbor(Cbor),
+    #[cfg(feature = "protobuf")]
+    #[serde(rename = "protobuf")]
+    #[serde(alias = "proto")]
+    Protobuf(Protobuf),
+    #[cfg(feature = "xlsx")]
+    #[serde(rename = "xlsx")]
+    #[serde(alias = "xls")]
+    #[serde(alias = "ods")]
+    #[serde(alias = "excel")]
+    Xlsx(Xlsx),
+    #[cfg(feature = "bson")]
+    #[serde(rename = "bson")]
+    Bson(Bson),
 }
 
 impl Default for DocumentType {
@@ -77,9 +162,31 @@ impl DocumentType {
             #[cfg(feature = "toml")]
             DocumentType::Toml(document) => Box::new(document),
             DocumentType::Text(document) => Box::new(document),
+            #[cfg(feature = "fixed_width")]
+            DocumentType::FixedWidth(document) => Box::new(document),
+            #[cfg(feature = "log")]
+            DocumentType::Log(document) => Box::new(document),
+            #[cfg(feature = "geojson")]
+            DocumentType::GeoJson(document) => Box::new(document),
             DocumentType::Byte(document) => Box::new(document),
             #[cfg(feature = "parquet")]
             DocumentType::Parquet(document) => Box::new(document),
+            #[cfg(feature = "avro")]
+            DocumentType::Avro(document) => Box::new(document),
+            #[cfg(feature = "arrow")]
+            DocumentType::Arrow(document) => Box::new(document),
+            #[cfg(feature = "orc")]
+            DocumentType::Orc(document) => Box::new(document),
+            #[cfg(feature = "msgpack")]
+            DocumentType::Msgpack(document) => Box::new(document),
+            #[cfg(feature = "cbor")]
+            DocumentType::Cbor(document) => Box::new(document),
+            #[cfg(feature = "protobuf")]
+            DocumentType::Protobuf(document) => Box::new(document),
+            #[cfg(feature = "xlsx")]
+            DocumentType::Xlsx(document) => Box::new(document),
+            #[cfg(feature = "bson")]
+            DocumentType::Bson(document) => Box::new(document),
         }
     }
     pub fn ref_inner(&self) -> &dyn Document {
@@ -94,9 +201,31 @@ impl DocumentType {
             #[cfg(feature = "toml")]
             DocumentType::Toml(document) => document,
             DocumentType::Text(document) => document,
+            #[cfg(feature = "fixed_width")]
+            DocumentType::FixedWidth(document) => document,
+            #[cfg(feature = "log")]
+            DocumentType::Log(document) => document,
+            #[cfg(feature = "geojson")]
+            DocumentType::GeoJson(document) => document,
             DocumentType::Byte(document) => document,
             #[cfg(feature = "parquet")]
             DocumentType::Parquet(document) => document,
+            #[cfg(feature = "avro")]
+            DocumentType::Avro(document) => document,
+            #[cfg(feature = "arrow")]
+            DocumentType::Arrow(document) => document,
+            #[cfg(feature = "orc")]
+            DocumentType::Orc(document) => document,
+            #[cfg(feature = "msgpack")]
+            DocumentType::Msgpack(document) => document,
+            #[cfg(feature = "cbor")]
+            DocumentType::Cbor(document) => document,
+            #[cfg(feature = "protobuf")]
+            DocumentType::Protobuf(document) => document,
+            #[cfg(feature = "xlsx")]
+            DocumentType::Xlsx(document) => document,
+            #[cfg(feature = "bson")]
+            DocumentType::Bson(document) => document,
         }
     }
     pub fn ref_mut_inner(&mut self) -> &mut dyn Document {
@@ -111,9 +240,31 @@ impl DocumentType {
             #[cfg(feature = "toml")]
             DocumentType::Toml(document) => document,
             DocumentType::Text(document) => document,
+            #[cfg(feature = "fixed_width")]
+            DocumentType::FixedWidth(document) => document,
+            #[cfg(feature = "log")]
+            DocumentType::Log(document) => document,
+            #[cfg(feature = "geojson")]
+            DocumentType::GeoJson(document) => document,
             DocumentType::Byte(document) => document,
             #[cfg(feature = "parquet")]
             DocumentType::Parquet(document) => document,
+            #[cfg(feature = "avro")]
+            DocumentType::Avro(document) => document,
+            #[cfg(feature = "arrow")]
+            DocumentType::Arrow(document) => document,
+            #[cfg(feature = "orc")]
+            DocumentType::Orc(document) => document,
+            #[cfg(feature = "msgpack")]
+            DocumentType::Msgpack(document) => document,
+            #[cfg(feature = "cbor")]
+            DocumentType::Cbor(document) => document,
+            #[cfg(feature = "protobuf")]
+            DocumentType::Protobuf(document) => document,
+            #[cfg(feature = "xlsx")]
+            DocumentType::Xlsx(document) => document,
+            #[cfg(feature = "bson")]
+            DocumentType::Bson(document) => document,
         }
     }
     pub fn guess(metadata: &Metadata) -> Result<Box<dyn Document>> {
@@ -137,6 +288,57 @@ impl DocumentType {
                     metadata: metadata.clone(),
                     ..Default::default()
                 }),
+                #[cfg(feature = "avro")]
+                "avro" | "vnd.apache.avro+binary" => Box::new(Avro {
+                    metadata: metadata.clone(),
+                    ..Default::default()
+                }),
+                #[cfg(feature = "arrow")]
+                "vnd.apache.arrow.file" | "arrow" | "feather" => Box::new(Arrow {
+                    metadata: metadata.clone(),
+                    ..Default::default()
+                }),
+                #[cfg(feature = "arrow")]
+                "vnd.apache.arrow.stream" => Box::new(Arrow {
+                    metadata: metadata.clone(),
+                    format: self::arrow::Format::Stream,
+                    ..Default::default()
+                }),
+                #[cfg(feature = "orc")]
+                "orc" | "x-orc" => Box::new(Orc {
+                    metadata: metadata.clone(),
+                    ..Default::default()
+                }),
+                #[cfg(feature = "msgpack")]
+                "msgpack" | "x-msgpack" | "vnd.msgpack" => Box::new(Msgpack {
+                    metadata: metadata.clone(),
+                    ..Default::default()
+                }),
+                #[cfg(feature = "cbor")]
+                "cbor" | "cbor-seq" => Box::new(Cbor {
+                    metadata: metadata.clone(),
+                    ..Default::default()
+                }),
+                #[cfg(feature = "xlsx")]
+                "vnd.openxmlformats-officedocument.spreadsheetml.sheet"
+                | "vnd.oasis.opendocument.spreadsheet"
+                | "vnd.ms-excel"
+                | "xlsx"
+                | "xls"
+                | "ods" => Box::new(Xlsx {
+                    metadata: metadata.clone(),
+                    ..Default::default()
+                }),
+                #[cfg(feature = "bson")]
+                "bson" => Box::new(Bson {
+                    metadata: metadata.clone(),
+                    ..Default::default()
+                }),
+                #[cfg(feature = "geojson")]
+                "geo+json" | "geojson" => Box::new(GeoJson {
+                    metadata: metadata.clone(),
+                    ..Default::default()
+                }),
                 "text" | "txt" => Box::new(Text {
                     metadata: metadata.clone(),
                 }),
@@ -286,6 +488,141 @@ mod test {
             serde_json::from_str(config).expect("Can't deserialize the config");
         assert_eq!(document_builder_expected, document_builder_result);
     }
+    #[cfg(feature = "avro")]
+    #[test]
+    fn it_should_deserialize_in_avro_type() {
+        let config = r#"{"type":"avro"}"#;
+        let document_builder_expected = DocumentType::Avro(Avro::default());
+        let document_builder_result: DocumentType =
+            serde_json::from_str(config).expect("Can't deserialize the config");
+        assert_eq!(document_builder_expected, document_builder_result);
+    }
+    #[cfg(feature = "arrow")]
+    #[test]
+    fn it_should_deserialize_in_arrow_type() {
+        let config = r#"{"type":"feather"}"#;
+        let document_builder_expected = DocumentType::Arrow(Arrow::default());
+        let document_builder_result: DocumentType =
+            serde_json::from_str(config).expect("Can't deserialize the config");
+        assert_eq!(document_builder_expected, document_builder_result);
+    }
+    #[cfg(feature = "orc")]
+    #[test]
+    fn it_should_deserialize_in_orc_type() {
+        let config = r#"{"type":"orc"}"#;
+        let document_builder_expected = DocumentType::Orc(Orc::default());
+        let document_builder_result: DocumentType =
+            serde_json::from_str(config).expect("Can't deserialize the config");
+        assert_eq!(document_builder_expected, document_builder_result);
+    }
+    #[cfg(feature = "msgpack")]
+    #[test]
+    fn it_should_deserialize_in_msgpack_type() {
+        let config = r#"{"type":"msgpack"}"#;
+        let document_builder_expected = DocumentType::Msgpack(Msgpack::default());
+        let document_builder_result: DocumentType =
+            serde_json::from_str(config).expect("Can't deserialize the config");
+        assert_eq!(document_builder_expected, document_builder_result);
+    }
+    #[cfg(feature = "cbor")]
+    #[test]
+    fn it_should_deserialize_in_cbor_type() {
+        let config = r#"{"type":"cbor"}"#;
+        let document_builder_expected = DocumentType::Cbor(Cbor::default());
+        let document_builder_result: DocumentType =
+            serde_json::from_str(config).expect("Can't deserialize the config");
+        assert_eq!(document_builder_expected, document_builder_result);
+    }
+    #[cfg(feature = "protobuf")]
+    #[test]
+    fn it_should_deserialize_in_protobuf_type() {
+        let config = r#"{"type":"proto"}"#;
+        let document_builder_expected = DocumentType::Protobuf(Protobuf::default());
+        let document_builder_result: DocumentType =
+            serde_json::from_str(config).expect("Can't deserialize the config");
+        assert_eq!(document_builder_expected, document_builder_result);
+    }
+    #[cfg(feature = "xlsx")]
+    #[test]
+    fn it_should_deserialize_in_xlsx_type() {
+        let config = r#"{"type":"ods"}"#;
+        let document_builder_expected = DocumentType::Xlsx(Xlsx::default());
+        let document_builder_result: DocumentType =
+            serde_json::from_str(config).expect("Can't deserialize the config");
+        assert_eq!(document_builder_expected, document_builder_result);
+    }
+    #[cfg(feature = "fixed_width")]
+    #[test]
+    fn it_should_deserialize_in_fixed_width_type() {
+        let config = r#"{"type":"fwf"}"#;
+        let document_builder_expected = DocumentType::FixedWidth(FixedWidth::default());
+        let document_builder_result: DocumentType =
+            serde_json::from_str(config).expect("Can't deserialize the config");
+        assert_eq!(document_builder_expected, document_builder_result);
+    }
+    #[cfg(feature = "log")]
+    #[test]
+    fn it_should_deserialize_in_log_type() {
+        let config = r#"{"type":"log","format":"combined"}"#;
+        let document_builder_expected = DocumentType::Log(Log {
+            format: self::log::LogFormat::Combined,
+            ..Default::default()
+        });
+        let document_builder_result: DocumentType =
+            serde_json::from_str(config).expect("Can't deserialize the config");
+        assert_eq!(document_builder_expected, document_builder_result);
+    }
+    #[cfg(feature = "geojson")]
+    #[test]
+    fn it_should_deserialize_in_geojson_type() {
+        let config = r#"{"type":"geojson","geometry_format":"wkt"}"#;
+        let document_builder_expected = DocumentType::GeoJson(GeoJson {
+            geometry_format: self::geojson::GeometryFormat::Wkt,
+            ..Default::default()
+        });
+        let document_builder_result: DocumentType =
+            serde_json::from_str(config).expect("Can't deserialize the config");
+        assert_eq!(document_builder_expected, document_builder_result);
+    }
+    #[cfg(feature = "bson")]
+    #[test]
+    fn it_should_deserialize_in_bson_type() {
+        let config = r#"{"type":"bson"}"#;
+        let document_builder_expected = DocumentType::Bson(Bson::default());
+        let document_builder_result: DocumentType =
+            serde_json::from_str(config).expect("Can't deserialize the config");
+        assert_eq!(document_builder_expected, document_builder_result);
+    }
+    #[cfg(feature = "msgpack")]
+    #[test]
+    fn it_should_guess_the_msgpack_document() {
+        for mime_subtype in ["msgpack", "x-msgpack", "vnd.msgpack"] {
+            let metadata = Metadata {
+                mime_type: Some("application".to_string()),
+                mime_subtype: Some(mime_subtype.to_string()),
+                ..Default::default()
+            };
+            let document = DocumentType::guess(&metadata).unwrap();
+            assert!(
+                format!("{:?}", document).starts_with("Msgpack"),
+                "{}",
+                mime_subtype
+            );
+            assert_eq!(metadata, document.metadata());
+        }
+    }
+    #[cfg(feature = "cbor")]
+    #[test]
+    fn it_should_guess_the_cbor_document() {
+        let metadata = Metadata {
+            mime_type: Some("application".to_string()),
+            mime_subtype: Some("cbor".to_string()),
+            ..Default::default()
+        };
+        let document = DocumentType::guess(&metadata).unwrap();
+        assert!(format!("{:?}", document).starts_with("Cbor"));
+        assert_eq!(metadata, document.metadata());
+    }
     #[test]
     #[should_panic(expected = "missing field `type`")]
     fn it_should_not_deserialize_without_type() {
@@ -0,0 +1,392 @@
+//! Read and write data in **MessagePack** format.
+//!
+//! The document can contain a single value, an array of values or a stream of concatenated values.
+//! Each object is a record and each item of an array is a record.
+//! The binary values and the strings that are not valid UTF-8 are converted into base64 strings and the extension values into `{"type": <i8>, "data": <base64>}`.
+//!
+//! By default, the records are written as a stream of concatenated values that can be appended to the document.
+//! With `is_array`, the records are written in one array and the document is replaced.
+//!
+//! ### Configuration
+//!
+//! | key        | alias | Description                                            | Default Value | Possible Values                                                                 |
+//! | ---------- | ----- | ------------------------------------------------------ | ------------- | ------------------------------------------------------------------------------- |
+//! | type       | -     | Required in order to use this document.                | `msgpack`     | `msgpack` / `messagepack`                                                       |
+//! | metadata   | meta  | Metadata describe the resource.                        | `null`        | [`crate::Metadata`]                                                             |
+//! | entry_path | -     | Use this field to read the records from a sub path.    | `null`        | JSON Pointer string ([RFC 6901](https://datatracker.ietf.org/doc/html/rfc6901)) |
+//! | is_array   | -     | Write the records in one array.                        | `false`       | `true` / `false`                                                                |
+//!
+//! ### Examples
+//!
+//! ```json
+//! [
+//!     {
+//!         "type": "read",
+//!         "document": {
+//!             "type": "msgpack",
+//!             "entry_path": "/items"
+//!         },
+//!         "connector":{
+//!             "type": "local",
+//!             "path": "./data/out/messages.msgpack"
+//!         }
+//!     },
+//!     {
+//!         "type": "write",
+//!         "document": {
+//!             "type": "msgpack",
+//!             "is_array": true
+//!         },
+//!         "connector":{
+//!             "type": "local",
+//!             "path": "./data/out/items.msgpack"
+//!         }
+//!     }
+//! ]
+//! ```
+use crate::document::Document;
+use crate::helper::string::DisplayOnlyForDebugging;
+use crate::DataResult;
+use crate::{DataSet, Metadata};
+use base64::Engine;
+use json_value_search::Search;
+use rmpv::decode::read_value;
+use rmpv::encode::write_value;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Number, Value};
+use std::io::{self, Cursor};
+
+const DEFAULT_SUBTYPE: &str = "msgpack";
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Msgpack {
+    #[serde(rename = "metadata")]
+    #[serde(alias = "meta")]
+    pub metadata: Metadata,
+    pub entry_path: Option<String>,
+    pub is_array: bool,
+}
+
+impl Default for Msgpack {
+    fn default() -> Self {
+        let metadata = Metadata {
+            mime_type: Some(mime::APPLICATION.to_string()),
+            mime_subtype: Some(DEFAULT_SUBTYPE.to_string()),
+            ..Default::default()
+        };
+        Msgpack {
+            metadata,
+            entry_path: None,
+            is_array: false,
+        }
+    }
+}
+
+/// Transform a MessagePack value into a json value.
+///
+/// # Examples
+///
+/// ```
+/// use chewdata::document::msgpack::to_json;
+/// use serde_json::json;
+///
+/// let value = rmpv::Value::Map(vec![
+///     (rmpv::Value::from(1), rmpv::Value::Binary(b"bytes".to_vec())),
+///     (rmpv::Value::from("number"), rmpv::Value::from(10)),
+/// ]);
+/// assert_eq!(json!({"1": "Ynl0ZXM=", "number": 10}), to_json(value));
+/// ```
+pub fn to_json(value: rmpv::Value) -> Value {
+    let base64 = |data: &[u8]| base64::engine::general_purpose::STANDARD.encode(data);
+
+    match value {
+        rmpv::Value::Nil => Value::Null,
+        rmpv::Value::Boolean(boolean) => Value::Bool(boolean),
+        rmpv::Value::Integer(integer) => match (integer.as_i64(), integer.as_u64()) {
+            (Some(integer), _) => Value::from(integer),
+            (_, Some(integer)) => Value::from(integer),
+            _ => Value::Null,
+        },
+        rmpv::Value::F32(float) => {
+            Number::from_f64(float.into()).map_or(Value::Null, Value::Number)
+        }
+        rmpv::Value::F64(float) => Number::from_f64(float).map_or(Value::Null, Value::Number),
+        rmpv::Value::String(string) => match String::from_utf8(string.into_bytes()) {
+            Ok(string) => Value::String(string),
+            Err(e) => Value::String(base64(e.as_bytes())),
+        },
+        rmpv::Value::Binary(data) => Value::String(base64(&data)),
+        rmpv::Value::Array(array) => Value::Array(array.into_iter().map(to_json).collect()),
+        rmpv::Value::Map(map) => Value::Object(
+            map.into_iter()
+                .map(|(key, value)| {
+                    let key = match to_json(key) {
+                        Value::String(key) => key,
+                        key => key.to_string(),
+                    };
+                    (key, to_json(value))
+                })
+                .collect::<Map<String, Value>>(),
+        ),
+        rmpv::Value::Ext(kind, data) => {
+            serde_json::json!({"type": kind, "data": base64(&data)})
+        }
+    }
+}
+
+impl Msgpack {
+    fn push_records(&self, record: Value, dataset: &mut DataSet) -> io::Result<()> {
+        let records = match &self.entry_path {
+            Some(entry_path) => match record.clone().search(entry_path)? {
+                Some(records) => records,
+                None => {
+                    warn!(
+                        entry_path = format!("{:?}", entry_path).as_str(),
+                        record = record.display_only_for_debugging(),
+                        "Entry path not found"
+                    );
+                    dataset.push(DataResult::Err((
+                        record,
+                        io::Error::new(
+                            io::ErrorKind::InvalidInput,
+                            format!("Entry path '{}' not found", entry_path),
+                        ),
+                    )));
+                    return Ok(());
+                }
+            },
+            None => record,
+        };
+
+        let records = match records {
+            Value::Array(records) => records,
+            record => vec![record],
+        };
+
+        for record in records {
+            trace!(
+                record = record.display_only_for_debugging(),
+                "Record deserialized"
+            );
+            dataset.push(DataResult::Ok(record));
+        }
+
+        Ok(())
+    }
+}
+
+impl Document for Msgpack {
+    /// See [`Document::set_metadata`] for more details.
+    fn set_metadata(&mut self, metadata: Metadata) {
+        self.metadata = metadata.clone();
+    }
+    /// See [`Document::metadata`] for more details.
+    fn metadata(&self) -> Metadata {
+        Msgpack::default().metadata.merge(&self.metadata)
+    }
+    /// See [`Document::set_entry_path`] for more details.
+    fn set_entry_path(&mut self, entry_path: String) {
+        if entry_path.is_empty() {
+            self.entry_path = None;
+            return;
+        }
+
+        self.entry_path = Some(entry_path);
+    }
+    /// See [`Document::can_append`] for more details.
+    fn can_append(&self) -> bool {
+        !self.is_array
+    }
+    /// See [`Document::read`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::document::msgpack::Msgpack;
+    /// use chewdata::document::Document;
+    /// use serde_json::json;
+    ///
+    /// let document = Msgpack::default();
+    /// // [{"number": 10}, {"number": 20}]
+    /// let buffer = vec![0x92, 0x81, 0xa6, b'n', b'u', b'm', b'b', b'e', b'r', 0x0a, 0x81, 0xa6, b'n', b'u', b'm', b'b', b'e', b'r', 0x14];
+    ///
+    /// let mut dataset = document.read(&buffer).unwrap().into_iter();
+    /// assert_eq!(json!({"number": 10}), dataset.next().unwrap().to_value());
+    /// assert_eq!(json!({"number": 20}), dataset.next().unwrap().to_value());
+    /// ```
+    #[instrument(skip(buffer), name = "msgpack::read")]
+    fn read(&self, buffer: &[u8]) -> io::Result<DataSet> {
+        let mut cursor = Cursor::new(buffer);
+        let mut dataset = Vec::default();
+
+        while (cursor.position() as usize) < buffer.len() {
+            match read_value(&mut cursor) {
+                Ok(value) => self.push_records(to_json(value), &mut dataset)?,
+                Err(e) => {
+                    warn!(
+                        error = format!("{:?}", e).as_str(),
+                        "Can't deserialize the record"
+                    );
+                    dataset.push(DataResult::Err((
+                        Value::Null,
+                        io::Error::new(io::ErrorKind::InvalidInput, e.to_string()),
+                    )));
+                    break;
+                }
+            }
+        }
+
+        Ok(dataset)
+    }
+    /// See [`Document::write`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::document::msgpack::Msgpack;
+    /// use chewdata::document::Document;
+    /// use chewdata::DataResult;
+    /// use serde_json::json;
+    ///
+    /// let document = Msgpack::default();
+    /// let dataset = vec![DataResult::Ok(json!({"number": 10}))];
+    ///
+    /// let buffer = document.write(&dataset).unwrap();
+    /// assert_eq!(vec![0x81, 0xa6, b'n', b'u', b'm', b'b', b'e', b'r', 0x0a], buffer);
+    /// ```
+    #[instrument(skip(dataset), name = "msgpack::write")]
+    fn write(&self, dataset: &DataSet) -> io::Result<Vec<u8>> {
+        let mut buffer = Vec::default();
+        let values = dataset.iter().map(|data| {
+            let record = data.to_value();
+            trace!(
+                record = record.display_only_for_debugging(),
+                "Record serialized"
+            );
+            rmpv::ext::to_value(record).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))
+        });
+
+        if self.is_array {
+            let array = rmpv::Value::Array(values.collect::<io::Result<_>>()?);
+            write_value(&mut buffer, &array)?;
+            return Ok(buffer);
+        }
+
+        for value in values {
+            write_value(&mut buffer, &value?)?;
+        }
+
+        Ok(buffer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn read_stream_of_values() {
+        let document = Msgpack::default();
+        let mut buffer = Vec::default();
+        write_value(
+            &mut buffer,
+            &rmpv::ext::to_value(json!({"number": 10})).unwrap(),
+        )
+        .unwrap();
+        write_value(
+            &mut buffer,
+            &rmpv::ext::to_value(json!([{"number": 20}, {"number": 30}])).unwrap(),
+        )
+        .unwrap();
+
+        let values: Vec<Value> = document
+            .read(&buffer)
+            .unwrap()
+            .iter()
+            .map(DataResult::to_value)
+            .collect();
+        assert_eq!(
+            vec![
+                json!({"number": 10}),
+                json!({"number": 20}),
+                json!({"number": 30})
+            ],
+            values
+        );
+    }
+    #[test]
+    fn read_string_with_invalid_utf8() {
+        let document = Msgpack::default();
+        let mut buffer = vec![0x81, 0xa6];
+        buffer.extend_from_slice(b"string");
+        buffer.extend_from_slice(&[0xa2, 0xff, 0xfe]);
+
+        let values: Vec<Value> = document
+            .read(&buffer)
+            .unwrap()
+            .iter()
+            .map(DataResult::to_value)
+            .collect();
+        assert_eq!(vec![json!({"string": "//4="})], values);
+    }
+    #[test]
+    fn read_with_entry_path() {
+        let document = Msgpack {
+            entry_path: Some("/items".to_string()),
+            ..Default::default()
+        };
+        let buffer = Msgpack::default()
+            .write(&vec![
+                DataResult::Ok(json!({"items": [{"number": 10}, {"number": 20}]})),
+                DataResult::Ok(json!({"other": true})),
+            ])
+            .unwrap();
+
+        let dataset = document.read(&buffer).unwrap();
+        assert_eq!(3, dataset.len());
+        assert_eq!(json!({"number": 20}), dataset[1].to_value());
+        assert!(matches!(dataset[2], DataResult::Err(_)));
+    }
+    #[test]
+    fn read_truncated_value() {
+        let document = Msgpack::default();
+        let mut buffer = document
+            .write(&vec![
+                DataResult::Ok(json!({"number": 10})),
+                DataResult::Ok(json!({"string": "value"})),
+            ])
+            .unwrap();
+        buffer.truncate(buffer.len() - 2);
+
+        let dataset = document.read(&buffer).unwrap();
+        assert_eq!(2, dataset.len());
+        assert_eq!(json!({"number": 10}), dataset[0].to_value());
+        assert!(matches!(dataset[1], DataResult::Err(_)));
+    }
+    #[test]
+    fn write_array() {
+        let document = Msgpack {
+            is_array: true,
+            ..Default::default()
+        };
+        let dataset = vec![
+            DataResult::Ok(json!({"number": 10, "float": 1.5, "list": [true, null]})),
+            DataResult::Ok(json!({"number": -20, "string": "é"})),
+        ];
+
+        let buffer = document.write(&dataset).unwrap();
+        assert_eq!(0x92, buffer[0]);
+        assert!(!document.can_append());
+
+        let values: Vec<Value> = document
+            .read(&buffer)
+            .unwrap()
+            .iter()
+            .map(DataResult::to_value)
+            .collect();
+        let expected: Vec<Value> = dataset.iter().map(DataResult::to_value).collect();
+        assert_eq!(expected, values);
+    }
+}
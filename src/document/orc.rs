@@ -0,0 +1,547 @@
+//! Read and write data in **ORC** format.
+//!
+//! The records are read stripe by stripe with the selected `columns` only.
+//! The `filter` conditions are first checked against the statistics of each stripe and the stripes that
+//! can't contain a matching record are not read at all. The remaining records are then filtered one by one.
+//! The stripe statistics are written by Hive, Spark or the Java ORC writer.
+//!
+//! The records are written with the schema inferred from the first record or with the `schema` override
+//! like the [`crate::document::parquet`] document. Only the primitive types can be written: integer, float, string, binary and boolean.
+//!
+//! ### Configuration
+//!
+//! | key         | alias | Description                                                  | Default Value | Possible Values                                                                                   |
+//! | ----------- | ----- | ------------------------------------------------------------ | ------------- | ------------------------------------------------------------------------------------------------- |
+//! | type        | -     | Required in order to use this document.                      | `orc`         | `orc`                                                                                             |
+//! | metadata    | meta  | Metadata describe the resource.                              | `null`        | [`crate::Metadata`]                                                                               |
+//! | columns     | -     | Columns to read. All the columns are read if empty.          | `[]`          | List of column names                                                                              |
+//! | filter      | -     | Conditions that the records must all match to be read.       | `[]`          | List of [`crate::document::orc::Condition`]                                                       |
+//! | schema      | -     | Overrides the schema inferred from the first record.         | `null`        | JSON schema definition (https://github.com/apache/arrow-rs/blob/main/arrow-schema/src/schema.rs) |
+//! | batch_size  | -     | Number of records per batch.                                 | `1000`        | Unsigned integer                                                                                  |
+//! | stripe_size | -     | Size in bytes of the stripes to write.                       | `null`        | Unsigned integer                                                                                  |
+//!
+//! ### Condition
+//!
+//! | key      | alias | Description                                | Default Value | Possible Values                      |
+//! | -------- | ----- | ------------------------------------------ | ------------- | ------------------------------------ |
+//! | column   | -     | Name of the column to compare.             | `null`        | String                               |
+//! | operator | op    | Comparison between the column and value.   | `=`           | `=` / `!=` / `<` / `<=` / `>` / `>=` |
+//! | value    | -     | Value to compare with. `null` is allowed.  | `null`        | Number / String / Boolean / `null`   |
+//!
+//! ### Examples
+//!
+//! ```json
+//! [
+//!     {
+//!         "type": "read",
+//!         "document": {
+//!             "type": "orc",
+//!             "columns": ["number", "string", "date"],
+//!             "filter": [
+//!                 {"column": "date", "operator": ">=", "value": "2020-01-01"},
+//!                 {"column": "number", "operator": "!=", "value": 30}
+//!             ]
+//!         },
+//!         "connector":{
+//!             "type": "local",
+//!             "path": "./data/multi_lines.orc"
+//!         }
+//!     }
+//! ]
+//! ```
+use crate::document::Document;
+use crate::helper::arrow::{record_batches, records, schema};
+use crate::helper::string::DisplayOnlyForDebugging;
+use crate::DataResult;
+use crate::{DataSet, Metadata};
+use arrow_schema::DataType;
+use bytes::Bytes;
+use chrono::{Duration, NaiveDate};
+use orc_rust::projection::ProjectionMask;
+use orc_rust::reader::metadata::FileMetadata;
+use orc_rust::statistics::{ColumnStatistics, TypeStatistics};
+use orc_rust::{ArrowReaderBuilder, ArrowWriterBuilder};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::io;
+use std::ops::Range;
+use std::sync::Arc;
+
+const DEFAULT_SUBTYPE: &str = "orc";
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Orc {
+    #[serde(rename = "metadata")]
+    #[serde(alias = "meta")]
+    pub metadata: Metadata,
+    pub columns: Vec<String>,
+    pub filter: Vec<Condition>,
+    pub schema: Option<Value>,
+    pub batch_size: usize,
+    pub stripe_size: Option<usize>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct Condition {
+    pub column: String,
+    #[serde(alias = "op")]
+    pub operator: Operator,
+    pub value: Value,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Operator {
+    #[default]
+    #[serde(rename = "=", alias = "==", alias = "eq")]
+    Equal,
+    #[serde(rename = "!=", alias = "<>", alias = "ne")]
+    NotEqual,
+    #[serde(rename = "<", alias = "lt")]
+    LessThan,
+    #[serde(rename = "<=", alias = "lte")]
+    LessThanOrEqual,
+    #[serde(rename = ">", alias = "gt")]
+    GreaterThan,
+    #[serde(rename = ">=", alias = "gte")]
+    GreaterThanOrEqual,
+}
+
+impl Default for Orc {
+    fn default() -> Self {
+        let metadata = Metadata {
+            mime_type: Some(mime::APPLICATION.to_string()),
+            mime_subtype: Some(DEFAULT_SUBTYPE.to_string()),
+            ..Default::default()
+        };
+        Orc {
+            metadata,
+            columns: Vec::default(),
+            filter: Vec::default(),
+            schema: None,
+            batch_size: 1000,
+            stripe_size: None,
+        }
+    }
+}
+
+/// Compare two json values of the same type.
+fn compare(left: &Value, right: &Value) -> Option<Ordering> {
+    match (left, right) {
+        (Value::Number(left), Value::Number(right)) => left.as_f64()?.partial_cmp(&right.as_f64()?),
+        (Value::String(left), Value::String(right)) => Some(left.cmp(right)),
+        (Value::Bool(left), Value::Bool(right)) => Some(left.cmp(right)),
+        _ => None,
+    }
+}
+
+/// Transform a number of days since the epoch into an ISO 8601 date comparable with the date of the records.
+fn date(days: i32) -> Option<Value> {
+    NaiveDate::from_ymd_opt(1970, 1, 1)?
+        .checked_add_signed(Duration::days(days.into()))
+        .map(|date| Value::String(date.format("%Y-%m-%d").to_string()))
+}
+
+impl Condition {
+    /// Check if the record matches the condition.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::document::orc::{Condition, Operator};
+    /// use serde_json::json;
+    ///
+    /// let condition = Condition {
+    ///     column: "number".to_string(),
+    ///     operator: Operator::GreaterThan,
+    ///     value: json!(10),
+    /// };
+    /// assert!(condition.matches(&json!({"number": 20})));
+    /// assert!(!condition.matches(&json!({"number": 10})));
+    /// assert!(!condition.matches(&json!({"string": "value"})));
+    /// ```
+    pub fn matches(&self, record: &Value) -> bool {
+        let field = record.get(&self.column).unwrap_or(&Value::Null);
+
+        if self.value.is_null() {
+            return match self.operator {
+                Operator::Equal => field.is_null(),
+                Operator::NotEqual => !field.is_null(),
+                _ => false,
+            };
+        }
+
+        match compare(field, &self.value) {
+            Some(ordering) => match self.operator {
+                Operator::Equal => ordering.is_eq(),
+                Operator::NotEqual => ordering.is_ne(),
+                Operator::LessThan => ordering.is_lt(),
+                Operator::LessThanOrEqual => ordering.is_le(),
+                Operator::GreaterThan => ordering.is_gt(),
+                Operator::GreaterThanOrEqual => ordering.is_ge(),
+            },
+            None => false,
+        }
+    }
+    /// Check if a column with these statistics may contain a value matching the condition.
+    /// Return true when the statistics are not enough to decide.
+    pub fn may_match(
+        &self,
+        number_of_values: u64,
+        has_null: bool,
+        statistics: Option<&TypeStatistics>,
+    ) -> bool {
+        if self.value.is_null() {
+            return match self.operator {
+                Operator::Equal => has_null,
+                Operator::NotEqual => 0 < number_of_values,
+                _ => false,
+            };
+        }
+
+        if 0 == number_of_values {
+            return false;
+        }
+
+        let (min, max) = match statistics {
+            Some(TypeStatistics::Integer { min, max, .. }) => {
+                (Value::from(*min), Value::from(*max))
+            }
+            Some(TypeStatistics::Double { min, max, .. }) => (Value::from(*min), Value::from(*max)),
+            Some(TypeStatistics::String { min, max, .. }) => {
+                (Value::from(min.as_str()), Value::from(max.as_str()))
+            }
+            Some(TypeStatistics::Date { min, max }) => match (date(*min), date(*max)) {
+                (Some(min), Some(max)) => (min, max),
+                _ => return true,
+            },
+            Some(TypeStatistics::Bucket { true_count }) => (
+                Value::Bool(*true_count == number_of_values),
+                Value::Bool(0 < *true_count),
+            ),
+            _ => return true,
+        };
+
+        let (Some(with_min), Some(with_max)) =
+            (compare(&min, &self.value), compare(&max, &self.value))
+        else {
+            return true;
+        };
+
+        match self.operator {
+            Operator::Equal => with_min.is_le() && with_max.is_ge(),
+            Operator::NotEqual => !(with_min.is_eq() && with_max.is_eq()),
+            Operator::LessThan => with_min.is_lt(),
+            Operator::LessThanOrEqual => with_min.is_le(),
+            Operator::GreaterThan => with_max.is_gt(),
+            Operator::GreaterThanOrEqual => with_max.is_ge(),
+        }
+    }
+}
+
+fn invalid_data<E>(error: E) -> io::Error
+where
+    E: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+impl Orc {
+    /// Check if the stripe may contain records matching all the conditions of the filter.
+    fn stripe_may_match(&self, metadata: &FileMetadata, statistics: &[ColumnStatistics]) -> bool {
+        self.filter.iter().all(|condition| {
+            metadata
+                .root_data_type()
+                .children()
+                .iter()
+                .find(|column| column.name() == condition.column)
+                .and_then(|column| statistics.get(column.data_type().column_index()))
+                .map(|statistics| {
+                    condition.may_match(
+                        statistics.number_of_values(),
+                        statistics.has_null(),
+                        statistics.type_statistics(),
+                    )
+                })
+                .unwrap_or(true)
+        })
+    }
+    /// Get the byte ranges of the consecutive stripes to read.
+    fn stripe_ranges(&self, metadata: &FileMetadata) -> Vec<Range<usize>> {
+        let mut ranges: Vec<Range<usize>> = Vec::default();
+        let mut previous_matched = false;
+
+        for stripe in metadata.stripe_metadatas() {
+            let matched = self.stripe_may_match(metadata, stripe.column_statistics());
+            let offset = stripe.offset() as usize;
+
+            match ranges.last_mut() {
+                Some(range) if matched && previous_matched => range.end = offset + 1,
+                _ if matched => ranges.push(offset..offset + 1),
+                _ => trace!(offset, "Stripe skipped by the filter"),
+            }
+            previous_matched = matched;
+        }
+
+        ranges
+    }
+    /// Get the columns to read, with the columns used by the filter.
+    fn projection(&self, metadata: &FileMetadata) -> ProjectionMask {
+        if self.columns.is_empty() {
+            return ProjectionMask::all();
+        }
+
+        let mut columns = self.columns.clone();
+        for condition in &self.filter {
+            if !columns.contains(&condition.column) {
+                columns.push(condition.column.clone());
+            }
+        }
+
+        ProjectionMask::named_roots(metadata.root_data_type(), &columns)
+    }
+}
+
+impl Document for Orc {
+    /// See [`Document::set_metadata`] for more details.
+    fn set_metadata(&mut self, metadata: Metadata) {
+        self.metadata = metadata.clone();
+    }
+    /// See [`Document::metadata`] for more details.
+    fn metadata(&self) -> Metadata {
+        Orc::default().metadata.merge(&self.metadata)
+    }
+    /// See [`Document::can_append`] for more details.
+    fn can_append(&self) -> bool {
+        false
+    }
+    /// See [`Document::read`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::document::orc::Orc;
+    /// use chewdata::document::Document;
+    /// use chewdata::DataResult;
+    /// use serde_json::json;
+    ///
+    /// let document = Orc::default();
+    /// let dataset = vec![DataResult::Ok(json!({"number": 10, "string": "value"}))];
+    /// let buffer = document.write(&dataset).unwrap();
+    ///
+    /// let mut dataset = document.read(&buffer).unwrap().into_iter();
+    /// assert_eq!(json!({"number": 10, "string": "value"}), dataset.next().unwrap().to_value());
+    /// ```
+    #[instrument(skip(buffer), name = "orc::read")]
+    fn read(&self, buffer: &[u8]) -> io::Result<DataSet> {
+        let bytes = Bytes::copy_from_slice(buffer);
+        let builder = ArrowReaderBuilder::try_new(bytes.clone()).map_err(invalid_data)?;
+        let projection = self.projection(builder.file_metadata());
+        let file_metadata = Arc::new(builder.file_metadata().clone());
+
+        let mut dataset = Vec::default();
+        for range in self.stripe_ranges(&file_metadata) {
+            let reader = ArrowReaderBuilder::try_new(bytes.clone())
+                .map_err(invalid_data)?
+                .with_batch_size(self.batch_size)
+                .with_projection(projection.clone())
+                .with_file_byte_range(range)
+                .build();
+
+            for batch in reader {
+                for mut record in records(&batch.map_err(invalid_data)?)? {
+                    if !self
+                        .filter
+                        .iter()
+                        .all(|condition| condition.matches(&record))
+                    {
+                        continue;
+                    }
+
+                    if let (false, Value::Object(map)) = (self.columns.is_empty(), &mut record) {
+                        map.retain(|key, _| self.columns.contains(key));
+                    }
+
+                    trace!(
+                        record = record.display_only_for_debugging(),
+                        "Record deserialized"
+                    );
+                    dataset.push(DataResult::Ok(record));
+                }
+            }
+        }
+
+        Ok(dataset)
+    }
+    /// See [`Document::write`] for more details.
+    #[instrument(skip(dataset), name = "orc::write")]
+    fn write(&self, dataset: &DataSet) -> io::Result<Vec<u8>> {
+        let Some(first) = dataset.first() else {
+            return Ok(vec![]);
+        };
+
+        let schema = schema(self.schema.as_ref(), &first.to_value())?;
+        // The ORC writer only supports the primitive types and panics with the others.
+        if let Some(field) = schema.fields().iter().find(|field| {
+            !matches!(
+                field.data_type(),
+                DataType::Float32
+                    | DataType::Float64
+                    | DataType::Int8
+                    | DataType::Int16
+                    | DataType::Int32
+                    | DataType::Int64
+                    | DataType::Utf8
+                    | DataType::LargeUtf8
+                    | DataType::Binary
+                    | DataType::LargeBinary
+                    | DataType::Boolean
+            )
+        }) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "The type '{}' of the field '{}' can't be written in ORC",
+                    field.data_type(),
+                    field.name()
+                ),
+            ));
+        }
+
+        let values: Vec<Value> = dataset.iter().map(|data| data.to_value()).collect();
+        let batches = record_batches(&schema, &values, self.batch_size)?;
+
+        let mut buffer = Vec::default();
+        let mut builder =
+            ArrowWriterBuilder::new(&mut buffer, Arc::new(schema)).with_batch_size(self.batch_size);
+        if let Some(stripe_size) = self.stripe_size {
+            builder = builder.with_stripe_byte_size(stripe_size);
+        }
+
+        let mut writer = builder
+            .try_build()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        for batch in &batches {
+            writer.write(batch).map_err(invalid_data)?;
+        }
+        writer.close().map_err(invalid_data)?;
+
+        Ok(buffer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn dataset() -> DataSet {
+        (0..5)
+            .map(|number| {
+                DataResult::Ok(json!({
+                    "number": number,
+                    "string": format!("value {}", number),
+                    "boolean": 0 == number % 2,
+                    "round": number as f64 / 2.0
+                }))
+            })
+            .collect()
+    }
+    fn condition(column: &str, operator: Operator, value: Value) -> Condition {
+        Condition {
+            column: column.to_string(),
+            operator,
+            value,
+        }
+    }
+
+    #[test]
+    fn write_and_read() {
+        let document = Orc {
+            batch_size: 2,
+            stripe_size: Some(64),
+            ..Default::default()
+        };
+        let buffer = document.write(&dataset()).unwrap();
+        assert_eq!(b"ORC", &buffer[..3]);
+
+        let values: Vec<Value> = document
+            .read(&buffer)
+            .unwrap()
+            .iter()
+            .map(DataResult::to_value)
+            .collect();
+        let expected: Vec<Value> = dataset().iter().map(DataResult::to_value).collect();
+        assert_eq!(expected, values);
+    }
+    #[test]
+    fn read_with_columns_and_filter() {
+        let buffer = Orc::default().write(&dataset()).unwrap();
+        let document = Orc {
+            columns: vec!["string".to_string()],
+            filter: vec![
+                condition("number", Operator::GreaterThanOrEqual, json!(2)),
+                condition("boolean", Operator::Equal, json!(true)),
+            ],
+            ..Default::default()
+        };
+
+        let values: Vec<Value> = document
+            .read(&buffer)
+            .unwrap()
+            .iter()
+            .map(DataResult::to_value)
+            .collect();
+        assert_eq!(
+            vec![json!({"string": "value 2"}), json!({"string": "value 4"})],
+            values
+        );
+    }
+    #[test]
+    fn write_nested_record() {
+        let dataset = vec![DataResult::Ok(json!({"object": {"number": 10}}))];
+        let error = Orc::default().write(&dataset).unwrap_err();
+        assert_eq!(io::ErrorKind::InvalidInput, error.kind());
+    }
+    #[test]
+    fn may_match_integer_statistics() {
+        let statistics = TypeStatistics::Integer {
+            min: 10,
+            max: 20,
+            sum: Some(30),
+        };
+        let may_match = |operator, value| {
+            condition("number", operator, value).may_match(2, false, Some(&statistics))
+        };
+
+        assert!(may_match(Operator::Equal, json!(15)));
+        assert!(!may_match(Operator::Equal, json!(21)));
+        assert!(may_match(Operator::NotEqual, json!(10)));
+        assert!(!may_match(Operator::LessThan, json!(10)));
+        assert!(may_match(Operator::LessThanOrEqual, json!(10)));
+        assert!(!may_match(Operator::GreaterThan, json!(20.5)));
+        assert!(may_match(Operator::GreaterThanOrEqual, json!(20)));
+        assert!(!may_match(Operator::Equal, Value::Null));
+        assert!(may_match(Operator::Equal, json!("not a number")));
+    }
+    #[test]
+    fn may_match_date_and_string_statistics() {
+        let statistics = TypeStatistics::Date { min: 0, max: 366 };
+        let condition_date = condition("date", Operator::GreaterThan, json!("1971-01-01"));
+        assert!(condition_date.may_match(1, false, Some(&statistics)));
+        let condition_date = condition("date", Operator::GreaterThan, json!("1971-01-02"));
+        assert!(!condition_date.may_match(1, false, Some(&statistics)));
+
+        let statistics = TypeStatistics::String {
+            min: "a".to_string(),
+            max: "c".to_string(),
+            sum: 2,
+        };
+        let condition_string = condition("string", Operator::Equal, json!("b"));
+        assert!(condition_string.may_match(1, true, Some(&statistics)));
+        assert!(!condition_string.may_match(0, true, Some(&statistics)));
+        assert!(condition_string.may_match(1, false, None));
+    }
+}
@@ -106,12 +106,10 @@
 //!
 
 use crate::document::Document;
+use crate::helper::arrow::{record_batches, schema};
 use crate::helper::string::DisplayOnlyForDebugging;
 use crate::DataResult;
 use crate::{DataSet, Metadata};
-use arrow_integration_test::schema_from_json;
-use arrow_json::reader::infer_json_schema_from_iterator;
-use arrow_json::ReaderBuilder;
 use bytes::Bytes;
 use json_value_search::Search;
 use parquet::arrow::ArrowWriter;
@@ -335,37 +333,22 @@ impl Document for Parquet {
             return Ok(vec![]);
         };
 
-        let schema = match &self.schema {
-            Some(schema) => schema_from_json(schema),
-            None => infer_json_schema_from_iterator(std::iter::once(Ok(first.to_value()))),
-        }
-        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
-
-        let mut decoder = ReaderBuilder::new(Arc::new(schema.clone()))
-            .build_decoder()
-            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
-
-        for chunk in dataset.chunks(self.batch_size) {
-            let values: Vec<Value> = chunk.iter().map(|d| d.to_value()).collect();
-
-            decoder
-                .serialize(&values)
-                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
-        }
+        let schema = schema(self.schema.as_ref(), &first.to_value())?;
+        let values: Vec<Value> = dataset.iter().map(|data| data.to_value()).collect();
+        let batches = record_batches(&schema, &values, self.batch_size)?;
 
-        let Some(batch) = decoder
-            .flush()
-            .map_err(|e| io::Error::new(io::ErrorKind::Interrupted, e))?
-        else {
+        if batches.is_empty() {
             return Ok(vec![]);
-        };
+        }
 
         let properties = build_writer_properties(self.batch_size, self.options.as_ref())?;
 
         let mut buffer = Vec::new();
         {
             let mut writer = ArrowWriter::try_new(&mut buffer, Arc::new(schema), Some(properties))?;
-            writer.write(&batch)?;
+            for batch in batches {
+                writer.write(&batch)?;
+            }
             writer.close()?;
         }
 
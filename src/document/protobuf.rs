@@ -0,0 +1,425 @@
+//! Read and write data in **Protocol Buffers** format.
+//!
+//! The message type is found in a `.proto` file compiled on the fly, or in a compiled `FileDescriptorSet`
+//! generated with `protoc --include_imports --descriptor_set_out=...`. The well known types like `google.protobuf.Timestamp` are included.
+//!
+//! The messages are converted into records with the [canonical JSON mapping](https://protobuf.dev/programming-guides/json/):
+//! the fields are in lowerCamelCase, the 64 bits integers are strings, the enums are names and the bytes are base64 strings.
+//! The fields of the records unknown by the message type are ignored.
+//!
+//! By default, the document contains one message. With `is_length_delimited`, the document contains a stream of messages,
+//! each one prefixed by its length in varint, like the `writeDelimitedTo` and `parseDelimitedFrom` methods of the protobuf libraries.
+//!
+//! ### Configuration
+//!
+//! | key                  | alias      | Description                                                          | Default Value | Possible Values                         |
+//! | -------------------- | ---------- | -------------------------------------------------------------------- | ------------- | --------------------------------------- |
+//! | type                 | -          | Required in order to use this document.                              | `protobuf`    | `protobuf` / `proto`                    |
+//! | metadata             | meta       | Metadata describe the resource.                                      | `null`        | [`crate::Metadata`]                     |
+//! | descriptor_path      | proto_path | Path of the `.proto` file or of the compiled `FileDescriptorSet`.   | `null`        | String                                  |
+//! | includes             | -          | Directories where to find the imports of the `.proto` file.          | `[]`          | List of paths                           |
+//! | message              | -          | Full name of the message type.                                       | `null`        | String, `package.Message`               |
+//! | is_length_delimited  | delimited  | The document contains a stream of length delimited messages.         | `false`       | `true` / `false`                        |
+//! | skip_default_fields  | -          | Skip the fields with the default value in the records.               | `true`        | `true` / `false`                        |
+//! | use_proto_field_name | -          | Use the field names of the `.proto` file instead of lowerCamelCase.  | `false`       | `true` / `false`                        |
+//!
+//! If `includes` is empty, the imports are resolved from the directory of the `.proto` file.
+//!
+//! ### Examples
+//!
+//! ```json
+//! [
+//!     {
+//!         "type": "read",
+//!         "connector":{
+//!             "type": "local",
+//!             "path": "./data/multi_lines.json"
+//!         }
+//!     },
+//!     {
+//!         "type": "write",
+//!         "document": {
+//!             "type": "protobuf",
+//!             "descriptor_path": "./data/multi_lines.proto",
+//!             "message": "chewdata.Line",
+//!             "is_length_delimited": true
+//!         },
+//!         "connector":{
+//!             "type": "local",
+//!             "path": "./data/out/multi_lines.binpb"
+//!         }
+//!     }
+//! ]
+//! ```
+use crate::document::Document;
+use crate::helper::string::DisplayOnlyForDebugging;
+use crate::DataResult;
+use crate::{DataSet, Metadata};
+use dashmap::DashMap;
+use prost::Message;
+use prost_reflect::{
+    DescriptorPool, DeserializeOptions, DynamicMessage, MessageDescriptor, SerializeOptions,
+};
+use protox::Compiler;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::io;
+use std::path::Path;
+use std::sync::OnceLock;
+
+const DEFAULT_SUBTYPE: &str = "x-protobuf";
+
+static DESCRIPTOR_POOLS: OnceLock<DashMap<String, DescriptorPool>> = OnceLock::new();
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Protobuf {
+    #[serde(rename = "metadata")]
+    #[serde(alias = "meta")]
+    pub metadata: Metadata,
+    #[serde(alias = "proto_path")]
+    pub descriptor_path: String,
+    pub includes: Vec<String>,
+    pub message: String,
+    #[serde(alias = "delimited")]
+    pub is_length_delimited: bool,
+    pub skip_default_fields: bool,
+    pub use_proto_field_name: bool,
+}
+
+impl Default for Protobuf {
+    fn default() -> Self {
+        let metadata = Metadata {
+            mime_type: Some(mime::APPLICATION.to_string()),
+            mime_subtype: Some(DEFAULT_SUBTYPE.to_string()),
+            ..Default::default()
+        };
+        Protobuf {
+            metadata,
+            descriptor_path: String::default(),
+            includes: Vec::default(),
+            message: String::default(),
+            is_length_delimited: false,
+            skip_default_fields: true,
+            use_proto_field_name: false,
+        }
+    }
+}
+
+impl Protobuf {
+    /// Load the descriptors of the `.proto` file or of the `FileDescriptorSet`.
+    fn descriptor_pool(&self) -> io::Result<DescriptorPool> {
+        let key = format!("{}:{}", self.descriptor_path, self.includes.join(","));
+        let pools = DESCRIPTOR_POOLS.get_or_init(DashMap::default);
+        if let Some(pool) = pools.get(&key) {
+            return Ok(pool.clone());
+        }
+
+        let path = Path::new(&self.descriptor_path);
+        let pool = match path.extension().and_then(|extension| extension.to_str()) {
+            Some("proto") => {
+                let includes = match self.includes.is_empty() {
+                    true => vec![path
+                        .parent()
+                        .map(|parent| parent.to_string_lossy().to_string())
+                        .unwrap_or_default()],
+                    false => self.includes.clone(),
+                };
+                let mut compiler = Compiler::new(includes)
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+                compiler
+                    .include_imports(true)
+                    .open_file(path)
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+                compiler.descriptor_pool()
+            }
+            _ => DescriptorPool::decode(std::fs::read(path)?.as_slice())
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?,
+        };
+
+        pools.insert(key, pool.clone());
+
+        Ok(pool)
+    }
+    /// Get the descriptor of the message type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::document::protobuf::Protobuf;
+    ///
+    /// let document = Protobuf {
+    ///     descriptor_path: "./data/multi_lines.proto".to_string(),
+    ///     message: "chewdata.Line".to_string(),
+    ///     ..Default::default()
+    /// };
+    /// let descriptor = document.message_descriptor().unwrap();
+    /// assert_eq!("chewdata.Line", descriptor.full_name());
+    /// ```
+    pub fn message_descriptor(&self) -> io::Result<MessageDescriptor> {
+        if self.descriptor_path.is_empty() || self.message.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "The protobuf document requires the 'descriptor_path' and the 'message' fields",
+            ));
+        }
+
+        self.descriptor_pool()?
+            .get_message_by_name(&self.message)
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!(
+                        "The message '{}' is not found in '{}'",
+                        self.message, self.descriptor_path
+                    ),
+                )
+            })
+    }
+    fn to_value(&self, message: &DynamicMessage) -> io::Result<Value> {
+        let options = SerializeOptions::new()
+            .skip_default_fields(self.skip_default_fields)
+            .use_proto_field_name(self.use_proto_field_name);
+
+        message
+            .serialize_with_options(serde_json::value::Serializer, &options)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+    fn to_message(
+        &self,
+        descriptor: &MessageDescriptor,
+        value: Value,
+    ) -> io::Result<DynamicMessage> {
+        let options = DeserializeOptions::new().deny_unknown_fields(false);
+
+        DynamicMessage::deserialize_with_options(descriptor.clone(), value, &options)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))
+    }
+}
+
+impl Document for Protobuf {
+    /// See [`Document::set_metadata`] for more details.
+    fn set_metadata(&mut self, metadata: Metadata) {
+        self.metadata = metadata.clone();
+    }
+    /// See [`Document::metadata`] for more details.
+    fn metadata(&self) -> Metadata {
+        Protobuf::default().metadata.merge(&self.metadata)
+    }
+    /// See [`Document::can_append`] for more details.
+    fn can_append(&self) -> bool {
+        self.is_length_delimited
+    }
+    /// See [`Document::read`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::document::protobuf::Protobuf;
+    /// use chewdata::document::Document;
+    /// use serde_json::json;
+    ///
+    /// let document = Protobuf {
+    ///     descriptor_path: "./data/multi_lines.proto".to_string(),
+    ///     message: "chewdata.Line".to_string(),
+    ///     ..Default::default()
+    /// };
+    /// // number: 10, string: "value"
+    /// let buffer = vec![0x08, 0x0a, 0x1a, 0x05, b'v', b'a', b'l', b'u', b'e'];
+    ///
+    /// let mut dataset = document.read(&buffer).unwrap().into_iter();
+    /// assert_eq!(json!({"number": 10, "string": "value"}), dataset.next().unwrap().to_value());
+    /// ```
+    #[instrument(skip(buffer), name = "protobuf::read")]
+    fn read(&self, buffer: &[u8]) -> io::Result<DataSet> {
+        let descriptor = self.message_descriptor()?;
+        let mut dataset = Vec::default();
+
+        if !self.is_length_delimited {
+            let message = DynamicMessage::decode(descriptor, buffer)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            let record = self.to_value(&message)?;
+            trace!(
+                record = record.display_only_for_debugging(),
+                "Record deserialized"
+            );
+            dataset.push(DataResult::Ok(record));
+
+            return Ok(dataset);
+        }
+
+        let mut buffer = buffer;
+        while !buffer.is_empty() {
+            let mut message = DynamicMessage::new(descriptor.clone());
+            match message
+                .merge_length_delimited(&mut buffer)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+                .and_then(|_| self.to_value(&message))
+            {
+                Ok(record) => {
+                    trace!(
+                        record = record.display_only_for_debugging(),
+                        "Record deserialized"
+                    );
+                    dataset.push(DataResult::Ok(record));
+                }
+                Err(e) => {
+                    warn!(
+                        error = format!("{:?}", e).as_str(),
+                        "Can't deserialize the record"
+                    );
+                    dataset.push(DataResult::Err((Value::Null, e)));
+                    break;
+                }
+            }
+        }
+
+        Ok(dataset)
+    }
+    /// See [`Document::write`] for more details.
+    #[instrument(skip(dataset), name = "protobuf::write")]
+    fn write(&self, dataset: &DataSet) -> io::Result<Vec<u8>> {
+        let descriptor = self.message_descriptor()?;
+
+        if !self.is_length_delimited && 1 < dataset.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Only one message can be written without 'is_length_delimited'",
+            ));
+        }
+
+        let mut buffer = Vec::default();
+        for data in dataset {
+            let record = data.to_value();
+            let message = self.to_message(&descriptor, record.clone())?;
+
+            match self.is_length_delimited {
+                true => message.encode_length_delimited(&mut buffer),
+                false => message.encode(&mut buffer),
+            }
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+            trace!(
+                record = record.display_only_for_debugging(),
+                "Record serialized"
+            );
+        }
+
+        Ok(buffer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn document() -> Protobuf {
+        Protobuf {
+            descriptor_path: "./data/multi_lines.proto".to_string(),
+            message: "chewdata.Line".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn write_and_read_with_canonical_json() {
+        let document = document();
+        let record = json!({
+            "number": 10,
+            "specialChar": "é",
+            "tags": ["a", "b"],
+            "status": "STATUS_ACTIVE",
+            "updatedAt": "2020-12-31T10:00:00Z"
+        });
+        let dataset = vec![DataResult::Ok(record.clone())];
+
+        let buffer = document.write(&dataset).unwrap();
+        let dataset = document.read(&buffer).unwrap();
+        assert_eq!(record, dataset[0].to_value());
+    }
+    #[test]
+    fn write_with_unknown_and_proto_fields() {
+        let document = Protobuf {
+            use_proto_field_name: true,
+            skip_default_fields: false,
+            ..document()
+        };
+        let dataset = vec![DataResult::Ok(
+            json!({"number": 10, "special_char": "é", "unknown": true}),
+        )];
+
+        let buffer = document.write(&dataset).unwrap();
+        let record = document.read(&buffer).unwrap()[0].to_value();
+        assert_eq!(json!("é"), record["special_char"]);
+        assert_eq!(json!(0), record["group"]);
+        assert_eq!(json!("STATUS_UNSPECIFIED"), record["status"]);
+        assert_eq!(None, record.get("unknown"));
+    }
+    #[test]
+    fn write_and_read_length_delimited() {
+        let document = Protobuf {
+            is_length_delimited: true,
+            ..document()
+        };
+        let dataset: DataSet = (1..4)
+            .map(|number| DataResult::Ok(json!({ "number": number })))
+            .collect();
+
+        let mut buffer = document.write(&dataset).unwrap();
+        assert_eq!(vec![0x02, 0x08, 0x01], buffer[..3].to_vec());
+        let values: Vec<Value> = document
+            .read(&buffer)
+            .unwrap()
+            .iter()
+            .map(DataResult::to_value)
+            .collect();
+        let expected: Vec<Value> = dataset.iter().map(DataResult::to_value).collect();
+        assert_eq!(expected, values);
+
+        buffer.push(0x05);
+        let dataset = document.read(&buffer).unwrap();
+        assert_eq!(4, dataset.len());
+        assert!(matches!(dataset[3], DataResult::Err(_)));
+    }
+    #[test]
+    fn write_many_messages_without_delimiter() {
+        let dataset = vec![
+            DataResult::Ok(json!({"number": 1})),
+            DataResult::Ok(json!({"number": 2})),
+        ];
+        let error = document().write(&dataset).unwrap_err();
+        assert_eq!(io::ErrorKind::InvalidInput, error.kind());
+    }
+    #[test]
+    fn read_with_file_descriptor_set() {
+        let path = std::env::temp_dir().join("chewdata_multi_lines.binpb");
+        let mut compiler = Compiler::new(["./data"]).unwrap();
+        compiler
+            .include_imports(true)
+            .open_file("multi_lines.proto")
+            .unwrap();
+        std::fs::write(&path, compiler.encode_file_descriptor_set()).unwrap();
+
+        let document = Protobuf {
+            descriptor_path: path.to_string_lossy().to_string(),
+            ..document()
+        };
+        let buffer = document
+            .write(&vec![DataResult::Ok(json!({"string": "value"}))])
+            .unwrap();
+        assert_eq!(
+            json!({"string": "value"}),
+            document.read(&buffer).unwrap()[0].to_value()
+        );
+    }
+    #[test]
+    fn read_with_unknown_message() {
+        let document = Protobuf {
+            message: "chewdata.Unknown".to_string(),
+            ..document()
+        };
+        let error = document.read(&[]).unwrap_err();
+        assert_eq!(io::ErrorKind::NotFound, error.kind());
+    }
+}
@@ -0,0 +1,622 @@
+//! Read and write data in **Excel** spreadsheet format.
+//!
+//! The reader supports `xlsx`, `xlsm`, `xlsb`, `xls` and `ods` workbooks. It reads one sheet, chosen by its name or its index,
+//! and an optional cell `range`. The first row of the range contains the headers. Without headers, the fields are named with the column letters.
+//! The cells keep their type: numbers, booleans, strings and dates. The dates are formatted in ISO 8601, `YYYY-MM-DD` for the dates
+//! without time. The formulas give their cached value. The empty rows are skipped.
+//!
+//! The writer creates an `xlsx` workbook. The records are written in the `sheet`, or in one sheet per value of `sheet_field`.
+//! The headers are the fields of the records in the order of their first appearance and are styled with `header_style`.
+//! The strings in ISO 8601 are written as dates and the objects and arrays as json strings.
+//!
+//! ### Configuration
+//!
+//! | key          | alias   | Description                                                            | Default Value | Possible Values                                         |
+//! | ------------ | ------- | ---------------------------------------------------------------------- | ------------- | ------------------------------------------------------- |
+//! | type         | -       | Required in order to use this document.                                | `xlsx`        | `xlsx` / `xls` / `ods` / `excel`                        |
+//! | metadata     | meta    | Metadata describe the resource.                                        | `null`        | [`crate::Metadata`]                                     |
+//! | sheet        | -       | Name or index of the sheet to read or name of the sheet to write.      | `0`           | String / Unsigned integer                               |
+//! | range        | -       | Cells to read. The end of the range is optional.                       | `null`        | `B2:D10` / `B2`                                         |
+//! | has_headers  | headers | The first row of the range contains the headers.                       | `true`        | `true` / `false`                                        |
+//! | sheet_field  | -       | Field of the records with the name of the sheet where to write them.   | `null`        | String                                                  |
+//! | header_style | -       | Style of the headers written.                                          | `bold`        | [`crate::document::xlsx::HeaderStyle`]                  |
+//!
+//! ### Header style
+//!
+//! | key              | alias | Description                             | Default Value | Possible Values  |
+//! | ---------------- | ----- | --------------------------------------- | ------------- | ---------------- |
+//! | bold             | -     | Write the headers in bold.              | `true`        | `true` / `false` |
+//! | font_color       | -     | Color of the headers.                   | `null`        | `#RRGGBB`        |
+//! | background_color | -     | Background color of the headers.        | `null`        | `#RRGGBB`        |
+//! | freeze           | -     | Freeze the header row when scrolling.   | `true`        | `true` / `false` |
+//! | filter           | -     | Add an autofilter on the header row.    | `false`       | `true` / `false` |
+//!
+//! ### Examples
+//!
+//! ```json
+//! [
+//!     {
+//!         "type": "read",
+//!         "document": {
+//!             "type": "xlsx",
+//!             "sheet": "Orders",
+//!             "range": "A3:F200"
+//!         },
+//!         "connector":{
+//!             "type": "local",
+//!             "path": "./data/orders.xlsx"
+//!         }
+//!     },
+//!     {
+//!         "type": "write",
+//!         "document": {
+//!             "type": "xlsx",
+//!             "sheet_field": "country",
+//!             "header_style": {
+//!                 "background_color": "#D9D9D9",
+//!                 "filter": true
+//!             }
+//!         },
+//!         "connector":{
+//!             "type": "local",
+//!             "path": "./data/out/orders_by_country.xlsx"
+//!         }
+//!     }
+//! ]
+//! ```
+use crate::document::Document;
+use crate::helper::string::DisplayOnlyForDebugging;
+use crate::DataResult;
+use crate::{DataSet, Metadata};
+use calamine::{open_workbook_auto_from_rs, Data, DataType, Range, Reader};
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use rust_xlsxwriter::{ExcelDateTime, Format, FormatBorder, Workbook, Worksheet, XlsxError};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Number, Value};
+use std::io::{self, Cursor};
+
+const DEFAULT_SUBTYPE: &str = "vnd.openxmlformats-officedocument.spreadsheetml.sheet";
+const DEFAULT_SHEET_NAME: &str = "Sheet1";
+const DATE_FORMAT: &str = "yyyy-mm-dd";
+const DATETIME_FORMAT: &str = "yyyy-mm-dd hh:mm:ss";
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum Sheet {
+    Index(usize),
+    Name(String),
+}
+
+impl Default for Sheet {
+    fn default() -> Self {
+        Sheet::Index(0)
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct HeaderStyle {
+    pub bold: bool,
+    pub font_color: Option<String>,
+    pub background_color: Option<String>,
+    pub freeze: bool,
+    pub filter: bool,
+}
+
+impl Default for HeaderStyle {
+    fn default() -> Self {
+        HeaderStyle {
+            bold: true,
+            font_color: None,
+            background_color: None,
+            freeze: true,
+            filter: false,
+        }
+    }
+}
+
+impl HeaderStyle {
+    fn format(&self) -> Format {
+        let mut format = Format::new().set_border_bottom(FormatBorder::Thin);
+        if self.bold {
+            format = format.set_bold();
+        }
+        if let Some(color) = &self.font_color {
+            format = format.set_font_color(color.as_str());
+        }
+        if let Some(color) = &self.background_color {
+            format = format.set_background_color(color.as_str());
+        }
+
+        format
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Xlsx {
+    #[serde(rename = "metadata")]
+    #[serde(alias = "meta")]
+    pub metadata: Metadata,
+    pub sheet: Sheet,
+    pub range: Option<String>,
+    #[serde(alias = "headers")]
+    pub has_headers: bool,
+    pub sheet_field: Option<String>,
+    pub header_style: HeaderStyle,
+}
+
+impl Default for Xlsx {
+    fn default() -> Self {
+        let metadata = Metadata {
+            mime_type: Some(mime::APPLICATION.to_string()),
+            mime_subtype: Some(DEFAULT_SUBTYPE.to_string()),
+            ..Default::default()
+        };
+        Xlsx {
+            metadata,
+            sheet: Sheet::default(),
+            range: None,
+            has_headers: true,
+            sheet_field: None,
+            header_style: HeaderStyle::default(),
+        }
+    }
+}
+
+/// Get the name of a column from its index.
+///
+/// # Examples
+///
+/// ```
+/// use chewdata::document::xlsx::column_name;
+///
+/// assert_eq!("A", column_name(0));
+/// assert_eq!("AB", column_name(27));
+/// ```
+pub fn column_name(index: u32) -> String {
+    let mut name = String::default();
+    let mut index = index + 1;
+    while 0 < index {
+        let rest = (index - 1) % 26;
+        name.insert(0, (b'A' + rest as u8) as char);
+        index = (index - rest - 1) / 26;
+    }
+
+    name
+}
+
+/// Get the position (row, column) of a cell from its reference. The position starts at 0.
+///
+/// # Examples
+///
+/// ```
+/// use chewdata::document::xlsx::cell_position;
+///
+/// assert_eq!(Some((0, 0)), cell_position("A1"));
+/// assert_eq!(Some((9, 27)), cell_position("$AB$10"));
+/// assert_eq!(None, cell_position("10"));
+/// ```
+pub fn cell_position(reference: &str) -> Option<(u32, u32)> {
+    let reference = reference.trim().replace('$', "").to_uppercase();
+    let split = reference.find(|c: char| c.is_ascii_digit())?;
+    let (letters, digits) = reference.split_at(split);
+    if letters.is_empty() || !letters.chars().all(|c| c.is_ascii_uppercase()) {
+        return None;
+    }
+
+    let column = letters
+        .bytes()
+        .try_fold(0u32, |column, letter| {
+            column
+                .checked_mul(26)?
+                .checked_add((letter - b'A' + 1) as u32)
+        })?
+        .checked_sub(1)?;
+    let row = digits.parse::<u32>().ok()?.checked_sub(1)?;
+
+    Some((row, column))
+}
+
+/// Transform a cell into a json value.
+fn cell_value(cell: &Data) -> Value {
+    match cell {
+        Data::Int(number) => Value::from(*number),
+        Data::Float(number) if 0.0 == number.fract() && number.abs() < 9_007_199_254_740_992.0 => {
+            Value::from(*number as i64)
+        }
+        Data::Float(number) => Number::from_f64(*number).map_or(Value::Null, Value::Number),
+        Data::String(string) => Value::String(string.clone()),
+        Data::Bool(boolean) => Value::Bool(*boolean),
+        Data::DateTime(datetime) if datetime.is_duration() => datetime
+            .as_duration()
+            .map_or(Value::Null, |duration| Value::String(duration.to_string())),
+        Data::DateTime(_) => match cell.as_datetime() {
+            Some(datetime) if NaiveTime::MIN == datetime.time() => {
+                Value::String(datetime.format("%Y-%m-%d").to_string())
+            }
+            Some(datetime) => Value::String(datetime.format("%Y-%m-%dT%H:%M:%S").to_string()),
+            None => Value::Null,
+        },
+        Data::DateTimeIso(string) | Data::DurationIso(string) => Value::String(string.clone()),
+        Data::Error(_) | Data::Empty => Value::Null,
+    }
+}
+
+fn write_error(error: XlsxError) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, error)
+}
+
+/// Write a json value into a cell with the type of the value.
+fn write_cell(worksheet: &mut Worksheet, row: u32, column: u16, value: &Value) -> io::Result<()> {
+    match value {
+        Value::Null => Ok(()),
+        Value::Bool(boolean) => worksheet.write_boolean(row, column, *boolean).map(|_| ()),
+        Value::Number(number) => worksheet
+            .write_number(row, column, number.as_f64().unwrap_or_default())
+            .map(|_| ()),
+        Value::String(string) => {
+            let date = NaiveDate::parse_from_str(string, "%Y-%m-%d").is_ok();
+            let datetime =
+                NaiveDateTime::parse_from_str(string.trim_end_matches('Z'), "%Y-%m-%dT%H:%M:%S")
+                    .is_ok();
+
+            match (date, datetime, ExcelDateTime::parse_from_str(string)) {
+                (true, _, Ok(excel_datetime)) => worksheet
+                    .write_datetime_with_format(
+                        row,
+                        column,
+                        &excel_datetime,
+                        &Format::new().set_num_format(DATE_FORMAT),
+                    )
+                    .map(|_| ()),
+                (_, true, Ok(excel_datetime)) => worksheet
+                    .write_datetime_with_format(
+                        row,
+                        column,
+                        &excel_datetime,
+                        &Format::new().set_num_format(DATETIME_FORMAT),
+                    )
+                    .map(|_| ()),
+                _ => worksheet.write_string(row, column, string).map(|_| ()),
+            }
+        }
+        Value::Array(_) | Value::Object(_) => worksheet
+            .write_string(row, column, value.to_string())
+            .map(|_| ()),
+    }
+    .map_err(write_error)
+}
+
+impl Xlsx {
+    fn range(&self, range: Range<Data>) -> io::Result<Range<Data>> {
+        let Some(reference) = &self.range else {
+            return Ok(range);
+        };
+
+        let invalid_range = || {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("The range '{}' is not valid", reference),
+            )
+        };
+        let mut references = reference.split(':');
+        let start = references
+            .next()
+            .and_then(cell_position)
+            .ok_or_else(invalid_range)?;
+        let end = match references.next() {
+            Some(end) => cell_position(end).ok_or_else(invalid_range)?,
+            None => range.end().unwrap_or(start),
+        };
+
+        Ok(range.range(start, end))
+    }
+    fn sheet_name(&self) -> String {
+        match &self.sheet {
+            Sheet::Name(name) => name.clone(),
+            Sheet::Index(_) => DEFAULT_SHEET_NAME.to_string(),
+        }
+    }
+    /// Group the records by sheet, in the order of their first appearance.
+    fn sheets(&self, dataset: &DataSet) -> Vec<(String, Vec<Map<String, Value>>)> {
+        let mut sheets: Vec<(String, Vec<Map<String, Value>>)> = Vec::default();
+
+        for data in dataset {
+            let mut record = match data.to_value() {
+                Value::Object(record) => record,
+                value => Map::from_iter([("value".to_string(), value)]),
+            };
+
+            let sheet_name = self
+                .sheet_field
+                .as_ref()
+                .and_then(|field| record.remove(field))
+                .map(|value| match value {
+                    Value::String(name) => name,
+                    value => value.to_string(),
+                })
+                .unwrap_or_else(|| self.sheet_name());
+
+            match sheets.iter_mut().find(|(name, _)| *name == sheet_name) {
+                Some((_, records)) => records.push(record),
+                None => sheets.push((sheet_name, vec![record])),
+            }
+        }
+
+        sheets
+    }
+}
+
+impl Document for Xlsx {
+    /// See [`Document::set_metadata`] for more details.
+    fn set_metadata(&mut self, metadata: Metadata) {
+        self.metadata = metadata.clone();
+    }
+    /// See [`Document::metadata`] for more details.
+    fn metadata(&self) -> Metadata {
+        Xlsx::default().metadata.merge(&self.metadata)
+    }
+    /// See [`Document::can_append`] for more details.
+    fn can_append(&self) -> bool {
+        false
+    }
+    /// See [`Document::read`] for more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::document::xlsx::Xlsx;
+    /// use chewdata::document::Document;
+    /// use chewdata::DataResult;
+    /// use serde_json::json;
+    ///
+    /// let document = Xlsx::default();
+    /// let dataset = vec![DataResult::Ok(json!({"number": 10, "date": "2020-12-31", "boolean": true}))];
+    /// let buffer = document.write(&dataset).unwrap();
+    ///
+    /// let mut dataset = document.read(&buffer).unwrap().into_iter();
+    /// assert_eq!(json!({"number": 10, "date": "2020-12-31", "boolean": true}), dataset.next().unwrap().to_value());
+    /// ```
+    #[instrument(skip(buffer), name = "xlsx::read")]
+    fn read(&self, buffer: &[u8]) -> io::Result<DataSet> {
+        let mut workbook = open_workbook_auto_from_rs(Cursor::new(buffer))
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        let range = match &self.sheet {
+            Sheet::Name(name) => Some(workbook.worksheet_range(name)),
+            Sheet::Index(index) => workbook.worksheet_range_at(*index),
+        }
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("The sheet '{:?}' is not found", self.sheet),
+            )
+        })?
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let range = self.range(range)?;
+
+        let first_column = range.start().map(|(_, column)| column).unwrap_or_default();
+        let mut rows = range.rows();
+        let headers: Vec<String> = match self.has_headers {
+            true => rows
+                .next()
+                .unwrap_or_default()
+                .iter()
+                .enumerate()
+                .map(|(position, cell)| match cell_value(cell) {
+                    Value::Null => column_name(first_column + position as u32),
+                    Value::String(header) => header,
+                    header => header.to_string(),
+                })
+                .collect(),
+            false => (0..range.width() as u32)
+                .map(|position| column_name(first_column + position))
+                .collect(),
+        };
+
+        let mut dataset = Vec::default();
+        for row in rows {
+            if row.iter().all(|cell| cell.is_empty()) {
+                continue;
+            }
+
+            let record = Value::Object(
+                headers
+                    .iter()
+                    .cloned()
+                    .zip(row.iter().map(cell_value))
+                    .collect(),
+            );
+            trace!(
+                record = record.display_only_for_debugging(),
+                "Record deserialized"
+            );
+            dataset.push(DataResult::Ok(record));
+        }
+
+        Ok(dataset)
+    }
+    /// See [`Document::write`] for more details.
+    #[instrument(skip(dataset), name = "xlsx::write")]
+    fn write(&self, dataset: &DataSet) -> io::Result<Vec<u8>> {
+        let mut workbook = Workbook::new();
+        let header_format = self.header_style.format();
+
+        for (sheet_name, records) in self.sheets(dataset) {
+            let worksheet = workbook.add_worksheet();
+            worksheet.set_name(&sheet_name).map_err(write_error)?;
+
+            let mut headers: Vec<&String> = Vec::default();
+            for key in records.iter().flat_map(|record| record.keys()) {
+                if !headers.contains(&key) {
+                    headers.push(key);
+                }
+            }
+
+            for (column, header) in headers.iter().enumerate() {
+                worksheet
+                    .write_string_with_format(0, column as u16, header.as_str(), &header_format)
+                    .map_err(write_error)?;
+            }
+            if self.header_style.freeze {
+                worksheet.set_freeze_panes(1, 0).map_err(write_error)?;
+            }
+            if self.header_style.filter && !headers.is_empty() {
+                worksheet
+                    .autofilter(0, 0, records.len() as u32, headers.len() as u16 - 1)
+                    .map_err(write_error)?;
+            }
+
+            for (position, record) in records.iter().enumerate() {
+                for (column, header) in headers.iter().enumerate() {
+                    if let Some(value) = record.get(header.as_str()) {
+                        write_cell(worksheet, position as u32 + 1, column as u16, value)?;
+                    }
+                }
+                let record = Value::Object(record.clone());
+                trace!(
+                    record = record.display_only_for_debugging(),
+                    "Record serialized"
+                );
+            }
+        }
+
+        workbook.save_to_buffer().map_err(write_error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn dataset() -> DataSet {
+        vec![
+            DataResult::Ok(json!({
+                "number": 10,
+                "round": 10.156,
+                "string": "value to test",
+                "boolean": true,
+                "date": "2019-12-31",
+                "datetime": "2019-12-31T10:20:30",
+                "list": [1, 2],
+                "country": "FR"
+            })),
+            DataResult::Ok(json!({
+                "number": 20,
+                "string": "value to test 2",
+                "boolean": false,
+                "country": "DE"
+            })),
+            DataResult::Ok(json!({
+                "number": 30,
+                "comment": "new column",
+                "country": "FR"
+            })),
+        ]
+    }
+
+    #[test]
+    fn write_and_read_typed_cells() {
+        let document = Xlsx::default();
+        let buffer = document.write(&dataset()).unwrap();
+
+        let dataset = document.read(&buffer).unwrap();
+        assert_eq!(3, dataset.len());
+        assert_eq!(
+            json!({
+                "number": 10,
+                "round": 10.156,
+                "string": "value to test",
+                "boolean": true,
+                "date": "2019-12-31",
+                "datetime": "2019-12-31T10:20:30",
+                "list": "[1,2]",
+                "country": "FR",
+                "comment": null
+            }),
+            dataset[0].to_value()
+        );
+        assert_eq!(json!("new column"), dataset[2].to_value()["comment"]);
+    }
+    #[test]
+    fn write_and_read_several_sheets() {
+        let document = Xlsx {
+            sheet_field: Some("country".to_string()),
+            header_style: HeaderStyle {
+                background_color: Some("#D9D9D9".to_string()),
+                filter: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let buffer = document.write(&dataset()).unwrap();
+
+        let document = Xlsx {
+            sheet: Sheet::Name("FR".to_string()),
+            ..Default::default()
+        };
+        let dataset = document.read(&buffer).unwrap();
+        assert_eq!(2, dataset.len());
+        assert_eq!(None, dataset[0].to_value().get("country"));
+
+        let document = Xlsx {
+            sheet: Sheet::Index(1),
+            ..Default::default()
+        };
+        let dataset = document.read(&buffer).unwrap();
+        assert_eq!(json!(20), dataset[0].to_value()["number"]);
+
+        let document = Xlsx {
+            sheet: Sheet::Name("US".to_string()),
+            ..Default::default()
+        };
+        assert!(document.read(&buffer).is_err());
+    }
+    #[test]
+    fn read_range_without_headers() {
+        let buffer = Xlsx::default().write(&dataset()).unwrap();
+        let document = Xlsx {
+            range: Some("B2:C3".to_string()),
+            has_headers: false,
+            ..Default::default()
+        };
+
+        let values: Vec<Value> = document
+            .read(&buffer)
+            .unwrap()
+            .iter()
+            .map(DataResult::to_value)
+            .collect();
+        assert_eq!(
+            vec![
+                json!({"B": 10.156, "C": "value to test"}),
+                json!({"B": null, "C": "value to test 2"})
+            ],
+            values
+        );
+    }
+    #[test]
+    fn read_range_with_open_end() {
+        let buffer = Xlsx::default().write(&dataset()).unwrap();
+        let document = Xlsx {
+            range: Some("A3".to_string()),
+            ..Default::default()
+        };
+
+        let dataset = document.read(&buffer).unwrap();
+        assert_eq!(1, dataset.len());
+        assert_eq!(json!(30), dataset[0].to_value()["20"]);
+    }
+    #[test]
+    fn read_with_invalid_range() {
+        let buffer = Xlsx::default().write(&dataset()).unwrap();
+        let document = Xlsx {
+            range: Some("1A:B2".to_string()),
+            ..Default::default()
+        };
+        let error = document.read(&buffer).unwrap_err();
+        assert_eq!(io::ErrorKind::InvalidInput, error.kind());
+    }
+}
@@ -0,0 +1,74 @@
+use arrow_array::RecordBatch;
+use arrow_integration_test::schema_from_json;
+use arrow_json::reader::infer_json_schema_from_iterator;
+use arrow_json::writer::{JsonArray, WriterBuilder};
+use arrow_json::ReaderBuilder;
+use arrow_schema::Schema;
+use serde_json::Value;
+use std::io;
+use std::sync::Arc;
+
+/// Get the Arrow schema from its json definition or infer it from a record.
+///
+/// # Examples
+///
+/// ```
+/// use chewdata::helper::arrow::schema;
+/// use serde_json::json;
+///
+/// let schema = schema(None, &json!({"number": 10})).unwrap();
+/// assert_eq!("number", schema.field(0).name());
+/// ```
+pub fn schema(definition: Option<&Value>, record: &Value) -> io::Result<Schema> {
+    match definition {
+        Some(definition) => schema_from_json(definition),
+        None => infer_json_schema_from_iterator(std::iter::once(Ok(record.clone()))),
+    }
+    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// Transform records into record batches of `batch_size` rows.
+pub fn record_batches(
+    schema: &Schema,
+    values: &[Value],
+    batch_size: usize,
+) -> io::Result<Vec<RecordBatch>> {
+    let mut decoder = ReaderBuilder::new(Arc::new(schema.clone()))
+        .build_decoder()
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    let mut batches = Vec::default();
+
+    for chunk in values.chunks(batch_size.max(1)) {
+        decoder
+            .serialize(chunk)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        if let Some(batch) = decoder
+            .flush()
+            .map_err(|e| io::Error::new(io::ErrorKind::Interrupted, e))?
+        {
+            batches.push(batch);
+        }
+    }
+
+    Ok(batches)
+}
+
+/// Transform a record batch into records. The null fields are kept.
+pub fn records(batch: &RecordBatch) -> io::Result<Vec<Value>> {
+    let mut writer = WriterBuilder::new()
+        .with_explicit_nulls(true)
+        .build::<_, JsonArray>(Vec::default());
+
+    writer
+        .write(batch)
+        .and_then(|_| writer.finish())
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+    let buffer = writer.into_inner();
+    if buffer.is_empty() {
+        return Ok(Vec::default());
+    }
+
+    serde_json::from_slice(&buffer).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
@@ -0,0 +1,86 @@
+use async_compat::Compat;
+use async_lock::OnceCell;
+use aws_config::meta::credentials::CredentialsProviderChain;
+use aws_config::Region;
+use aws_credential_types::provider::SharedCredentialsProvider;
+use aws_credential_types::Credentials;
+use dashmap::DashMap;
+use std::any::{type_name, Any};
+use std::env;
+use std::sync::{Arc, OnceLock};
+
+type SharedClients = DashMap<String, Arc<OnceCell<Box<dyn Any + Send + Sync>>>>;
+static CLIENTS: OnceLock<SharedClients> = OnceLock::new();
+
+/// Get the credentials provider used by the AWS clients.
+///
+/// If the variables `{prefix}_ACCESS_KEY_ID` and `{prefix}_SECRET_ACCESS_KEY` are set, they are the credentials of the client.
+/// Otherwise, the credentials are read from the environment, the profile or the role of the instance.
+pub async fn credentials_provider(prefix: &str) -> SharedCredentialsProvider {
+    match (
+        env::var(format!("{}_ACCESS_KEY_ID", prefix)),
+        env::var(format!("{}_SECRET_ACCESS_KEY", prefix)),
+    ) {
+        (Ok(key), Ok(secret)) => {
+            SharedCredentialsProvider::new(Credentials::new(key, secret, None, None, "chewdata"))
+        }
+        _ => SharedCredentialsProvider::new(CredentialsProviderChain::default_provider().await),
+    }
+}
+
+/// Get the client shared by the connectors with the same credentials `prefix`, endpoint and region.
+///
+/// The client is built once with the credentials of the `prefix`, see [`credentials_provider`].
+pub async fn shared_client<C, F>(
+    prefix: &str,
+    endpoint: Option<String>,
+    region: String,
+    build: F,
+) -> C
+where
+    C: Clone + Send + Sync + 'static,
+    F: FnOnce(SharedCredentialsProvider, Region, Option<String>) -> C,
+{
+    let clients = CLIENTS.get_or_init(DashMap::new);
+    let key = format!("{}:{}:{:?}:{}", type_name::<C>(), prefix, endpoint, region);
+
+    let cell = clients
+        .entry(key)
+        .or_insert_with(|| Arc::new(OnceCell::new()))
+        .clone();
+
+    let client = cell
+        .get_or_init(|| async {
+            trace!(prefix, "storing client in shared container");
+
+            let provider = Compat::new(credentials_provider(prefix)).await;
+            Box::new(build(provider, Region::new(region), endpoint)) as Box<dyn Any + Send + Sync>
+        })
+        .await;
+
+    // The type of the client is part of the key.
+    client.downcast_ref::<C>().unwrap().clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aws_credential_types::provider::ProvideCredentials;
+    use macro_rules_attribute::apply;
+    use smol_macros::test;
+
+    #[apply(test!)]
+    async fn credentials_provider_with_prefix() {
+        env::set_var("HELPER_AWS_ACCESS_KEY_ID", "my_key");
+        env::set_var("HELPER_AWS_SECRET_ACCESS_KEY", "my_secret");
+
+        let credentials = credentials_provider("HELPER_AWS")
+            .await
+            .provide_credentials()
+            .await
+            .unwrap();
+        assert_eq!("my_key", credentials.access_key_id());
+        assert_eq!("my_secret", credentials.secret_access_key());
+        assert_ne!(Ok("my_key".to_string()), env::var("AWS_ACCESS_KEY_ID"));
+    }
+}
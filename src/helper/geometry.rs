@@ -0,0 +1,822 @@
+//! Geometries in GeoJSON ([RFC 7946](https://datatracker.ietf.org/doc/html/rfc7946)) and WKT formats.
+//!
+//! The positions are `[longitude, latitude]` in degrees, with an optional altitude.
+//! The bounding boxes, the centroids and the point in polygon tests are computed in a plane,
+//! the distances on a sphere with the haversine formula.
+use serde_json::{Map, Number, Value};
+use std::io;
+
+/// Mean radius of the earth in meters.
+const EARTH_RADIUS: f64 = 6_371_008.8;
+
+pub type Position = Vec<f64>;
+/// The points, the lines and the polygons of a geometry.
+type Parts<'a> = (
+    Vec<&'a Position>,
+    Vec<&'a Vec<Position>>,
+    Vec<&'a Vec<Vec<Position>>>,
+);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Geometry {
+    Point(Position),
+    LineString(Vec<Position>),
+    Polygon(Vec<Vec<Position>>),
+    MultiPoint(Vec<Position>),
+    MultiLineString(Vec<Vec<Position>>),
+    MultiPolygon(Vec<Vec<Vec<Position>>>),
+    GeometryCollection(Vec<Geometry>),
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+fn position_from_json(value: &Value) -> io::Result<Position> {
+    let position = value
+        .as_array()
+        .ok_or_else(|| invalid_data(format!("The position '{}' is not an array", value)))?
+        .iter()
+        .map(|number| {
+            number
+                .as_f64()
+                .ok_or_else(|| invalid_data(format!("The position '{}' is not valid", value)))
+        })
+        .collect::<io::Result<Position>>()?;
+
+    match position.len() {
+        2.. => Ok(position),
+        _ => Err(invalid_data(format!(
+            "The position '{}' needs at least two numbers",
+            value
+        ))),
+    }
+}
+
+fn positions_from_json(value: &Value) -> io::Result<Vec<Position>> {
+    value
+        .as_array()
+        .ok_or_else(|| invalid_data(format!("The coordinates '{}' are not an array", value)))?
+        .iter()
+        .map(position_from_json)
+        .collect()
+}
+
+fn rings_from_json(value: &Value) -> io::Result<Vec<Vec<Position>>> {
+    value
+        .as_array()
+        .ok_or_else(|| invalid_data(format!("The coordinates '{}' are not an array", value)))?
+        .iter()
+        .map(positions_from_json)
+        .collect()
+}
+
+fn number_to_json(number: f64) -> Value {
+    match 0.0 == number.fract() && number.abs() < 9_007_199_254_740_992.0 {
+        true => Value::from(number as i64),
+        false => Number::from_f64(number).map_or(Value::Null, Value::Number),
+    }
+}
+
+fn position_to_json(position: &Position) -> Value {
+    Value::Array(position.iter().copied().map(number_to_json).collect())
+}
+
+fn positions_to_json(positions: &[Position]) -> Value {
+    Value::Array(positions.iter().map(position_to_json).collect())
+}
+
+fn rings_to_json(rings: &[Vec<Position>]) -> Value {
+    Value::Array(rings.iter().map(|ring| positions_to_json(ring)).collect())
+}
+
+fn number_to_wkt(number: f64) -> String {
+    match number_to_json(number) {
+        Value::Number(number) => number.to_string(),
+        _ => number.to_string(),
+    }
+}
+
+fn position_to_wkt(position: &Position) -> String {
+    position
+        .iter()
+        .copied()
+        .map(number_to_wkt)
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+fn positions_to_wkt(positions: &[Position]) -> String {
+    format!(
+        "({})",
+        positions
+            .iter()
+            .map(position_to_wkt)
+            .collect::<Vec<String>>()
+            .join(", ")
+    )
+}
+
+fn rings_to_wkt(rings: &[Vec<Position>]) -> String {
+    format!(
+        "({})",
+        rings
+            .iter()
+            .map(|ring| positions_to_wkt(ring))
+            .collect::<Vec<String>>()
+            .join(", ")
+    )
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Number(f64),
+    Open,
+    Close,
+    Comma,
+}
+
+struct WktParser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl WktParser {
+    fn new(wkt: &str) -> io::Result<Self> {
+        let mut tokens = Vec::default();
+        let mut chars = wkt.chars().peekable();
+
+        while let Some(char) = chars.next() {
+            match char {
+                '(' => tokens.push(Token::Open),
+                ')' => tokens.push(Token::Close),
+                ',' => tokens.push(Token::Comma),
+                char if char.is_whitespace() => (),
+                char if char.is_ascii_alphabetic() => {
+                    let mut word = char.to_string();
+                    while let Some(char) = chars.next_if(|char| char.is_ascii_alphabetic()) {
+                        word.push(char);
+                    }
+                    tokens.push(Token::Word(word.to_uppercase()));
+                }
+                char if char.is_ascii_digit() || matches!(char, '-' | '+' | '.') => {
+                    let mut number = char.to_string();
+                    while let Some(char) = chars.next_if(|char| {
+                        char.is_ascii_digit() || matches!(char, '-' | '+' | '.' | 'e' | 'E')
+                    }) {
+                        number.push(char);
+                    }
+                    tokens.push(Token::Number(number.parse::<f64>().map_err(|_| {
+                        invalid_data(format!("The number '{}' is not valid", number))
+                    })?));
+                }
+                char => {
+                    return Err(invalid_data(format!(
+                        "The character '{}' is not expected in WKT",
+                        char
+                    )))
+                }
+            }
+        }
+
+        Ok(WktParser {
+            tokens,
+            position: 0,
+        })
+    }
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+    fn expect(&mut self, expected: Token) -> io::Result<()> {
+        match self.next() {
+            Some(token) if token == expected => Ok(()),
+            token => Err(invalid_data(format!(
+                "Expected {:?} in WKT and found {:?}",
+                expected, token
+            ))),
+        }
+    }
+    /// Consume the `EMPTY` keyword if it's the next token.
+    fn is_empty(&mut self) -> bool {
+        if let Some(Token::Word(word)) = self.peek() {
+            if "EMPTY" == word {
+                self.position += 1;
+                return true;
+            }
+        }
+
+        false
+    }
+    /// Parse the items of a list between brackets.
+    fn list<T>(&mut self, parse: impl Fn(&mut Self) -> io::Result<T>) -> io::Result<Vec<T>> {
+        if self.is_empty() {
+            return Ok(Vec::default());
+        }
+
+        self.expect(Token::Open)?;
+        let mut items = vec![parse(self)?];
+        while let Some(Token::Comma) = self.peek() {
+            self.position += 1;
+            items.push(parse(self)?);
+        }
+        self.expect(Token::Close)?;
+
+        Ok(items)
+    }
+    fn position(&mut self) -> io::Result<Position> {
+        let mut position = Vec::default();
+        while let Some(Token::Number(number)) = self.peek() {
+            position.push(*number);
+            self.position += 1;
+        }
+
+        match position.len() {
+            2.. => Ok(position),
+            _ => Err(invalid_data(
+                "A position of WKT needs at least two numbers".to_string(),
+            )),
+        }
+    }
+    /// Parse a position of a multipoint, with or without brackets.
+    fn point(&mut self) -> io::Result<Position> {
+        match self.peek() {
+            Some(Token::Open) => {
+                self.position += 1;
+                let position = self.position()?;
+                self.expect(Token::Close)?;
+                Ok(position)
+            }
+            _ => self.position(),
+        }
+    }
+    fn positions(&mut self) -> io::Result<Vec<Position>> {
+        self.list(Self::position)
+    }
+    fn rings(&mut self) -> io::Result<Vec<Vec<Position>>> {
+        self.list(Self::positions)
+    }
+    fn geometry(&mut self) -> io::Result<Geometry> {
+        let kind = match self.next() {
+            Some(Token::Word(word)) => word,
+            token => {
+                return Err(invalid_data(format!(
+                    "Expected a geometry type in WKT and found {:?}",
+                    token
+                )))
+            }
+        };
+        // Dimensions like `POINT Z (1 2 3)`.
+        if let Some(Token::Word(word)) = self.peek() {
+            if matches!(word.as_str(), "Z" | "M" | "ZM") {
+                self.position += 1;
+            }
+        }
+
+        Ok(match kind.as_str() {
+            "POINT" => Geometry::Point(self.list(Self::position)?.pop().unwrap_or_default()),
+            "LINESTRING" => Geometry::LineString(self.positions()?),
+            "POLYGON" => Geometry::Polygon(self.rings()?),
+            "MULTIPOINT" => Geometry::MultiPoint(self.list(Self::point)?),
+            "MULTILINESTRING" => Geometry::MultiLineString(self.rings()?),
+            "MULTIPOLYGON" => Geometry::MultiPolygon(self.list(Self::rings)?),
+            "GEOMETRYCOLLECTION" => Geometry::GeometryCollection(self.list(Self::geometry)?),
+            kind => {
+                return Err(invalid_data(format!(
+                    "The geometry type '{}' is not supported",
+                    kind
+                )))
+            }
+        })
+    }
+}
+
+impl Geometry {
+    /// Parse a geometry in WKT. The `SRID=<srid>;` prefix of EWKT is ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::helper::geometry::Geometry;
+    ///
+    /// let geometry = Geometry::from_wkt("SRID=4326;POINT (2.35 48.85)").unwrap();
+    /// assert_eq!(Geometry::Point(vec![2.35, 48.85]), geometry);
+    /// ```
+    pub fn from_wkt(wkt: &str) -> io::Result<Geometry> {
+        let wkt = match wkt.trim().split_once(';') {
+            Some((srid, wkt)) if srid.to_uppercase().starts_with("SRID=") => wkt,
+            _ => wkt,
+        };
+
+        let mut parser = WktParser::new(wkt)?;
+        let geometry = parser.geometry()?;
+        match parser.next() {
+            None => Ok(geometry),
+            Some(token) => Err(invalid_data(format!(
+                "The token {:?} is not expected at the end of the WKT",
+                token
+            ))),
+        }
+    }
+    /// Format the geometry in WKT.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::helper::geometry::Geometry;
+    ///
+    /// let geometry = Geometry::Polygon(vec![vec![vec![0.0, 0.0], vec![1.0, 0.0], vec![1.0, 1.0], vec![0.0, 0.0]]]);
+    /// assert_eq!("POLYGON ((0 0, 1 0, 1 1, 0 0))", geometry.to_wkt());
+    /// ```
+    pub fn to_wkt(&self) -> String {
+        let (kind, body) = match self {
+            Geometry::Point(position) if position.is_empty() => ("POINT", None),
+            Geometry::Point(position) => {
+                ("POINT", Some(format!("({})", position_to_wkt(position))))
+            }
+            Geometry::LineString(positions) => ("LINESTRING", Some(positions_to_wkt(positions))),
+            Geometry::Polygon(rings) => ("POLYGON", Some(rings_to_wkt(rings))),
+            Geometry::MultiPoint(positions) => (
+                "MULTIPOINT",
+                Some(format!(
+                    "({})",
+                    positions
+                        .iter()
+                        .map(|position| format!("({})", position_to_wkt(position)))
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                )),
+            ),
+            Geometry::MultiLineString(lines) => ("MULTILINESTRING", Some(rings_to_wkt(lines))),
+            Geometry::MultiPolygon(polygons) => (
+                "MULTIPOLYGON",
+                Some(format!(
+                    "({})",
+                    polygons
+                        .iter()
+                        .map(|polygon| rings_to_wkt(polygon))
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                )),
+            ),
+            Geometry::GeometryCollection(geometries) => (
+                "GEOMETRYCOLLECTION",
+                Some(format!(
+                    "({})",
+                    geometries
+                        .iter()
+                        .map(Geometry::to_wkt)
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                )),
+            ),
+        };
+
+        match body {
+            Some(body) if "()" != body => match self.has_altitude() {
+                true => format!("{} Z {}", kind, body),
+                false => format!("{} {}", kind, body),
+            },
+            _ => format!("{} EMPTY", kind),
+        }
+    }
+    /// Parse a geometry in GeoJSON. A feature gives its geometry and a feature collection gives a geometry collection.
+    pub fn from_json(value: &Value) -> io::Result<Geometry> {
+        let kind = value
+            .get("type")
+            .and_then(Value::as_str)
+            .ok_or_else(|| invalid_data(format!("The geometry '{}' has no type", value)))?;
+        let coordinates = || {
+            value
+                .get("coordinates")
+                .ok_or_else(|| invalid_data(format!("The geometry '{}' has no coordinates", value)))
+        };
+
+        Ok(match kind {
+            // Only a point can be empty, like `POINT EMPTY` in WKT.
+            "Point" => match coordinates()? {
+                Value::Array(position) if position.is_empty() => Geometry::Point(Vec::default()),
+                position => Geometry::Point(position_from_json(position)?),
+            },
+            "LineString" => Geometry::LineString(positions_from_json(coordinates()?)?),
+            "Polygon" => Geometry::Polygon(rings_from_json(coordinates()?)?),
+            "MultiPoint" => Geometry::MultiPoint(positions_from_json(coordinates()?)?),
+            "MultiLineString" => Geometry::MultiLineString(rings_from_json(coordinates()?)?),
+            "MultiPolygon" => Geometry::MultiPolygon(
+                coordinates()?
+                    .as_array()
+                    .ok_or_else(|| invalid_data(format!("The geometry '{}' is not valid", value)))?
+                    .iter()
+                    .map(rings_from_json)
+                    .collect::<io::Result<_>>()?,
+            ),
+            "GeometryCollection" => Geometry::GeometryCollection(
+                value
+                    .get("geometries")
+                    .and_then(Value::as_array)
+                    .ok_or_else(|| invalid_data(format!("The geometry '{}' is not valid", value)))?
+                    .iter()
+                    .map(Geometry::from_json)
+                    .collect::<io::Result<_>>()?,
+            ),
+            "Feature" => match value.get("geometry") {
+                Some(Value::Null) | None => Geometry::GeometryCollection(Vec::default()),
+                Some(geometry) => Geometry::from_json(geometry)?,
+            },
+            "FeatureCollection" => Geometry::GeometryCollection(
+                value
+                    .get("features")
+                    .and_then(Value::as_array)
+                    .ok_or_else(|| invalid_data(format!("The geometry '{}' is not valid", value)))?
+                    .iter()
+                    .map(Geometry::from_json)
+                    .collect::<io::Result<_>>()?,
+            ),
+            kind => {
+                return Err(invalid_data(format!(
+                    "The geometry type '{}' is not supported",
+                    kind
+                )))
+            }
+        })
+    }
+    /// Parse a geometry in GeoJSON or, if the value is a string, in WKT.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::helper::geometry::Geometry;
+    /// use serde_json::json;
+    ///
+    /// let geometry = Geometry::from_value(&json!({"type": "Point", "coordinates": [2.35, 48.85]})).unwrap();
+    /// assert_eq!(Geometry::from_value(&json!("POINT (2.35 48.85)")).unwrap(), geometry);
+    /// ```
+    pub fn from_value(value: &Value) -> io::Result<Geometry> {
+        match value {
+            Value::String(wkt) => Geometry::from_wkt(wkt),
+            value => Geometry::from_json(value),
+        }
+    }
+    /// Format the geometry in GeoJSON.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::helper::geometry::Geometry;
+    /// use serde_json::json;
+    ///
+    /// let geometry = Geometry::from_wkt("LINESTRING (0 0, 1.5 1)").unwrap();
+    /// assert_eq!(json!({"type": "LineString", "coordinates": [[0, 0], [1.5, 1]]}), geometry.to_json());
+    /// ```
+    pub fn to_json(&self) -> Value {
+        let (kind, key, value) = match self {
+            Geometry::Point(position) => ("Point", "coordinates", position_to_json(position)),
+            Geometry::LineString(positions) => {
+                ("LineString", "coordinates", positions_to_json(positions))
+            }
+            Geometry::Polygon(rings) => ("Polygon", "coordinates", rings_to_json(rings)),
+            Geometry::MultiPoint(positions) => {
+                ("MultiPoint", "coordinates", positions_to_json(positions))
+            }
+            Geometry::MultiLineString(lines) => {
+                ("MultiLineString", "coordinates", rings_to_json(lines))
+            }
+            Geometry::MultiPolygon(polygons) => (
+                "MultiPolygon",
+                "coordinates",
+                Value::Array(polygons.iter().map(|rings| rings_to_json(rings)).collect()),
+            ),
+            Geometry::GeometryCollection(geometries) => (
+                "GeometryCollection",
+                "geometries",
+                Value::Array(geometries.iter().map(Geometry::to_json).collect()),
+            ),
+        };
+
+        let mut geometry = Map::default();
+        geometry.insert("type".to_string(), Value::from(kind));
+        geometry.insert(key.to_string(), value);
+        Value::Object(geometry)
+    }
+    fn has_altitude(&self) -> bool {
+        self.positions()
+            .first()
+            .is_some_and(|position| 2 < position.len())
+    }
+    /// Get all the positions of the geometry.
+    fn positions(&self) -> Vec<&Position> {
+        match self {
+            Geometry::Point(position) if position.is_empty() => Vec::default(),
+            Geometry::Point(position) => vec![position],
+            Geometry::LineString(positions) | Geometry::MultiPoint(positions) => {
+                positions.iter().collect()
+            }
+            Geometry::Polygon(rings) | Geometry::MultiLineString(rings) => {
+                rings.iter().flatten().collect()
+            }
+            Geometry::MultiPolygon(polygons) => polygons.iter().flatten().flatten().collect(),
+            Geometry::GeometryCollection(geometries) => {
+                geometries.iter().flat_map(Geometry::positions).collect()
+            }
+        }
+    }
+    /// Get the points, the lines and the polygons of the geometry.
+    fn parts(&self) -> Parts<'_> {
+        match self {
+            Geometry::Point(position) if position.is_empty() => Default::default(),
+            Geometry::Point(position) => (vec![position], Vec::default(), Vec::default()),
+            Geometry::MultiPoint(positions) => {
+                (positions.iter().collect(), Vec::default(), Vec::default())
+            }
+            Geometry::LineString(positions) => (Vec::default(), vec![positions], Vec::default()),
+            Geometry::MultiLineString(lines) => {
+                (Vec::default(), lines.iter().collect(), Vec::default())
+            }
+            Geometry::Polygon(rings) => (Vec::default(), Vec::default(), vec![rings]),
+            Geometry::MultiPolygon(polygons) => {
+                (Vec::default(), Vec::default(), polygons.iter().collect())
+            }
+            Geometry::GeometryCollection(geometries) => {
+                geometries.iter().map(Geometry::parts).fold(
+                    Default::default(),
+                    |(mut points, mut lines, mut polygons),
+                     (other_points, other_lines, other_polygons)| {
+                        points.extend(other_points);
+                        lines.extend(other_lines);
+                        polygons.extend(other_polygons);
+                        (points, lines, polygons)
+                    },
+                )
+            }
+        }
+    }
+    /// Get the bounding box `[min_x, min_y, max_x, max_y]` of the geometry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::helper::geometry::Geometry;
+    ///
+    /// let geometry = Geometry::from_wkt("LINESTRING (2 48, -1 50, 3 49)").unwrap();
+    /// assert_eq!(Some([-1.0, 48.0, 3.0, 50.0]), geometry.bbox());
+    /// ```
+    pub fn bbox(&self) -> Option<[f64; 4]> {
+        self.positions().into_iter().fold(None, |bbox, position| {
+            let (x, y) = (position[0], position[1]);
+            Some(match bbox {
+                None => [x, y, x, y],
+                Some([min_x, min_y, max_x, max_y]) => {
+                    [min_x.min(x), min_y.min(y), max_x.max(x), max_y.max(y)]
+                }
+            })
+        })
+    }
+    /// Get the centroid of the geometry. The areas of the polygons weight the centroid, then the length of the lines, then the number of points.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::helper::geometry::Geometry;
+    ///
+    /// let geometry = Geometry::from_wkt("POLYGON ((0 0, 4 0, 4 2, 0 2, 0 0))").unwrap();
+    /// assert_eq!(Some(Geometry::Point(vec![2.0, 1.0])), geometry.centroid());
+    /// ```
+    pub fn centroid(&self) -> Option<Geometry> {
+        let (points, lines, polygons) = self.parts();
+
+        let (mut area, mut x, mut y) = (0.0, 0.0, 0.0);
+        for polygon in &polygons {
+            for (index, ring) in polygon.iter().enumerate() {
+                let (ring_area, ring_x, ring_y) = ring_centroid(ring);
+                // The holes are removed from the exterior ring.
+                let ring_area = match index {
+                    0 => ring_area.abs(),
+                    _ => -ring_area.abs(),
+                };
+                area += ring_area;
+                x += ring_x * ring_area;
+                y += ring_y * ring_area;
+            }
+        }
+        if 0.0 != area {
+            return Some(Geometry::Point(vec![x / area, y / area]));
+        }
+
+        let mut lines: Vec<&Vec<Position>> = lines;
+        lines.extend(polygons.iter().flat_map(|polygon| polygon.iter()));
+        let (mut length, mut x, mut y) = (0.0, 0.0, 0.0);
+        for line in lines {
+            for segment in line.windows(2) {
+                let segment_length =
+                    (segment[1][0] - segment[0][0]).hypot(segment[1][1] - segment[0][1]);
+                length += segment_length;
+                x += (segment[0][0] + segment[1][0]) / 2.0 * segment_length;
+                y += (segment[0][1] + segment[1][1]) / 2.0 * segment_length;
+            }
+        }
+        if 0.0 != length {
+            return Some(Geometry::Point(vec![x / length, y / length]));
+        }
+
+        let positions = match points.is_empty() {
+            true => self.positions(),
+            false => points,
+        };
+        if positions.is_empty() {
+            return None;
+        }
+        let count = positions.len() as f64;
+        Some(Geometry::Point(vec![
+            positions.iter().map(|position| position[0]).sum::<f64>() / count,
+            positions.iter().map(|position| position[1]).sum::<f64>() / count,
+        ]))
+    }
+    /// Get the distance in meters between the centroids of the geometries, on the earth.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::helper::geometry::Geometry;
+    ///
+    /// let paris = Geometry::Point(vec![2.3522, 48.8566]);
+    /// let london = Geometry::Point(vec![-0.1276, 51.5072]);
+    /// assert_eq!(343.5, (paris.distance(&london).unwrap() / 100.0).round() / 10.0);
+    /// ```
+    pub fn distance(&self, other: &Geometry) -> Option<f64> {
+        let (Some(Geometry::Point(from)), Some(Geometry::Point(to))) =
+            (self.centroid(), other.centroid())
+        else {
+            return None;
+        };
+
+        let (from_lat, to_lat) = (from[1].to_radians(), to[1].to_radians());
+        let delta_lat = to_lat - from_lat;
+        let delta_lon = (to[0] - from[0]).to_radians();
+        let a = (delta_lat / 2.0).sin().powi(2)
+            + from_lat.cos() * to_lat.cos() * (delta_lon / 2.0).sin().powi(2);
+
+        Some(2.0 * EARTH_RADIUS * a.sqrt().asin())
+    }
+    /// Check if the position is inside one of the polygons of the geometry and outside their holes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chewdata::helper::geometry::Geometry;
+    ///
+    /// let polygon = Geometry::from_wkt("POLYGON ((0 0, 10 0, 10 10, 0 10, 0 0), (4 4, 6 4, 6 6, 4 6, 4 4))").unwrap();
+    /// assert!(polygon.contains(&vec![2.0, 2.0]));
+    /// assert!(!polygon.contains(&vec![5.0, 5.0]));
+    /// assert!(!polygon.contains(&vec![12.0, 5.0]));
+    /// ```
+    pub fn contains(&self, position: &Position) -> bool {
+        if position.len() < 2 {
+            return false;
+        }
+
+        let (_, _, polygons) = self.parts();
+        polygons.iter().any(|rings| {
+            let mut rings = rings.iter();
+            rings
+                .next()
+                .is_some_and(|exterior| ring_contains(exterior, position))
+                && !rings.any(|hole| ring_contains(hole, position))
+        })
+    }
+}
+
+/// Get the signed area and the centroid of a ring.
+fn ring_centroid(ring: &[Position]) -> (f64, f64, f64) {
+    let (mut area, mut x, mut y) = (0.0, 0.0, 0.0);
+    for segment in ring.windows(2) {
+        let cross = segment[0][0] * segment[1][1] - segment[1][0] * segment[0][1];
+        area += cross;
+        x += (segment[0][0] + segment[1][0]) * cross;
+        y += (segment[0][1] + segment[1][1]) * cross;
+    }
+
+    match area {
+        0.0 => (0.0, 0.0, 0.0),
+        area => (area / 2.0, x / (3.0 * area), y / (3.0 * area)),
+    }
+}
+
+/// Check if a position is inside a ring with the ray casting algorithm.
+fn ring_contains(ring: &[Position], position: &Position) -> bool {
+    let (x, y) = (position[0], position[1]);
+    let mut is_inside = false;
+
+    for segment in ring.windows(2) {
+        let (x1, y1, x2, y2) = (segment[0][0], segment[0][1], segment[1][0], segment[1][1]);
+        if (y1 > y) != (y2 > y) && x < (x2 - x1) * (y - y1) / (y2 - y1) + x1 {
+            is_inside = !is_inside;
+        }
+    }
+
+    is_inside
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn wkt_round_trip() {
+        for wkt in [
+            "POINT (1 2)",
+            "POINT Z (1 2 3)",
+            "POINT EMPTY",
+            "LINESTRING (0 0, 1.5 -2.25)",
+            "POLYGON ((0 0, 10 0, 10 10, 0 0), (1 1, 2 1, 2 2, 1 1))",
+            "MULTIPOINT ((0 0), (1 1))",
+            "MULTILINESTRING ((0 0, 1 1), (2 2, 3 3))",
+            "MULTIPOLYGON (((0 0, 1 0, 1 1, 0 0)), ((5 5, 6 5, 6 6, 5 5)))",
+            "GEOMETRYCOLLECTION (POINT (1 2), LINESTRING (0 0, 1 1))",
+            "GEOMETRYCOLLECTION EMPTY",
+        ] {
+            let geometry = Geometry::from_wkt(wkt).unwrap();
+            assert_eq!(wkt, geometry.to_wkt());
+            assert_eq!(geometry, Geometry::from_json(&geometry.to_json()).unwrap());
+        }
+
+        assert_eq!(
+            Geometry::MultiPoint(vec![vec![0.0, 0.0], vec![1.0, 1e-5]]),
+            Geometry::from_wkt("multipoint(0 0,1 1e-5)").unwrap()
+        );
+    }
+    #[test]
+    fn wkt_invalid() {
+        for wkt in [
+            "POINT (1)",
+            "POINT (1 2",
+            "CIRCLE (1 2)",
+            "POINT (1 2) POINT (3 4)",
+            "POINT (1 2 #)",
+        ] {
+            assert!(Geometry::from_wkt(wkt).is_err(), "{}", wkt);
+        }
+    }
+    #[test]
+    fn json_feature_collection() {
+        let geometry = Geometry::from_json(&json!({
+            "type": "FeatureCollection",
+            "features": [
+                {"type": "Feature", "geometry": {"type": "Point", "coordinates": [1, 1]}, "properties": {}},
+                {"type": "Feature", "geometry": null, "properties": {}},
+                {"type": "Feature", "geometry": {"type": "Point", "coordinates": [3, 5]}, "properties": {}}
+            ]
+        }))
+        .unwrap();
+        assert_eq!(Some([1.0, 1.0, 3.0, 5.0]), geometry.bbox());
+        assert_eq!(Some(Geometry::Point(vec![2.0, 3.0])), geometry.centroid());
+
+        assert!(Geometry::from_json(&json!({"type": "Point", "coordinates": "1 2"})).is_err());
+        assert!(Geometry::from_json(&json!({"coordinates": [1, 2]})).is_err());
+    }
+    #[test]
+    fn json_empty_position() {
+        assert_eq!(
+            Geometry::Point(Vec::default()),
+            Geometry::from_json(&json!({"type": "Point", "coordinates": []})).unwrap()
+        );
+        for geometry in [
+            json!({"type": "LineString", "coordinates": [[]]}),
+            json!({"type": "MultiPoint", "coordinates": [[1, 2], []]}),
+            json!({"type": "Polygon", "coordinates": [[[0, 0], [], [1, 1], [0, 0]]]}),
+            json!({"type": "MultiPolygon", "coordinates": [[[[]]]]}),
+        ] {
+            assert!(Geometry::from_json(&geometry).is_err(), "{}", geometry);
+        }
+    }
+    #[test]
+    fn centroid_of_polygon_with_hole_and_lines() {
+        let polygon =
+            Geometry::from_wkt("POLYGON ((0 0, 4 0, 4 4, 0 4, 0 0), (0 0, 2 0, 2 2, 0 2, 0 0))")
+                .unwrap();
+        let Some(Geometry::Point(centroid)) = polygon.centroid() else {
+            panic!("The centroid must be a point");
+        };
+        assert!((centroid[0] - 7.0 / 3.0).abs() < 1e-9);
+        assert!((centroid[1] - 7.0 / 3.0).abs() < 1e-9);
+
+        let line = Geometry::from_wkt("MULTILINESTRING ((0 0, 2 0), (0 4, 0 4))").unwrap();
+        assert_eq!(Some(Geometry::Point(vec![1.0, 0.0])), line.centroid());
+        assert_eq!(None, Geometry::from_wkt("POINT EMPTY").unwrap().centroid());
+    }
+    #[test]
+    fn contains_in_multipolygon() {
+        let geometry = Geometry::from_wkt(
+            "MULTIPOLYGON (((0 0, 1 0, 1 1, 0 1, 0 0)), ((5 5, 6 5, 6 6, 5 6, 5 5)))",
+        )
+        .unwrap();
+        assert!(geometry.contains(&vec![5.5, 5.5]));
+        assert!(!geometry.contains(&vec![3.0, 3.0]));
+        assert!(!Geometry::Point(vec![0.0, 0.0]).contains(&vec![0.0, 0.0]));
+    }
+}
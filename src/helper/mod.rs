@@ -1,4 +1,15 @@
+#[cfg(any(feature = "parquet", feature = "arrow", feature = "orc"))]
+pub mod arrow;
+#[cfg(any(
+    feature = "bucket",
+    feature = "dynamodb",
+    feature = "sqs",
+    feature = "sns"
+))]
+pub mod aws;
 pub mod checksum;
+#[cfg(feature = "geojson")]
+pub mod geometry;
 pub mod json_pointer;
 pub mod mustache;
 pub mod string;
@@ -165,11 +165,9 @@ fn value_replace_mustache(value: &mut Value, object: &Value) {
                 value_replace_mustache(i, object);
             }
         }
-        Value::String(ref mut a) => {
-            if a.has_mustache() {
-                a.replace_mustache(object.clone());
-                *value = Value::resolve(a.clone());
-            }
+        Value::String(ref mut a) if a.has_mustache() => {
+            a.replace_mustache(object.clone());
+            *value = Value::resolve(a.clone());
         }
         _ => (),
     }
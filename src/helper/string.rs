@@ -85,3 +85,26 @@ fn log_enabled() -> bool {
         _ => false,
     }
 }
+
+/// Percent-encode a value in order to use it in an uri. Keep the '/' if the value is a path.
+///
+/// # Examples
+///
+/// ```
+/// use chewdata::helper::string::percent_encode;
+///
+/// assert_eq!("data/my%20file.json", percent_encode("data/my file.json", true));
+/// assert_eq!("data%2Fmy%20file.json", percent_encode("data/my file.json", false));
+/// ```
+pub fn percent_encode(value: &str, keep_slash: bool) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (byte as char).to_string()
+            }
+            b'/' if keep_slash => "/".to_string(),
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
@@ -29,6 +29,8 @@ use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
 use std::io::Result;
 use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use std::{collections::HashMap, io};
 
 #[cfg(feature = "curl")]
@@ -301,11 +303,54 @@ impl DataResult {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone)]
 pub struct Context {
     // Previous steps history
     steps: Value,
     input: DataResult,
+    // Notify the source of the data with the result of the writer. Shared by the clones of the context.
+    acknowledgement: Option<Arc<Acknowledgement>>,
+}
+
+/// Notify the source of the data only once for a record.
+///
+/// A record dropped by a step or skipped by the writers is considered as processed:
+/// when the last context of the record is dropped without result, the original record is sent.
+#[derive(Debug)]
+struct Acknowledgement {
+    sender: Sender<DataResult>,
+    record: Value,
+    is_sent: AtomicBool,
+}
+
+impl Acknowledgement {
+    async fn send(&self, data_result: &DataResult) {
+        if self.is_sent.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        // The source may not wait the result anymore.
+        if self.sender.send(data_result.clone()).await.is_err() {
+            trace!("The source doesn't wait the acknowledgement anymore");
+        }
+    }
+}
+
+impl Drop for Acknowledgement {
+    fn drop(&mut self) {
+        if *self.is_sent.get_mut() {
+            return;
+        }
+        let record = std::mem::take(&mut self.record);
+        if self.sender.try_send(DataResult::Ok(record)).is_err() {
+            trace!("The source doesn't wait the acknowledgement anymore");
+        }
+    }
+}
+
+impl PartialEq for Context {
+    fn eq(&self, other: &Self) -> bool {
+        self.steps == other.steps && self.input == other.input
+    }
 }
 
 impl Context {
@@ -316,6 +361,23 @@ impl Context {
         Context {
             steps: Value::Object(map),
             input: data_result,
+            acknowledgement: None,
+        }
+    }
+    /// Attach a channel that will receive the result of the writer for the current record.
+    ///
+    /// If the record is dropped by a step or skipped by the writers, the channel receives the record in success once all its contexts are dropped.
+    pub fn set_acknowledgement(&mut self, acknowledgement: Sender<DataResult>) {
+        self.acknowledgement = Some(Arc::new(Acknowledgement {
+            sender: acknowledgement,
+            record: self.input.to_value(),
+            is_sent: AtomicBool::new(false),
+        }));
+    }
+    /// Send the result of the writer to the source of the data, if it waits for it. Only the first result of the record is sent.
+    pub async fn acknowledge(&self, data_result: &DataResult) {
+        if let Some(acknowledgement) = &self.acknowledgement {
+            acknowledgement.send(data_result).await;
         }
     }
     pub fn insert_step_result(&mut self, step_name: String, data_result: DataResult) {
@@ -7,6 +7,7 @@
 //! 3 - Put the data in the parameter of the [`crate::connector`].  
 //! 4 - Read bytes from the [`crate::document`] through the [`crate::connector`].  
 //! 5 - Create a new [`crate::Context`] and attach the [`crate::DataResult`] to it.  
+//!     If the connector waits the result of the writer, the acknowledgement channel is attached too.  
 //! 6 - Push the new [`crate::Context`] into the output queue.  
 //! 7 - Go to step 1 until the input queue is not empty.  
 //!
@@ -224,17 +225,21 @@ async fn read<'step>(
 
     let step = step.clone();
     let context = context.clone();
+    let acknowledgement = connector.acknowledgement();
 
     smol::spawn(async move {
         let step: Reader = step.clone();
         dataset.map(|data_result| async {
-            let context = match context.clone() {
+            let mut context = match context.clone() {
                 Some(ref mut context) => {
                     context.insert_step_result(step.name(), data_result);
                     context.clone()
                 },
                 None => Context::new(step.name(), data_result),
             };
+            if let Some(acknowledgement) = &acknowledgement {
+                context.set_acknowledgement(acknowledgement.clone());
+            }
             step.send(&context).await;
         }).buffer_unordered(usize::MAX)
         .collect::<Vec<_>>()
@@ -56,7 +56,7 @@
 use crate::connector::ConnectorType;
 use crate::document::DocumentType;
 use crate::step::{DataResult, Step};
-use crate::Context;
+use crate::{Context, DataSet, DataStream};
 use async_channel::{Receiver, Sender};
 use async_trait::async_trait;
 use smol::stream::StreamExt;
@@ -138,6 +138,8 @@ impl Step for Writer {
         connector.set_document(document.clone())?;
         
         let mut dataset = Vec::default();
+        // Contexts of the dataset, used to acknowledge the result of each record.
+        let mut contexts: Vec<Context> = Vec::default();
 
         let mut receiver_stream = self.receive().await;
 
@@ -162,8 +164,10 @@ impl Step for Writer {
                     info!(dataset_length = dataset.len(), "Next write");
 
                     match connector.send(&dataset).await {
-                        Ok(_) => {
-                            total_written+=dataset.len();
+                        Ok(results) => {
+                            let dataset = dataset_sent(connector.has_send_results(), dataset, results).await;
+                            acknowledge(&contexts, &dataset).await;
+                            total_written+=dataset.iter().filter(|data| data.is_type(DataResult::OK)).count();
                             info!(dataset_length = dataset.len(), total = &total_written, "Write with success");
 
                             for data in dataset {
@@ -180,6 +184,7 @@ impl Step for Writer {
                                 "Can't write data"
                             );
 
+                            acknowledge_error(&contexts, &dataset, &e).await;
                             for data in dataset {
                                 let mut context = context_received.clone();
                                 context.insert_step_result(
@@ -195,19 +200,23 @@ impl Step for Writer {
                         }
                     };
                     dataset = Vec::default();
+                    contexts = Vec::default();
                     connector = default_connector.clone();
                 }
             }
 
             connector.set_parameters(context_received.to_value()?);
             dataset.push(context_received.input());
+            contexts.push(context_received.clone());
 
             if self.record_limit <= dataset.len() && document.can_append() {
                 info!(dataset_length = dataset.len(), "Next write");
 
                 match connector.send(&dataset).await {
-                    Ok(_) => {
-                        total_written+=dataset.len();
+                    Ok(results) => {
+                        let dataset = dataset_sent(connector.has_send_results(), dataset, results).await;
+                        acknowledge(&contexts, &dataset).await;
+                        total_written+=dataset.iter().filter(|data| data.is_type(DataResult::OK)).count();
                         info!(dataset_length = dataset.len(), total = total_written, "Write with success");
 
                         for data in dataset {
@@ -223,6 +232,7 @@ impl Step for Writer {
                             "Can't write data"
                         );
 
+                        acknowledge_error(&contexts, &dataset, &e).await;
                         for data in dataset {
                             let mut context = context_received.clone();
                             context.insert_step_result(
@@ -239,6 +249,7 @@ impl Step for Writer {
                 };
 
                 dataset = Vec::default();
+                contexts = Vec::default();
             }
         }
 
@@ -246,8 +257,10 @@ impl Step for Writer {
             info!(dataset_length = dataset.len(), "Last write");
 
             match connector.send(&dataset).await {
-                Ok(_) => {
-                    total_written+=dataset.len();
+                Ok(results) => {
+                    let dataset = dataset_sent(connector.has_send_results(), dataset, results).await;
+                    acknowledge(&contexts, &dataset).await;
+                    total_written+=dataset.iter().filter(|data| data.is_type(DataResult::OK)).count();
                     info!(dataset_length = dataset.len(), total = total_written, "Write with success");
 
                     for data in dataset {
@@ -270,6 +283,7 @@ impl Step for Writer {
                         "Can't write data"
                     );
 
+                    acknowledge_error(&contexts, &dataset, &e).await;
                     for data in dataset {
                         let context = match &last_context_received {
                             Some(context_received) => {
@@ -313,6 +327,37 @@ impl Step for Writer {
     }
 }
 
+/// Send the result of each record to the source of the data.
+async fn acknowledge(contexts: &[Context], dataset: &DataSet) {
+    for (context, data) in contexts.iter().zip(dataset) {
+        context.acknowledge(data).await;
+    }
+}
+
+/// Send the error of each record to the source of the data.
+async fn acknowledge_error(contexts: &[Context], dataset: &DataSet, error: &io::Error) {
+    for (context, data) in contexts.iter().zip(dataset) {
+        context
+            .acknowledge(&DataResult::Err((
+                data.to_value(),
+                io::Error::new(error.kind(), error.to_string()),
+            )))
+            .await;
+    }
+}
+
+/// Return the dataset sent. If the connector returns the result of each record, the results replace the records sent.
+async fn dataset_sent(
+    has_send_results: bool,
+    dataset: DataSet,
+    results: Option<DataStream>,
+) -> DataSet {
+    match (has_send_results, results) {
+        (true, Some(results)) => results.collect().await,
+        _ => dataset,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -344,6 +389,30 @@ mod tests {
         assert_eq!(expected_context, receiver_output.recv().await.unwrap());
     }
     #[apply(test!)]
+    async fn exec_with_different_data_result_type_and_acknowledgement() {
+        let mut step = Writer::default();
+        let (sender_input, receiver_input) = async_channel::unbounded();
+        let (acknowledgement, receiver_acknowledgement) = async_channel::unbounded();
+        let data: Value = serde_json::from_str(r#"{"field_1":"value_1"}"#).unwrap();
+        let error = Error::new(ErrorKind::InvalidData, "My error");
+        let mut context = Context::new("before".to_string(), DataResult::Err((data, error)));
+        context.set_acknowledgement(acknowledgement);
+        let record = context.input().to_value();
+
+        sender_input.try_send(context).unwrap();
+        drop(sender_input);
+
+        step.receiver = Some(receiver_input);
+        step.exec().await.unwrap();
+
+        // The writer skips the context, the record is considered as processed.
+        assert_eq!(
+            DataResult::Ok(record),
+            receiver_acknowledgement.recv().await.unwrap()
+        );
+        assert!(receiver_acknowledgement.recv().await.is_err());
+    }
+    #[apply(test!)]
     async fn exec_with_same_data_result_type() {
         let mut step = Writer::default();
         let (sender_input, receiver_input) = async_channel::unbounded();
@@ -41,6 +41,15 @@ pub fn engine() -> Arc<Mutex<TeraClient>> {
             engine.register_filter("keys", filters::object::keys);
             engine.register_filter("update", filters::object::update);
             engine.register_filter("map", filters::object::map);
+            #[cfg(feature = "geojson")]
+            {
+                engine.register_filter("wkt_to_geojson", filters::geometry::wkt_to_geojson);
+                engine.register_filter("geojson_to_wkt", filters::geometry::geojson_to_wkt);
+                engine.register_filter("bbox", filters::geometry::bbox);
+                engine.register_filter("centroid", filters::geometry::centroid);
+                engine.register_filter("distance", filters::geometry::distance);
+                engine.register_filter("within", filters::geometry::within);
+            }
 
             // faker
             engine.register_function("fake_words", function::faker::words);
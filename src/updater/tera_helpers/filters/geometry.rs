@@ -0,0 +1,273 @@
+use crate::helper::geometry::Geometry;
+use serde_json::value::Value;
+use std::collections::HashMap;
+use tera::*;
+
+fn geometry(filter: &str, value: &Value) -> Result<Geometry> {
+    Geometry::from_value(value).map_err(|e| {
+        Error::msg(format!(
+            "Filter `{}` require a GeoJSON or a WKT geometry: {}",
+            filter, e
+        ))
+    })
+}
+
+fn geometry_argument(
+    filter: &str,
+    argument: &str,
+    args: &HashMap<String, Value>,
+) -> Result<Geometry> {
+    let value = args.get(argument).ok_or_else(|| {
+        Error::msg(format!(
+            "Filter `{}` didn't receive a `{}` argument",
+            filter, argument
+        ))
+    })?;
+
+    geometry(filter, value)
+}
+
+/// Convert a WKT geometry into a GeoJSON geometry.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::HashMap;
+/// use chewdata::updater::tera_helpers::filters::geometry::wkt_to_geojson;
+/// use serde_json::json;
+///
+/// let value = json!("POINT (2.35 48.85)");
+/// let result = wkt_to_geojson(&value, &HashMap::new()).unwrap();
+/// assert_eq!(json!({"type": "Point", "coordinates": [2.35, 48.85]}), result);
+/// ```
+pub fn wkt_to_geojson(value: &Value, _args: &HashMap<String, Value>) -> Result<Value> {
+    let wkt = try_get_value!("wkt_to_geojson", "value", String, value);
+
+    Geometry::from_wkt(&wkt)
+        .map(|geometry| geometry.to_json())
+        .map_err(|e| {
+            Error::msg(format!(
+                "Filter `wkt_to_geojson` can't parse the WKT: {}",
+                e
+            ))
+        })
+}
+
+/// Convert a GeoJSON geometry or feature into a WKT geometry.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::HashMap;
+/// use chewdata::updater::tera_helpers::filters::geometry::geojson_to_wkt;
+/// use serde_json::json;
+///
+/// let value = json!({"type": "LineString", "coordinates": [[0, 0], [1.5, 2]]});
+/// let result = geojson_to_wkt(&value, &HashMap::new()).unwrap();
+/// assert_eq!(json!("LINESTRING (0 0, 1.5 2)"), result);
+/// ```
+pub fn geojson_to_wkt(value: &Value, _args: &HashMap<String, Value>) -> Result<Value> {
+    Ok(Value::String(geometry("geojson_to_wkt", value)?.to_wkt()))
+}
+
+/// Returns the bounding box `[min_x, min_y, max_x, max_y]` of a geometry. Returns `null` for an empty geometry.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::HashMap;
+/// use chewdata::updater::tera_helpers::filters::geometry::bbox;
+/// use serde_json::json;
+///
+/// let value = json!("LINESTRING (2 48, -1 50, 3 49)");
+/// let result = bbox(&value, &HashMap::new()).unwrap();
+/// assert_eq!(json!([-1.0, 48.0, 3.0, 50.0]), result);
+/// ```
+pub fn bbox(value: &Value, _args: &HashMap<String, Value>) -> Result<Value> {
+    Ok(geometry("bbox", value)?
+        .bbox()
+        .map_or(Value::Null, |bbox| Value::from(bbox.to_vec())))
+}
+
+/// Returns the centroid of a geometry as a point. Returns `null` for an empty geometry.
+///
+/// # Arguments
+///
+/// * `format` - (optional) Format of the point: `geojson` | `wkt`. By default, the format of the input.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::HashMap;
+/// use chewdata::updater::tera_helpers::filters::geometry::centroid;
+/// use serde_json::json;
+///
+/// let value = json!({"type": "Polygon", "coordinates": [[[0, 0], [4, 0], [4, 2], [0, 2], [0, 0]]]});
+/// let result = centroid(&value, &HashMap::new()).unwrap();
+/// assert_eq!(json!({"type": "Point", "coordinates": [2, 1]}), result);
+///
+/// let mut args = HashMap::new();
+/// args.insert("format".to_string(), json!("wkt"));
+/// let result = centroid(&value, &args).unwrap();
+/// assert_eq!(json!("POINT (2 1)"), result);
+/// ```
+pub fn centroid(value: &Value, args: &HashMap<String, Value>) -> Result<Value> {
+    let format = match args.get("format") {
+        Some(format) => try_get_value!("centroid", "format", String, format),
+        None if value.is_string() => "wkt".to_string(),
+        None => "geojson".to_string(),
+    };
+
+    let centroid = match geometry("centroid", value)?.centroid() {
+        Some(centroid) => centroid,
+        None => return Ok(Value::Null),
+    };
+
+    match format.as_str() {
+        "geojson" => Ok(centroid.to_json()),
+        "wkt" => Ok(Value::String(centroid.to_wkt())),
+        format => Err(Error::msg(format!(
+            "Filter `centroid` doesn't support the format '{}'. Possible values: geojson | wkt",
+            format
+        ))),
+    }
+}
+
+/// Returns the distance between the centroids of two geometries on the earth, with the haversine formula.
+///
+/// # Arguments
+///
+/// * `to` - The other geometry in GeoJSON or WKT.
+/// * `unit` - (optional) Unit of the distance: `m` | `km` | `mi`. By default, `m`.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::HashMap;
+/// use chewdata::updater::tera_helpers::filters::geometry::distance;
+/// use serde_json::json;
+///
+/// let value = json!("POINT (2.3522 48.8566)");
+/// let mut args = HashMap::new();
+/// args.insert("to".to_string(), json!({"type": "Point", "coordinates": [-0.1276, 51.5072]}));
+/// args.insert("unit".to_string(), json!("km"));
+///
+/// let result = distance(&value, &args).unwrap();
+/// assert_eq!(344, result.as_f64().unwrap().round() as i64);
+/// ```
+pub fn distance(value: &Value, args: &HashMap<String, Value>) -> Result<Value> {
+    let to = geometry_argument("distance", "to", args)?;
+    let unit = match args.get("unit") {
+        Some(unit) => try_get_value!("distance", "unit", String, unit),
+        None => "m".to_string(),
+    };
+    let ratio = match unit.as_str() {
+        "m" => 1.0,
+        "km" => 1_000.0,
+        "mi" => 1_609.344,
+        unit => {
+            return Err(Error::msg(format!(
+                "Filter `distance` doesn't support the unit '{}'. Possible values: m | km | mi",
+                unit
+            )))
+        }
+    };
+
+    Ok(geometry("distance", value)?
+        .distance(&to)
+        .map_or(Value::Null, |distance| Value::from(distance / ratio)))
+}
+
+/// Check if a point is inside a polygon or a multipolygon, and outside of its holes.
+///
+/// # Arguments
+///
+/// * `polygon` - The polygon in GeoJSON or WKT.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::HashMap;
+/// use chewdata::updater::tera_helpers::filters::geometry::within;
+/// use serde_json::json;
+///
+/// let mut args = HashMap::new();
+/// args.insert("polygon".to_string(), json!("POLYGON ((0 0, 10 0, 10 10, 0 10, 0 0))"));
+///
+/// assert_eq!(json!(true), within(&json!({"type": "Point", "coordinates": [2, 3]}), &args).unwrap());
+/// assert_eq!(json!(false), within(&json!("POINT (12 3)"), &args).unwrap());
+/// ```
+pub fn within(value: &Value, args: &HashMap<String, Value>) -> Result<Value> {
+    let polygon = geometry_argument("within", "polygon", args)?;
+
+    match geometry("within", value)? {
+        Geometry::Point(position) => Ok(Value::Bool(polygon.contains(&position))),
+        _ => Err(Error::msg("Filter `within` require a point in input")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::updater::tera::engine;
+    use serde_json::json;
+
+    #[test]
+    fn wkt_to_geojson_invalid() {
+        assert!(wkt_to_geojson(&json!("POINT (1)"), &HashMap::new()).is_err());
+        assert!(wkt_to_geojson(&json!({"type": "Point"}), &HashMap::new()).is_err());
+    }
+    #[test]
+    fn geojson_to_wkt_from_feature() {
+        let value = json!({
+            "type": "Feature",
+            "geometry": {"type": "Polygon", "coordinates": [[[0, 0], [1, 0], [1, 1], [0, 0]]]},
+            "properties": {"name": "triangle"}
+        });
+        assert_eq!(
+            json!("POLYGON ((0 0, 1 0, 1 1, 0 0))"),
+            geojson_to_wkt(&value, &HashMap::new()).unwrap()
+        );
+    }
+    #[test]
+    fn bbox_empty() {
+        assert_eq!(
+            Value::Null,
+            bbox(&json!("GEOMETRYCOLLECTION EMPTY"), &HashMap::new()).unwrap()
+        );
+    }
+    #[test]
+    fn distance_with_wrong_arguments() {
+        let value = json!("POINT (0 0)");
+        assert!(distance(&value, &HashMap::new()).is_err());
+
+        let mut args = HashMap::new();
+        args.insert("to".to_string(), json!("POINT (0 1)"));
+        args.insert("unit".to_string(), json!("ft"));
+        assert!(distance(&value, &args).is_err());
+    }
+    #[test]
+    fn within_not_a_point() {
+        let mut args = HashMap::new();
+        args.insert(
+            "polygon".to_string(),
+            json!("POLYGON ((0 0, 1 0, 1 1, 0 0))"),
+        );
+        assert!(within(&json!("LINESTRING (0 0, 1 1)"), &args).is_err());
+    }
+    #[test]
+    fn filters_in_template() {
+        let engine = engine();
+        let mut engine = engine.lock().unwrap();
+        let mut context = tera::Context::new();
+        context.insert("location", &json!("POINT (2.3522 48.8566)"));
+
+        let result = engine
+            .render_str(
+                r#"{{ location | wkt_to_geojson | centroid(format="wkt") }}|{{ location | within(polygon="POLYGON ((2 48, 3 48, 3 49, 2 49, 2 48))") }}"#,
+                &context,
+            )
+            .unwrap();
+        assert_eq!("POINT (2.3522 48.8566)|true", result);
+    }
+}
@@ -1,3 +1,4 @@
-
+#[cfg(feature = "geojson")]
+pub mod geometry;
 pub mod object;
 pub mod string;